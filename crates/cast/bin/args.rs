@@ -488,7 +488,8 @@ pub enum CastSubcommand {
     /// Publish a raw transaction to the network.
     #[command(name = "publish", visible_alias = "p")]
     PublishTx {
-        /// The raw transaction
+        /// The raw transaction, or the path of a transaction file signed with
+        /// `cast wallet sign-tx`.
         raw_tx: String,
 
         /// Only print the transaction hash and exit immediately.
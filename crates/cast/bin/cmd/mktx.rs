@@ -1,11 +1,11 @@
-use crate::tx::{self, CastTxBuilder};
+use crate::tx::{self, CastTxBuilder, SenderKind, TransactionFile};
 use alloy_network::{eip2718::Encodable2718, EthereumWallet, TransactionBuilder};
-use alloy_primitives::hex;
+use alloy_primitives::{hex, TxKind};
 use alloy_signer::Signer;
 use clap::Parser;
-use eyre::Result;
+use eyre::{Result, WrapErr};
 use foundry_cli::{
-    opts::{EthereumOpts, TransactionOpts},
+    opts::{EthereumOpts, GasStrategyOpts, TransactionOpts},
     utils::{get_provider, LoadConfig},
 };
 use foundry_common::ens::NameOrAddress;
@@ -32,6 +32,9 @@ pub struct MakeTxArgs {
     #[command(flatten)]
     tx: TransactionOpts,
 
+    #[command(flatten)]
+    gas: GasStrategyOpts,
+
     /// The path of blob data to be sent.
     #[arg(
         long,
@@ -42,6 +45,18 @@ pub struct MakeTxArgs {
     )]
     path: Option<PathBuf>,
 
+    /// Build the transaction without signing it, with its nonce, fees and chain ID filled, to be
+    /// signed with `cast wallet sign-tx`.
+    ///
+    /// The sender is set with --from, or is the address of the given wallet. Keystores aren't
+    /// decrypted, their address is read from the keystore file.
+    #[arg(long, requires = "out")]
+    unsigned: bool,
+
+    /// The path of the file to write the unsigned transaction to.
+    #[arg(long, value_name = "PATH", requires = "unsigned")]
+    out: Option<PathBuf>,
+
     #[command(flatten)]
     eth: EthereumOpts,
 }
@@ -64,7 +79,12 @@ pub enum MakeTxSubcommands {
 
 impl MakeTxArgs {
     pub async fn run(self) -> Result<()> {
-        let Self { to, mut sig, mut args, command, tx, path, eth } = self;
+        let Self { to, mut sig, mut args, command, tx, gas, path, unsigned, out, eth } = self;
+
+        // Transactions are only built here, so there's nothing to replace.
+        if gas.bump_timeout.is_some() {
+            eyre::bail!("--bump-timeout only applies to sent transactions");
+        }
 
         let blob_data = if let Some(path) = path { Some(std::fs::read(path)?) } else { None };
 
@@ -82,6 +102,49 @@ pub async fn run(self) -> Result<()> {
         };
 
         let config = eth.load_config()?;
+        let provider = get_provider(&config)?;
+        let (gas_price, priority_gas_price) = (tx.gas_price, tx.priority_gas_price);
+
+        if unsigned {
+            // Keystores aren't unlocked just to read their address.
+            let sender: SenderKind<'_> = match eth.wallet.from {
+                Some(from) => from.into(),
+                None => match eth
+                    .wallet
+                    .keystore_address()
+                    .wrap_err("Specify the sender of the unsigned transaction with --from")?
+                {
+                    Some(address) => address.into(),
+                    None => SenderKind::from_wallet_opts(eth.wallet).await?,
+                },
+            };
+            if sender.address().is_zero() {
+                eyre::bail!("Specify the sender of the unsigned transaction with --from");
+            }
+
+            let (mut tx, func) = CastTxBuilder::new(&provider, tx, &config)
+                .await?
+                .with_to(to)
+                .await?
+                .with_code_sig_and_args(code, sig, args)
+                .await?
+                .with_blob_data(blob_data)?
+                .build(sender)
+                .await?;
+            gas.apply(&provider, &mut tx, gas_price, priority_gas_price).await?;
+
+            let function = match tx.to {
+                Some(TxKind::Call(_)) => func.map(|func| func.signature()),
+                _ => None,
+            };
+            let file = TransactionFile { function, transaction: tx, signed: None };
+            let out = out.expect("required by clap");
+            file.save(&out)?;
+
+            sh_println!("{}", file.describe()?)?;
+            sh_println!("\nUnsigned transaction written to {}", out.display())?;
+            return Ok(());
+        }
 
         // Retrieve the signer, and bail if it can't be constructed.
         let signer = eth.wallet.signer().await?;
@@ -89,9 +152,7 @@ pub async fn run(self) -> Result<()> {
 
         tx::validate_from_address(eth.wallet.from, from)?;
 
-        let provider = get_provider(&config)?;
-
-        let (tx, _) = CastTxBuilder::new(provider, tx, &config)
+        let (mut tx, _) = CastTxBuilder::new(&provider, tx, &config)
             .await?
             .with_to(to)
             .await?
@@ -100,6 +161,10 @@ pub async fn run(self) -> Result<()> {
             .with_blob_data(blob_data)?
             .build(&signer)
             .await?;
+        gas.apply(&provider, &mut tx, gas_price, priority_gas_price).await?;
+        if let Some(chain_id) = tx.chain_id {
+            eth.wallet.ensure_chain_allowed(chain_id)?;
+        }
 
         let tx = tx.build(&EthereumWallet::new(signer)).await?;
 
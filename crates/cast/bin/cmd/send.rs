@@ -8,7 +8,7 @@
 use clap::Parser;
 use eyre::Result;
 use foundry_cli::{
-    opts::{EthereumOpts, TransactionOpts},
+    opts::{EthereumOpts, GasStrategyOpts, TransactionOpts},
     utils,
     utils::LoadConfig,
 };
@@ -52,6 +52,9 @@ pub struct SendTxArgs {
     #[command(flatten)]
     tx: TransactionOpts,
 
+    #[command(flatten)]
+    gas: GasStrategyOpts,
+
     #[command(flatten)]
     eth: EthereumOpts,
 
@@ -92,6 +95,7 @@ pub async fn run(self) -> eyre::Result<()> {
             cast_async,
             mut args,
             tx,
+            gas,
             confirmations,
             command,
             unlocked,
@@ -116,6 +120,7 @@ pub async fn run(self) -> eyre::Result<()> {
 
         let config = eth.load_config()?;
         let provider = utils::get_provider(&config)?;
+        let (gas_price, priority_gas_price) = (tx.gas_price, tx.priority_gas_price);
 
         let builder = CastTxBuilder::new(&provider, tx, &config)
             .await?
@@ -151,9 +156,10 @@ pub async fn run(self) -> eyre::Result<()> {
                 }
             }
 
-            let (tx, _) = builder.build(config.sender).await?;
+            let (mut tx, _) = builder.build(config.sender).await?;
+            gas.apply(&provider, &mut tx, gas_price, priority_gas_price).await?;
 
-            cast_send(provider, tx, cast_async, confirmations, timeout).await
+            cast_send(provider, tx, &gas, cast_async, confirmations, timeout).await
         // Case 2:
         // An option to use a local signer was provided.
         // If we cannot successfully instantiate a local signer, then we will assume we don't have
@@ -165,14 +171,18 @@ pub async fn run(self) -> eyre::Result<()> {
 
             tx::validate_from_address(eth.wallet.from, from)?;
 
-            let (tx, _) = builder.build(&signer).await?;
+            let (mut tx, _) = builder.build(&signer).await?;
+            if let Some(chain_id) = tx.chain_id {
+                eth.wallet.ensure_chain_allowed(chain_id)?;
+            }
+            gas.apply(&provider, &mut tx, gas_price, priority_gas_price).await?;
 
             let wallet = EthereumWallet::from(signer);
             let provider = ProviderBuilder::<_, _, AnyNetwork>::default()
                 .wallet(wallet)
                 .on_provider(&provider);
 
-            cast_send(provider, tx, cast_async, confirmations, timeout).await
+            cast_send(provider, tx, &gas, cast_async, confirmations, timeout).await
         }
     }
 }
@@ -180,18 +190,26 @@ pub async fn run(self) -> eyre::Result<()> {
 async fn cast_send<P: Provider<AnyNetwork>>(
     provider: P,
     tx: WithOtherFields<TransactionRequest>,
+    gas: &GasStrategyOpts,
     cast_async: bool,
     confs: u64,
     timeout: u64,
 ) -> Result<()> {
-    let cast = Cast::new(provider);
-    let pending_tx = cast.send(tx).await?;
+    let cast = Cast::new(&provider);
+    let pending_tx = cast.send(tx.clone()).await?;
 
-    let tx_hash = pending_tx.inner().tx_hash();
+    let tx_hash = *pending_tx.inner().tx_hash();
 
     if cast_async {
         sh_println!("{tx_hash:#x}")?;
     } else {
+        // Replace the transaction with bumped fees while it's pending, if `--bump-timeout` is set.
+        let cast = &cast;
+        let tx_hash = gas
+            .wait_for_inclusion(&provider, tx, tx_hash, timeout, |tx| async move {
+                Ok(*cast.send(tx).await?.inner().tx_hash())
+            })
+            .await?;
         let receipt =
             cast.receipt(format!("{tx_hash:#x}"), None, confs, Some(timeout), false).await?;
         sh_println!("{receipt}")?;
@@ -1,9 +1,11 @@
+use alloy_chains::Chain;
 use clap::Parser;
 use eyre::Result;
+use itertools::Itertools;
 
 use foundry_common::{fs, sh_err, sh_println};
 use foundry_config::Config;
-use foundry_wallets::multi_wallet::MultiWalletOptsBuilder;
+use foundry_wallets::{keystore::KeystoreMetadata, multi_wallet::MultiWalletOptsBuilder};
 
 /// CLI arguments for `cast wallet list`.
 #[derive(Clone, Debug, Parser)]
@@ -94,12 +96,25 @@ fn list_local_senders(&self) -> Result<()> {
         };
 
         // List all files within the keystore directory.
-        for entry in std::fs::read_dir(keystore_dir)? {
+        for entry in std::fs::read_dir(&keystore_dir)? {
             let path = entry?.path();
             if path.is_file() {
                 if let Some(file_name) = path.file_name() {
                     if let Some(name) = file_name.to_str() {
-                        sh_println!("{name} (Local)")?;
+                        let metadata = KeystoreMetadata::load(&keystore_dir, name)
+                            .ok()
+                            .flatten()
+                            .unwrap_or_default();
+                        let mut details = String::new();
+                        if let Some(label) = &metadata.label {
+                            details.push_str(&format!(" - {label}"));
+                        }
+                        if !metadata.chains.is_empty() {
+                            let chains =
+                                metadata.chains.iter().map(|id| Chain::from(*id)).join(", ");
+                            details.push_str(&format!(" [chains: {chains}]"));
+                        }
+                        sh_println!("{name} (Local){details}")?;
                     }
                 }
             }
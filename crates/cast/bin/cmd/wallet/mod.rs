@@ -1,5 +1,7 @@
+use crate::tx::TransactionFile;
 use alloy_chains::Chain;
 use alloy_dyn_abi::TypedData;
+use alloy_network::{eip2718::Encodable2718, EthereumWallet, TransactionBuilder};
 use alloy_primitives::{hex, Address, PrimitiveSignature as Signature, B256, U256};
 use alloy_provider::Provider;
 use alloy_signer::Signer;
@@ -13,10 +15,17 @@
 use foundry_cli::{opts::RpcOpts, utils, utils::LoadConfig};
 use foundry_common::{fs, sh_println, shell};
 use foundry_config::Config;
-use foundry_wallets::{RawWalletOpts, WalletOpts, WalletSigner};
+use foundry_wallets::{
+    keystore::{self, KeystoreMetadata, ScryptParams},
+    RawWalletOpts, WalletOpts, WalletSigner,
+};
+use itertools::Itertools;
 use rand::thread_rng;
 use serde_json::json;
-use std::path::Path;
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+};
 use yansi::Paint;
 
 pub mod vanity;
@@ -135,6 +144,24 @@ pub enum WalletSubcommands {
         wallet: WalletOpts,
     },
 
+    /// Sign a transaction built with `cast mktx --unsigned`, e.g. on an offline machine.
+    ///
+    /// The signed transaction is written to the transaction file, to be published with
+    /// `cast publish`.
+    #[command(name = "sign-tx", visible_alias = "st")]
+    SignTx {
+        /// The path of the transaction file.
+        #[arg(value_name = "PATH")]
+        path: PathBuf,
+
+        /// Write the signed transaction to this file instead of the transaction file.
+        #[arg(long, value_name = "PATH")]
+        out: Option<PathBuf>,
+
+        #[command(flatten)]
+        wallet: WalletOpts,
+    },
+
     /// Verify the signature of a message.
     #[command(visible_alias = "v")]
     Verify {
@@ -165,6 +192,100 @@ pub enum WalletSubcommands {
         unsafe_password: Option<String>,
         #[command(flatten)]
         raw_wallet_options: RawWalletOpts,
+        /// Import the accounts of a range of mnemonic indexes, e.g. `0..5`, into keystores named
+        /// `<ACCOUNT_NAME>-<INDEX>`.
+        #[arg(
+            long,
+            value_name = "START..END",
+            value_parser = parse_index_range,
+            requires = "mnemonic",
+            conflicts_with_all = ["hd_path", "mnemonic_index"]
+        )]
+        mnemonic_range: Option<Range<u32>>,
+        /// A label for the account, stored in the keystore metadata.
+        #[arg(long)]
+        label: Option<String>,
+        /// Restrict the account to signing transactions for the given chain.
+        ///
+        /// Can be used multiple times.
+        #[arg(long = "chain", value_name = "CHAIN")]
+        chains: Vec<Chain>,
+    },
+
+    /// Change the password of a keystore.
+    #[command(name = "change-password")]
+    ChangePassword {
+        /// The name for the account in the keystore.
+        #[arg(value_name = "ACCOUNT_NAME")]
+        account_name: String,
+        /// If not provided, keystore will try to be located at the default keystores directory
+        /// (~/.foundry/keystores)
+        #[arg(long, short)]
+        keystore_dir: Option<String>,
+        /// Current password for the JSON keystore in cleartext
+        /// This is unsafe, we recommend using the default hidden password prompt
+        #[arg(long, env = "CAST_UNSAFE_PASSWORD", value_name = "PASSWORD")]
+        unsafe_password: Option<String>,
+        /// New password for the JSON keystore in cleartext
+        /// This is unsafe, we recommend using the default hidden password prompt
+        #[arg(long, env = "CAST_UNSAFE_NEW_PASSWORD", value_name = "NEW_PASSWORD")]
+        unsafe_new_password: Option<String>,
+    },
+
+    /// Show or update the metadata of a keystore account: its label and the chains it's
+    /// restricted to.
+    #[command(visible_alias = "meta")]
+    Metadata {
+        /// The name for the account in the keystore.
+        #[arg(value_name = "ACCOUNT_NAME")]
+        account_name: String,
+        /// If not provided, keystore will try to be located at the default keystores directory
+        /// (~/.foundry/keystores)
+        #[arg(long, short)]
+        keystore_dir: Option<String>,
+        /// Set the label of the account.
+        #[arg(long)]
+        label: Option<String>,
+        /// Restrict the account to signing transactions for the given chain, replacing the
+        /// previous restriction.
+        ///
+        /// Can be used multiple times.
+        #[arg(long = "chain", value_name = "CHAIN")]
+        chains: Vec<Chain>,
+        /// Remove the metadata of the account.
+        #[arg(long, conflicts_with_all = ["label", "chains"])]
+        clear: bool,
+    },
+
+    /// Export a keystore to a new version 3 keystore file, with custom scrypt parameters.
+    Export {
+        /// The name for the account in the keystore.
+        #[arg(value_name = "ACCOUNT_NAME")]
+        account_name: String,
+        /// The path of the exported keystore.
+        #[arg(value_name = "OUT")]
+        out: PathBuf,
+        /// If not provided, keystore will try to be located at the default keystores directory
+        /// (~/.foundry/keystores)
+        #[arg(long, short)]
+        keystore_dir: Option<String>,
+        /// The base 2 logarithm of the scrypt CPU/memory cost `n`.
+        #[arg(long, default_value_t = ScryptParams::default().log_n, value_name = "LOG_N")]
+        scrypt_log_n: u8,
+        /// The scrypt block size `r`.
+        #[arg(long, default_value_t = ScryptParams::default().r, value_name = "R")]
+        scrypt_r: u32,
+        /// The scrypt parallelization `p`.
+        #[arg(long, default_value_t = ScryptParams::default().p, value_name = "P")]
+        scrypt_p: u32,
+        /// Password for the JSON keystore in cleartext
+        /// This is unsafe, we recommend using the default hidden password prompt
+        #[arg(long, env = "CAST_UNSAFE_PASSWORD", value_name = "PASSWORD")]
+        unsafe_password: Option<String>,
+        /// Password for the exported keystore in cleartext
+        /// This is unsafe, we recommend using the default hidden password prompt
+        #[arg(long, value_name = "EXPORT_PASSWORD")]
+        unsafe_export_password: Option<String>,
     },
 
     /// List all the accounts in the keystore default directory
@@ -350,7 +471,6 @@ pub async fn run(self) -> Result<()> {
                 sh_println!("{}", addr.to_checksum(None))?;
             }
             Self::Sign { message, data, from_file, no_hash, wallet } => {
-                let wallet = wallet.signer().await?;
                 let sig = if data {
                     let typed_data: TypedData = if from_file {
                         // data is a file name, read json from file
@@ -359,32 +479,71 @@ pub async fn run(self) -> Result<()> {
                         // data is a json string
                         serde_json::from_str(&message)?
                     };
-                    wallet.sign_dynamic_typed_data(&typed_data).await?
+                    match typed_data.domain.chain_id {
+                        Some(chain_id) => wallet.ensure_chain_allowed(chain_id.saturating_to())?,
+                        None => wallet.ensure_chain_unrestricted()?,
+                    }
+                    wallet.signer().await?.sign_dynamic_typed_data(&typed_data).await?
                 } else if no_hash {
-                    wallet.sign_hash(&hex::decode(&message)?[..].try_into()?).await?
+                    // A raw hash may be the signing hash of a transaction on any chain.
+                    wallet.ensure_chain_unrestricted()?;
+                    let hash = hex::decode(&message)?[..].try_into()?;
+                    wallet.signer().await?.sign_hash(&hash).await?
                 } else {
-                    wallet.sign_message(&Self::hex_str_to_bytes(&message)?).await?
+                    wallet.signer().await?.sign_message(&Self::hex_str_to_bytes(&message)?).await?
                 };
                 sh_println!("0x{}", hex::encode(sig.as_bytes()))?;
             }
             Self::SignAuth { rpc, nonce, chain, wallet, address } => {
-                let wallet = wallet.signer().await?;
                 let provider = utils::get_provider(&rpc.load_config()?)?;
-                let nonce = if let Some(nonce) = nonce {
-                    nonce
-                } else {
-                    provider.get_transaction_count(wallet.address()).await?
-                };
                 let chain_id = if let Some(chain) = chain {
                     chain.id()
                 } else {
                     provider.get_chain_id().await?
                 };
+                // Authorizations for chain 0 are valid on any chain.
+                if chain_id == 0 {
+                    wallet.ensure_chain_unrestricted()?;
+                } else {
+                    wallet.ensure_chain_allowed(chain_id)?;
+                }
+                let signer = wallet.signer().await?;
+                let nonce = if let Some(nonce) = nonce {
+                    nonce
+                } else {
+                    provider.get_transaction_count(signer.address()).await?
+                };
                 let auth = Authorization { chain_id: U256::from(chain_id), address, nonce };
-                let signature = wallet.sign_hash(&auth.signature_hash()).await?;
+                let signature = signer.sign_hash(&auth.signature_hash()).await?;
                 let auth = auth.into_signed(signature);
                 sh_println!("{}", hex::encode_prefixed(alloy_rlp::encode(&auth)))?;
             }
+            Self::SignTx { path, out, wallet } => {
+                let mut file = TransactionFile::load(&path)?;
+                if file.signed.is_some() {
+                    eyre::bail!("The transaction in {} is already signed", path.display());
+                }
+                sh_println!("{}", file.describe()?)?;
+
+                let signer = wallet.signer().await?;
+                let from = file.transaction.from.unwrap_or_else(|| signer.address());
+                if from != signer.address() {
+                    eyre::bail!(
+                        "The transaction is from {from}, but the wallet signs for {}",
+                        signer.address()
+                    );
+                }
+                if let Some(chain_id) = file.transaction.chain_id {
+                    wallet.ensure_chain_allowed(chain_id)?;
+                }
+
+                let tx = file.transaction.clone().build(&EthereumWallet::new(signer)).await?;
+                file.signed = Some(tx.encoded_2718().into());
+
+                let out = out.unwrap_or(path);
+                file.save(&out)?;
+                sh_println!("\nSigned transaction written to {}", out.display())?;
+            }
             Self::Verify { message, signature, address } => {
                 let recovered_address = Self::recover_address_from_message(&message, &signature)?;
                 if address == recovered_address {
@@ -393,32 +552,41 @@ pub async fn run(self) -> Result<()> {
                     eyre::bail!("Validation failed. Address {address} did not sign this message.");
                 }
             }
-            Self::Import { account_name, keystore_dir, unsafe_password, raw_wallet_options } => {
+            Self::Import {
+                account_name,
+                keystore_dir,
+                unsafe_password,
+                raw_wallet_options,
+                mnemonic_range,
+                label,
+                chains,
+            } => {
                 // Set up keystore directory
-                let dir = if let Some(path) = keystore_dir {
-                    Path::new(&path).to_path_buf()
-                } else {
-                    Config::foundry_keystores_dir().ok_or_else(|| {
-                        eyre::eyre!("Could not find the default keystore directory.")
-                    })?
-                };
-
+                let dir = keystores_dir(keystore_dir)?;
                 fs::create_dir_all(&dir)?;
 
-                // check if account exists already
-                let keystore_path = Path::new(&dir).join(&account_name);
-                if keystore_path.exists() {
-                    eyre::bail!("Keystore file already exists at {}", keystore_path.display());
-                }
-
-                // get wallet
-                let wallet = raw_wallet_options
-                    .signer()?
-                    .and_then(|s| match s {
-                        WalletSigner::Local(s) => Some(s),
-                        _ => None,
-                    })
-                    .ok_or_else(|| {
+                // get wallets
+                let local = |signer: Option<WalletSigner>| match signer {
+                    Some(WalletSigner::Local(s)) => Some(s),
+                    _ => None,
+                };
+                let wallets = if let Some(range) = mnemonic_range {
+                    let mnemonic =
+                        raw_wallet_options.mnemonic.as_deref().expect("required by clap");
+                    range
+                        .map(|index| {
+                            let signer = foundry_wallets::utils::create_mnemonic_signer(
+                                mnemonic,
+                                raw_wallet_options.mnemonic_passphrase.as_deref(),
+                                None,
+                                index,
+                            )?;
+                            let wallet = local(Some(signer)).expect("mnemonic signer is local");
+                            Ok((format!("{account_name}-{index}"), wallet))
+                        })
+                        .collect::<Result<Vec<_>>>()?
+                } else {
+                    let wallet = local(raw_wallet_options.signer()?).ok_or_else(|| {
                         eyre::eyre!(
                             "\
 Did you set a private key or mnemonic?
@@ -427,8 +595,17 @@ pub async fn run(self) -> Result<()> {
 --private-key, --mnemonic-path or --interactive."
                         )
                     })?;
+                    vec![(account_name, wallet)]
+                };
+
+                // check if accounts exist already
+                for (name, _) in &wallets {
+                    let keystore_path = dir.join(name);
+                    if keystore_path.exists() {
+                        eyre::bail!("Keystore file already exists at {}", keystore_path.display());
+                    }
+                }
 
-                let private_key = wallet.credential().to_bytes();
                 let password = if let Some(password) = unsafe_password {
                     password
                 } else {
@@ -436,19 +613,139 @@ pub async fn run(self) -> Result<()> {
                     rpassword::prompt_password("Enter password: ")?
                 };
 
+                let chains = chains.iter().map(|chain| chain.id()).collect();
+                let metadata = KeystoreMetadata { label, chains };
+
                 let mut rng = thread_rng();
-                let (wallet, _) = PrivateKeySigner::encrypt_keystore(
-                    dir,
-                    &mut rng,
-                    private_key,
-                    password,
-                    Some(&account_name),
+                for (name, wallet) in wallets {
+                    let (wallet, _) = PrivateKeySigner::encrypt_keystore(
+                        &dir,
+                        &mut rng,
+                        wallet.credential().to_bytes(),
+                        &password,
+                        Some(&name),
+                    )?;
+                    metadata.save(&dir, &name)?;
+                    let address = wallet.address();
+                    let success_message =
+                        format!("`{name}` keystore was saved successfully. Address: {address:?}");
+                    sh_println!("{}", success_message.green())?;
+                }
+            }
+            Self::ChangePassword {
+                account_name,
+                keystore_dir,
+                unsafe_password,
+                unsafe_new_password,
+            } => {
+                let dir = keystores_dir(keystore_dir)?;
+                let keypath = existing_keystore(&dir, &account_name)?;
+
+                let password = if let Some(password) = unsafe_password {
+                    password
+                } else {
+                    rpassword::prompt_password("Enter current password: ")?
+                };
+                let wallet = PrivateKeySigner::decrypt_keystore(&keypath, password)
+                    .wrap_err_with(|| format!("Failed to decrypt keystore {keypath:?}"))?;
+
+                let new_password = if let Some(password) = unsafe_new_password {
+                    password
+                } else {
+                    let password = rpassword::prompt_password("Enter new password: ")?;
+                    if password != rpassword::prompt_password("Confirm new password: ")? {
+                        eyre::bail!("Passwords do not match");
+                    }
+                    password
+                };
+
+                // Write the new keystore next to the current one before replacing it, so that the
+                // key isn't lost if encryption fails.
+                let tmp_name = format!(".{account_name}.tmp");
+                PrivateKeySigner::encrypt_keystore(
+                    &dir,
+                    &mut thread_rng(),
+                    wallet.credential().to_bytes(),
+                    new_password,
+                    Some(&tmp_name),
+                )?;
+                std::fs::rename(dir.join(&tmp_name), &keypath)
+                    .wrap_err_with(|| format!("Failed to replace keystore {keypath:?}"))?;
+
+                let success_message =
+                    format!("`{account_name}` keystore password was changed successfully.");
+                sh_println!("{}", success_message.green())?;
+            }
+            Self::Metadata { account_name, keystore_dir, label, chains, clear } => {
+                let dir = keystores_dir(keystore_dir)?;
+                existing_keystore(&dir, &account_name)?;
+
+                let mut metadata = KeystoreMetadata::load(&dir, &account_name)?.unwrap_or_default();
+                if clear {
+                    metadata = KeystoreMetadata::default();
+                } else {
+                    if label.is_some() {
+                        metadata.label = label;
+                    }
+                    if !chains.is_empty() {
+                        metadata.chains = chains.iter().map(|chain| chain.id()).collect();
+                    }
+                }
+                metadata.save(&dir, &account_name)?;
+
+                if shell::is_json() {
+                    sh_println!("{}", serde_json::to_string_pretty(&metadata)?)?;
+                } else {
+                    sh_println!("Label:  {}", metadata.label.as_deref().unwrap_or("-"))?;
+                    let chains = if metadata.chains.is_empty() {
+                        "any".to_string()
+                    } else {
+                        metadata.chains.iter().map(|chain| Chain::from(*chain)).join(", ")
+                    };
+                    sh_println!("Chains: {chains}")?;
+                }
+            }
+            Self::Export {
+                account_name,
+                out,
+                keystore_dir,
+                scrypt_log_n,
+                scrypt_r,
+                scrypt_p,
+                unsafe_password,
+                unsafe_export_password,
+            } => {
+                let dir = keystores_dir(keystore_dir)?;
+                let keypath = existing_keystore(&dir, &account_name)?;
+                if out.exists() {
+                    eyre::bail!("File already exists at {}", out.display());
+                }
+
+                let password = if let Some(password) = unsafe_password {
+                    password
+                } else {
+                    rpassword::prompt_password("Enter password: ")?
+                };
+                let wallet = PrivateKeySigner::decrypt_keystore(&keypath, &password)
+                    .wrap_err_with(|| format!("Failed to decrypt keystore {keypath:?}"))?;
+
+                let export_password = if let Some(password) = unsafe_export_password {
+                    password
+                } else {
+                    rpassword::prompt_password("Enter password of the exported keystore: ")?
+                };
+
+                let params = ScryptParams { log_n: scrypt_log_n, r: scrypt_r, p: scrypt_p };
+                let exported = keystore::encrypt_keystore_v3(
+                    &mut thread_rng(),
+                    &wallet,
+                    &export_password,
+                    params,
                 )?;
-                let address = wallet.address();
-                let success_message = format!(
-                    "`{}` keystore was saved successfully. Address: {:?}",
-                    &account_name, address,
-                );
+                fs::write_pretty_json_file(&out, &exported)?;
+
+                let success_message =
+                    format!("`{account_name}` keystore was exported to {}", out.display());
                 sh_println!("{}", success_message.green())?;
             }
             Self::List(cmd) => {
@@ -545,6 +842,36 @@ fn hex_str_to_bytes(s: &str) -> Result<Vec<u8>> {
     }
 }
 
+/// Returns the given keystores directory, or the default one.
+fn keystores_dir(keystore_dir: Option<String>) -> Result<PathBuf> {
+    if let Some(path) = keystore_dir {
+        Ok(PathBuf::from(path))
+    } else {
+        Config::foundry_keystores_dir()
+            .ok_or_else(|| eyre::eyre!("Could not find the default keystore directory."))
+    }
+}
+
+/// Returns the path of the keystore of `account_name`, which must exist.
+fn existing_keystore(dir: &Path, account_name: &str) -> Result<PathBuf> {
+    let keypath = dir.join(account_name);
+    if !keypath.exists() {
+        eyre::bail!("Keystore file does not exist at {}", keypath.display());
+    }
+    Ok(keypath)
+}
+
+/// Parses a `START..END` range of mnemonic indexes.
+fn parse_index_range(s: &str) -> Result<Range<u32>> {
+    let (start, end) =
+        s.split_once("..").ok_or_else(|| eyre::eyre!("expected `START..END`, got `{s}`"))?;
+    let range = start.parse()?..end.parse()?;
+    if range.is_empty() {
+        eyre::bail!("empty range `{s}`");
+    }
+    Ok(range)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -601,6 +928,78 @@ fn can_parse_wallet_sign_data() {
         }
     }
 
+    #[test]
+    fn can_parse_wallet_import_range() {
+        let args = WalletSubcommands::parse_from([
+            "foundry-cli",
+            "import",
+            "deployer",
+            "--mnemonic",
+            "test test test test test test test test test test test junk",
+            "--mnemonic-range",
+            "2..5",
+            "--label",
+            "Deployers",
+            "--chain",
+            "mainnet",
+            "--chain",
+            "10",
+        ]);
+        match args {
+            WalletSubcommands::Import { account_name, mnemonic_range, label, chains, .. } => {
+                assert_eq!(account_name, "deployer");
+                assert_eq!(mnemonic_range, Some(2..5));
+                assert_eq!(label.as_deref(), Some("Deployers"));
+                assert_eq!(chains, vec![Chain::mainnet(), Chain::optimism_mainnet()]);
+            }
+            _ => panic!("expected WalletSubcommands::Import"),
+        }
+
+        assert!(parse_index_range("5..5").is_err());
+        assert!(parse_index_range("5").is_err());
+    }
+
+    #[test]
+    fn can_parse_wallet_export() {
+        let args = WalletSubcommands::parse_from([
+            "foundry-cli",
+            "export",
+            "deployer",
+            "deployer.json",
+            "--scrypt-log-n",
+            "14",
+        ]);
+        match args {
+            WalletSubcommands::Export { out, scrypt_log_n, scrypt_r, .. } => {
+                assert_eq!(out, PathBuf::from("deployer.json"));
+                assert_eq!(scrypt_log_n, 14);
+                assert_eq!(scrypt_r, 8);
+            }
+            _ => panic!("expected WalletSubcommands::Export"),
+        }
+    }
+
+    #[test]
+    fn can_parse_wallet_sign_tx() {
+        let args = WalletSubcommands::parse_from([
+            "foundry-cli",
+            "sign-tx",
+            "tx.json",
+            "--out",
+            "signed.json",
+            "--account",
+            "deployer",
+        ]);
+        match args {
+            WalletSubcommands::SignTx { path, out, wallet } => {
+                assert_eq!(path, PathBuf::from("tx.json"));
+                assert_eq!(out, Some(PathBuf::from("signed.json")));
+                assert_eq!(wallet.keystore_account_name.as_deref(), Some("deployer"));
+            }
+            _ => panic!("expected WalletSubcommands::SignTx"),
+        }
+    }
+
     #[test]
     fn can_parse_wallet_sign_data_file() {
         let args = WalletSubcommands::parse_from([
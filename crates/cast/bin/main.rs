@@ -23,7 +23,7 @@
     shell, stdin,
 };
 use foundry_config::Config;
-use std::time::Instant;
+use std::{path::Path, time::Instant};
 
 pub mod args;
 pub mod cmd;
@@ -31,6 +31,7 @@
 
 use args::{Cast as CastArgs, CastSubcommand, ToBaseArgs};
 use cast::traces::identifier::SignaturesIdentifier;
+use tx::TransactionFile;
 
 #[macro_use]
 extern crate foundry_common;
@@ -474,6 +475,19 @@ async fn main_args(args: CastArgs) -> Result<()> {
         CastSubcommand::PublishTx { raw_tx, cast_async, rpc } => {
             let config = rpc.load_config()?;
             let provider = utils::get_provider(&config)?;
+            let raw_tx = if Path::new(&raw_tx).is_file() {
+                let file = TransactionFile::load(Path::new(&raw_tx))?;
+                let Some(signed) = &file.signed else {
+                    eyre::bail!(
+                        "The transaction in {raw_tx} is not signed, sign it with \
+                         `cast wallet sign-tx`"
+                    );
+                };
+                sh_eprintln!("{}\n", file.describe()?)?;
+                hex::encode_prefixed(signed)
+            } else {
+                raw_tx
+            };
             let cast = Cast::new(&provider);
             let pending_tx = cast.publish(raw_tx).await?;
             let tx_hash = pending_tx.inner().tx_hash();
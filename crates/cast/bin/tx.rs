@@ -1,10 +1,15 @@
-use alloy_consensus::{SidecarBuilder, SimpleCoder};
-use alloy_dyn_abi::ErrorExt;
+use alloy_consensus::{SidecarBuilder, SimpleCoder, TxEnvelope};
+use alloy_dyn_abi::{ErrorExt, JsonAbiExt};
 use alloy_json_abi::Function;
 use alloy_network::{
-    AnyNetwork, TransactionBuilder, TransactionBuilder4844, TransactionBuilder7702,
+    eip2718::Decodable2718, AnyNetwork, TransactionBuilder, TransactionBuilder4844,
+    TransactionBuilder7702,
+};
+use alloy_primitives::{
+    hex,
+    utils::{format_ether, format_units},
+    Address, Bytes, TxKind, U256,
 };
-use alloy_primitives::{hex, Address, Bytes, TxKind, U256};
 use alloy_provider::Provider;
 use alloy_rpc_types::{AccessList, Authorization, TransactionInput, TransactionRequest};
 use alloy_serde::WithOtherFields;
@@ -20,8 +25,9 @@
 use foundry_config::{Chain, Config};
 use foundry_wallets::{WalletOpts, WalletSigner};
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use serde_json::value::RawValue;
-use std::fmt::Write;
+use std::{fmt::Write, path::Path};
 
 /// Different sender kinds used by [`CastTxBuilder`].
 pub enum SenderKind<'a> {
@@ -453,3 +459,156 @@ async fn decode_execution_revert(data: &RawValue) -> Result<Option<String>> {
     }
     Ok(None)
 }
+
+/// A transaction crafted, signed and published by different commands, possibly on different
+/// machines: `cast mktx --unsigned`, `cast wallet sign-tx` and `cast publish`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionFile {
+    /// The signature of the called function, used to decode the calldata.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+    /// The transaction, with all the fields required to sign it.
+    pub transaction: WithOtherFields<TransactionRequest>,
+    /// The EIP-2718 encoded signed transaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signed: Option<Bytes>,
+}
+
+impl TransactionFile {
+    /// Loads a transaction file.
+    pub fn load(path: &Path) -> Result<Self> {
+        Ok(foundry_common::fs::read_json_file(path)?)
+    }
+
+    /// Saves the transaction file to `path`.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        Ok(foundry_common::fs::write_pretty_json_file(path, self)?)
+    }
+
+    /// Decodes the signed transaction, if any, checking that it's signed by the sender of the
+    /// transaction.
+    pub fn signed_transaction(&self) -> Result<Option<TransactionRequest>> {
+        let Some(signed) = &self.signed else { return Ok(None) };
+        let envelope = TxEnvelope::decode_2718(&mut signed.as_ref())?;
+        let signer =
+            envelope.signature().recover_address_from_prehash(&envelope.signature_hash())?;
+        if let Some(from) = self.transaction.from {
+            if from != signer {
+                eyre::bail!("The transaction from {from} is signed by {signer}");
+            }
+        }
+        Ok(Some(TransactionRequest::from_transaction_with_sender(envelope, signer)))
+    }
+
+    /// Returns a human readable description of the transaction, decoded from the signed
+    /// transaction if it's signed.
+    pub fn describe(&self) -> Result<String> {
+        let signed = self.signed_transaction()?;
+        let tx = signed.as_ref().unwrap_or(&self.transaction.inner);
+        let unset = || "-".to_string();
+
+        let mut s = String::new();
+        let chain = tx.chain_id.map(|id| Chain::from(id).to_string());
+        writeln!(s, "chain                    {}", chain.unwrap_or_else(unset))?;
+        writeln!(s, "from                     {}", tx.from.map_or_else(unset, |f| f.to_string()))?;
+        match tx.to {
+            Some(TxKind::Call(to)) => writeln!(s, "to                       {to}")?,
+            _ => writeln!(s, "to                       contract creation")?,
+        }
+        writeln!(s, "value                    {} ETH", format_ether(tx.value.unwrap_or_default()))?;
+        writeln!(s, "nonce                    {}", tx.nonce.map_or_else(unset, |n| n.to_string()))?;
+        writeln!(s, "gas limit                {}", tx.gas.map_or_else(unset, |g| g.to_string()))?;
+        if let Some(gas_price) = tx.gas_price {
+            writeln!(s, "gas price                {} gwei", format_units(gas_price, "gwei")?)?;
+        }
+        if let Some(max_fee) = tx.max_fee_per_gas {
+            writeln!(s, "max fee per gas          {} gwei", format_units(max_fee, "gwei")?)?;
+        }
+        if let Some(priority_fee) = tx.max_priority_fee_per_gas {
+            writeln!(s, "max priority fee per gas {} gwei", format_units(priority_fee, "gwei")?)?;
+        }
+
+        let input = tx.input.input().map(|input| &input[..]).unwrap_or_default();
+        if matches!(tx.to, Some(TxKind::Call(_))) {
+            if !input.is_empty() {
+                writeln!(s, "call                     {}", self.decode_calldata(input))?;
+            }
+        } else {
+            writeln!(s, "init code                {} bytes", input.len())?;
+        }
+        if let Some(authorizations) = &tx.authorization_list {
+            for auth in authorizations {
+                writeln!(s, "authorization            delegate to {}", auth.address)?;
+            }
+        }
+        s.pop();
+        Ok(s)
+    }
+
+    /// Decodes calldata with the signature of the called function, falling back to its hex
+    /// encoding.
+    fn decode_calldata(&self, input: &[u8]) -> String {
+        let decoded = self.function.as_deref().and_then(|function| {
+            let function = Function::parse(function).ok()?;
+            let args = input.strip_prefix(function.selector().as_slice())?;
+            let values = function.abi_decode_input(args, false).ok()?;
+            Some(format!("{}({})", function.name, format_tokens(&values).format(", ")))
+        });
+        decoded.unwrap_or_else(|| hex::encode_prefixed(input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::{SignableTransaction, TxEip1559};
+    use alloy_network::eip2718::Encodable2718;
+    use alloy_primitives::address;
+    use alloy_signer::SignerSync;
+    use alloy_signer_local::PrivateKeySigner;
+    use alloy_sol_types::{sol, SolCall};
+
+    sol! {
+        function transfer(address to, uint256 amount) external returns (bool);
+    }
+
+    #[test]
+    fn can_decode_transaction_file() {
+        let signer = PrivateKeySigner::random();
+        let to = address!("000000000000000000000000000000000000bEEF");
+        let input = transferCall { to, amount: U256::from(100) }.abi_encode();
+        let tx = TxEip1559 {
+            chain_id: 1,
+            nonce: 7,
+            gas_limit: 50_000,
+            max_fee_per_gas: 30_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            to: TxKind::Call(to),
+            input: input.into(),
+            ..Default::default()
+        };
+
+        let mut file = TransactionFile {
+            function: Some("transfer(address,uint256)".to_string()),
+            transaction: WithOtherFields::new(TransactionRequest::from_transaction_with_sender(
+                tx.clone(),
+                signer.address(),
+            )),
+            signed: None,
+        };
+        let description = file.describe().unwrap();
+        assert!(description.contains(&format!("transfer({to}, 100)")), "{description}");
+        assert!(description.contains("30.000000000 gwei"), "{description}");
+
+        let signature = signer.sign_hash_sync(&tx.signature_hash()).unwrap();
+        let signed = TxEnvelope::from(tx.into_signed(signature));
+        file.signed = Some(signed.encoded_2718().into());
+        let decoded = file.signed_transaction().unwrap().unwrap();
+        assert_eq!(decoded.from, Some(signer.address()));
+        assert_eq!(decoded.nonce, Some(7));
+
+        file.transaction.from = Some(to);
+        assert!(file.signed_transaction().is_err());
+    }
+}
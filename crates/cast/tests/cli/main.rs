@@ -2,7 +2,7 @@
 
 use alloy_chains::NamedChain;
 use alloy_network::{TransactionBuilder, TransactionResponse};
-use alloy_primitives::{address, b256, Bytes, B256};
+use alloy_primitives::{address, b256, Address, Bytes, B256, U256};
 use alloy_provider::{Provider, ProviderBuilder};
 use alloy_rpc_types::{BlockNumberOrTag, Index, TransactionRequest};
 use anvil::{EthereumHardfork, NodeConfig};
@@ -519,6 +519,196 @@
     assert_eq!(decrypted_private_key, test_private_key);
 });
 
+// tests that `cast wallet import --mnemonic-range` creates a keystore with metadata per index
+casttest!(wallet_import_mnemonic_range, |prj, cmd| {
+    cmd.set_current_dir(prj.root());
+
+    cmd.cast_fuse()
+        .args([
+            "wallet",
+            "import",
+            "deployer",
+            "--mnemonic",
+            "test test test test test test test test test test test junk",
+            "--mnemonic-range",
+            "0..3",
+            "--label",
+            "Deployers",
+            "--chain",
+            "1",
+            "--chain",
+            "10",
+            "-k",
+            "keystore",
+            "--unsafe-password",
+            "test",
+        ])
+        .assert_success()
+        .stdout_eq(str![[r#"
+`deployer-0` keystore was saved successfully. [ADDRESS]
+`deployer-1` keystore was saved successfully. [ADDRESS]
+`deployer-2` keystore was saved successfully. [ADDRESS]
+
+"#]]);
+
+    cmd.cast_fuse()
+        .args(["wallet", "decrypt-keystore", "deployer-2", "-k", "keystore"])
+        .args(["--unsafe-password", "test"])
+        .assert_success()
+        .stdout_eq(str![[r#"
+deployer-2's private key is: 0x5de4111afa1a4b94908f83103eb1f1706367c2e68ca870fc3fb9a804cdab365a
+
+"#]]);
+
+    cmd.cast_fuse()
+        .args(["wallet", "metadata", "deployer-1", "-k", "keystore"])
+        .assert_success()
+        .stdout_eq(str![[r#"
+Label:  Deployers
+Chains: mainnet, optimism
+
+"#]]);
+});
+
+// tests that `cast wallet change-password` re-encrypts a keystore with the new password
+casttest!(wallet_change_password, |prj, cmd| {
+    cmd.set_current_dir(prj.root());
+
+    cmd.cast_fuse()
+        .args(["wallet", "import", "account", "-k", "keystore", "--unsafe-password", "old"])
+        .args([
+            "--private-key",
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        ])
+        .assert_success();
+
+    cmd.cast_fuse()
+        .args(["wallet", "change-password", "account", "-k", "keystore"])
+        .args(["--unsafe-password", "old", "--unsafe-new-password", "new"])
+        .assert_success()
+        .stdout_eq(str![[r#"
+`account` keystore password was changed successfully.
+
+"#]]);
+
+    cmd.cast_fuse()
+        .args(["wallet", "decrypt-keystore", "account", "-k", "keystore"])
+        .args(["--unsafe-password", "new"])
+        .assert_success()
+        .stdout_eq(str![[r#"
+account's private key is: 0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80
+
+"#]]);
+    cmd.cast_fuse()
+        .args(["wallet", "decrypt-keystore", "account", "-k", "keystore"])
+        .args(["--unsafe-password", "old"])
+        .assert_failure();
+});
+
+// tests that the chains a keystore is restricted to by its metadata are enforced when signing
+casttest!(wallet_metadata_restricts_chains, |prj, cmd| {
+    cmd.set_current_dir(prj.root());
+
+    cmd.cast_fuse()
+        .args(["wallet", "import", "account", "-k", "keystore", "--unsafe-password", "test"])
+        .args([
+            "--private-key",
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        ])
+        .assert_success();
+
+    cmd.cast_fuse()
+        .args(["wallet", "metadata", "account", "-k", "keystore", "--label", "Deployer"])
+        .args(["--chain", "1"])
+        .assert_success()
+        .stdout_eq(str![[r#"
+Label:  Deployer
+Chains: mainnet
+
+"#]]);
+
+    let sign_auth = |chain: &str| {
+        [
+            "wallet",
+            "sign-auth",
+            "--keystore",
+            "keystore/account",
+            "--password",
+            "test",
+            "--nonce",
+            "0",
+            "--chain",
+            chain,
+            "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf",
+        ]
+        .map(str::to_string)
+    };
+    cmd.cast_fuse().args(sign_auth("1")).assert_success();
+    cmd.cast_fuse().args(sign_auth("10")).assert_failure().stderr_eq(str![[r#"
+Error: Keystore account `Deployer` is restricted to chains [1] and can't be used on chain 10
+
+"#]]);
+
+    // Raw hashes may be transactions of any chain.
+    cmd.cast_fuse()
+        .args(["wallet", "sign", "--keystore", "keystore/account", "--password", "test"])
+        .args(["--no-hash", "0xb7a8e2c7bfb1d4b1a4d5a1e2d9f4c7a3b6e1f0d2c5b8a7e4d3c2b1a0f9e8d7c6"])
+        .assert_failure()
+        .stderr_eq(str![[r#"
+Error: Keystore account is restricted to chains [1] and can't sign data which isn't bound to a chain
+
+"#]]);
+
+    cmd.cast_fuse()
+        .args(["wallet", "metadata", "account", "-k", "keystore", "--clear"])
+        .assert_success()
+        .stdout_eq(str![[r#"
+Label:  -
+Chains: any
+
+"#]]);
+    cmd.cast_fuse().args(sign_auth("10")).assert_success();
+});
+
+// tests that `cast wallet export` writes a version 3 keystore with the given scrypt parameters,
+// which can be decrypted with the export password
+casttest!(wallet_export_v3, |prj, cmd| {
+    cmd.set_current_dir(prj.root());
+
+    cmd.cast_fuse()
+        .args(["wallet", "import", "account", "-k", "keystore", "--unsafe-password", "test"])
+        .args([
+            "--private-key",
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        ])
+        .assert_success();
+
+    cmd.cast_fuse()
+        .args(["wallet", "export", "account", "keystore/exported", "-k", "keystore"])
+        .args(["--unsafe-password", "test", "--unsafe-export-password", "exported"])
+        .args(["--scrypt-log-n", "10"])
+        .assert_success()
+        .stdout_eq(str![[r#"
+`account` keystore was exported to keystore/exported
+
+"#]]);
+
+    let exported: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(prj.root().join("keystore/exported")).unwrap())
+            .unwrap();
+    assert_eq!(exported["version"], 3);
+    assert_eq!(exported["crypto"]["kdfparams"]["n"], 1024);
+
+    cmd.cast_fuse()
+        .args(["wallet", "decrypt-keystore", "exported", "-k", "keystore"])
+        .args(["--unsafe-password", "exported"])
+        .assert_success()
+        .stdout_eq(str![[r#"
+exported's private key is: 0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80
+
+"#]]);
+});
+
 // tests that `cast estimate` is working correctly.
 casttest!(estimate_function_gas, |_prj, cmd| {
     let eth_rpc_url = next_http_rpc_endpoint();
@@ -960,6 +1150,42 @@
 "#]]);
 });
 
+// ensure the fees are limited to `--max-fee-cap`
+casttest!(mktx_max_fee_cap, |_prj, cmd| {
+    let raw = cmd
+        .args([
+            "mktx",
+            "--private-key",
+            "0x0000000000000000000000000000000000000000000000000000000000000001",
+            "--chain",
+            "1",
+            "--nonce",
+            "0",
+            "--gas-limit",
+            "21000",
+            "--gas-price",
+            "10gwei",
+            "--priority-gas-price",
+            "1gwei",
+            "--max-fee-cap",
+            "5gwei",
+            "0x0000000000000000000000000000000000000001",
+        ])
+        .assert_success()
+        .get_output()
+        .stdout_lossy();
+
+    let tx = cmd
+        .cast_fuse()
+        .args(["decode-transaction", raw.trim()])
+        .assert_success()
+        .get_output()
+        .stdout_lossy();
+    let tx: serde_json::Value = serde_json::from_str(&tx).unwrap();
+    assert_eq!(tx["maxFeePerGas"], "0x12a05f200");
+    assert_eq!(tx["maxPriorityFeePerGas"], "0x3b9aca00");
+});
+
 // ensure recipient or code is required
 casttest!(mktx_requires_to, |_prj, cmd| {
     cmd.args([
@@ -1019,6 +1245,59 @@
 "#]]);
 });
 
+// tests that a transaction built with `cast mktx --unsigned` can be signed with `cast wallet
+// sign-tx` and published with `cast publish`
+casttest!(mktx_unsigned_sign_and_publish, async |prj, cmd| {
+    let (api, handle) = anvil::spawn(NodeConfig::test()).await;
+    let endpoint = handle.http_endpoint();
+    let keystore_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/keystore");
+    let keystore = keystore_dir
+        .join("UTC--2022-12-20T10-30-43.591916000Z--ec554aeafe75601aaab43bd4621a22284db566c2");
+    let password_file = keystore_dir.join("password-ec554");
+    let sender = address!("ec554aeafe75601aaab43bd4621a22284db566c2");
+    let recipient = address!("000000000000000000000000000000000000dEaD");
+    api.anvil_set_balance(sender, U256::from(10u64.pow(18))).await.unwrap();
+    let tx_path = prj.root().join("tx.json");
+
+    // The sender is read from the keystore, which isn't decrypted.
+    cmd.args(["mktx", "--unsigned", "--out", tx_path.to_str().unwrap()])
+        .args(["--keystore", keystore.to_str().unwrap(), "--rpc-url", &endpoint])
+        .args(["--value", "0.1ether", &recipient.to_string()])
+        .assert_success()
+        .stdout_eq(str![[r#"
+chain                    [..]
+from                     0xeC554aeAFE75601AaAb43Bd4621A22284dB566C2
+to                       0x000000000000000000000000000000000000dEaD
+value                    0.100000000000000000 ETH
+nonce                    0
+...
+Unsigned transaction written to [..]
+
+"#]]);
+    let file: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&tx_path).unwrap()).unwrap();
+    assert_eq!(Address::from_str(file["transaction"]["from"].as_str().unwrap()).unwrap(), sender);
+    assert!(file.get("signed").is_none());
+
+    cmd.cast_fuse()
+        .args(["wallet", "sign-tx", tx_path.to_str().unwrap()])
+        .args(["--keystore", keystore.to_str().unwrap()])
+        .args(["--password-file", password_file.to_str().unwrap()])
+        .assert_success()
+        .stdout_eq(str![[r#"
+...
+Signed transaction written to [..]
+
+"#]]);
+
+    cmd.cast_fuse()
+        .args(["publish", tx_path.to_str().unwrap(), "--rpc-url", &endpoint])
+        .assert_success();
+
+    assert_eq!(api.balance(recipient, None).await.unwrap(), U256::from(10u64.pow(17)));
+    assert_eq!(api.transaction_count(sender, None).await.unwrap(), U256::from(1));
+});
+
 // tests that the raw encoded transaction is returned
 casttest!(tx_raw, |_prj, cmd| {
     let rpc = next_http_rpc_endpoint();
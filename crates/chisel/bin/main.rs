@@ -5,7 +5,7 @@
 
 use chisel::{
     history::chisel_history_file,
-    prelude::{ChiselCommand, ChiselDispatcher, DispatchResult, SolidityHelper},
+    prelude::{ChiselCommand, ChiselDispatcher, DispatchResult, Notebook, SolidityHelper},
 };
 use clap::{Parser, Subcommand};
 use eyre::Context;
@@ -27,7 +27,7 @@
     Config,
 };
 use rustyline::{config::Configurer, error::ReadlineError, Editor};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tracing::debug;
 use yansi::Paint;
 
@@ -100,6 +100,18 @@ pub enum ChiselSubcommand {
         /// The command to be evaluated.
         command: String,
     },
+
+    /// Execute a `.chisel` notebook top-to-bottom, recording the output of each input inline
+    Run {
+        /// Path to the `.chisel` file.
+        path: PathBuf,
+
+        /// Check that the recorded outputs are up to date instead of updating the file.
+        ///
+        /// Exits with a non-zero status code if any output changed.
+        #[arg(long)]
+        check: bool,
+    },
 }
 
 fn main() {
@@ -188,6 +200,9 @@ async fn main_args(args: Chisel) -> eyre::Result<()> {
             dispatch_repl_line(&mut dispatcher, command).await?;
             return Ok(())
         }
+        Some(ChiselSubcommand::Run { path, check }) => {
+            return run_notebook(&mut dispatcher, path, *check).await
+        }
         None => { /* No chisel subcommand present; Continue */ }
     }
 
@@ -282,6 +297,44 @@ async fn dispatch_repl_line(dispatcher: &mut ChiselDispatcher, line: &str) -> ey
     Ok(r.is_error())
 }
 
+/// Executes a `.chisel` notebook and either updates its recorded outputs or, with `check`,
+/// fails if any of them changed.
+async fn run_notebook(
+    dispatcher: &mut ChiselDispatcher,
+    path: &Path,
+    check: bool,
+) -> eyre::Result<()> {
+    let contents = fs::read_to_string(path)?;
+    let notebook = Notebook::parse(&contents);
+
+    // Recorded outputs must not depend on the terminal.
+    yansi::disable();
+    let updated = notebook.run(dispatcher).await;
+
+    if !check {
+        fs::write(path, updated.render())?;
+        sh_println!("Recorded outputs of {}", path.display())?;
+        return Ok(())
+    }
+
+    let mismatches = notebook.mismatches(&updated);
+    if mismatches.is_empty() {
+        sh_println!("Outputs of {} are up to date", path.display())?;
+        return Ok(())
+    }
+
+    for mismatch in &mismatches {
+        sh_println!("Output changed for input:\n{}", mismatch.input)?;
+        for line in &mismatch.expected {
+            sh_println!("-{line}")?;
+        }
+        for line in &mismatch.actual {
+            sh_println!("+{line}")?;
+        }
+    }
+    eyre::bail!("{} output(s) of {} changed", mismatches.len(), path.display())
+}
+
 /// Evaluate multiple Solidity source files contained within a
 /// Chisel prelude directory.
 async fn evaluate_prelude(
@@ -38,6 +38,9 @@ pub enum ChiselCommand {
     Traces,
     /// Set calldata (`msg.data`) for the current session (appended after function selector)
     Calldata,
+    /// Run the current session in the context of a project test contract after its `setUp()`
+    /// Takes: `<test-contract>`
+    Setup,
     /// Dump the raw memory
     MemDump,
     /// Dump the raw stack
@@ -51,6 +54,15 @@ pub enum ChiselCommand {
     Exec,
     /// Display the raw value of a variable's stack allocation.
     RawStack,
+    /// Dump the decoded storage of an address
+    /// Takes: `<address> [contract-name]`
+    Storage,
+    /// Display the balance, nonce and code size of accounts
+    /// Takes: `[address...]`
+    Account,
+    /// Diff the state between two steps of the session
+    /// Takes: `[from-step] [to-step]`
+    StateDiff,
     /// Open the current session in an editor
     Edit,
 }
@@ -72,12 +84,16 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
             "fork" | "f" => Ok(Self::Fork),
             "traces" | "t" => Ok(Self::Traces),
             "calldata" | "cd" => Ok(Self::Calldata),
+            "setup" | "su" => Ok(Self::Setup),
             "memdump" | "md" => Ok(Self::MemDump),
             "stackdump" | "sd" => Ok(Self::StackDump),
             "export" | "ex" => Ok(Self::Export),
             "fetch" | "fe" => Ok(Self::Fetch),
             "exec" | "e" => Ok(Self::Exec),
             "rawstack" | "rs" => Ok(Self::RawStack),
+            "storage" | "sto" => Ok(Self::Storage),
+            "account" | "acc" => Ok(Self::Account),
+            "statediff" | "sdiff" => Ok(Self::StateDiff),
             "edit" => Ok(Self::Edit),
             _ => Err(ChiselDispatcher::make_error(format!(
                 "Unknown command \"{s}\"! See available commands with `!help`.",
@@ -136,11 +152,15 @@ fn from(cmd: ChiselCommand) -> Self {
             ChiselCommand::Fork => (&["fork <url>", "f <url>"], "Fork an RPC for the current session. Supply 0 arguments to return to a local network", CmdCategory::Env),
             ChiselCommand::Traces => (&["traces", "t"], "Enable / disable traces for the current session", CmdCategory::Env),
             ChiselCommand::Calldata => (&["calldata [data]", "cd [data]"], "Set calldata (`msg.data`) for the current session (appended after function selector). Clears it if no argument provided.", CmdCategory::Env),
+            ChiselCommand::Setup => (&["setup <contract>", "su <contract>"], "Compile the project and run the current session inside of a test contract, after its `setUp()` function. Supply 0 arguments to leave the test contract", CmdCategory::Env),
             // Debug
             ChiselCommand::MemDump => (&["memdump", "md"], "Dump the raw memory of the current state", CmdCategory::Debug),
             ChiselCommand::StackDump => (&["stackdump", "sd"], "Dump the raw stack of the current state", CmdCategory::Debug),
             ChiselCommand::Edit => (&["edit"], "Open the current session in an editor", CmdCategory::Session),
             ChiselCommand::RawStack => (&["rawstack <var>", "rs <var>"], "Display the raw value of a variable's stack allocation. For variables that are > 32 bytes in length, this will display their memory pointer.", CmdCategory::Debug),
+            ChiselCommand::Storage => (&["storage <addr> [contract]", "sto <addr> [contract]"], "Dump the storage of an address, decoded with the layout of a session or project contract, or of its verified source on Etherscan when forking", CmdCategory::Debug),
+            ChiselCommand::Account => (&["account [addr...]", "acc [addr...]"], "Display the balance, nonce and code size of the given addresses, or of all accounts touched by the session", CmdCategory::Debug),
+            ChiselCommand::StateDiff => (&["statediff [from] [to]", "sdiff [from] [to]"], "Diff the balances, nonces and storage between two steps of the session. Defaults to the changes made by the last step", CmdCategory::Debug),
         }
     }
 }
@@ -8,12 +8,24 @@
         ChiselCommand, ChiselResult, ChiselSession, CmdCategory, CmdDescriptor,
         SessionSourceConfig, SolidityHelper,
     },
-    session_source::SessionSource,
+    session_source::{SessionSource, TestContract},
+    state::SessionState,
 };
 use alloy_json_abi::{InternalType, JsonAbi};
-use alloy_primitives::{hex, Address};
+use alloy_primitives::{hex, Address, Bytes, Log};
 use forge_fmt::FormatterConfig;
-use foundry_config::{Config, RpcEndpointUrl};
+use foundry_block_explorers::Client;
+use foundry_common::{
+    abi::find_source,
+    compile::{etherscan_project, ProjectCompiler},
+    ContractsByArtifact,
+};
+use foundry_compilers::{
+    artifacts::{output_selection::ContractOutputSelection, StorageLayout},
+    compilers::solc::{Solc, SolcCompiler},
+    Artifact,
+};
+use foundry_config::{Chain, Config, RpcEndpointUrl};
 use foundry_evm::{
     decode::decode_console_logs,
     traces::{
@@ -21,6 +33,7 @@
         identifier::{SignaturesIdentifier, TraceIdentifiers},
         render_trace_arena, CallTraceDecoder, CallTraceDecoderBuilder, TraceKind,
     },
+    utils::StateChangeset,
 };
 use regex::Regex;
 use reqwest::Url;
@@ -63,6 +76,10 @@
 pub struct ChiselDispatcher {
     /// A Chisel Session
     pub session: ChiselSession,
+    /// The state changes of each executed step of the session, in order
+    pub state_history: Vec<StateChangeset>,
+    /// The output of the current dispatch, if it is captured instead of printed
+    captured_output: Option<Vec<String>>,
 }
 
 /// Chisel dispatch result variants
@@ -139,7 +156,11 @@ pub fn format_source(source: &str, config: FormatterConfig) -> eyre::Result<Stri
 impl ChiselDispatcher {
     /// Associated public function to create a new Dispatcher instance
     pub fn new(config: SessionSourceConfig) -> eyre::Result<Self> {
-        ChiselSession::new(config).map(|session| Self { session })
+        ChiselSession::new(config).map(|session| Self {
+            session,
+            state_history: Vec::new(),
+            captured_output: None,
+        })
     }
 
     /// Returns the optional ID of the current session.
@@ -157,6 +178,17 @@ pub fn source_mut(&mut self) -> &mut SessionSource {
         &mut self.session.session_source
     }
 
+    /// Prints the output of a dispatch, or captures it if requested by
+    /// [Self::dispatch_with_output].
+    fn print(&mut self, output: impl Into<String>) {
+        match &mut self.captured_output {
+            Some(captured) => captured.push(output.into()),
+            None => {
+                let _ = sh_println!("{}", output.into());
+            }
+        }
+    }
+
     fn format_source(&self) -> eyre::Result<String> {
         format_source(
             &self.source().to_repl_source(),
@@ -239,6 +271,7 @@ pub async fn dispatch_command(&mut self, cmd: ChiselCommand, args: &[&str]) -> D
                 self.source_mut().drain_run();
                 self.source_mut().drain_global_code();
                 self.source_mut().drain_top_level_code();
+                self.state_history = Vec::new();
                 DispatchResult::CommandSuccess(Some(String::from("Cleared session!")))
             }
             ChiselCommand::Save => {
@@ -277,7 +310,7 @@ pub async fn dispatch_command(&mut self, cmd: ChiselCommand, args: &[&str]) -> D
                     if let Err(e) = self.session.write() {
                         return DispatchResult::FileIoError(e.into())
                     }
-                    let _ = sh_println!("{}", "Saved current session!".green());
+                    self.print("Saved current session!".green().to_string());
                 }
 
                 // Parse the arguments
@@ -296,6 +329,7 @@ pub async fn dispatch_command(&mut self, cmd: ChiselCommand, args: &[&str]) -> D
                     new_session.session_source.build().unwrap();
 
                     self.session = new_session;
+                    self.state_history = Vec::new();
                     DispatchResult::CommandSuccess(Some(format!(
                         "Loaded Chisel session! (ID = {})",
                         self.session.id.as_ref().unwrap()
@@ -338,6 +372,7 @@ pub async fn dispatch_command(&mut self, cmd: ChiselCommand, args: &[&str]) -> D
             ChiselCommand::Fork => {
                 if args.is_empty() || args[0].trim().is_empty() {
                     self.source_mut().config.evm_opts.fork_url = None;
+                    self.reset_state();
                     return DispatchResult::CommandSuccess(Some(
                         "Now using local environment.".to_string(),
                     ))
@@ -383,7 +418,7 @@ pub async fn dispatch_command(&mut self, cmd: ChiselCommand, args: &[&str]) -> D
 
                 // Clear the backend so that it is re-instantiated with the new fork
                 // upon the next execution of the session source.
-                self.source_mut().config.backend = None;
+                self.reset_state();
 
                 DispatchResult::CommandSuccess(Some(success_msg))
             }
@@ -421,30 +456,92 @@ pub async fn dispatch_command(&mut self, cmd: ChiselCommand, args: &[&str]) -> D
                     ))),
                 }
             }
+            ChiselCommand::Setup => {
+                if args.is_empty() || args[0].trim().is_empty() {
+                    self.source_mut().with_test_contract(None);
+                    self.state_history = Vec::new();
+                    return DispatchResult::CommandSuccess(Some(
+                        "Left test contract context.".to_string(),
+                    ))
+                }
+                if args.len() != 1 {
+                    return DispatchResult::CommandFailed(Self::make_error(
+                        "Must supply a test contract name as the argument.",
+                    ))
+                }
+
+                let test_contract = match self.find_test_contract(args[0]) {
+                    Ok(test_contract) => test_contract,
+                    Err(e) => return DispatchResult::CommandFailed(Self::make_error(e)),
+                };
+                let name = test_contract.name.clone();
+                let has_set_up = test_contract.has_set_up;
+
+                // Deploy the test contract and run `setUp()` once with the current session's
+                // code, keeping the previous source around if that fails.
+                let mut new_source = self.source().clone();
+                new_source.with_test_contract(Some(test_contract));
+                let res =
+                    if has_set_up { new_source.set_up().await } else { new_source.execute().await };
+                match res {
+                    Ok((_, mut res)) => {
+                        let failed = !res.success;
+                        if new_source.config.traces || failed {
+                            if let Ok(decoder) =
+                                Self::decode_traces(&new_source.config, &mut res).await
+                            {
+                                match Self::format_traces(&decoder, &mut res).await {
+                                    Ok(traces) => self.print(traces),
+                                    Err(e) => return DispatchResult::CommandFailed(e.to_string()),
+                                }
+                            }
+                        }
+                        if failed {
+                            return DispatchResult::CommandFailed(Self::make_error(format!(
+                                "Failed to run `{name}.setUp()`!"
+                            )))
+                        }
+
+                        *self.source_mut() = new_source;
+                        self.state_history = Vec::new();
+                        DispatchResult::CommandSuccess(Some(format!(
+                            "Now running in the context of `{}`",
+                            name.yellow()
+                        )))
+                    }
+                    Err(e) => DispatchResult::CommandFailed(Self::make_error(e.to_string())),
+                }
+            }
             ChiselCommand::MemDump | ChiselCommand::StackDump => {
                 match self.source_mut().execute().await {
                     Ok((_, res)) => {
                         if let Some((stack, mem, _)) = res.state.as_ref() {
-                            if matches!(cmd, ChiselCommand::MemDump) {
+                            let lines: Vec<String> = if matches!(cmd, ChiselCommand::MemDump) {
                                 // Print memory by word
-                                (0..mem.len()).step_by(32).for_each(|i| {
-                                    let _ = sh_println!(
-                                        "{}: {}",
-                                        format!("[0x{:02x}:0x{:02x}]", i, i + 32).yellow(),
-                                        hex::encode_prefixed(&mem[i..i + 32]).cyan()
-                                    );
-                                });
+                                (0..mem.len())
+                                    .step_by(32)
+                                    .map(|i| {
+                                        format!(
+                                            "{}: {}",
+                                            format!("[0x{:02x}:0x{:02x}]", i, i + 32).yellow(),
+                                            hex::encode_prefixed(&mem[i..i + 32]).cyan()
+                                        )
+                                    })
+                                    .collect()
                             } else {
                                 // Print all stack items
-                                (0..stack.len()).rev().for_each(|i| {
-                                    let _ = sh_println!(
-                                        "{}: {}",
-                                        format!("[{}]", stack.len() - i - 1).yellow(),
-                                        format!("0x{:02x}", stack[i]).cyan()
-                                    );
-                                });
-                            }
-                            DispatchResult::CommandSuccess(None)
+                                (0..stack.len())
+                                    .rev()
+                                    .map(|i| {
+                                        format!(
+                                            "{}: {}",
+                                            format!("[{}]", stack.len() - i - 1).yellow(),
+                                            format!("0x{:02x}", stack[i]).cyan()
+                                        )
+                                    })
+                                    .collect()
+                            };
+                            DispatchResult::CommandSuccess(Some(lines.join("\n")))
                         } else {
                             DispatchResult::CommandFailed(Self::make_error(
                                 "Run function is empty.",
@@ -707,17 +804,14 @@ pub async fn dispatch_command(&mut self, cmd: ChiselCommand, args: &[&str]) -> D
                             if let Ok(decoder) =
                                 Self::decode_traces(&new_session_source.config, &mut res).await
                             {
-                                if let Err(e) = Self::show_traces(&decoder, &mut res).await {
-                                    return DispatchResult::CommandFailed(e.to_string())
-                                };
+                                match Self::format_traces(&decoder, &mut res).await {
+                                    Ok(traces) => self.print(traces),
+                                    Err(e) => return DispatchResult::CommandFailed(e.to_string()),
+                                }
 
                                 // Show console logs, if there are any
-                                let decoded_logs = decode_console_logs(&res.logs);
-                                if !decoded_logs.is_empty() {
-                                    let _ = sh_println!("{}", "Logs:".green());
-                                    for log in decoded_logs {
-                                        let _ = sh_println!("  {log}");
-                                    }
+                                if let Some(logs) = Self::format_logs(&res.logs) {
+                                    self.print(logs);
                                 }
                             }
 
@@ -732,6 +826,7 @@ pub async fn dispatch_command(&mut self, cmd: ChiselCommand, args: &[&str]) -> D
 
                         // the code could be compiled, save it
                         *self.source_mut() = new_session_source;
+                        self.state_history.push(std::mem::take(&mut res.state_changeset));
                         DispatchResult::CommandSuccess(Some(String::from(
                             "Successfully edited `run()` function's body!",
                         )))
@@ -772,9 +867,346 @@ pub async fn dispatch_command(&mut self, cmd: ChiselCommand, args: &[&str]) -> D
                     "Variable must exist within `run()` function.".to_string(),
                 )
             }
+            ChiselCommand::Storage => {
+                if args.is_empty() || args.len() > 2 {
+                    return DispatchResult::CommandFailed(Self::make_error(
+                        "Incorrect number of arguments supplied. Expected: <address> [contract]",
+                    ))
+                }
+                let address = match args[0].parse::<Address>() {
+                    Ok(address) => address,
+                    Err(e) => {
+                        return DispatchResult::CommandFailed(Self::make_error(format!(
+                            "Invalid address: {e}"
+                        )))
+                    }
+                };
+
+                match self.format_storage(address, args.get(1).copied()).await {
+                    Ok(output) => DispatchResult::CommandSuccess(Some(output)),
+                    Err(e) => DispatchResult::CommandFailed(Self::make_error(e)),
+                }
+            }
+            ChiselCommand::Account => {
+                let addresses = match args
+                    .iter()
+                    .map(|arg| arg.parse::<Address>())
+                    .collect::<Result<Vec<_>, _>>()
+                {
+                    Ok(addresses) => addresses,
+                    Err(e) => {
+                        return DispatchResult::CommandFailed(Self::make_error(format!(
+                            "Invalid address: {e}"
+                        )))
+                    }
+                };
+
+                match self.format_accounts(addresses).await {
+                    Ok(output) => DispatchResult::CommandSuccess(Some(output)),
+                    Err(e) => DispatchResult::CommandFailed(Self::make_error(e)),
+                }
+            }
+            ChiselCommand::StateDiff => {
+                let steps = match args
+                    .iter()
+                    .map(|arg| arg.parse::<usize>())
+                    .collect::<Result<Vec<_>, _>>()
+                {
+                    Ok(steps) => steps,
+                    Err(e) => {
+                        return DispatchResult::CommandFailed(Self::make_error(format!(
+                            "Invalid step: {e}"
+                        )))
+                    }
+                };
+
+                // Step `0` is the state before any code was executed.
+                let last = self.state_history.len();
+                let (from, to) = match steps[..] {
+                    [] => (last.saturating_sub(1), last),
+                    [from] => (from, last),
+                    [from, to] => (from, to),
+                    _ => {
+                        return DispatchResult::CommandFailed(Self::make_error(
+                            "Too many arguments supplied! Expected: [from] [to]",
+                        ))
+                    }
+                };
+                if from > last || to > last {
+                    return DispatchResult::CommandFailed(Self::make_error(format!(
+                        "Step out of range! The session has {last} step(s)."
+                    )))
+                }
+
+                match self.format_state_diff(from, to).await {
+                    Ok(output) => DispatchResult::CommandSuccess(Some(output)),
+                    Err(e) => DispatchResult::CommandFailed(Self::make_error(e)),
+                }
+            }
+        }
+    }
+
+    /// Executes the current session and returns the resulting [SessionState].
+    async fn current_state(&mut self) -> eyre::Result<SessionState> {
+        let backend = self.source_mut().backend().await?;
+        let changeset = if self.source().run_code.is_empty() {
+            StateChangeset::default()
+        } else {
+            let (_, res) = self.source_mut().execute().await?;
+            res.state_changeset
+        };
+        Ok(SessionState::new(backend, changeset))
+    }
+
+    /// Clears the state of the session, e.g. after switching to another fork.
+    ///
+    /// The backend is re-instantiated and `setUp()` runs again upon the next execution.
+    fn reset_state(&mut self) {
+        let source = self.source_mut();
+        source.config.backend = None;
+        if let Some(test_contract) = &mut source.test_contract {
+            test_contract.set_up_state = None;
+        }
+        self.state_history = Vec::new();
+    }
+
+    /// Returns the [SessionState] after the given step of the session.
+    async fn state_at(&mut self, step: usize) -> eyre::Result<SessionState> {
+        let changeset = match step {
+            0 => StateChangeset::default(),
+            step => self.state_history[step - 1].clone(),
+        };
+        let backend = self.source_mut().backend().await?;
+        Ok(SessionState::new(backend, changeset))
+    }
+
+    /// Formats the decoded storage of `address`.
+    async fn format_storage(
+        &mut self,
+        address: Address,
+        name: Option<&str>,
+    ) -> eyre::Result<String> {
+        let state = self.current_state().await?;
+        let code = state.code(address)?;
+        if code.is_empty() && name.is_none() {
+            eyre::bail!("{address} has no code. Supply a contract name to decode its storage.");
+        }
+
+        let (contract, layout) = self.find_storage_layout(address, &code, name).await?;
+        let entries = state.decode_storage(address, &layout)?;
+        let mut lines = vec![format!("Storage of {address} ({contract})").cyan().to_string()];
+        if entries.is_empty() {
+            lines.push("Storage layout is empty.".yellow().to_string());
+        }
+        for entry in entries {
+            lines.push(format!(
+                "{} {}: {} {}",
+                format!("[slot {}, offset {}]", entry.slot, entry.offset).yellow(),
+                entry.label,
+                entry.ty.blue(),
+                entry.value.cyan()
+            ));
+        }
+        Ok(lines.join("\n"))
+    }
+
+    /// Finds the storage layout of the contract named `name`, or of the contract whose runtime
+    /// code is `code`.
+    ///
+    /// The contracts of the session are searched first, then the artifacts of the project, and
+    /// finally the verified source of `address` on Etherscan, if the session is forking.
+    async fn find_storage_layout(
+        &mut self,
+        address: Address,
+        code: &Bytes,
+        name: Option<&str>,
+    ) -> eyre::Result<(String, StorageLayout)> {
+        let matches = |contract_name: &str, deployed_code: Option<&Bytes>| match name {
+            Some(name) => contract_name == name,
+            None => deployed_code.is_some_and(|deployed_code| deployed_code == code),
+        };
+
+        let output = self.source_mut().build()?;
+        for (contract_name, contract) in output.compiler_output.contracts_into_iter() {
+            if matches(&contract_name, contract.get_deployed_bytecode_bytes().as_deref()) {
+                return Ok((contract_name, contract.storage_layout))
+            }
+        }
+
+        let mut config = self.source().config.foundry_config.clone();
+        config.extra_output.push(ContractOutputSelection::StorageLayout);
+        let project = config.project()?;
+        if project.paths.has_input_files() {
+            let output = ProjectCompiler::new().quiet(true).compile(&project)?;
+            for (id, artifact) in output.artifact_ids() {
+                if matches(&id.name, artifact.get_deployed_bytecode_bytes().as_deref()) {
+                    if let Some(layout) = artifact.storage_layout.clone() {
+                        return Ok((id.name, layout))
+                    }
+                }
+            }
+        }
+
+        let source_config = &self.source().config;
+        if source_config.evm_opts.fork_url.is_some() {
+            if let Some(chain) = source_config.evm_opts.get_remote_chain_id().await {
+                if let Some(api_key) =
+                    source_config.foundry_config.get_etherscan_api_key(Some(chain))
+                {
+                    return Self::fetch_storage_layout(chain, api_key, address).await
+                }
+            }
+        }
+
+        match name {
+            Some(name) => eyre::bail!("Could not find the storage layout of `{name}`."),
+            None => eyre::bail!(
+                "Could not find a contract matching the code at {address}. Supply a contract name \
+                 or fork a chain with an Etherscan API key to use its verified source."
+            ),
         }
     }
 
+    /// Compiles the verified source of `address` on Etherscan to get its storage layout.
+    async fn fetch_storage_layout(
+        chain: Chain,
+        api_key: String,
+        address: Address,
+    ) -> eyre::Result<(String, StorageLayout)> {
+        let client = Client::new(chain, api_key)?;
+        let source = find_source(client, address).await?;
+        let metadata = source
+            .items
+            .first()
+            .ok_or_else(|| eyre::eyre!("Etherscan returned no source for {address}"))?;
+        if metadata.is_vyper() {
+            eyre::bail!("Contract at {address} is not a Solidity contract");
+        }
+
+        let root = tempfile::tempdir()?;
+        let mut project = etherscan_project(metadata, root.path())?;
+        project.artifacts.additional_values.storage_layout = true;
+        project.update_output_selection(|selection| {
+            selection.0.values_mut().for_each(|contract_selection| {
+                contract_selection
+                    .values_mut()
+                    .for_each(|selection| selection.push("storageLayout".to_string()))
+            });
+        });
+        project.compiler =
+            SolcCompiler::Specific(Solc::find_or_install(&metadata.compiler_version()?)?);
+
+        let output = ProjectCompiler::new().quiet(true).compile(&project)?;
+        let layout = output
+            .artifacts()
+            .find(|(name, _)| name == &metadata.contract_name)
+            .and_then(|(_, artifact)| artifact.storage_layout.clone())
+            .ok_or_else(|| eyre::eyre!("Could not find the storage layout of {address}"))?;
+        Ok((metadata.contract_name.clone(), layout))
+    }
+
+    /// Formats the balance, nonce and code size of `addresses`, or of all accounts touched by the
+    /// session if empty.
+    async fn format_accounts(&mut self, mut addresses: Vec<Address>) -> eyre::Result<String> {
+        let state = self.current_state().await?;
+        if addresses.is_empty() {
+            addresses = state.touched_accounts();
+        }
+
+        let mut lines = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            let info = state.account(address)?;
+            let code = state.code(address)?;
+            lines.push(format!(
+                "{}: balance: {}, nonce: {}, code size: {}",
+                address.to_string().yellow(),
+                info.balance.cyan(),
+                info.nonce.cyan(),
+                code.len().cyan()
+            ));
+        }
+        Ok(lines.join("\n"))
+    }
+
+    /// Formats the state changes between steps `from` and `to` of the session.
+    async fn format_state_diff(&mut self, from: usize, to: usize) -> eyre::Result<String> {
+        let old = self.state_at(from).await?;
+        let new = self.state_at(to).await?;
+        let diffs = old.diff(&new)?;
+
+        let mut lines =
+            vec![format!("State diff between steps {from} and {to}").cyan().to_string()];
+        if diffs.is_empty() {
+            lines.push("No changes.".yellow().to_string());
+        }
+        for diff in diffs {
+            lines.push(diff.address.to_string().yellow().to_string());
+            if let Some((old, new)) = diff.balance {
+                lines.push(format!("  balance: {} → {}", old.red(), new.green()));
+            }
+            if let Some((old, new)) = diff.nonce {
+                lines.push(format!("  nonce: {} → {}", old.red(), new.green()));
+            }
+            if let Some((old, new)) = diff.code_hash {
+                lines.push(format!("  code hash: {} → {}", old.red(), new.green()));
+            }
+            for (slot, old, new) in diff.storage {
+                lines.push(format!(
+                    "  {}: {} → {}",
+                    format!("[0x{slot:x}]").blue(),
+                    format!("0x{old:064x}").red(),
+                    format!("0x{new:064x}").green()
+                ));
+            }
+        }
+        Ok(lines.join("\n"))
+    }
+
+    /// Compiles the current project and looks up the test contract with the given name or
+    /// identifier.
+    fn find_test_contract(&self, id: &str) -> eyre::Result<TestContract> {
+        let config = &self.source().config.foundry_config;
+        let project = config.project()?;
+        if !project.paths.has_input_files() {
+            eyre::bail!("Must be in a foundry project to set up a test contract.");
+        }
+
+        let output = ProjectCompiler::new().quiet(true).compile(&project)?;
+        let contracts = ContractsByArtifact::new(
+            output.artifact_ids().map(|(id, artifact)| (id, artifact.clone().into())),
+        );
+        let Some((artifact_id, contract)) = contracts.find_by_name_or_identifier(id)? else {
+            eyre::bail!("Could not find contract `{id}` in the project.");
+        };
+
+        if contract.abi.constructor.as_ref().is_some_and(|c| !c.inputs.is_empty()) {
+            eyre::bail!("Test contract `{id}` must not take constructor arguments.");
+        }
+        let has_set_up =
+            contract.abi.functions().any(|func| func.name == "setUp" && func.inputs.is_empty());
+
+        let path = artifact_id
+            .source
+            .strip_prefix(project.root())
+            .unwrap_or(&artifact_id.source)
+            .to_path_buf();
+        let name = contract.name.clone();
+        Ok(TestContract { name, path, has_set_up, set_up_state: None })
+    }
+
+    /// Same as [Self::dispatch], but passes everything the input outputs, such as inspected
+    /// values, traces and console logs, to `on_output` instead of printing it.
+    pub async fn dispatch_with_output(
+        &mut self,
+        input: &str,
+        mut on_output: impl FnMut(String),
+    ) -> DispatchResult {
+        self.captured_output = Some(Vec::new());
+        let result = self.dispatch(input).await;
+        self.captured_output.take().into_iter().flatten().for_each(&mut on_output);
+        result
+    }
+
     /// Dispatches an input as a command via [Self::dispatch_command] or as a Solidity snippet.
     pub async fn dispatch(&mut self, mut input: &str) -> DispatchResult {
         // Check if the input is a builtin command.
@@ -832,9 +1264,7 @@ pub async fn dispatch(&mut self, mut input: &str) -> DispatchResult {
         // Should change up how this works.
         match source.inspect(input).await {
             // Continue and print
-            Ok((true, Some(res))) => {
-                let _ = sh_println!("{res}");
-            }
+            Ok((true, Some(res))) => self.print(res),
             Ok((true, None)) => {}
             // Return successfully
             Ok((false, res)) => {
@@ -856,17 +1286,14 @@ pub async fn dispatch(&mut self, mut input: &str) -> DispatchResult {
                     if new_source.config.traces || failed {
                         if let Ok(decoder) = Self::decode_traces(&new_source.config, &mut res).await
                         {
-                            if let Err(e) = Self::show_traces(&decoder, &mut res).await {
-                                return DispatchResult::CommandFailed(e.to_string())
-                            };
+                            match Self::format_traces(&decoder, &mut res).await {
+                                Ok(traces) => self.print(traces),
+                                Err(e) => return DispatchResult::CommandFailed(e.to_string()),
+                            }
 
                             // Show console logs, if there are any
-                            let decoded_logs = decode_console_logs(&res.logs);
-                            if !decoded_logs.is_empty() {
-                                let _ = sh_println!("{}", "Logs:".green());
-                                for log in decoded_logs {
-                                    let _ = sh_println!("  {log}");
-                                }
+                            if let Some(logs) = Self::format_logs(&res.logs) {
+                                self.print(logs);
                             }
 
                             // If the contract execution failed, continue on without adding the new
@@ -881,6 +1308,7 @@ pub async fn dispatch(&mut self, mut input: &str) -> DispatchResult {
 
                     // Replace the old session source with the new version
                     *self.source_mut() = new_source;
+                    self.state_history.push(std::mem::take(&mut res.state_changeset));
 
                     DispatchResult::Success(None)
                 }
@@ -934,7 +1362,7 @@ pub async fn decode_traces(
         Ok(decoder)
     }
 
-    /// Display the gathered traces of a REPL execution.
+    /// Formats the gathered traces of a REPL execution for display.
     ///
     /// ### Takes
     ///
@@ -943,25 +1371,45 @@ pub async fn decode_traces(
     ///
     /// ### Returns
     ///
-    /// Optionally, a unit type signifying a successful result.
-    pub async fn show_traces(
+    /// The rendered traces.
+    pub async fn format_traces(
         decoder: &CallTraceDecoder,
         result: &mut ChiselResult,
-    ) -> eyre::Result<()> {
+    ) -> eyre::Result<String> {
         if result.traces.is_empty() {
             eyre::bail!("Unexpected error: No traces gathered. Please report this as a bug: https://github.com/foundry-rs/foundry/issues/new?assignees=&labels=T-bug&template=BUG-FORM.yml");
         }
 
-        sh_println!("{}", "Traces:".green())?;
+        let mut out = "Traces:".green().to_string();
         for (kind, trace) in &mut result.traces {
             // Display all Setup + Execution traces.
             if matches!(kind, TraceKind::Setup | TraceKind::Execution) {
                 decode_trace_arena(trace, decoder).await?;
-                sh_println!("{}", render_trace_arena(trace))?;
+                out.push('\n');
+                out.push_str(&render_trace_arena(trace));
             }
         }
 
-        Ok(())
+        Ok(out)
+    }
+
+    /// Formats the decoded console logs of a REPL execution for display.
+    ///
+    /// ### Returns
+    ///
+    /// The rendered logs, or `None` if there are no console logs.
+    pub fn format_logs(logs: &[Log]) -> Option<String> {
+        let decoded_logs = decode_console_logs(logs);
+        if decoded_logs.is_empty() {
+            return None
+        }
+
+        let mut out = "Logs:".green().to_string();
+        for log in decoded_logs {
+            out.push_str("\n  ");
+            out.push_str(&log);
+        }
+        Some(out)
     }
 
     /// Format a type that implements [std::fmt::Display] as a chisel error string.
@@ -1004,4 +1452,26 @@ fn test_address_regex() {
         assert!(!ADDRESS_RE.is_match("'    0xe5f3aF50FE5d0bF402a3C6F55ccC47d4307922d4'"));
         assert!(!ADDRESS_RE.is_match("'0xe5f3aF50FE5d0bF402a3C6F55ccC47d4307922d4'"));
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_state_commands() {
+        let mut dispatcher = ChiselDispatcher::new(Default::default()).unwrap();
+        assert!(!dispatcher.dispatch("uint256 a = 1;").await.is_error());
+        assert_eq!(dispatcher.state_history.len(), 1);
+
+        let DispatchResult::CommandSuccess(Some(diff)) = dispatcher.dispatch("!statediff").await
+        else {
+            panic!("expected a state diff")
+        };
+        assert!(diff.contains("State diff between steps 0 and 1"), "{diff}");
+        let DispatchResult::CommandSuccess(Some(accounts)) = dispatcher.dispatch("!account").await
+        else {
+            panic!("expected accounts")
+        };
+        assert!(accounts.contains("balance:"), "{accounts}");
+
+        // Switching forks starts over from a fresh state.
+        assert!(!dispatcher.dispatch("!fork").await.is_error());
+        assert!(dispatcher.state_history.is_empty());
+    }
 }
@@ -12,8 +12,7 @@
 use eyre::{Result, WrapErr};
 use foundry_compilers::Artifact;
 use foundry_evm::{
-    backend::Backend, decode::decode_console_logs, executors::ExecutorBuilder,
-    inspectors::CheatsConfig, traces::TraceMode,
+    backend::Backend, executors::ExecutorBuilder, inspectors::CheatsConfig, traces::TraceMode,
 };
 use solang_parser::pt::{self, CodeLocation};
 use std::str::FromStr;
@@ -33,6 +32,14 @@ impl SessionSource {
     ///
     /// Returns an error if compilation fails.
     pub async fn execute(&mut self) -> Result<(Address, ChiselResult)> {
+        // Run the test contract's `setUp()` first if it hasn't run yet, e.g. in a loaded session.
+        if self.test_contract.as_ref().is_some_and(|c| c.has_set_up && c.set_up_state.is_none()) {
+            let (_, res) = self.set_up().await?;
+            if !res.success {
+                eyre::bail!("Failed to run `setUp()`!")
+            }
+        }
+
         // Recompile the project and ensure no errors occurred.
         let compiled = self.build()?;
         if let Some((_, contract)) =
@@ -146,6 +153,41 @@ pub async fn execute(&mut self) -> Result<(Address, ChiselResult)> {
         }
     }
 
+    /// Deploys the REPL contract and runs its test contract's `setUp()` function.
+    ///
+    /// If it succeeds, the resulting state is kept in the [TestContract] so that later executions
+    /// start from it instead of running `setUp()` again.
+    ///
+    /// ### Returns
+    ///
+    /// A tuple containing the [Address] of the REPL contract `setUp()` ran on as well as the
+    /// [ChiselResult] of the call.
+    ///
+    /// Returns an error if there is no test contract or compilation fails.
+    pub async fn set_up(&mut self) -> Result<(Address, ChiselResult)> {
+        if self.test_contract.is_none() {
+            eyre::bail!("No test contract to set up!")
+        }
+
+        let compiled = self.build()?;
+        let bytecode = compiled
+            .compiler_output
+            .contracts_into_iter()
+            .find(|(name, _)| name == "REPL")
+            .and_then(|(_, contract)| contract.get_bytecode_bytes().map(|b| b.into_owned()))
+            .ok_or_else(|| eyre::eyre!("No bytecode found for `REPL` contract"))?;
+
+        let mut runner = self.prepare_runner(0).await;
+        let (address, res) = runner.set_up(bytecode)?;
+        if res.success {
+            let backend = runner.executor.backend().clone();
+            if let Some(test_contract) = &mut self.test_contract {
+                test_contract.set_up_state = Some((address, backend));
+            }
+        }
+        Ok((address, res))
+    }
+
     /// Inspect a contract element inside of the current session
     ///
     /// ### Takes
@@ -215,19 +257,18 @@ pub async fn inspect(&self, input: &str) -> Result<(bool, Option<String>)> {
         }
 
         let Some((stack, memory, _)) = &res.state else {
-            // Show traces and logs, if there are any, and return an error
+            // Return an error with the traces and logs, if there are any
+            let mut msg = String::from("Failed to inspect expression");
             if let Ok(decoder) = ChiselDispatcher::decode_traces(&source.config, &mut res).await {
-                ChiselDispatcher::show_traces(&decoder, &mut res).await?;
+                msg.push('\n');
+                msg.push_str(&ChiselDispatcher::format_traces(&decoder, &mut res).await?);
             }
-            let decoded_logs = decode_console_logs(&res.logs);
-            if !decoded_logs.is_empty() {
-                sh_println!("{}", "Logs:".green())?;
-                for log in decoded_logs {
-                    sh_println!("  {log}")?;
-                }
+            if let Some(logs) = ChiselDispatcher::format_logs(&res.logs) {
+                msg.push('\n');
+                msg.push_str(&logs);
             }
 
-            return Err(eyre::eyre!("Failed to inspect expression"))
+            return Err(eyre::eyre!(msg))
         };
 
         let generated_output = source
@@ -302,6 +343,27 @@ fn infer_inner_expr_type(&self) -> Option<&pt::Expression> {
         }
     }
 
+    /// Returns the backend of the session, spawning it if it doesn't exist yet.
+    ///
+    /// This is the state that every execution of the REPL contract starts from, including the
+    /// changes of the test contract's `setUp()` if it ran.
+    pub async fn backend(&mut self) -> Result<Backend> {
+        if let Some((_, backend)) =
+            self.test_contract.as_ref().and_then(|c| c.set_up_state.as_ref())
+        {
+            return Ok(backend.clone())
+        }
+        if let Some(backend) = &self.config.backend {
+            return Ok(backend.clone())
+        }
+
+        let env = self.config.evm_opts.evm_env().await?;
+        let fork = self.config.evm_opts.get_fork(&self.config.foundry_config, env);
+        let backend = Backend::spawn(fork);
+        self.config.backend = Some(backend.clone());
+        Ok(backend)
+    }
+
     /// Prepare a runner for the Chisel REPL environment
     ///
     /// ### Takes
@@ -315,8 +377,10 @@ async fn prepare_runner(&mut self, final_pc: usize) -> ChiselRunner {
         let env =
             self.config.evm_opts.evm_env().await.expect("Could not instantiate fork environment");
 
-        // Create an in-memory backend
-        let backend = match self.config.backend.take() {
+        // Create an in-memory backend, starting from the state left by `setUp()` if it ran
+        let set_up_backend =
+            self.test_contract.as_ref().and_then(|c| c.set_up_state.as_ref()).map(|(_, b)| b);
+        let backend = match set_up_backend.cloned().or_else(|| self.config.backend.take()) {
             Some(backend) => backend,
             None => {
                 let fork = self.config.evm_opts.get_fork(&self.config.foundry_config, env.clone());
@@ -346,7 +410,16 @@ async fn prepare_runner(&mut self, final_pc: usize) -> ChiselRunner {
 
         // Create a [ChiselRunner] with a default balance of [U256::MAX] and
         // the sender [Address::zero].
-        ChiselRunner::new(executor, U256::MAX, Address::ZERO, self.config.calldata.clone())
+        let mut runner = ChiselRunner::new(
+            executor,
+            U256::MAX,
+            Address::ZERO,
+            self.entry_point(),
+            self.config.calldata.clone(),
+        );
+        runner.set_up_address =
+            self.test_contract.as_ref().and_then(|c| c.set_up_state.as_ref()).map(|(a, _)| *a);
+        runner
     }
 }
 
@@ -1692,6 +1765,81 @@ fn test_global_vars() {
         generic_type_test(&mut source(), global_variables);
     }
 
+    #[test]
+    fn test_test_contract_source() {
+        let mut source = source();
+        source.with_test_contract(Some(counter_test()));
+
+        let repl_source = source.to_repl_source();
+        assert!(repl_source.contains("import {CounterTest} from \"test/Counter.t.sol\";"));
+        assert!(repl_source.contains("contract REPL is CounterTest {"));
+        assert!(repl_source.contains("function __chisel_run() public {"));
+        assert!(!repl_source.contains("setUp();"));
+        assert!(!repl_source.contains("Vm internal constant vm"));
+
+        source.with_test_contract(None);
+        let repl_source = source.to_repl_source();
+        assert!(!repl_source.contains("CounterTest"));
+        assert!(repl_source.contains("function run() public {"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_test_contract_execution() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir(root.path().join("test")).unwrap();
+        std::fs::write(
+            root.path().join("test/Counter.t.sol"),
+            r#"
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^0.8.0;
+
+contract CounterTest {
+    uint256 public number;
+    uint256 public setUps;
+
+    function setUp() public {
+        number = 41;
+        setUps++;
+    }
+
+    function run() public virtual {
+        number = 0;
+    }
+}
+"#,
+        )
+        .unwrap();
+
+        let mut source = source();
+        source.solc.base_path = Some(root.path().to_path_buf());
+        source.with_test_contract(Some(counter_test()));
+
+        let (address, res) = source.set_up().await.unwrap();
+        assert!(res.success);
+        let (set_up_address, _) =
+            source.test_contract.as_ref().unwrap().set_up_state.clone().unwrap();
+        assert_eq!(set_up_address, address);
+
+        // The session runs on the state `setUp()` left behind, without calling it again.
+        source.with_run_code("number++;");
+        let (address, res) = source.execute().await.unwrap();
+        assert!(res.success);
+        assert_eq!(address, set_up_address);
+
+        source.with_run_code("require(number == 42 && setUps == 1);");
+        let (_, res) = source.execute().await.unwrap();
+        assert!(res.success);
+    }
+
+    fn counter_test() -> crate::session_source::TestContract {
+        crate::session_source::TestContract {
+            name: "CounterTest".to_string(),
+            path: "test/Counter.t.sol".into(),
+            has_set_up: true,
+            set_up_state: None,
+        }
+    }
+
     #[track_caller]
     fn source() -> SessionSource {
         // synchronize solc install
@@ -9,13 +9,16 @@
 pub mod dispatcher;
 pub mod executor;
 pub mod history;
+pub mod notebook;
 pub mod runner;
 pub mod session;
 pub mod session_source;
 pub mod solidity_helper;
+pub mod state;
 
 pub mod prelude {
     pub use crate::{
-        cmd::*, dispatcher::*, runner::*, session::*, session_source::*, solidity_helper::*,
+        cmd::*, dispatcher::*, notebook::*, runner::*, session::*, session_source::*,
+        solidity_helper::*, state::*,
     };
 }
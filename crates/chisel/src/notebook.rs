@@ -0,0 +1,264 @@
+//! Notebook
+//!
+//! This module contains the [Notebook] struct, which executes a `.chisel` script of Solidity
+//! snippets and builtin commands top-to-bottom and records the output of each input inline.
+
+use crate::prelude::{ChiselDispatcher, DispatchResult, SolidityHelper, COMMAND_LEADER};
+
+/// Prefix of the comment lines that hold the recorded output of an input.
+pub const OUTPUT_PREFIX: &str = "//>";
+
+/// A single entry of a [Notebook]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Cell {
+    /// A blank or comment line, which is not executed
+    Text(String),
+    /// A Solidity snippet or builtin command, followed by its recorded output lines
+    Input {
+        /// The input, which may span multiple lines
+        input: String,
+        /// The recorded output lines, without the [OUTPUT_PREFIX]
+        output: Vec<String>,
+    },
+}
+
+/// A replayable `.chisel` script
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Notebook {
+    /// The cells of the notebook, in order
+    pub cells: Vec<Cell>,
+}
+
+/// An input whose output changed when re-running a [Notebook]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutputMismatch {
+    /// The input
+    pub input: String,
+    /// The recorded output lines
+    pub expected: Vec<String>,
+    /// The output lines of the latest run
+    pub actual: Vec<String>,
+}
+
+impl Notebook {
+    /// Parses the contents of a `.chisel` file.
+    ///
+    /// Lines starting with [OUTPUT_PREFIX] are attached to the preceding input. Inputs with
+    /// unclosed braces or parentheses span multiple lines, the same way they do in the REPL.
+    pub fn parse(contents: &str) -> Self {
+        let helper = SolidityHelper::new();
+        let mut cells = Vec::new();
+        let mut pending: Option<String> = None;
+
+        for line in contents.lines() {
+            if let Some(input) = pending.as_mut() {
+                input.push('\n');
+                input.push_str(line);
+                if helper.is_closed(input) {
+                    let input = pending.take().unwrap();
+                    cells.push(Cell::Input { input, output: Vec::new() });
+                }
+                continue
+            }
+
+            let trimmed = line.trim();
+            if let Some(output) = trimmed.strip_prefix(OUTPUT_PREFIX) {
+                let output = output.strip_prefix(' ').unwrap_or(output).to_string();
+                match cells.last_mut() {
+                    Some(Cell::Input { output: outputs, .. }) => outputs.push(output),
+                    // Dangling output without an input; keep it around as text.
+                    _ => cells.push(Cell::Text(line.to_string())),
+                }
+            } else if trimmed.is_empty() || trimmed.starts_with("//") {
+                cells.push(Cell::Text(line.to_string()));
+            } else if trimmed.starts_with(COMMAND_LEADER) || helper.is_closed(line) {
+                cells.push(Cell::Input { input: line.to_string(), output: Vec::new() });
+            } else {
+                pending = Some(line.to_string());
+            }
+        }
+
+        // An unclosed input at the end of the file is executed as is, so that the error is
+        // recorded instead of silently dropping it.
+        if let Some(input) = pending {
+            cells.push(Cell::Input { input, output: Vec::new() });
+        }
+
+        Self { cells }
+    }
+
+    /// Renders the notebook back into the `.chisel` file format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for cell in &self.cells {
+            match cell {
+                Cell::Text(text) => {
+                    out.push_str(text);
+                    out.push('\n');
+                }
+                Cell::Input { input, output } => {
+                    out.push_str(input);
+                    out.push('\n');
+                    for line in output {
+                        out.push_str(OUTPUT_PREFIX);
+                        if !line.is_empty() {
+                            out.push(' ');
+                            out.push_str(line);
+                        }
+                        out.push('\n');
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Executes every input of the notebook in order with the given dispatcher.
+    ///
+    /// Failing inputs don't stop the execution; their error is recorded as output instead.
+    ///
+    /// ### Returns
+    ///
+    /// A new [Notebook] with the same inputs and freshly recorded outputs.
+    pub async fn run(&self, dispatcher: &mut ChiselDispatcher) -> Self {
+        let mut cells = Vec::with_capacity(self.cells.len());
+        for cell in &self.cells {
+            let Cell::Input { input, .. } = cell else {
+                cells.push(cell.clone());
+                continue
+            };
+
+            let mut messages = Vec::new();
+            let result =
+                dispatcher.dispatch_with_output(input, |output| messages.push(output)).await;
+            match result {
+                DispatchResult::Success(msg) | DispatchResult::CommandSuccess(msg) => {
+                    messages.extend(msg)
+                }
+                DispatchResult::UnrecognizedCommand(e) => messages.push(format!("Error: {e}")),
+                DispatchResult::SolangParserFailed(e) => {
+                    messages.push(format!("Error: Compilation error {e:?}"))
+                }
+                DispatchResult::FileIoError(e) => messages.push(format!("Error: File IO - {e}")),
+                DispatchResult::CommandFailed(msg) | DispatchResult::Failure(Some(msg)) => {
+                    messages.push(format!("Error: {msg}"))
+                }
+                DispatchResult::Failure(None) => {
+                    messages.push("Error: Unknown failure".to_string())
+                }
+            }
+
+            let output = messages
+                .iter()
+                .flat_map(|msg| msg.lines())
+                .map(|line| line.trim_end().to_string())
+                .collect();
+            cells.push(Cell::Input { input: input.clone(), output });
+        }
+        Self { cells }
+    }
+
+    /// Returns the inputs whose output differs between `self` and `other`.
+    ///
+    /// Both notebooks are expected to have the same inputs, as is the case for the result of
+    /// [Self::run].
+    pub fn mismatches(&self, other: &Self) -> Vec<OutputMismatch> {
+        self.inputs()
+            .zip(other.inputs())
+            .filter(|((_, expected), (_, actual))| expected != actual)
+            .map(|((input, expected), (_, actual))| OutputMismatch {
+                input: input.clone(),
+                expected: expected.clone(),
+                actual: actual.clone(),
+            })
+            .collect()
+    }
+
+    /// Returns an iterator over all inputs and their recorded output.
+    pub fn inputs(&self) -> impl Iterator<Item = (&String, &Vec<String>)> {
+        self.cells.iter().filter_map(|cell| match cell {
+            Cell::Input { input, output } => Some((input, output)),
+            Cell::Text(_) => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTEBOOK: &str = r#"// Fixed point math
+uint256 a = 1e18
+
+a * 3
+//> Type: uint256
+//> ├ Hex: 0x29a2241af62c0000
+function double(uint256 x) internal pure returns (uint256) {
+    return x * 2;
+}
+!source
+"#;
+
+    #[test]
+    fn test_parse_notebook() {
+        let notebook = Notebook::parse(NOTEBOOK);
+        assert_eq!(
+            notebook.cells,
+            vec![
+                Cell::Text("// Fixed point math".to_string()),
+                Cell::Input { input: "uint256 a = 1e18".to_string(), output: vec![] },
+                Cell::Text(String::new()),
+                Cell::Input {
+                    input: "a * 3".to_string(),
+                    output: vec![
+                        "Type: uint256".to_string(),
+                        "├ Hex: 0x29a2241af62c0000".to_string()
+                    ],
+                },
+                Cell::Input {
+                    input: "function double(uint256 x) internal pure returns (uint256) {\n    return x * 2;\n}"
+                        .to_string(),
+                    output: vec![],
+                },
+                Cell::Input { input: "!source".to_string(), output: vec![] },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_roundtrip() {
+        let notebook = Notebook::parse(NOTEBOOK);
+        assert_eq!(notebook.render(), NOTEBOOK);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_run_records_console_logs() {
+        let mut dispatcher = ChiselDispatcher::new(Default::default()).unwrap();
+        let notebook = Notebook::parse(
+            r#"!traces
+(bool ok, ) = address(0x000000000000000000636F6e736F6c652e6c6f67).staticcall(abi.encodeWithSignature("log(string)", "hello"));
+"#,
+        );
+
+        let updated = notebook.run(&mut dispatcher).await;
+        let (_, output) = updated.inputs().nth(1).unwrap();
+        assert!(output.iter().any(|line| line.contains("Traces:")), "{output:?}");
+        assert!(output.iter().any(|line| line.contains("Logs:")), "{output:?}");
+        assert!(output.iter().any(|line| line.trim() == "hello"), "{output:?}");
+    }
+
+    #[test]
+    fn test_mismatches() {
+        let expected = Notebook::parse(NOTEBOOK);
+        let mut actual = expected.clone();
+        if let Cell::Input { output, .. } = &mut actual.cells[3] {
+            output[1] = "├ Hex: 0x0".to_string();
+        }
+
+        assert!(expected.mismatches(&expected).is_empty());
+        let mismatches = expected.mismatches(&actual);
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].input, "a * 3");
+        assert_eq!(mismatches[0].actual[1], "├ Hex: 0x0");
+    }
+}
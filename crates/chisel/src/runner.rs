@@ -3,16 +3,20 @@
 //! This module contains the `ChiselRunner` struct, which assists with deploying
 //! and calling the REPL contract on a in-memory REVM instance.
 
-use alloy_primitives::{map::AddressHashMap, Address, Bytes, Log, U256};
+use alloy_primitives::{keccak256, map::AddressHashMap, Address, Bytes, Log, Selector, U256};
 use eyre::Result;
 use foundry_evm::{
     executors::{DeployResult, Executor, RawCallResult},
     traces::{TraceKind, Traces},
+    utils::StateChangeset,
+};
+use revm::{
+    interpreter::{return_ok, InstructionResult},
+    DatabaseRef,
 };
-use revm::interpreter::{return_ok, InstructionResult};
 
-/// The function selector of the REPL contract's entrypoint, the `run()` function.
-static RUN_SELECTOR: [u8; 4] = [0xc0, 0x40, 0x62, 0x26];
+/// The function selector of a test contract's `setUp()` function.
+static SET_UP_SELECTOR: [u8; 4] = [0x0a, 0x92, 0x54, 0xe4];
 
 /// The Chisel Runner
 ///
@@ -26,8 +30,12 @@ pub struct ChiselRunner {
     pub initial_balance: U256,
     /// The sender
     pub sender: Address,
-    /// Input calldata appended to `RUN_SELECTOR`
+    /// The function selector of the REPL contract's entry point
+    pub selector: Selector,
+    /// Input calldata appended to `selector`
     pub input: Option<Vec<u8>>,
+    /// The address of the REPL contract that its test contract's `setUp()` ran on, if any
+    pub set_up_address: Option<Address>,
 }
 
 /// Represents the result of a Chisel REPL run
@@ -49,6 +57,8 @@ pub struct ChiselResult {
     pub address: Option<Address>,
     /// EVM State at the final instruction of the `run()` function
     pub state: Option<(Vec<U256>, Vec<u8>, InstructionResult)>,
+    /// Accounts changed by deploying the REPL contract and calling its `run()` function
+    pub state_changeset: StateChangeset,
 }
 
 /// ChiselRunner implementation
@@ -57,7 +67,8 @@ impl ChiselRunner {
     ///
     /// ### Takes
     ///
-    /// An [Executor], the initial balance of the sender, and the sender's [Address].
+    /// An [Executor], the initial balance of the sender, the sender's [Address] and the name of
+    /// the REPL contract's entry point.
     ///
     /// ### Returns
     ///
@@ -66,9 +77,40 @@ pub fn new(
         executor: Executor,
         initial_balance: U256,
         sender: Address,
+        entry_point: &str,
         input: Option<Vec<u8>>,
     ) -> Self {
-        Self { executor, initial_balance, sender, input }
+        let selector = Selector::from_slice(&keccak256(format!("{entry_point}()"))[..4]);
+        Self { executor, initial_balance, sender, selector, input, set_up_address: None }
+    }
+
+    /// Deploys the REPL contract and calls its test contract's `setUp()` function, committing
+    /// the changes so that later runs start from them.
+    ///
+    /// ### Takes
+    ///
+    /// The creation bytecode of the REPL contract
+    ///
+    /// ### Returns
+    ///
+    /// A tuple containing the deployed address of the bytecode as well as a [ChiselResult]
+    /// containing information about the result of the `setUp()` call.
+    pub fn set_up(&mut self, bytecode: Bytes) -> Result<(Address, ChiselResult)> {
+        self.executor.set_balance(self.sender, U256::MAX)?;
+        let DeployResult { address, .. } = self
+            .executor
+            .deploy(self.sender, bytecode, U256::ZERO, None)
+            .map_err(|err| eyre::eyre!("Failed to deploy REPL contract:\n{}", err))?;
+        self.executor.set_balance(self.sender, self.initial_balance)?;
+
+        let res = self.call(
+            self.sender,
+            address,
+            Bytes::from_static(&SET_UP_SELECTOR),
+            U256::ZERO,
+            true,
+        )?;
+        Ok((address, res))
     }
 
     /// Run a contract as a REPL session
@@ -88,24 +130,70 @@ pub fn run(&mut self, bytecode: Bytes) -> Result<(Address, ChiselResult)> {
 
         // Deploy an instance of the REPL contract
         // We don't care about deployment traces / logs here
-        let DeployResult { address, .. } = self
+        let DeployResult { address, raw } = self
             .executor
             .deploy(self.sender, bytecode, U256::ZERO, None)
             .map_err(|err| eyre::eyre!("Failed to deploy REPL contract:\n{}", err))?;
+        let mut state_changeset = raw.state_changeset;
 
         // Reset the sender's balance to the initial balance for calls.
         self.executor.set_balance(self.sender, self.initial_balance)?;
 
-        // Append the input to the `RUN_SELECTOR` to form the calldata
-        let mut calldata = RUN_SELECTOR.to_vec();
+        // Run the new code on the contract that `setUp()` ran on, so it keeps that state.
+        let address = match self.set_up_address {
+            Some(set_up_address) => {
+                self.etch(&state_changeset, address, set_up_address)?;
+                set_up_address
+            }
+            None => address,
+        };
+
+        // Append the input to the entry point's selector to form the calldata
+        let mut calldata = self.selector.to_vec();
         if let Some(mut input) = self.input.clone() {
             calldata.append(&mut input);
         }
 
-        // Call the "run()" function of the REPL contract
-        let call_res = self.call(self.sender, address, Bytes::from(calldata), U256::from(0), true);
+        // Call the entry point of the REPL contract
+        let mut res =
+            self.call(self.sender, address, Bytes::from(calldata), U256::from(0), true)?;
 
-        call_res.map(|res| (address, res))
+        // Merge the changes of the call on top of the deployment's.
+        for (address, account) in std::mem::take(&mut res.state_changeset) {
+            match state_changeset.get_mut(&address) {
+                Some(existing) => {
+                    existing.info = account.info;
+                    existing.status = account.status;
+                    existing.storage.extend(account.storage);
+                }
+                None => {
+                    state_changeset.insert(address, account);
+                }
+            }
+        }
+        res.state_changeset = state_changeset;
+
+        Ok((address, res))
+    }
+
+    /// Replaces the code of `to` with the freshly deployed code of `from`.
+    ///
+    /// Storage that the deployment of `from` initialized is copied over unless `to` already has a
+    /// value for it, so that state variables declared after `setUp()` ran are initialized without
+    /// overwriting its changes.
+    fn etch(&mut self, deployment: &StateChangeset, from: Address, to: Address) -> Result<()> {
+        let Some(deployed) = deployment.get(&from) else { return Ok(()) };
+        let backend = self.executor.backend_mut();
+        let mut info = backend.basic_ref(to)?.unwrap_or_default();
+        info.code_hash = deployed.info.code_hash;
+        info.code = deployed.info.code.clone();
+        backend.insert_account_info(to, info);
+        for (slot, value) in &deployed.storage {
+            if backend.storage_ref(to, *slot)?.is_zero() {
+                backend.insert_account_storage(to, *slot, value.present_value)?;
+            }
+        }
+        Ok(())
     }
 
     /// Executes the call.
@@ -192,7 +280,16 @@ fn call(
             res = self.executor.transact_raw(from, to, calldata, value)?;
         }
 
-        let RawCallResult { result, reverted, logs, traces, labels, chisel_state, .. } = res;
+        let RawCallResult {
+            result,
+            reverted,
+            logs,
+            traces,
+            labels,
+            chisel_state,
+            state_changeset,
+            ..
+        } = res;
 
         Ok(ChiselResult {
             returned: result,
@@ -209,6 +306,7 @@ fn call(
             labeled_addresses: labels,
             address: None,
             state: chisel_state,
+            state_changeset,
         })
     }
 }
@@ -4,11 +4,14 @@
 //! the REPL contract's source code. It provides simple compilation, parsing, and
 //! execution helpers.
 
-use alloy_primitives::map::HashMap;
+use alloy_primitives::{map::HashMap, Address};
 use eyre::Result;
 use forge_fmt::solang_ext::SafeUnwrap;
 use foundry_compilers::{
-    artifacts::{CompilerOutput, Settings, SolcInput, Source, Sources},
+    artifacts::{
+        output_selection::ContractOutputSelection, CompilerOutput, Settings, SolcInput, Source,
+        Sources,
+    },
     compilers::solc::Solc,
 };
 use foundry_config::{Config, SolcReq};
@@ -26,6 +29,13 @@
 /// Solidity source for the `Vm` interface in [forge-std](https://github.com/foundry-rs/forge-std)
 static VM_SOURCE: &str = include_str!("../../../testdata/cheats/Vm.sol");
 
+/// The name of the REPL contract's entry point.
+pub const REPL_ENTRY_POINT: &str = "run";
+
+/// The name of the REPL contract's entry point when it inherits from a test contract, namespaced
+/// so that it doesn't clash with the test contract's own `run()` function.
+pub const TEST_REPL_ENTRY_POINT: &str = "__chisel_run";
+
 /// Intermediate output for the compiled [SessionSource]
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct IntermediateOutput {
@@ -67,6 +77,22 @@ pub struct GeneratedOutput {
     pub compiler_output: CompilerOutput,
 }
 
+/// A project test contract that the REPL contract inherits from, set with `!setup`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TestContract {
+    /// The name of the test contract
+    pub name: String,
+    /// The path of the test contract's source file, relative to the project root
+    pub path: PathBuf,
+    /// Whether the test contract defines a `setUp()` function
+    pub has_set_up: bool,
+    /// The address of the REPL contract that `setUp()` ran on and the state it left behind.
+    ///
+    /// Executions start from this state instead of running `setUp()` again.
+    #[serde(skip)]
+    pub set_up_state: Option<(Address, Backend)>,
+}
+
 /// Configuration for the [SessionSource]
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct SessionSourceConfig {
@@ -152,6 +178,9 @@ pub struct SessionSource {
     pub run_code: String,
     /// The generated output
     pub generated_output: Option<GeneratedOutput>,
+    /// The test contract whose `setUp()` state the REPL runs in, if any
+    #[serde(default)]
+    pub test_contract: Option<TestContract>,
     /// Session Source configuration
     pub config: SessionSourceConfig,
 }
@@ -187,6 +216,7 @@ pub fn new(solc: Solc, mut config: SessionSourceConfig) -> Self {
             top_level_code: Default::default(),
             run_code: Default::default(),
             generated_output: None,
+            test_contract: None,
         }
     }
 
@@ -205,6 +235,7 @@ pub fn shallow_clone(&self) -> Self {
             top_level_code: self.top_level_code.clone(),
             run_code: self.run_code.clone(),
             generated_output: None,
+            test_contract: self.test_contract.clone(),
             config: self.config.clone(),
         }
     }
@@ -294,6 +325,13 @@ pub fn drain_run(&mut self) -> &mut Self {
         self
     }
 
+    /// Sets the test contract that the REPL contract inherits from, or clears it if `None`.
+    pub fn with_test_contract(&mut self, test_contract: Option<TestContract>) -> &mut Self {
+        self.test_contract = test_contract;
+        self.generated_output = None;
+        self
+    }
+
     /// Generates and [`SolcInput`] from the source.
     ///
     /// ### Returns
@@ -319,7 +357,8 @@ pub fn compiler_input(&self) -> SolcInput {
                 .evm_version
                 .normalize_version_solc(&self.solc.version),
             ..Default::default()
-        };
+        }
+        .with_extra_output([ContractOutputSelection::StorageLayout]);
 
         // we only care about the solidity source, so we can safely unwrap
         SolcInput::resolve_and_build(sources, settings)
@@ -459,6 +498,15 @@ pub fn to_script_source(&self) -> String {
         )
     }
 
+    /// Returns the name of the REPL contract's entry point.
+    pub fn entry_point(&self) -> &'static str {
+        if self.test_contract.is_some() {
+            TEST_REPL_ENTRY_POINT
+        } else {
+            REPL_ENTRY_POINT
+        }
+    }
+
     /// Convert the [SessionSource] to a valid REPL contract
     ///
     /// ### Returns
@@ -468,7 +516,16 @@ pub fn to_repl_source(&self) -> String {
         let Version { major, minor, patch, .. } = self.solc.version;
         let Self { contract_name, global_code, top_level_code, run_code, config, .. } = self;
         let (mut vm_import, mut vm_constant) = (String::new(), String::new());
-        if !config.no_vm {
+        let (mut test_import, mut inheritance) = (String::new(), String::new());
+        if let Some(test_contract) = &self.test_contract {
+            // Test contracts already expose `vm` through forge-std, so don't redeclare it.
+            test_import = format!(
+                "import {{{}}} from \"{}\";\n",
+                test_contract.name,
+                test_contract.path.display()
+            );
+            inheritance = format!(" is {}", test_contract.name);
+        } else if !config.no_vm {
             // Check if there's any `forge-std` remapping and determine proper path to it by
             // searching remapping path.
             if let Some(remapping) = config
@@ -487,6 +544,7 @@ pub fn to_repl_source(&self) -> String {
                 }
             }
         }
+        let entry_point = self.entry_point();
 
         format!(
             r#"
@@ -494,14 +552,15 @@ pub fn to_repl_source(&self) -> String {
 pragma solidity ^{major}.{minor}.{patch};
 
 {vm_import}
+{test_import}
 {global_code}
 
-contract {contract_name} {{
+contract {contract_name}{inheritance} {{
     {vm_constant}
     {top_level_code}
   
     /// @notice REPL contract entry point
-    function run() public {{
+    function {entry_point}() public {{
         {run_code}
     }}
 }}"#,
@@ -618,12 +677,14 @@ impl IntermediateOutput {
     ///
     /// Optionally, the last statement within the "run" function of the REPL contract.
     pub fn run_func_body(&self) -> Result<&Vec<pt::Statement>> {
-        match self
+        let repl = self
             .intermediate_contracts
             .get("REPL")
-            .ok_or_else(|| eyre::eyre!("Could not find REPL intermediate contract!"))?
+            .ok_or_else(|| eyre::eyre!("Could not find REPL intermediate contract!"))?;
+        match repl
             .function_definitions
-            .get("run")
+            .get(TEST_REPL_ENTRY_POINT)
+            .or_else(|| repl.function_definitions.get(REPL_ENTRY_POINT))
             .ok_or_else(|| eyre::eyre!("Could not find run function definition in REPL contract!"))?
             .body
             .as_ref()
@@ -131,6 +131,11 @@ fn with_contiguous_styles(&self, input: &str, mut f: impl FnMut(Style, Range<usi
         });
     }
 
+    /// Returns whether a source snippet is closed (i.e., all braces and parenthesis are matched).
+    pub fn is_closed(&self, input: &str) -> bool {
+        matches!(self.validate_closed(input), ValidationResult::Valid(_))
+    }
+
     /// Validate that a source snippet is closed (i.e., all braces and parenthesis are matched).
     fn validate_closed(&self, input: &str) -> ValidationResult {
         let mut depth = [0usize; 3];
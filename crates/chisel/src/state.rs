@@ -0,0 +1,256 @@
+//! State
+//!
+//! This module contains the [SessionState] struct, which reads, decodes and diffs the EVM state
+//! left behind by an execution of the REPL contract.
+
+use alloy_primitives::{Address, Bytes, B256, I256, U256};
+use eyre::Result;
+use foundry_compilers::artifacts::StorageLayout;
+use foundry_evm::{
+    backend::Backend,
+    revm::{
+        primitives::{AccountInfo, KECCAK_EMPTY},
+        DatabaseRef,
+    },
+    utils::StateChangeset,
+};
+use std::collections::BTreeSet;
+
+/// The EVM state after an execution of the REPL contract.
+///
+/// Accounts touched by the execution are read from its changeset, everything else is read from
+/// the session's backend, which is either the local in-memory database or a fork.
+#[derive(Clone, Debug)]
+pub struct SessionState {
+    /// The backend the execution started from
+    pub backend: Backend,
+    /// The accounts changed by the execution
+    pub changeset: StateChangeset,
+}
+
+/// The changes made to a single account between two [SessionState]s
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountDiff {
+    /// The account's address
+    pub address: Address,
+    /// The old and new balance, if it changed
+    pub balance: Option<(U256, U256)>,
+    /// The old and new nonce, if it changed
+    pub nonce: Option<(u64, u64)>,
+    /// The old and new code hash, if the code changed
+    pub code_hash: Option<(B256, B256)>,
+    /// The changed storage slots with their old and new value, sorted by slot
+    pub storage: Vec<(U256, U256, U256)>,
+}
+
+/// A decoded entry of a contract's storage layout
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StorageEntry {
+    /// The name of the variable
+    pub label: String,
+    /// The type of the variable
+    pub ty: String,
+    /// The slot of the variable
+    pub slot: U256,
+    /// The byte offset of the variable within its slot
+    pub offset: i64,
+    /// The decoded value of the variable
+    pub value: String,
+}
+
+impl SessionState {
+    /// Create a new [SessionState]
+    pub fn new(backend: Backend, changeset: StateChangeset) -> Self {
+        Self { backend, changeset }
+    }
+
+    /// Returns the account info of `address`, or an empty account if it doesn't exist.
+    pub fn account(&self, address: Address) -> Result<AccountInfo> {
+        if let Some(account) = self.changeset.get(&address) {
+            return Ok(account.info.clone())
+        }
+        Ok(self.backend.basic_ref(address)?.unwrap_or_default())
+    }
+
+    /// Returns the runtime code of `address`.
+    pub fn code(&self, address: Address) -> Result<Bytes> {
+        let info = self.account(address)?;
+        if let Some(code) = info.code {
+            return Ok(code.original_bytes())
+        }
+        if info.code_hash == KECCAK_EMPTY {
+            return Ok(Bytes::new())
+        }
+        Ok(self.backend.code_by_hash_ref(info.code_hash)?.original_bytes())
+    }
+
+    /// Returns the value of storage `slot` of `address`.
+    pub fn storage(&self, address: Address, slot: U256) -> Result<U256> {
+        if let Some(value) =
+            self.changeset.get(&address).and_then(|account| account.storage.get(&slot))
+        {
+            return Ok(value.present_value)
+        }
+        Ok(self.backend.storage_ref(address, slot)?)
+    }
+
+    /// Returns the accounts touched by the execution, sorted by address.
+    pub fn touched_accounts(&self) -> Vec<Address> {
+        let mut accounts = self.changeset.keys().copied().collect::<Vec<_>>();
+        accounts.sort();
+        accounts
+    }
+
+    /// Diffs the state of all accounts touched by either `self` or `newer`.
+    ///
+    /// ### Returns
+    ///
+    /// The accounts that changed, sorted by address.
+    pub fn diff(&self, newer: &Self) -> Result<Vec<AccountDiff>> {
+        let addresses =
+            self.changeset.keys().chain(newer.changeset.keys()).copied().collect::<BTreeSet<_>>();
+
+        let mut diffs = Vec::new();
+        for address in addresses {
+            let (old, new) = (self.account(address)?, newer.account(address)?);
+            let mut diff = AccountDiff { address, ..Default::default() };
+            if old.balance != new.balance {
+                diff.balance = Some((old.balance, new.balance));
+            }
+            if old.nonce != new.nonce {
+                diff.nonce = Some((old.nonce, new.nonce));
+            }
+            if old.code_hash != new.code_hash {
+                diff.code_hash = Some((old.code_hash, new.code_hash));
+            }
+
+            let slots = [self, newer]
+                .iter()
+                .filter_map(|state| state.changeset.get(&address))
+                .flat_map(|account| account.storage.keys().copied())
+                .collect::<BTreeSet<_>>();
+            for slot in slots {
+                let (old, new) = (self.storage(address, slot)?, newer.storage(address, slot)?);
+                if old != new {
+                    diff.storage.push((slot, old, new));
+                }
+            }
+
+            if diff != (AccountDiff { address, ..Default::default() }) {
+                diffs.push(diff);
+            }
+        }
+        Ok(diffs)
+    }
+
+    /// Decodes the storage of `address` with the given [StorageLayout].
+    pub fn decode_storage(
+        &self,
+        address: Address,
+        layout: &StorageLayout,
+    ) -> Result<Vec<StorageEntry>> {
+        layout
+            .storage
+            .iter()
+            .map(|storage| {
+                let slot: U256 = storage.slot.parse()?;
+                let (ty, number_of_bytes) = layout
+                    .types
+                    .get(&storage.storage_type)
+                    .map(|ty| (ty.label.clone(), ty.number_of_bytes.parse::<usize>().unwrap_or(32)))
+                    .unwrap_or_else(|| ("?".to_string(), 32));
+                let word = self.storage(address, slot)?;
+                let value =
+                    format_storage_value(&ty, word, storage.offset as usize, number_of_bytes);
+                Ok(StorageEntry {
+                    label: storage.label.clone(),
+                    ty,
+                    slot,
+                    offset: storage.offset,
+                    value,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Formats the value of a variable of type `ty`, which is stored in `word` at the byte `offset`
+/// and is `number_of_bytes` long.
+///
+/// Value types are decoded, short strings and bytes are read in place, and the raw slot is
+/// returned for everything else (mappings, dynamic arrays, long strings and structs).
+pub fn format_storage_value(ty: &str, word: U256, offset: usize, number_of_bytes: usize) -> String {
+    let word_bytes = B256::from(word);
+    if number_of_bytes == 0 || offset + number_of_bytes > 32 {
+        return word_bytes.to_string()
+    }
+
+    // Values are packed from the right (lower-order bytes) of the slot.
+    let end = 32 - offset;
+    let bytes = &word_bytes[end - number_of_bytes..end];
+    let value = U256::from_be_slice(bytes);
+
+    if ty == "bool" {
+        (!value.is_zero()).to_string()
+    } else if ty == "address" || ty.starts_with("address ") || ty.starts_with("contract ") {
+        Address::from_word(B256::from(value)).to_string()
+    } else if ty.starts_with("uint") || ty.starts_with("enum ") {
+        value.to_string()
+    } else if ty.starts_with("int") {
+        // Sign-extend the packed value to 256 bits.
+        let bits = number_of_bytes * 8;
+        let value =
+            if bits < 256 && value.bit(bits - 1) { value | (U256::MAX << bits) } else { value };
+        I256::from_raw(value).to_string()
+    } else if ty.starts_with("bytes") && ty != "bytes" {
+        alloy_primitives::hex::encode_prefixed(bytes)
+    } else if (ty == "string" || ty == "bytes") && !word.bit(0) {
+        // Short strings and bytes are stored in place, with `length * 2` in the lowest byte.
+        let len = (word_bytes[31] / 2) as usize;
+        let data = &word_bytes[..len.min(31)];
+        if ty == "string" {
+            format!("{:?}", String::from_utf8_lossy(data))
+        } else {
+            alloy_primitives::hex::encode_prefixed(data)
+        }
+    } else {
+        word_bytes.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_storage_value() {
+        // uint128 packed at offset 16
+        let word = (U256::from(7) << 128) | U256::from(1);
+        assert_eq!(format_storage_value("uint128", word, 16, 16), "7");
+        assert_eq!(format_storage_value("uint128", word, 0, 16), "1");
+
+        // int8 -1 at offset 0
+        assert_eq!(format_storage_value("int8", U256::from(0xff), 0, 1), "-1");
+
+        // bool at offset 1
+        assert_eq!(format_storage_value("bool", U256::from(0x0100), 1, 1), "true");
+
+        // address
+        let address = Address::repeat_byte(0x11);
+        let word = U256::from_be_slice(address.as_slice());
+        assert_eq!(format_storage_value("address", word, 0, 20), address.to_string());
+
+        // short string "hi"
+        let mut bytes = [0u8; 32];
+        bytes[0] = b'h';
+        bytes[1] = b'i';
+        bytes[31] = 4;
+        assert_eq!(format_storage_value("string", U256::from_be_bytes(bytes), 0, 32), "\"hi\"");
+
+        // mappings are not decoded
+        assert_eq!(
+            format_storage_value("mapping(address => uint256)", U256::ZERO, 0, 32),
+            B256::ZERO.to_string()
+        );
+    }
+}
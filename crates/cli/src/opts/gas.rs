@@ -0,0 +1,446 @@
+use crate::utils::parse_ether_value;
+use alloy_eips::BlockNumberOrTag;
+use alloy_network::{AnyNetwork, TransactionBuilder};
+use alloy_primitives::{TxHash, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::TransactionRequest;
+use alloy_serde::WithOtherFields;
+use async_trait::async_trait;
+use clap::{Parser, ValueEnum};
+use eyre::{Context, Result};
+use foundry_common::provider::RetryProvider;
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+
+/// Interval at which pending transactions are polled when waiting to replace them.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The fees of a transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fees {
+    Legacy { gas_price: u128 },
+    Eip1559 { max_fee_per_gas: u128, max_priority_fee_per_gas: u128 },
+}
+
+impl Fees {
+    /// Returns the fees set on a transaction, if any.
+    pub fn from_tx(tx: &WithOtherFields<TransactionRequest>) -> Option<Self> {
+        if let (Some(max_fee_per_gas), Some(max_priority_fee_per_gas)) =
+            (tx.max_fee_per_gas, tx.max_priority_fee_per_gas)
+        {
+            Some(Self::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas })
+        } else {
+            tx.gas_price.map(|gas_price| Self::Legacy { gas_price })
+        }
+    }
+
+    /// Sets the fees on a transaction.
+    pub fn apply(&self, tx: &mut WithOtherFields<TransactionRequest>) {
+        match *self {
+            Self::Legacy { gas_price } => tx.set_gas_price(gas_price),
+            Self::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas } => {
+                tx.set_max_priority_fee_per_gas(max_priority_fee_per_gas);
+                tx.set_max_fee_per_gas(max_fee_per_gas);
+            }
+        }
+    }
+
+    /// Returns the fees increased by `percent`, and at least by 1 wei.
+    pub fn bumped(&self, percent: u64) -> Self {
+        let bump = |fee: u128| (fee + fee * percent as u128 / 100).max(fee + 1);
+        match *self {
+            Self::Legacy { gas_price } => Self::Legacy { gas_price: bump(gas_price) },
+            Self::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas } => Self::Eip1559 {
+                max_fee_per_gas: bump(max_fee_per_gas),
+                max_priority_fee_per_gas: bump(max_priority_fee_per_gas),
+            },
+        }
+    }
+
+    /// Returns the fees with the gas price or max fee per gas limited to `cap`.
+    pub fn capped(&self, cap: u128) -> Self {
+        match *self {
+            Self::Legacy { gas_price } => Self::Legacy { gas_price: gas_price.min(cap) },
+            Self::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas } => {
+                let max_fee_per_gas = max_fee_per_gas.min(cap);
+                Self::Eip1559 {
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas: max_priority_fee_per_gas.min(max_fee_per_gas),
+                }
+            }
+        }
+    }
+}
+
+/// A strategy to price transactions.
+#[async_trait]
+pub trait GasStrategy: Send + Sync {
+    /// Estimates the fees of a transaction sent now.
+    async fn estimate(&self, provider: &RetryProvider, legacy: bool) -> Result<Fees>;
+}
+
+/// Uses the gas price or the EIP-1559 fees estimated by the node.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ProviderGasStrategy;
+
+#[async_trait]
+impl GasStrategy for ProviderGasStrategy {
+    async fn estimate(&self, provider: &RetryProvider, legacy: bool) -> Result<Fees> {
+        if legacy {
+            let gas_price = provider.get_gas_price().await?;
+            return Ok(Fees::Legacy { gas_price });
+        }
+        let fees = provider.estimate_eip1559_fees(None).await.wrap_err("Failed to estimate EIP1559 fees. This chain might not support EIP1559, try adding --legacy to your command.")?;
+        Ok(Fees::Eip1559 {
+            max_fee_per_gas: fees.max_fee_per_gas,
+            max_priority_fee_per_gas: fees.max_priority_fee_per_gas,
+        })
+    }
+}
+
+/// Uses a percentile of the priority fees paid in the recent blocks, from `eth_feeHistory`.
+///
+/// The max fee per gas leaves room for the base fee to grow to `base_fee_multiplier` times the
+/// base fee of the next block. Legacy transactions use the node's gas price.
+#[derive(Clone, Copy, Debug)]
+pub struct PercentileGasStrategy {
+    /// The percentile of the priority fees paid in each block, between 0 and 100.
+    pub percentile: f64,
+    /// The number of blocks to get the priority fees of.
+    pub blocks: u64,
+    /// The factor of the next base fee the max fee per gas allows for.
+    pub base_fee_multiplier: u128,
+}
+
+impl Default for PercentileGasStrategy {
+    fn default() -> Self {
+        Self { percentile: 50.0, blocks: 10, base_fee_multiplier: 2 }
+    }
+}
+
+#[async_trait]
+impl GasStrategy for PercentileGasStrategy {
+    async fn estimate(&self, provider: &RetryProvider, legacy: bool) -> Result<Fees> {
+        if legacy {
+            let gas_price = provider.get_gas_price().await?;
+            return Ok(Fees::Legacy { gas_price });
+        }
+
+        let history = provider
+            .get_fee_history(self.blocks, BlockNumberOrTag::Latest, &[self.percentile])
+            .await
+            .wrap_err("Failed to get the fee history")?;
+        let base_fee = history
+            .next_block_base_fee()
+            .ok_or_else(|| eyre::eyre!("The fee history is missing the base fee"))?;
+
+        // Take the median of the blocks' percentiles, ignoring empty blocks.
+        let mut rewards = history
+            .reward
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|rewards| rewards.first().copied())
+            .filter(|reward| *reward > 0)
+            .collect::<Vec<_>>();
+        rewards.sort_unstable();
+        let max_priority_fee_per_gas = rewards.get(rewards.len() / 2).copied().unwrap_or(1);
+
+        Ok(Fees::Eip1559 {
+            max_fee_per_gas: base_fee * self.base_fee_multiplier + max_priority_fee_per_gas,
+            max_priority_fee_per_gas,
+        })
+    }
+}
+
+/// Only uses the fees set on the command line, and never queries the node.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FixedGasStrategy;
+
+#[async_trait]
+impl GasStrategy for FixedGasStrategy {
+    async fn estimate(&self, _provider: &RetryProvider, legacy: bool) -> Result<Fees> {
+        if legacy {
+            eyre::bail!("The `fixed` gas strategy requires the gas price to be set");
+        }
+        eyre::bail!(
+            "The `fixed` gas strategy requires the gas price and the priority gas price to be set"
+        )
+    }
+}
+
+/// Pays more to be included fast: the 90th percentile of the recent priority fees with room for
+/// the base fee to triple, or 130% of the node's gas price for legacy transactions.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AggressiveGasStrategy;
+
+#[async_trait]
+impl GasStrategy for AggressiveGasStrategy {
+    async fn estimate(&self, provider: &RetryProvider, legacy: bool) -> Result<Fees> {
+        let strategy =
+            PercentileGasStrategy { percentile: 90.0, blocks: 10, base_fee_multiplier: 3 };
+        Ok(match strategy.estimate(provider, legacy).await? {
+            Fees::Legacy { gas_price } => Fees::Legacy { gas_price: gas_price * 13 / 10 },
+            fees => fees,
+        })
+    }
+}
+
+/// The strategies available on the command line.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum GasStrategyKind {
+    /// The fees estimated by the node.
+    #[default]
+    Provider,
+    /// A percentile of the priority fees paid in the recent blocks, see `--gas-percentile`.
+    Percentile,
+    /// Only the fees set with the gas price options.
+    Fixed,
+    /// High fees to be included as soon as possible.
+    Aggressive,
+}
+
+#[derive(Clone, Debug, Parser)]
+#[command(next_help_heading = "Gas strategy options")]
+pub struct GasStrategyOpts {
+    /// The strategy used to price the transactions.
+    ///
+    /// Gas prices passed explicitly always take precedence.
+    #[arg(long, value_enum, default_value_t = GasStrategyKind::Provider)]
+    pub gas_strategy: GasStrategyKind,
+
+    /// The percentile of the recent priority fees to pay with `--gas-strategy percentile`.
+    #[arg(long, default_value_t = 50.0, value_name = "PERCENTILE")]
+    pub gas_percentile: f64,
+
+    /// The maximum gas price, or max fee per gas for EIP1559 transactions, either specified in
+    /// wei, or as a string with a unit type.
+    ///
+    /// Estimated and bumped fees are limited to it.
+    #[arg(long, value_parser = parse_ether_value, value_name = "PRICE")]
+    pub max_fee_cap: Option<U256>,
+
+    /// The number of seconds after which a pending transaction is replaced by the same
+    /// transaction with bumped fees.
+    ///
+    /// Transactions are never replaced by default.
+    #[arg(long, value_name = "SECONDS")]
+    pub bump_timeout: Option<u64>,
+
+    /// The percentage by which fees are bumped when replacing a transaction.
+    ///
+    /// Most nodes only accept replacements with fees bumped by at least 10%.
+    #[arg(long, default_value_t = 15, value_name = "PERCENT")]
+    pub bump_percent: u64,
+
+    /// The maximum number of times a transaction is replaced.
+    #[arg(long, default_value_t = 3, value_name = "COUNT")]
+    pub max_bumps: usize,
+}
+
+impl Default for GasStrategyOpts {
+    fn default() -> Self {
+        Self {
+            gas_strategy: GasStrategyKind::default(),
+            gas_percentile: 50.0,
+            max_fee_cap: None,
+            bump_timeout: None,
+            bump_percent: 15,
+            max_bumps: 3,
+        }
+    }
+}
+
+impl GasStrategyOpts {
+    /// Returns the selected [GasStrategy].
+    pub fn strategy(&self) -> Box<dyn GasStrategy> {
+        match self.gas_strategy {
+            GasStrategyKind::Provider => Box::new(ProviderGasStrategy),
+            GasStrategyKind::Percentile => Box::new(PercentileGasStrategy {
+                percentile: self.gas_percentile,
+                ..Default::default()
+            }),
+            GasStrategyKind::Fixed => Box::new(FixedGasStrategy),
+            GasStrategyKind::Aggressive => Box::new(AggressiveGasStrategy),
+        }
+    }
+
+    /// Returns the fees of a transaction sent now, limited to `--max-fee-cap`.
+    ///
+    /// The gas price, or max fee per gas for EIP1559 transactions, and the priority gas price
+    /// override the estimated fees.
+    pub async fn fees(
+        &self,
+        provider: &RetryProvider,
+        legacy: bool,
+        gas_price: Option<U256>,
+        priority_gas_price: Option<U256>,
+    ) -> Result<Fees> {
+        let fees = match (legacy, gas_price, priority_gas_price) {
+            (true, Some(gas_price), _) => Fees::Legacy { gas_price: gas_price.to() },
+            (false, Some(max_fee_per_gas), Some(max_priority_fee_per_gas)) => Fees::Eip1559 {
+                max_fee_per_gas: max_fee_per_gas.to(),
+                max_priority_fee_per_gas: max_priority_fee_per_gas.to(),
+            },
+            _ => {
+                let mut fees = self.strategy().estimate(provider, legacy).await?;
+                if let Fees::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas } = &mut fees {
+                    if let Some(gas_price) = gas_price {
+                        *max_fee_per_gas = gas_price.to();
+                    }
+                    if let Some(priority_gas_price) = priority_gas_price {
+                        *max_priority_fee_per_gas = priority_gas_price.to();
+                    }
+                }
+                fees
+            }
+        };
+        Ok(self.capped(fees))
+    }
+
+    /// Re-prices a transaction filled with the node's fees with the selected gas strategy, and
+    /// limits its fees to `--max-fee-cap`.
+    pub async fn apply(
+        &self,
+        provider: &RetryProvider,
+        tx: &mut WithOtherFields<TransactionRequest>,
+        gas_price: Option<U256>,
+        priority_gas_price: Option<U256>,
+    ) -> Result<()> {
+        if self.gas_strategy != GasStrategyKind::Provider || self.max_fee_cap.is_some() {
+            let legacy = tx.gas_price.is_some();
+            self.fees(provider, legacy, gas_price, priority_gas_price).await?.apply(tx);
+        }
+        Ok(())
+    }
+
+    /// Returns the fees to replace a transaction with, or `None` if `--max-fee-cap` doesn't leave
+    /// room to bump them.
+    pub fn bump(&self, fees: Fees) -> Option<Fees> {
+        let bumped = fees.bumped(self.bump_percent);
+        (self.capped(bumped) == bumped).then_some(bumped)
+    }
+
+    fn capped(&self, fees: Fees) -> Fees {
+        match self.max_fee_cap {
+            Some(cap) => fees.capped(cap.saturating_to()),
+            None => fees,
+        }
+    }
+
+    /// Waits until the transaction sent with hash `tx_hash`, or one of its replacements, is
+    /// included, and returns the hash of the included transaction.
+    ///
+    /// If `--bump-timeout` is set, the pending transaction is replaced by `tx` with bumped fees,
+    /// sent with `send`, each time it stays pending for that long. Otherwise, returns `tx_hash`
+    /// right away.
+    pub async fn wait_for_inclusion<P, F, Fut>(
+        &self,
+        provider: &P,
+        mut tx: WithOtherFields<TransactionRequest>,
+        tx_hash: TxHash,
+        timeout: u64,
+        mut send: F,
+    ) -> Result<TxHash>
+    where
+        P: Provider<AnyNetwork>,
+        F: FnMut(WithOtherFields<TransactionRequest>) -> Fut,
+        Fut: Future<Output = Result<TxHash>>,
+    {
+        let Some(bump_timeout) = self.bump_timeout else { return Ok(tx_hash) };
+        let bump_timeout = Duration::from_secs(bump_timeout);
+
+        let mut fees = Fees::from_tx(&tx);
+        let mut hashes = vec![tx_hash];
+        let started = Instant::now();
+        let mut sent = Instant::now();
+        loop {
+            if let Some(hash) = self.find_included(provider, &hashes).await? {
+                return Ok(hash);
+            }
+            if started.elapsed() >= Duration::from_secs(timeout) {
+                eyre::bail!(
+                    "Transaction {:?} was not included after {timeout} seconds",
+                    hashes.last().unwrap()
+                );
+            }
+
+            if sent.elapsed() >= bump_timeout && hashes.len() <= self.max_bumps {
+                if let Some(bumped) = fees.and_then(|fees| self.bump(fees)) {
+                    bumped.apply(&mut tx);
+                    match send(tx.clone()).await {
+                        Ok(hash) => {
+                            let _ = sh_warn!(
+                                "Transaction {:?} is still pending, replaced it with {hash:?}",
+                                hashes.last().unwrap()
+                            );
+                            hashes.push(hash);
+                            fees = Some(bumped);
+                        }
+                        // One of the transactions may have been included in the meantime.
+                        Err(err) => {
+                            return match self.find_included(provider, &hashes).await? {
+                                Some(hash) => Ok(hash),
+                                None => Err(err.wrap_err("Failed to replace transaction")),
+                            };
+                        }
+                    }
+                }
+                sent = Instant::now();
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    async fn find_included<P: Provider<AnyNetwork>>(
+        &self,
+        provider: &P,
+        hashes: &[TxHash],
+    ) -> Result<Option<TxHash>> {
+        for hash in hashes {
+            if provider.get_transaction_receipt(*hash).await?.is_some() {
+                return Ok(Some(*hash));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_bump_fees() {
+        let opts = GasStrategyOpts::default();
+        let fees = Fees::Eip1559 { max_fee_per_gas: 100, max_priority_fee_per_gas: 0 };
+        assert_eq!(
+            opts.bump(fees),
+            Some(Fees::Eip1559 { max_fee_per_gas: 115, max_priority_fee_per_gas: 1 })
+        );
+
+        let opts = GasStrategyOpts { max_fee_cap: Some(U256::from(110)), ..Default::default() };
+        assert_eq!(opts.bump(fees), None);
+        assert_eq!(opts.bump(Fees::Legacy { gas_price: 100 }), None);
+        assert_eq!(opts.bump(Fees::Legacy { gas_price: 80 }), Some(Fees::Legacy { gas_price: 92 }));
+    }
+
+    #[test]
+    fn parse_gas_strategy_opts() {
+        let opts = GasStrategyOpts::parse_from([
+            "foundry-cli",
+            "--gas-strategy",
+            "percentile",
+            "--max-fee-cap",
+            "100gwei",
+            "--bump-timeout",
+            "60",
+        ]);
+        assert_eq!(opts.gas_strategy, GasStrategyKind::Percentile);
+        assert_eq!(opts.max_fee_cap, Some(U256::from(100_000_000_000u64)));
+        assert_eq!(opts.bump_timeout, Some(60));
+        assert_eq!(opts.bump_percent, 15);
+    }
+}
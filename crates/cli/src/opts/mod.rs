@@ -1,6 +1,7 @@
 mod build;
 mod chain;
 mod dependency;
+mod gas;
 mod global;
 mod rpc;
 mod transaction;
@@ -8,6 +9,7 @@
 pub use build::*;
 pub use chain::*;
 pub use dependency::*;
+pub use gas::*;
 pub use global::*;
 pub use rpc::*;
 pub use transaction::*;
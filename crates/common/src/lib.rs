@@ -28,6 +28,7 @@
 pub mod provider;
 pub mod reports;
 pub mod retry;
+pub mod sarif;
 pub mod selectors;
 pub mod serde_helpers;
 pub mod term;
@@ -0,0 +1,287 @@
+//! Static Analysis Results Interchange Format (SARIF) reports.
+//!
+//! SARIF is consumed by code scanning dashboards such as GitHub code scanning. See
+//! <https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html>.
+
+use foundry_compilers::{artifacts::Severity, multi::MultiCompilerError};
+use foundry_config::Config;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+/// The JSON schema of SARIF 2.1.0 reports.
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// The severity of a [`SarifResult`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SarifLevel {
+    Error,
+    Warning,
+    Note,
+}
+
+/// A rule reported by a tool.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SarifRule {
+    pub id: String,
+    pub description: String,
+    pub level: SarifLevel,
+    pub help_uri: Option<String>,
+}
+
+/// A single finding of a tool.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SarifResult {
+    pub rule_id: String,
+    pub level: SarifLevel,
+    pub message: String,
+    pub location: Option<SarifLocation>,
+}
+
+/// A region of a source file, with 1-based lines and columns.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SarifLocation {
+    /// The path of the file, relative to the project root.
+    pub path: PathBuf,
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+/// A SARIF report with a single run of one tool.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SarifReport {
+    tool: String,
+    rules: Vec<SarifRule>,
+    results: Vec<SarifResult>,
+}
+
+impl SarifReport {
+    /// Creates an empty report for the given tool, e.g. `forge-lint`.
+    pub fn new(tool: impl Into<String>) -> Self {
+        Self { tool: tool.into(), rules: Vec::new(), results: Vec::new() }
+    }
+
+    /// Adds a rule, unless a rule with the same id was already added.
+    pub fn add_rule(&mut self, rule: SarifRule) {
+        if !self.rules.iter().any(|r| r.id == rule.id) {
+            self.rules.push(rule);
+        }
+    }
+
+    /// Adds a result.
+    pub fn add_result(&mut self, result: SarifResult) {
+        self.results.push(result);
+    }
+
+    /// Returns the results of the report.
+    pub fn results(&self) -> &[SarifResult] {
+        &self.results
+    }
+
+    /// Serializes the report.
+    pub fn to_json(&self) -> Value {
+        let rules = self
+            .rules
+            .iter()
+            .map(|rule| {
+                let mut value = json!({
+                    "id": rule.id,
+                    "shortDescription": { "text": rule.description },
+                    "defaultConfiguration": { "level": rule.level },
+                });
+                if let Some(uri) = &rule.help_uri {
+                    value["helpUri"] = uri.as_str().into();
+                }
+                value
+            })
+            .collect::<Vec<_>>();
+        let results = self
+            .results
+            .iter()
+            .map(|result| {
+                let mut value = json!({
+                    "ruleId": result.rule_id,
+                    "level": result.level,
+                    "message": { "text": result.message },
+                });
+                if let Some(location) = &result.location {
+                    value["locations"] = json!([{
+                        "physicalLocation": {
+                            "artifactLocation": {
+                                // URIs always use forward slashes.
+                                "uri": location.path.to_string_lossy().replace('\\', "/"),
+                            },
+                            "region": {
+                                "startLine": location.start_line,
+                                "startColumn": location.start_column,
+                                "endLine": location.end_line,
+                                "endColumn": location.end_column,
+                            },
+                        },
+                    }]);
+                }
+                value
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "$schema": SARIF_SCHEMA,
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": self.tool,
+                        "semanticVersion": crate::version::SEMVER_VERSION,
+                        "informationUri": "https://github.com/foundry-rs/foundry",
+                        "rules": rules,
+                    },
+                },
+                "results": results,
+            }],
+        })
+    }
+
+    /// Writes the report to the given file.
+    pub fn write(&self, path: &Path) -> eyre::Result<()> {
+        crate::fs::write_pretty_json_file(path, &self.to_json())?;
+        Ok(())
+    }
+}
+
+/// Creates a report of the diagnostics of a compilation.
+///
+/// Diagnostics with an error code in `ignored_error_codes` or in a file of `ignored_file_paths` are
+/// skipped, warnings are reported as errors if `deny_warnings` is set.
+pub fn compiler_report(config: &Config, errors: &[MultiCompilerError]) -> SarifReport {
+    let ignored_error_codes =
+        config.ignored_error_codes.iter().map(|&code| u64::from(code)).collect::<Vec<_>>();
+    let level = |severity| match severity {
+        Severity::Error => SarifLevel::Error,
+        Severity::Warning if config.deny_warnings => SarifLevel::Error,
+        Severity::Warning => SarifLevel::Warning,
+        Severity::Info => SarifLevel::Note,
+    };
+    let mut report = SarifReport::new("solc");
+    for error in errors {
+        let error = match error {
+            MultiCompilerError::Solc(error) => error,
+            // Vyper diagnostics have neither an error code nor a byte range.
+            MultiCompilerError::Vyper(error) => {
+                let level = level(error.severity);
+                let rule_id = "vyper".to_string();
+                report.add_rule(SarifRule {
+                    id: rule_id.clone(),
+                    description: "Vyper diagnostic".to_string(),
+                    level,
+                    help_uri: None,
+                });
+                let message = error.message.clone();
+                report.add_result(SarifResult { rule_id, level, message, location: None });
+                continue;
+            }
+        };
+        if error.error_code.is_some_and(|code| ignored_error_codes.contains(&code)) {
+            continue;
+        }
+        let path = error.source_location.as_ref().map(|location| Path::new(&location.file));
+        if path.is_some_and(|path| {
+            config.ignored_file_paths.iter().any(|ignored| {
+                path.starts_with(ignored) || config.root.join(path).starts_with(ignored)
+            })
+        }) {
+            continue;
+        }
+
+        let level = level(error.severity);
+        let rule_id = match error.error_code {
+            Some(code) => code.to_string(),
+            None => error.r#type.clone(),
+        };
+        report.add_rule(SarifRule {
+            id: rule_id.clone(),
+            description: error.r#type.clone(),
+            level,
+            help_uri: None,
+        });
+
+        let location = error.source_location.as_ref().and_then(|location| {
+            let source = std::fs::read_to_string(config.root.join(&location.file)).ok()?;
+            let (start_line, start_column) = line_column(&source, location.start.try_into().ok()?);
+            let (end_line, end_column) = line_column(&source, location.end.try_into().ok()?);
+            Some(SarifLocation {
+                path: location.file.clone().into(),
+                start_line,
+                start_column,
+                end_line,
+                end_column,
+            })
+        });
+        report.add_result(SarifResult { rule_id, level, message: error.message.clone(), location });
+    }
+    report
+}
+
+/// Converts a byte `offset` in `source` to a 1-based line and column.
+///
+/// Columns are counted in characters.
+pub fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let line = before.matches('\n').count() + 1;
+    (line, before[line_start..].chars().count() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_report() {
+        let mut report = SarifReport::new("forge-lint");
+        let rule = SarifRule {
+            id: "tx-origin".to_string(),
+            description: "`tx.origin` used for authorization".to_string(),
+            level: SarifLevel::Error,
+            help_uri: None,
+        };
+        report.add_rule(rule.clone());
+        report.add_rule(rule);
+        report.add_result(SarifResult {
+            rule_id: "tx-origin".to_string(),
+            level: SarifLevel::Error,
+            message: "`tx.origin` used for authorization".to_string(),
+            location: Some(SarifLocation {
+                path: "src/Wallet.sol".into(),
+                start_line: 3,
+                start_column: 17,
+                end_line: 3,
+                end_column: 26,
+            }),
+        });
+
+        let json = report.to_json();
+        assert_eq!(json["version"], "2.1.0");
+        let run = &json["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 1);
+        assert_eq!(run["tool"]["driver"]["rules"][0]["defaultConfiguration"]["level"], "error");
+        let location = &run["results"][0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/Wallet.sol");
+        assert_eq!(location["region"]["startColumn"], 17);
+    }
+
+    #[test]
+    fn line_columns() {
+        let source = "ab\ncd\n";
+        assert_eq!(line_column(source, 0), (1, 1));
+        assert_eq!(line_column(source, 1), (1, 2));
+        assert_eq!(line_column(source, 3), (2, 1));
+        assert_eq!(line_column(source, 100), (3, 1));
+    }
+}
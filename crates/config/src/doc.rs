@@ -1,7 +1,7 @@
 //! Configuration specific to the `forge doc` command and the `forge_doc` package
 
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::{fmt, path::PathBuf};
 
 /// Contains the config for parsing and rendering docs
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -22,6 +22,8 @@ pub struct DocConfig {
     pub repository: Option<String>,
     /// Globs to ignore
     pub ignore: Vec<String>,
+    /// The NatSpec rules checked by `forge doc --lint`.
+    pub lint_rules: Vec<DocLintRule>,
 }
 
 impl Default for DocConfig {
@@ -33,6 +35,43 @@ fn default() -> Self {
             title: String::default(),
             repository: None,
             ignore: Vec::default(),
+            lint_rules: DocLintRule::ALL.to_vec(),
         }
     }
 }
+
+/// A NatSpec rule checked by `forge doc --lint`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DocLintRule {
+    /// Public and external functions, events and errors must have a `@notice`.
+    MissingNotice,
+    /// Each named parameter of a function, event or error must have a `@param`.
+    MissingParam,
+    /// Each `@param` must name a parameter of the item.
+    MismatchedParam,
+    /// Each `@inheritdoc` must name a base contract declaring the item.
+    StaleInheritdoc,
+}
+
+impl DocLintRule {
+    /// All the rules.
+    pub const ALL: [Self; 4] =
+        [Self::MissingNotice, Self::MissingParam, Self::MismatchedParam, Self::StaleInheritdoc];
+
+    /// Returns the name of the rule.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::MissingNotice => "missing-notice",
+            Self::MissingParam => "missing-param",
+            Self::MismatchedParam => "mismatched-param",
+            Self::StaleInheritdoc => "stale-inheritdoc",
+        }
+    }
+}
+
+impl fmt::Display for DocLintRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
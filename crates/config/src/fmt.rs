@@ -33,6 +33,17 @@ pub struct FormatterConfig {
     pub contract_new_lines: bool,
     /// Sort import statements alphabetically in groups (a group is separated by a newline).
     pub sort_imports: bool,
+    /// Group import statements by source: imports of each remapping in `import_groups` form a
+    /// group, followed by the other non-relative imports and the relative imports.
+    pub group_imports: bool,
+    /// Prefixes of import paths that form their own group, in order.
+    ///
+    /// Defaults to the names of the project's remappings.
+    pub import_groups: Vec<String>,
+    /// Reorder contract members following the order of the Solidity style guide: types, state
+    /// variables, events, errors, modifiers, constructor, receive and fallback functions, then
+    /// external, public, internal and private functions, with view and pure functions last.
+    pub sort_members: bool,
 }
 
 /// Style of uint/int256 types
@@ -183,6 +194,9 @@ fn default() -> Self {
             ignore: vec![],
             contract_new_lines: false,
             sort_imports: false,
+            group_imports: false,
+            import_groups: vec![],
+            sort_members: false,
         }
     }
 }
@@ -85,7 +85,10 @@
 pub use error::SolidityErrorCode;
 
 pub mod doc;
-pub use doc::DocConfig;
+pub use doc::{DocConfig, DocLintRule};
+
+pub mod lint;
+pub use lint::LintConfig;
 
 pub mod filter;
 pub use filter::SkipBuildFilters;
@@ -450,6 +453,8 @@ pub struct Config {
     pub doc: DocConfig,
     /// Configuration for `forge bind-json`
     pub bind_json: BindJsonConfig,
+    /// Configuration for `forge lint`
+    pub lint: LintConfig,
     /// Configures the permissions of cheat codes that touch the file system.
     ///
     /// This includes what operations can be executed (read, write)
@@ -567,6 +572,7 @@ impl Config {
         "soldeer",
         "vyper",
         "bind_json",
+        "lint",
     ];
 
     /// File name of config toml file
@@ -2410,6 +2416,7 @@ fn default() -> Self {
             fmt: Default::default(),
             doc: Default::default(),
             bind_json: Default::default(),
+            lint: Default::default(),
             labels: Default::default(),
             unchecked_cheatcode_artifacts: false,
             create2_library_salt: Self::DEFAULT_CREATE2_LIBRARY_SALT,
@@ -0,0 +1,63 @@
+//! Configuration specific to the `forge lint` command and the `forge_lint` package
+
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+/// Contains the config for `forge lint`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintConfig {
+    /// Overrides of the default severity of rules, keyed by rule id.
+    ///
+    /// A rule can be disabled by setting its severity to `off`.
+    pub severity: BTreeMap<String, Severity>,
+    /// Globs to ignore
+    pub ignore: Vec<String>,
+}
+
+/// The severity of a lint rule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The rule is disabled.
+    Off,
+    Info,
+    Low,
+    Medium,
+    High,
+}
+
+impl Severity {
+    /// Returns the name of the severity.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Info => "info",
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "info" => Ok(Self::Info),
+            "low" => Ok(Self::Low),
+            "medium" => Ok(Self::Medium),
+            "high" => Ok(Self::High),
+            s => {
+                Err(format!("unknown severity `{s}`, expected one of off, info, low, medium, high"))
+            }
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! Debug Adapter Protocol server.
+//!
+//! Lets editors such as VS Code and Neovim step through the recorded execution the same way as the
+//! TUI. See <https://microsoft.github.io/debug-adapter-protocol/specification>.
+
+use crate::debugger::DebuggerContext;
+use eyre::{Context, Result};
+use serde_json::{json, Value};
+use std::{
+    fmt,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{Ipv4Addr, TcpListener},
+    path::Path,
+    str::FromStr,
+};
+
+mod session;
+use session::Session;
+
+/// How the DAP server communicates with the editor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DapTransport {
+    /// Messages are read from stdin and written to stdout.
+    Stdio,
+    /// The server accepts a single connection on the given local port.
+    Tcp(u16),
+}
+
+impl FromStr for DapTransport {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "stdio" => Ok(Self::Stdio),
+            port => port
+                .parse()
+                .map(Self::Tcp)
+                .map_err(|_| format!("expected `stdio` or a port number, got `{port}`")),
+        }
+    }
+}
+
+impl fmt::Display for DapTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stdio => f.write_str("stdio"),
+            Self::Tcp(port) => port.fmt(f),
+        }
+    }
+}
+
+/// Runs the DAP server until the editor disconnects.
+pub(crate) fn run(context: &DebuggerContext, root: &Path, transport: DapTransport) -> Result<()> {
+    match transport {
+        DapTransport::Stdio => {
+            eyre::ensure!(
+                foundry_common::shell::is_quiet(),
+                "serving the debug adapter over stdio requires `--quiet`, \
+                 other output would corrupt the protocol"
+            );
+            let stdin = io::stdin().lock();
+            let stdout = io::stdout().lock();
+            serve(context, root, stdin, stdout)
+        }
+        DapTransport::Tcp(port) => {
+            let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+                .wrap_err_with(|| format!("failed to bind to port {port}"))?;
+            let _ = sh_eprintln!("Debug adapter listening on {}", listener.local_addr()?);
+            let (stream, _) = listener.accept()?;
+            serve(context, root, stream.try_clone()?, stream)
+        }
+    }
+}
+
+fn serve(
+    context: &DebuggerContext,
+    root: &Path,
+    reader: impl Read,
+    writer: impl Write,
+) -> Result<()> {
+    let mut connection = Connection::new(reader, writer);
+    let mut session = Session::new(context, root);
+    while let Some(request) = connection.read()? {
+        let command = request["command"].as_str().unwrap_or_default().to_string();
+        let seq = request["seq"].as_i64().unwrap_or_default();
+        let args = &request["arguments"];
+        trace!(target: "debugger::dap", %command, %args, "request");
+
+        let outcome = session.handle(&command, args);
+        connection.respond(seq, &command, outcome.response)?;
+        for (event, body) in outcome.events {
+            connection.event(event, body)?;
+        }
+        if outcome.disconnect {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// The result of handling a request.
+struct Outcome {
+    /// The body of the response, or the error message.
+    response: Result<Value, String>,
+    /// Events to send after the response.
+    events: Vec<(&'static str, Value)>,
+    /// Whether to stop serving after the response.
+    disconnect: bool,
+}
+
+impl Outcome {
+    fn new(response: Result<Value, String>) -> Self {
+        Self { response, events: Vec::new(), disconnect: false }
+    }
+
+    fn with_event(mut self, event: &'static str, body: Value) -> Self {
+        self.events.push((event, body));
+        self
+    }
+}
+
+impl From<Result<Value, String>> for Outcome {
+    fn from(response: Result<Value, String>) -> Self {
+        Self::new(response)
+    }
+}
+
+/// Reads and writes `Content-Length` framed JSON messages.
+struct Connection<R, W> {
+    reader: BufReader<R>,
+    writer: W,
+    seq: i64,
+}
+
+impl<R: Read, W: Write> Connection<R, W> {
+    fn new(reader: R, writer: W) -> Self {
+        Self { reader: BufReader::new(reader), writer, seq: 0 }
+    }
+
+    /// Reads the next message, or `None` if the editor closed the connection.
+    fn read(&mut self) -> Result<Option<Value>> {
+        let mut content_length = None;
+        loop {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("Content-Length") {
+                    content_length = Some(value.trim().parse::<usize>()?);
+                }
+            }
+        }
+        let content_length = content_length.ok_or_else(|| eyre::eyre!("missing Content-Length"))?;
+        let mut body = vec![0; content_length];
+        self.reader.read_exact(&mut body)?;
+        Ok(Some(serde_json::from_slice(&body)?))
+    }
+
+    fn respond(
+        &mut self,
+        request_seq: i64,
+        command: &str,
+        res: Result<Value, String>,
+    ) -> Result<()> {
+        let mut message = json!({
+            "type": "response",
+            "request_seq": request_seq,
+            "command": command,
+            "success": res.is_ok(),
+        });
+        match res {
+            Ok(Value::Null) => {}
+            Ok(body) => message["body"] = body,
+            Err(e) => message["message"] = e.into(),
+        }
+        self.write(message)
+    }
+
+    fn event(&mut self, event: &str, body: Value) -> Result<()> {
+        let mut message = json!({ "type": "event", "event": event });
+        if !body.is_null() {
+            message["body"] = body;
+        }
+        self.write(message)
+    }
+
+    fn write(&mut self, mut message: Value) -> Result<()> {
+        self.seq += 1;
+        message["seq"] = self.seq.into();
+        let body = serde_json::to_string(&message)?;
+        write!(self.writer, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_transport() {
+        assert_eq!("stdio".parse::<DapTransport>(), Ok(DapTransport::Stdio));
+        assert_eq!("4711".parse::<DapTransport>(), Ok(DapTransport::Tcp(4711)));
+        assert!("tcp".parse::<DapTransport>().is_err());
+    }
+
+    #[test]
+    fn connection_roundtrip() {
+        let request = r#"{"seq":1,"type":"request","command":"threads"}"#;
+        let input = format!("Content-Length: {}\r\n\r\n{request}", request.len());
+        let mut connection = Connection::new(input.as_bytes(), Vec::new());
+
+        let message = connection.read().unwrap().unwrap();
+        assert_eq!(message["command"], "threads");
+        assert!(connection.read().unwrap().is_none());
+
+        connection.respond(1, "threads", Ok(json!({ "threads": [] }))).unwrap();
+        let output = connection.writer;
+        let output = String::from_utf8(output).unwrap();
+        let (header, body) = output.split_once("\r\n\r\n").unwrap();
+        assert_eq!(header, format!("Content-Length: {}", body.len()));
+        let body: Value = serde_json::from_str(body).unwrap();
+        assert_eq!(body["success"], true);
+        assert_eq!(body["request_seq"], 1);
+    }
+}
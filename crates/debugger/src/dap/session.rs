@@ -0,0 +1,747 @@
+//! Debugging state of a DAP session.
+
+use super::Outcome;
+use crate::{debugger::DebuggerContext, DebugNode};
+use alloy_primitives::{hex, Address, U256};
+use foundry_compilers::artifacts::sourcemap::Jump;
+use revm::interpreter::OpCode;
+use revm_inspectors::tracing::types::CallTraceStep;
+use serde_json::{json, Value};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+/// The only thread of the debuggee.
+const THREAD_ID: i64 = 1;
+
+/// A position in the flattened debug arena.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Position {
+    node: usize,
+    step: usize,
+}
+
+/// A line in a source file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Location<'a> {
+    /// The path of the file, relative to the project root.
+    path: &'a Path,
+    /// The 1-based line number.
+    line: usize,
+}
+
+/// The variable scopes of a stack frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Scope {
+    Stack,
+    Memory,
+    Calldata,
+    Storage,
+}
+
+impl Scope {
+    const ALL: [Self; 4] = [Self::Stack, Self::Memory, Self::Calldata, Self::Storage];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Stack => "Stack",
+            Self::Memory => "Memory",
+            Self::Calldata => "Calldata",
+            Self::Storage => "Storage",
+        }
+    }
+
+    /// Returns the `variablesReference` of this scope in the frame of the given node.
+    ///
+    /// References must be positive, so both are offset by one.
+    fn reference(self, node: usize) -> i64 {
+        ((node + 1) * Self::ALL.len() + self as usize) as i64
+    }
+
+    fn from_reference(reference: i64) -> Option<(usize, Self)> {
+        let reference = usize::try_from(reference).ok()?;
+        let node = (reference / Self::ALL.len()).checked_sub(1)?;
+        Some((node, Self::ALL[reference % Self::ALL.len()]))
+    }
+}
+
+/// How far to step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum StepKind {
+    /// Stop at the next line, entering calls.
+    In,
+    /// Stop at the next line of the current function.
+    Over,
+    /// Stop after returning from the current function.
+    Out,
+    /// Stop at the next breakpoint.
+    Continue,
+}
+
+/// Why stepping stopped.
+enum Stop {
+    Step,
+    Breakpoint,
+    /// Reached the end, or the start when stepping backwards.
+    Boundary,
+}
+
+pub(super) struct Session<'a> {
+    context: &'a DebuggerContext,
+    root: &'a Path,
+    position: Position,
+    /// Breakpoint lines by source path, as sent by the editor.
+    breakpoints: HashMap<PathBuf, Vec<usize>>,
+    /// Cached offsets of line starts by source path.
+    line_starts: HashMap<&'a Path, Vec<usize>>,
+    /// Lines that are executed at least once, computed on the first breakpoint request.
+    executed_lines: Option<HashSet<Location<'a>>>,
+}
+
+impl<'a> Session<'a> {
+    pub(super) fn new(context: &'a DebuggerContext, root: &'a Path) -> Self {
+        Self {
+            context,
+            root,
+            position: Position { node: 0, step: 0 },
+            breakpoints: HashMap::new(),
+            line_starts: HashMap::new(),
+            executed_lines: None,
+        }
+    }
+
+    pub(super) fn handle(&mut self, command: &str, args: &Value) -> Outcome {
+        match command {
+            "initialize" => Outcome::new(Ok(json!({
+                "supportsConfigurationDoneRequest": true,
+                "supportsStepBack": true,
+            })))
+            .with_event("initialized", Value::Null),
+            "launch" | "attach" => Ok(Value::Null).into(),
+            "configurationDone" => Outcome::new(Ok(Value::Null)).with_event(
+                "stopped",
+                json!({ "reason": "entry", "threadId": THREAD_ID, "allThreadsStopped": true }),
+            ),
+            "setBreakpoints" => self.set_breakpoints(args).into(),
+            "setExceptionBreakpoints" | "setFunctionBreakpoints" => {
+                Ok(json!({ "breakpoints": [] })).into()
+            }
+            "threads" => Ok(json!({ "threads": [{ "id": THREAD_ID, "name": "main" }] })).into(),
+            "stackTrace" => Ok(self.stack_trace()).into(),
+            "scopes" => self.scopes(args).into(),
+            "variables" => self.variables(args).into(),
+            "continue" => self.resume(StepKind::Continue, true),
+            "next" => self.resume(StepKind::Over, true),
+            "stepIn" => self.resume(StepKind::In, true),
+            "stepOut" => self.resume(StepKind::Out, true),
+            "stepBack" => self.resume(StepKind::In, false),
+            "reverseContinue" => self.resume(StepKind::Continue, false),
+            // Execution is never running, so pausing is a no-op.
+            "pause" => Outcome::new(Ok(Value::Null)).with_event(
+                "stopped",
+                json!({ "reason": "pause", "threadId": THREAD_ID, "allThreadsStopped": true }),
+            ),
+            "disconnect" | "terminate" => {
+                let mut outcome = Outcome::new(Ok(Value::Null));
+                outcome.disconnect = true;
+                outcome
+            }
+            _ => Err(format!("unsupported request: {command}")).into(),
+        }
+    }
+
+    fn set_breakpoints(&mut self, args: &Value) -> Result<Value, String> {
+        let path =
+            args["source"]["path"].as_str().ok_or_else(|| "missing source path".to_string())?;
+        let lines: Vec<usize> = args["breakpoints"]
+            .as_array()
+            .map(|breakpoints| {
+                breakpoints
+                    .iter()
+                    .filter_map(|bp| bp["line"].as_u64())
+                    .map(|l| l as usize)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let path = PathBuf::from(path);
+        let executed_lines = self.executed_lines();
+        let breakpoints = lines
+            .iter()
+            .map(|&line| {
+                let verified = executed_lines
+                    .iter()
+                    .any(|location| location.line == line && path.ends_with(location.path));
+                json!({ "verified": verified, "line": line })
+            })
+            .collect::<Vec<_>>();
+        self.breakpoints.insert(path, lines);
+
+        Ok(json!({ "breakpoints": breakpoints }))
+    }
+
+    fn executed_lines(&mut self) -> HashSet<Location<'a>> {
+        if let Some(lines) = &self.executed_lines {
+            return lines.clone();
+        }
+        let mut lines = HashSet::new();
+        let mut position = Some(Position { node: 0, step: 0 });
+        while let Some(pos) = position {
+            lines.extend(self.location(pos));
+            position = self.next(pos);
+        }
+        self.executed_lines = Some(lines.clone());
+        lines
+    }
+
+    /// Returns one frame per call depth, innermost first.
+    fn stack_trace(&mut self) -> Value {
+        let context = self.context;
+        let frames = self
+            .call_stack()
+            .into_iter()
+            .map(|pos| {
+                let node = &context.debug_arena[pos.node];
+                let step = &node.steps[pos.step];
+                let mut frame = json!({
+                    "id": pos.node,
+                    "name": self.frame_name(node, step),
+                    "line": 0,
+                    "column": 0,
+                });
+                match self.location(pos) {
+                    Some(location) => {
+                        frame["line"] = location.line.into();
+                        frame["column"] = 1.into();
+                        frame["source"] = json!({
+                            "name": location.path.file_name().map(|name| name.to_string_lossy()),
+                            "path": self.root.join(location.path),
+                        });
+                    }
+                    None => frame["presentationHint"] = "subtle".into(),
+                }
+                frame
+            })
+            .collect::<Vec<_>>();
+        let total_frames = frames.len();
+        json!({ "stackFrames": frames, "totalFrames": total_frames })
+    }
+
+    /// Returns the position of each frame of the current call stack, innermost first.
+    ///
+    /// The arena is flattened in execution order, so the caller of a node is the closest node
+    /// before it with a smaller depth, stopped at its last step.
+    fn call_stack(&self) -> Vec<Position> {
+        let arena = &self.context.debug_arena;
+        let mut frames = vec![self.position];
+        let mut depth = node_depth(&arena[self.position.node]);
+        for (i, node) in arena[..self.position.node].iter().enumerate().rev() {
+            if depth == 0 {
+                break;
+            }
+            if node_depth(node) < depth {
+                depth = node_depth(node);
+                frames.push(Position { node: i, step: node.steps.len() - 1 });
+            }
+        }
+        frames
+    }
+
+    fn frame_name(&self, node: &DebugNode, step: &CallTraceStep) -> String {
+        let contract = self
+            .context
+            .identified_contracts
+            .get(&node.address)
+            .cloned()
+            .unwrap_or_else(|| node.address.to_string());
+        let function =
+            self.context.source_mapping(node, step.pc).ok().and_then(|(element, source)| {
+                source
+                    .find_function(element.offset() as usize)
+                    .map(|function| function.name.clone())
+            });
+        match function {
+            Some(function) => format!("{contract}::{function}"),
+            None => format!("{contract} (pc {})", step.pc),
+        }
+    }
+
+    fn scopes(&self, args: &Value) -> Result<Value, String> {
+        let node = args["frameId"]
+            .as_u64()
+            .map(|id| id as usize)
+            .filter(|&id| id < self.context.debug_arena.len())
+            .ok_or_else(|| "invalid frame".to_string())?;
+        let scopes = Scope::ALL
+            .iter()
+            .map(|scope| {
+                json!({
+                    "name": scope.name(),
+                    "variablesReference": scope.reference(node),
+                    "expensive": false,
+                })
+            })
+            .collect::<Vec<_>>();
+        Ok(json!({ "scopes": scopes }))
+    }
+
+    fn variables(&self, args: &Value) -> Result<Value, String> {
+        let (node_index, scope) = args["variablesReference"]
+            .as_i64()
+            .and_then(Scope::from_reference)
+            .filter(|(node, _)| *node < self.context.debug_arena.len())
+            .ok_or_else(|| "invalid variables reference".to_string())?;
+        let node = &self.context.debug_arena[node_index];
+        // Frames other than the innermost one are stopped at their last step.
+        let step_index = if node_index == self.position.node {
+            self.position.step
+        } else {
+            node.steps.len() - 1
+        };
+        let step = &node.steps[step_index];
+
+        let variables: Vec<(String, String)> = match scope {
+            Scope::Stack => step
+                .stack
+                .iter()
+                .flatten()
+                .rev()
+                .enumerate()
+                .map(|(i, value)| (i.to_string(), format!("{value:#x}")))
+                .collect(),
+            Scope::Memory => {
+                words(step.memory.as_ref().map(|memory| &memory.as_bytes()[..]).unwrap_or_default())
+            }
+            Scope::Calldata => words(&node.calldata),
+            // Storage is shared by all frames of the same contract, so it is always shown at the
+            // current position.
+            Scope::Storage => storage(&self.context.debug_arena, self.position, step.contract)
+                .into_iter()
+                .map(|(key, value)| (format!("{key:#x}"), format!("{value:#x}")))
+                .collect(),
+        };
+
+        let variables = variables
+            .into_iter()
+            .map(|(name, value)| json!({ "name": name, "value": value, "variablesReference": 0 }))
+            .collect::<Vec<_>>();
+        Ok(json!({ "variables": variables }))
+    }
+
+    /// Moves the position and returns the `stopped` or `terminated` event.
+    fn resume(&mut self, kind: StepKind, forward: bool) -> Outcome {
+        let stop = if forward { self.step_forward(kind) } else { self.step_backward(kind) };
+        let outcome = Outcome::new(Ok(json!({ "allThreadsContinued": true })));
+        let reason = match stop {
+            Stop::Boundary if forward && kind == StepKind::Continue => {
+                return outcome.with_event("terminated", Value::Null);
+            }
+            Stop::Breakpoint => "breakpoint",
+            Stop::Step | Stop::Boundary => "step",
+        };
+        outcome.with_event(
+            "stopped",
+            json!({ "reason": reason, "threadId": THREAD_ID, "allThreadsStopped": true }),
+        )
+    }
+
+    fn step_forward(&mut self, kind: StepKind) -> Stop {
+        let start = self.position;
+        let start_location = self.location(start);
+        let start_depth = self.depth(start);
+        // Depth of internal function calls relative to the start.
+        let mut internal_depth = 0i64;
+        let mut prev_location = start_location;
+
+        let mut pos = start;
+        while let Some(next) = self.next(pos) {
+            if self.depth(pos) == start_depth {
+                match self.jump(pos) {
+                    Some(Jump::In) => internal_depth += 1,
+                    Some(Jump::Out) => internal_depth -= 1,
+                    _ => {}
+                }
+            }
+            pos = next;
+
+            let depth = self.depth(pos);
+            let Some(location) = self.location(pos) else { continue };
+            let entered_line = Some(location) != prev_location;
+            prev_location = Some(location);
+
+            let returned = depth < start_depth || (depth == start_depth && internal_depth < 0);
+            let in_frame = depth < start_depth || (depth == start_depth && internal_depth <= 0);
+            let new_line = Some(location) != start_location;
+            let stop = match kind {
+                StepKind::In => new_line,
+                StepKind::Over => in_frame && new_line,
+                StepKind::Out => returned,
+                StepKind::Continue => false,
+            };
+            if stop {
+                self.position = pos;
+                return Stop::Step;
+            }
+            if entered_line && self.is_breakpoint(location) {
+                self.position = pos;
+                return Stop::Breakpoint;
+            }
+        }
+        self.position = pos;
+        Stop::Boundary
+    }
+
+    /// Steps backwards to the previous line, or to the previous breakpoint with
+    /// [`StepKind::Continue`].
+    fn step_backward(&mut self, kind: StepKind) -> Stop {
+        let start_location = self.location(self.position);
+        let mut pos = self.position;
+        let mut found = None;
+        while let Some(prev) = self.prev(pos) {
+            pos = prev;
+            let Some(location) = self.location(pos) else { continue };
+            if Some(location) == start_location {
+                continue;
+            }
+            if self.is_breakpoint(location) {
+                found = Some((location, Stop::Breakpoint));
+                break;
+            }
+            if kind != StepKind::Continue {
+                found = Some((location, Stop::Step));
+                break;
+            }
+        }
+
+        let Some((location, stop)) = found else {
+            self.position = pos;
+            return Stop::Boundary;
+        };
+        // Move to the first step of the line.
+        while let Some(prev) = self.prev(pos) {
+            if self.location(prev) != Some(location) {
+                break;
+            }
+            pos = prev;
+        }
+        self.position = pos;
+        stop
+    }
+
+    fn is_breakpoint(&self, location: Location<'_>) -> bool {
+        self.breakpoints
+            .iter()
+            .any(|(path, lines)| lines.contains(&location.line) && path.ends_with(location.path))
+    }
+
+    fn next(&self, pos: Position) -> Option<Position> {
+        let arena = &self.context.debug_arena;
+        if pos.step + 1 < arena[pos.node].steps.len() {
+            Some(Position { node: pos.node, step: pos.step + 1 })
+        } else if pos.node + 1 < arena.len() {
+            Some(Position { node: pos.node + 1, step: 0 })
+        } else {
+            None
+        }
+    }
+
+    fn prev(&self, pos: Position) -> Option<Position> {
+        if pos.step > 0 {
+            Some(Position { node: pos.node, step: pos.step - 1 })
+        } else if pos.node > 0 {
+            let node = pos.node - 1;
+            Some(Position { node, step: self.context.debug_arena[node].steps.len() - 1 })
+        } else {
+            None
+        }
+    }
+
+    fn step(&self, pos: Position) -> &'a CallTraceStep {
+        &self.context.debug_arena[pos.node].steps[pos.step]
+    }
+
+    fn depth(&self, pos: Position) -> u64 {
+        self.step(pos).depth
+    }
+
+    /// Returns the jump kind of the step, if it is a JUMP.
+    fn jump(&self, pos: Position) -> Option<Jump> {
+        let step = self.step(pos);
+        if step.op != OpCode::JUMP {
+            return None;
+        }
+        let node = &self.context.debug_arena[pos.node];
+        self.context.source_mapping(node, step.pc).ok().map(|(element, _)| element.jump())
+    }
+
+    fn location(&mut self, pos: Position) -> Option<Location<'a>> {
+        let context = self.context;
+        let node = &context.debug_arena[pos.node];
+        let (element, source) = context.source_mapping(node, node.steps[pos.step].pc).ok()?;
+        let line_starts = self
+            .line_starts
+            .entry(source.path.as_path())
+            .or_insert_with(|| line_starts(&source.source));
+        let line = line_starts.partition_point(|&start| start <= element.offset() as usize);
+        Some(Location { path: source.path.as_path(), line })
+    }
+}
+
+/// Returns the EVM call depth of the node.
+fn node_depth(node: &DebugNode) -> u64 {
+    node.steps.first().map_or(0, |step| step.depth)
+}
+
+/// Returns the storage slots of `address` accessed before the given position, with their values
+/// at that position.
+///
+/// The slots are replayed from the SLOAD and SSTORE steps, so slots that were never accessed are
+/// not known. Accesses in calls that reverted before the position are skipped, apart from the
+/// previous values of the slots they wrote, if recorded.
+fn storage(arena: &[DebugNode], pos: Position, address: Address) -> BTreeMap<U256, U256> {
+    let reverted = reverted_nodes(arena, pos.node);
+    let mut storage = BTreeMap::new();
+    // Slots written by the reverted calls being replayed, whose values read after are discarded.
+    let mut discarded = HashSet::new();
+    for (i, node) in arena[..=pos.node].iter().enumerate() {
+        if !reverted[i] {
+            discarded.clear();
+        }
+        let steps = if i == pos.node { &node.steps[..pos.step] } else { &node.steps[..] };
+        for step in steps.iter().filter(|step| step.contract == address) {
+            let Some(stack) = step.stack.as_deref() else { continue };
+            let Some(&key) = stack.last() else { continue };
+            match step.op {
+                OpCode::SLOAD if !(reverted[i] && discarded.contains(&key)) => {
+                    if let Some(&value) = step.push_stack.as_deref().and_then(|push| push.first()) {
+                        storage.insert(key, value);
+                    }
+                }
+                // The write is undone, so the slot keeps the value from before the first write.
+                OpCode::SSTORE if reverted[i] => {
+                    if discarded.insert(key) {
+                        let change = step.storage_change.as_ref();
+                        if let Some(had_value) = change.and_then(|change| change.had_value) {
+                            storage.insert(key, had_value);
+                        }
+                    }
+                }
+                OpCode::SSTORE => {
+                    if let Some(&value) = stack.len().checked_sub(2).map(|index| &stack[index]) {
+                        storage.insert(key, value);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    storage
+}
+
+/// Returns whether each node up to `current` belongs to a call that reverted before it.
+///
+/// A call reverts when its last step fails, together with all the calls it made. Calls that are
+/// still executing at `current` have not reverted yet, even if they do later.
+fn reverted_nodes(arena: &[DebugNode], current: usize) -> Vec<bool> {
+    /// A call that is executing.
+    struct Frame {
+        depth: u64,
+        /// The index of its first node.
+        start: usize,
+        /// The index of its latest node.
+        last: usize,
+    }
+
+    let mut reverted = vec![false; current + 1];
+    let mut frames = Vec::<Frame>::new();
+    for i in 0..=current {
+        let depth = node_depth(&arena[i]);
+        // A node at the depth of the innermost call continues it after a call it made, unless it
+        // follows the caller, in which case it starts a new call.
+        let follows_caller = i > 0 && node_depth(&arena[i - 1]) < depth;
+        while let Some(frame) = frames.last() {
+            if frame.depth < depth || (frame.depth == depth && !follows_caller) {
+                break;
+            }
+            let frame = frames.pop().unwrap();
+            let failed = arena[frame.last].steps.last().is_some_and(|step| !step.status.is_ok());
+            if failed {
+                reverted[frame.start..i].fill(true);
+            }
+        }
+        match frames.last_mut() {
+            Some(frame) if frame.depth == depth => frame.last = i,
+            _ => frames.push(Frame { depth, start: i, last: i }),
+        }
+    }
+    reverted
+}
+
+/// Returns the offsets at which the lines of `source` start.
+fn line_starts(source: &str) -> Vec<usize> {
+    std::iter::once(0).chain(source.match_indices('\n').map(|(i, _)| i + 1)).collect()
+}
+
+/// Splits a buffer into 32-byte words, named by their offset.
+fn words(buf: &[u8]) -> Vec<(String, String)> {
+    buf.chunks(32)
+        .enumerate()
+        .map(|(i, word)| (format!("{:#04x}", i * 32), hex::encode_prefixed(word)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::Bytes;
+    use revm::interpreter::InstructionResult;
+    use revm_inspectors::tracing::types::{CallKind, StorageChange, StorageChangeReason};
+
+    const CONTRACT: Address = Address::with_last_byte(1);
+
+    fn step(depth: u64, op: OpCode, stack: &[u64]) -> CallTraceStep {
+        CallTraceStep {
+            depth,
+            pc: 0,
+            code_section_idx: 0,
+            op,
+            contract: CONTRACT,
+            stack: Some(stack.iter().map(|&item| U256::from(item)).collect()),
+            push_stack: None,
+            memory: None,
+            returndata: Bytes::new(),
+            gas_remaining: 0,
+            gas_refund_counter: 0,
+            gas_used: 0,
+            gas_cost: 0,
+            storage_change: None,
+            status: InstructionResult::Continue,
+            immediate_bytes: None,
+            decoded: None,
+        }
+    }
+
+    fn sload(depth: u64, key: u64, value: u64) -> CallTraceStep {
+        CallTraceStep {
+            push_stack: Some(vec![U256::from(value)]),
+            ..step(depth, OpCode::SLOAD, &[key])
+        }
+    }
+
+    fn sstore(depth: u64, key: u64, value: u64) -> CallTraceStep {
+        step(depth, OpCode::SSTORE, &[value, key])
+    }
+
+    fn halt(depth: u64, op: OpCode, status: InstructionResult) -> CallTraceStep {
+        CallTraceStep { status, ..step(depth, op, &[]) }
+    }
+
+    fn node(steps: Vec<CallTraceStep>) -> DebugNode {
+        DebugNode::new(CONTRACT, CallKind::Call, steps, Bytes::new())
+    }
+
+    /// A call that writes slots 1 and 4, with a call to itself in between that writes slots 1
+    /// and 3 and reverts.
+    fn reverting_subcall() -> Vec<DebugNode> {
+        let mut overwrite = sstore(2, 3, 3);
+        overwrite.storage_change = Some(StorageChange {
+            key: U256::from(3),
+            value: U256::from(3),
+            had_value: Some(U256::from(9)),
+            reason: StorageChangeReason::SSTORE,
+        });
+        vec![
+            node(vec![sstore(1, 1, 1), sload(1, 2, 7), step(1, OpCode::CALL, &[])]),
+            node(vec![
+                sstore(2, 1, 2),
+                overwrite,
+                sload(2, 1, 2),
+                halt(2, OpCode::REVERT, InstructionResult::Revert),
+            ]),
+            node(vec![
+                sload(1, 1, 1),
+                sstore(1, 4, 4),
+                halt(1, OpCode::STOP, InstructionResult::Stop),
+            ]),
+        ]
+    }
+
+    #[test]
+    fn scope_references() {
+        for node in [0, 1, 42] {
+            for scope in Scope::ALL {
+                let reference = scope.reference(node);
+                assert!(reference > 0);
+                assert_eq!(Scope::from_reference(reference), Some((node, scope)));
+            }
+        }
+        assert_eq!(Scope::from_reference(0), None);
+    }
+
+    #[test]
+    fn line_start_offsets() {
+        let starts = line_starts("a\nbc\n\nd");
+        assert_eq!(starts, [0, 2, 5, 6]);
+        // Offset 3 (`c`) is on the second line.
+        assert_eq!(starts.partition_point(|&start| start <= 3), 2);
+    }
+
+    #[test]
+    fn reverted_calls() {
+        let arena = reverting_subcall();
+        assert_eq!(reverted_nodes(&arena, 1), [false, false]);
+        assert_eq!(reverted_nodes(&arena, 2), [false, true, false]);
+
+        // A call that reverts after a successful subcall reverts it too.
+        let mut arena = reverting_subcall();
+        arena[1].steps.last_mut().unwrap().status = InstructionResult::Return;
+        arena[2].steps.last_mut().unwrap().status = InstructionResult::Revert;
+        arena.push(node(vec![halt(0, OpCode::STOP, InstructionResult::Stop)]));
+        assert_eq!(reverted_nodes(&arena, 2), [false, false, false]);
+        assert_eq!(reverted_nodes(&arena, 3), [true, true, true, false]);
+
+        // Sibling calls at the same depth are separate calls.
+        let arena = vec![
+            node(vec![step(1, OpCode::CALL, &[])]),
+            node(vec![halt(2, OpCode::REVERT, InstructionResult::Revert)]),
+            node(vec![step(1, OpCode::CALL, &[])]),
+            node(vec![halt(2, OpCode::STOP, InstructionResult::Stop)]),
+            node(vec![halt(1, OpCode::STOP, InstructionResult::Stop)]),
+        ];
+        assert_eq!(reverted_nodes(&arena, 4), [false, true, false, false, false]);
+    }
+
+    #[test]
+    fn storage_scope() {
+        let context = DebuggerContext {
+            debug_arena: reverting_subcall(),
+            identified_contracts: Default::default(),
+            contracts_sources: Default::default(),
+            breakpoints: Default::default(),
+        };
+        let mut session = Session::new(&context, Path::new("."));
+        let mut storage = |position: Position| {
+            session.position = position;
+            let args = json!({ "variablesReference": Scope::Storage.reference(position.node) });
+            let outcome = session.handle("variables", &args);
+            outcome.response.unwrap()["variables"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|var| {
+                    format!("{}={}", var["name"].as_str().unwrap(), var["value"].as_str().unwrap())
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Slots that are read are shown as well as written ones.
+        assert_eq!(storage(Position { node: 0, step: 2 }), ["0x1=0x1", "0x2=0x7"]);
+        // The writes of the subcall are visible until it reverts.
+        assert_eq!(storage(Position { node: 1, step: 3 }), ["0x1=0x2", "0x2=0x7", "0x3=0x3"]);
+        assert_eq!(
+            storage(Position { node: 2, step: 2 }),
+            ["0x1=0x1", "0x2=0x7", "0x3=0x9", "0x4=0x4"]
+        );
+    }
+}
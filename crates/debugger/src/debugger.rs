@@ -1,10 +1,11 @@
 //! Debugger implementation.
 
-use crate::{tui::TUI, DebugNode, DebuggerBuilder, ExitReason};
+use crate::{tui::TUI, DapTransport, DebugNode, DebuggerBuilder, ExitReason};
 use alloy_primitives::map::AddressHashMap;
 use eyre::Result;
 use foundry_common::evm::Breakpoints;
-use foundry_evm_traces::debug::ContractSources;
+use foundry_compilers::artifacts::sourcemap::SourceElement;
+use foundry_evm_traces::debug::{ArtifactData, ContractSources, SourceData};
 use std::path::Path;
 
 pub struct DebuggerContext {
@@ -15,6 +16,39 @@ pub struct DebuggerContext {
     pub breakpoints: Breakpoints,
 }
 
+impl DebuggerContext {
+    /// Returns the source element and source file of the step at `pc` in the given call.
+    pub(crate) fn source_mapping(
+        &self,
+        node: &DebugNode,
+        pc: usize,
+    ) -> Result<(SourceElement, &SourceData), String> {
+        let address = &node.address;
+        let Some(contract_name) = self.identified_contracts.get(address) else {
+            return Err(format!("Unknown contract at address {address}"));
+        };
+
+        self.contracts_sources
+            .find_source_mapping(contract_name, pc as u32, node.kind.is_any_create())
+            .ok_or_else(|| format!("No source map for contract {contract_name}"))
+    }
+
+    /// Returns the source element of the step at `pc` in the given call and the artifact of its
+    /// contract, even if the element is not in a known source file.
+    pub(crate) fn source_element(
+        &self,
+        node: &DebugNode,
+        pc: usize,
+    ) -> Option<(&SourceElement, &ArtifactData)> {
+        let contract_name = self.identified_contracts.get(&node.address)?;
+        self.contracts_sources.find_source_element(
+            contract_name,
+            pc as u32,
+            node.kind.is_any_create(),
+        )
+    }
+}
+
 pub struct Debugger {
     context: DebuggerContext,
 }
@@ -63,6 +97,14 @@ pub fn try_run_tui(&mut self) -> Result<ExitReason> {
         tui.try_run()
     }
 
+    /// Serves the debugger over the Debug Adapter Protocol until the editor disconnects.
+    ///
+    /// Source paths are reported to the editor relative to `root`.
+    pub fn try_run_dap(&mut self, transport: DapTransport, root: &Path) -> Result<()> {
+        eyre::ensure!(!self.context.debug_arena.is_empty(), "debug arena is empty");
+        crate::dap::run(&self.context, root, transport)
+    }
+
     /// Dumps debugger data to file.
     pub fn dump_to_file(&mut self, path: &Path) -> Result<()> {
         eyre::ensure!(!self.context.debug_arena.is_empty(), "debug arena is empty");
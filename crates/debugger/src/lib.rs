@@ -14,6 +14,7 @@
 mod op;
 
 mod builder;
+mod dap;
 mod debugger;
 mod dump;
 mod tui;
@@ -23,5 +24,6 @@
 pub use node::DebugNode;
 
 pub use builder::DebuggerBuilder;
+pub use dap::DapTransport;
 pub use debugger::Debugger;
 pub use tui::{ExitReason, TUI};
@@ -0,0 +1,475 @@
+//! Interactive breakpoints set from the TUI command line.
+
+use crate::DebugNode;
+use alloy_primitives::{
+    map::{HashMap, HashSet},
+    Address, U256,
+};
+use revm::interpreter::OpCode;
+use revm_inspectors::tracing::types::CallTraceStep;
+use std::{fmt, path::Path, str::FromStr};
+
+/// A breakpoint set from the TUI command line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Breakpoint {
+    /// Break when execution enters the given line of a source file.
+    ///
+    /// The file matches any source path that ends with it.
+    Line { file: String, line: usize },
+    /// Break on every execution of the given opcode.
+    Opcode(OpCode),
+    /// Break on `SSTORE`s, optionally only to the given slot.
+    SlotWrite(Option<U256>),
+    /// Break when the condition on a stack or memory value becomes true.
+    Condition(Condition),
+}
+
+/// A condition on a stack or memory value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Condition {
+    pub(crate) operand: Operand,
+    pub(crate) cmp: Comparison,
+    pub(crate) value: U256,
+}
+
+/// The value compared by a [`Condition`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Operand {
+    /// The stack item at the given index, where 0 is the top of the stack.
+    Stack(usize),
+    /// The 32-byte memory word at the given offset.
+    Memory(usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// The program counters at which each source line breakpoint is hit, per contract.
+///
+/// Finding the source line of a step takes a source map lookup and counting the lines of its
+/// source file, so it is done once for every program counter of a contract, and not on every step
+/// that is searched for a breakpoint.
+#[derive(Debug, Default)]
+pub(crate) struct BreakpointPcs {
+    /// The program counters of each breakpoint, in the same order as the breakpoints, by contract
+    /// address and whether the code is init code.
+    pcs: HashMap<(Address, bool), Vec<HashSet<usize>>>,
+}
+
+impl BreakpointPcs {
+    /// Resolves the source line breakpoints to the program counters of the calls in `arena`.
+    ///
+    /// `location` returns the path and contents of the source file of a program counter of a
+    /// call and its byte offset in it, if it has a source map.
+    pub(crate) fn new<'a>(
+        breakpoints: &[Breakpoint],
+        arena: &[DebugNode],
+        location: impl Fn(&DebugNode, usize) -> Option<(&'a Path, &'a str, usize)>,
+    ) -> Self {
+        let mut pcs = HashMap::<_, Vec<HashSet<usize>>>::default();
+        if !breakpoints.iter().any(Breakpoint::needs_location) {
+            return Self { pcs };
+        }
+
+        let mut resolved = HashSet::<(Address, bool, usize)>::default();
+        for node in arena {
+            let (address, init_code) = (node.address, node.kind.is_any_create());
+            let contract_pcs = pcs
+                .entry((address, init_code))
+                .or_insert_with(|| vec![HashSet::default(); breakpoints.len()]);
+            for step in &node.steps {
+                if !resolved.insert((address, init_code, step.pc)) {
+                    continue;
+                }
+                let Some((path, source, offset)) = location(node, step.pc) else { continue };
+                for (breakpoint, breakpoint_pcs) in breakpoints.iter().zip(contract_pcs.iter_mut())
+                {
+                    if breakpoint.matches_location(path, source, offset) {
+                        breakpoint_pcs.insert(step.pc);
+                    }
+                }
+            }
+        }
+        Self { pcs }
+    }
+
+    /// Returns the program counters of the given call at which the breakpoint with the given
+    /// index is hit, if it is a source line breakpoint.
+    pub(crate) fn get(&self, node: &DebugNode, breakpoint: usize) -> Option<&HashSet<usize>> {
+        self.pcs.get(&(node.address, node.kind.is_any_create()))?.get(breakpoint)
+    }
+}
+
+impl Breakpoint {
+    /// Returns `true` if the breakpoint matches the given step.
+    ///
+    /// `line_pcs` are the program counters of the step's contract that are on the breakpoint's
+    /// source line, see [`BreakpointPcs`].
+    pub(crate) fn matches(&self, step: &CallTraceStep, line_pcs: Option<&HashSet<usize>>) -> bool {
+        match self {
+            Self::Line { .. } => line_pcs.is_some_and(|pcs| pcs.contains(&step.pc)),
+            Self::Opcode(op) => step.op == *op,
+            Self::SlotWrite(slot) => {
+                step.op == OpCode::SSTORE &&
+                    slot.is_none_or(|slot| stack_item(step, 0) == Some(slot))
+            }
+            Self::Condition(condition) => condition.matches(step),
+        }
+    }
+
+    /// Returns `true` if the breakpoint is a source line breakpoint on the line of the given
+    /// source location.
+    ///
+    /// `path` and `source` are the path and contents of the source file, and `offset` is the
+    /// byte offset of the location in it.
+    pub(crate) fn matches_location(&self, path: &Path, source: &str, offset: usize) -> bool {
+        match self {
+            // Check the path first, counting lines is comparatively expensive.
+            Self::Line { file, line } => {
+                path.to_str().is_some_and(|path| path.ends_with(file.as_str())) &&
+                    line_number(source, offset) == *line
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the breakpoint only fires on the first of consecutive matching steps.
+    ///
+    /// Lines and conditions usually hold for many steps in a row, so stopping at each of them
+    /// would make "run to next breakpoint" behave like single-stepping.
+    pub(crate) fn fires_on_enter(&self) -> bool {
+        matches!(self, Self::Line { .. } | Self::Condition(_))
+    }
+
+    /// Returns `true` if the breakpoint needs the source location of each step.
+    pub(crate) fn needs_location(&self) -> bool {
+        matches!(self, Self::Line { .. })
+    }
+}
+
+impl Condition {
+    fn matches(&self, step: &CallTraceStep) -> bool {
+        let value = match self.operand {
+            Operand::Stack(index) => stack_item(step, index),
+            Operand::Memory(offset) => step.memory.as_ref().map(|memory| {
+                let memory = memory.as_bytes();
+                let mut word = [0u8; 32];
+                if offset < memory.len() {
+                    let end = memory.len().min(offset + 32);
+                    word[..end - offset].copy_from_slice(&memory[offset..end]);
+                }
+                U256::from_be_bytes(word)
+            }),
+        };
+        value.is_some_and(|value| self.cmp.eval(value, self.value))
+    }
+}
+
+impl Comparison {
+    fn eval(self, a: U256, b: U256) -> bool {
+        match self {
+            Self::Eq => a == b,
+            Self::Ne => a != b,
+            Self::Lt => a < b,
+            Self::Le => a <= b,
+            Self::Gt => a > b,
+            Self::Ge => a >= b,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Eq => "==",
+            Self::Ne => "!=",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+        }
+    }
+}
+
+/// Returns the 1-based line number of the byte `offset` in `source`.
+pub(crate) fn line_number(source: &str, offset: usize) -> usize {
+    let offset = offset.min(source.len());
+    source.as_bytes()[..offset].iter().filter(|&&b| b == b'\n').count() + 1
+}
+
+/// Returns the stack item at `index`, where 0 is the top of the stack.
+fn stack_item(step: &CallTraceStep, index: usize) -> Option<U256> {
+    let stack = step.stack.as_ref()?;
+    stack.len().checked_sub(index + 1).map(|i| stack[i])
+}
+
+impl FromStr for Breakpoint {
+    type Err = String;
+
+    /// Parses a breakpoint in one of the following forms:
+    ///
+    /// - `<file>:<line>`, e.g. `Counter.sol:12`
+    /// - `op <opcode>`, e.g. `op SSTORE`
+    /// - `sstore [slot]`, e.g. `sstore 0x2`
+    /// - `stack[<index>] <cmp> <value>` or `memory[<offset>] <cmp> <value>`, e.g. `stack[0] ==
+    ///   0x40`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.starts_with("stack[") || s.starts_with("memory[") {
+            return s.parse().map(Self::Condition);
+        }
+
+        let mut words = s.split_whitespace();
+        match (words.next(), words.next(), words.next()) {
+            (Some("op"), Some(name), None) => parse_opcode(name).map(Self::Opcode),
+            (Some("sstore"), slot, None) => slot.map(parse_u256).transpose().map(Self::SlotWrite),
+            (Some(location), None, None) => {
+                let (file, line) =
+                    location.rsplit_once(':').ok_or_else(|| format!("invalid breakpoint: {s}"))?;
+                let line = line.parse().map_err(|_| format!("invalid line number: {line}"))?;
+                if file.is_empty() || line == 0 {
+                    return Err(format!("invalid breakpoint: {s}"));
+                }
+                Ok(Self::Line { file: file.to_string(), line })
+            }
+            _ => Err(format!("invalid breakpoint: {s}")),
+        }
+    }
+}
+
+impl FromStr for Condition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || format!("invalid condition: {s}");
+        let (kind, rest) = s.split_once('[').ok_or_else(err)?;
+        let (index, rest) = rest.split_once(']').ok_or_else(err)?;
+        let index = parse_u256(index.trim())?.try_into().map_err(|_| err())?;
+        let operand = match kind.trim() {
+            "stack" => Operand::Stack(index),
+            "memory" => Operand::Memory(index),
+            _ => return Err(err()),
+        };
+
+        let rest = rest.trim_start();
+        // Two-character operators must be checked first.
+        let (cmp, value) = [
+            ("==", Comparison::Eq),
+            ("!=", Comparison::Ne),
+            ("<=", Comparison::Le),
+            (">=", Comparison::Ge),
+            ("<", Comparison::Lt),
+            (">", Comparison::Gt),
+        ]
+        .into_iter()
+        .find_map(|(op, cmp)| rest.strip_prefix(op).map(|value| (cmp, value)))
+        .ok_or_else(err)?;
+        let value = parse_u256(value.trim())?;
+
+        Ok(Self { operand, cmp, value })
+    }
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Line { file, line } => write!(f, "{file}:{line}"),
+            Self::Opcode(op) => write!(f, "op {op}"),
+            Self::SlotWrite(None) => f.write_str("sstore"),
+            Self::SlotWrite(Some(slot)) => write!(f, "sstore {slot:#x}"),
+            Self::Condition(condition) => condition.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.operand {
+            Operand::Stack(index) => write!(f, "stack[{index}]")?,
+            Operand::Memory(offset) => write!(f, "memory[{offset:#x}]")?,
+        }
+        write!(f, " {} {:#x}", self.cmp.as_str(), self.value)
+    }
+}
+
+fn parse_opcode(name: &str) -> Result<OpCode, String> {
+    (0..=u8::MAX)
+        .filter_map(OpCode::new)
+        .find(|op| op.to_string().eq_ignore_ascii_case(name))
+        .ok_or_else(|| format!("unknown opcode: {name}"))
+}
+
+/// Parses a decimal or `0x`-prefixed hexadecimal number.
+fn parse_u256(s: &str) -> Result<U256, String> {
+    let res = if let Some(hex) = s.strip_prefix("0x") {
+        U256::from_str_radix(hex, 16)
+    } else {
+        U256::from_str_radix(s, 10)
+    };
+    res.map_err(|_| format!("invalid number: {s}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::Bytes;
+    use revm::interpreter::InstructionResult;
+    use revm_inspectors::tracing::types::CallKind;
+
+    fn step(pc: usize, op: OpCode, stack: &[u64]) -> CallTraceStep {
+        CallTraceStep {
+            depth: 1,
+            pc,
+            code_section_idx: 0,
+            op,
+            contract: Address::ZERO,
+            stack: Some(stack.iter().map(|&item| U256::from(item)).collect()),
+            push_stack: None,
+            memory: None,
+            returndata: Bytes::new(),
+            gas_remaining: 0,
+            gas_refund_counter: 0,
+            gas_used: 0,
+            gas_cost: 0,
+            storage_change: None,
+            status: InstructionResult::Continue,
+            immediate_bytes: None,
+            decoded: None,
+        }
+    }
+
+    fn node(address: Address, kind: CallKind, pcs: &[usize]) -> DebugNode {
+        let steps = pcs.iter().map(|&pc| step(pc, OpCode::JUMPDEST, &[])).collect();
+        DebugNode::new(address, kind, steps, Bytes::new())
+    }
+
+    #[test]
+    fn parse_breakpoints() {
+        assert_eq!(
+            "src/Counter.sol:12".parse::<Breakpoint>(),
+            Ok(Breakpoint::Line { file: "src/Counter.sol".to_string(), line: 12 })
+        );
+        assert_eq!("op sstore".parse::<Breakpoint>(), Ok(Breakpoint::Opcode(OpCode::SSTORE)));
+        assert_eq!("op CALL".parse::<Breakpoint>(), Ok(Breakpoint::Opcode(OpCode::CALL)));
+        assert_eq!("sstore".parse::<Breakpoint>(), Ok(Breakpoint::SlotWrite(None)));
+        assert_eq!(
+            "sstore 0x2".parse::<Breakpoint>(),
+            Ok(Breakpoint::SlotWrite(Some(U256::from(2))))
+        );
+        assert_eq!(
+            "stack[1] >= 100".parse::<Breakpoint>(),
+            Ok(Breakpoint::Condition(Condition {
+                operand: Operand::Stack(1),
+                cmp: Comparison::Ge,
+                value: U256::from(100),
+            }))
+        );
+        assert_eq!(
+            "memory[0x40]==0x80".parse::<Breakpoint>(),
+            Ok(Breakpoint::Condition(Condition {
+                operand: Operand::Memory(0x40),
+                cmp: Comparison::Eq,
+                value: U256::from(0x80),
+            }))
+        );
+
+        assert!("op NOTANOPCODE".parse::<Breakpoint>().is_err());
+        assert!("Counter.sol:0".parse::<Breakpoint>().is_err());
+        assert!("Counter.sol".parse::<Breakpoint>().is_err());
+        assert!("stack[0] = 1".parse::<Breakpoint>().is_err());
+    }
+
+    #[test]
+    fn line_numbers() {
+        let source = "a\nb\nc";
+        assert_eq!(line_number(source, 0), 1);
+        assert_eq!(line_number(source, 2), 2);
+        assert_eq!(line_number(source, 4), 3);
+        assert_eq!(line_number(source, 100), 3);
+    }
+
+    #[test]
+    fn display_roundtrip() {
+        for s in ["src/Counter.sol:12", "op SSTORE", "sstore", "sstore 0x2", "stack[0] != 0x1"] {
+            assert_eq!(s.parse::<Breakpoint>().unwrap().to_string(), s);
+        }
+    }
+
+    #[test]
+    fn matches_steps() {
+        let sstore = step(0, OpCode::SSTORE, &[7, 2]);
+        let add = step(1, OpCode::ADD, &[100, 1]);
+
+        assert!(Breakpoint::Opcode(OpCode::SSTORE).matches(&sstore, None));
+        assert!(!Breakpoint::Opcode(OpCode::SSTORE).matches(&add, None));
+        assert!(Breakpoint::SlotWrite(None).matches(&sstore, None));
+        assert!(Breakpoint::SlotWrite(Some(U256::from(2))).matches(&sstore, None));
+        assert!(!Breakpoint::SlotWrite(Some(U256::from(7))).matches(&sstore, None));
+        assert!("stack[0] == 1".parse::<Breakpoint>().unwrap().matches(&add, None));
+        assert!("stack[1] >= 100".parse::<Breakpoint>().unwrap().matches(&add, None));
+        assert!(!"stack[2] == 0".parse::<Breakpoint>().unwrap().matches(&add, None));
+
+        let line = "Counter.sol:2".parse::<Breakpoint>().unwrap();
+        let pcs = HashSet::from_iter([1]);
+        assert!(line.matches(&add, Some(&pcs)));
+        assert!(!line.matches(&sstore, Some(&pcs)));
+        assert!(!line.matches(&add, None));
+    }
+
+    #[test]
+    fn breakpoint_pcs() {
+        let source = "contract Counter {\n    uint x;\n    function f() {}\n}";
+        let path = Path::new("src/Counter.sol");
+        let breakpoints = [
+            Breakpoint::Opcode(OpCode::ADD),
+            "Counter.sol:2".parse().unwrap(),
+            "Other.sol:2".parse().unwrap(),
+            "src/Counter.sol:3".parse().unwrap(),
+        ];
+        let counter = Address::with_last_byte(1);
+        let arena = [
+            node(counter, CallKind::Create, &[0, 1]),
+            node(counter, CallKind::Call, &[0, 1, 2, 3]),
+            node(counter, CallKind::Call, &[1, 2]),
+        ];
+
+        let resolved = std::cell::Cell::new(0);
+        let pcs = BreakpointPcs::new(&breakpoints, &arena, |node, pc| {
+            resolved.set(resolved.get() + 1);
+            // Init code is all on the first line, runtime code at pc `n` is on line `n`.
+            let offset = match (node.kind.is_any_create(), pc) {
+                (true, _) | (false, 0) => 0,
+                (false, pc) => source.match_indices('\n').nth(pc - 1)?.0 + 1,
+            };
+            Some((path, source, offset))
+        });
+        // Each pc of the init and runtime code is only resolved once.
+        assert_eq!(resolved.get(), 6);
+
+        let pcs_of = |node: &DebugNode, breakpoint: usize| {
+            let mut pcs = pcs.get(node, breakpoint).unwrap().iter().copied().collect::<Vec<_>>();
+            pcs.sort_unstable();
+            pcs
+        };
+        assert_eq!(pcs_of(&arena[0], 1), Vec::<usize>::new());
+        assert_eq!(pcs_of(&arena[1], 0), Vec::<usize>::new());
+        assert_eq!(pcs_of(&arena[1], 1), [1]);
+        assert_eq!(pcs_of(&arena[2], 1), [1]);
+        assert_eq!(pcs_of(&arena[1], 2), Vec::<usize>::new());
+        assert_eq!(pcs_of(&arena[1], 3), [2]);
+        assert!(pcs.get(&node(Address::ZERO, CallKind::Call, &[]), 1).is_none());
+
+        // Nothing is resolved without source line breakpoints.
+        resolved.set(0);
+        BreakpointPcs::new(&breakpoints[..1], &arena, |_, _| {
+            resolved.set(resolved.get() + 1);
+            None
+        });
+        assert_eq!(resolved.get(), 0);
+    }
+}
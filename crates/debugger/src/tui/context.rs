@@ -1,9 +1,12 @@
 //! Debugger context and event handler implementation.
 
+use super::breakpoint::{Breakpoint, BreakpointPcs};
 use crate::{debugger::DebuggerContext, DebugNode, ExitReason};
 use alloy_primitives::{hex, Address};
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+use foundry_compilers::artifacts::sourcemap::SourceElement;
 use foundry_evm_core::buffer::BufferKind;
+use foundry_evm_traces::debug::SourceData;
 use revm::interpreter::OpCode;
 use revm_inspectors::tracing::types::{CallKind, CallTraceStep};
 use std::ops::ControlFlow;
@@ -33,6 +36,16 @@ pub(crate) struct TUIContext<'a> {
     pub(crate) show_shortcuts: bool,
     /// The currently active buffer (memory, calldata, returndata) to be drawn.
     pub(crate) active_buffer: BufferKind,
+
+    /// Breakpoints set from the command line.
+    pub(crate) breakpoints: Vec<Breakpoint>,
+    /// The program counters of the source line breakpoints, computed on the first search for a
+    /// breakpoint after they change.
+    breakpoint_pcs: Option<BreakpointPcs>,
+    /// The command line input, if the command line is open.
+    pub(crate) command: Option<String>,
+    /// The result of the last command, shown in the command line.
+    pub(crate) status: Option<Result<String, String>>,
 }
 
 impl<'a> TUIContext<'a> {
@@ -50,6 +63,11 @@ pub(crate) fn new(debugger_context: &'a mut DebuggerContext) -> Self {
             buf_utf: false,
             show_shortcuts: true,
             active_buffer: BufferKind::Memory,
+
+            breakpoints: Vec::new(),
+            breakpoint_pcs: None,
+            command: None,
+            status: None,
         }
     }
 
@@ -85,6 +103,15 @@ pub(crate) fn current_step(&self) -> &CallTraceStep {
         &self.debug_steps()[self.current_step]
     }
 
+    /// Returns the source element and source file of the step at `pc` in the given call.
+    pub(crate) fn source_mapping<'b>(
+        &'b self,
+        node: &DebugNode,
+        pc: usize,
+    ) -> Result<(SourceElement, &'b SourceData), String> {
+        self.debugger_context.source_mapping(node, pc)
+    }
+
     fn gen_opcode_list(&mut self) {
         self.opcode_list.clear();
         let debug_steps =
@@ -123,6 +150,11 @@ pub(crate) fn handle_event(&mut self, event: Event) -> ControlFlow<ExitReason> {
     }
 
     fn handle_key_event(&mut self, event: KeyEvent) -> ControlFlow<ExitReason> {
+        if self.command.is_some() {
+            self.handle_command_key(event);
+            return ControlFlow::Continue(());
+        }
+
         // Breakpoints
         if let KeyCode::Char(c) = event.code {
             if c.is_alphabetic() && self.key_buffer.starts_with('\'') {
@@ -240,6 +272,18 @@ fn handle_key_event(&mut self, event: KeyEvent) -> ControlFlow<ExitReason> {
             // Toggle help notice
             KeyCode::Char('h') => self.show_shortcuts = !self.show_shortcuts,
 
+            // Open the command line
+            KeyCode::Char(':') => {
+                self.command = Some(String::new());
+                self.status = None;
+            }
+
+            // Run to the next breakpoint
+            KeyCode::Char(']') => self.repeat(|this| this.status = this.run_to_breakpoint(true)),
+
+            // Run to the previous breakpoint
+            KeyCode::Char('[') => self.repeat(|this| this.status = this.run_to_breakpoint(false)),
+
             // Numbers for repeating commands or breakpoints
             KeyCode::Char(
                 other @ ('0' | '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' | '9' | '\''),
@@ -274,6 +318,131 @@ fn handle_breakpoint(&mut self, c: char) {
         self.key_buffer.clear();
     }
 
+    fn handle_command_key(&mut self, event: KeyEvent) {
+        let Some(command) = self.command.as_mut() else { return };
+        match event.code {
+            KeyCode::Esc => self.command = None,
+            KeyCode::Enter => {
+                let command = self.command.take().unwrap_or_default();
+                self.status = self.run_command(&command);
+            }
+            KeyCode::Backspace => {
+                if command.pop().is_none() {
+                    self.command = None;
+                }
+            }
+            KeyCode::Char(c) => command.push(c),
+            _ => {}
+        }
+    }
+
+    /// Runs a command entered in the command line.
+    ///
+    /// Returns the message to show in the command line, if any.
+    fn run_command(&mut self, command: &str) -> Option<Result<String, String>> {
+        let command = command.trim();
+        let (name, args) = command.split_once(' ').unwrap_or((command, ""));
+        let args = args.trim();
+        let res = match name {
+            "" => return None,
+            "b" | "break" => args.parse::<Breakpoint>().map(|breakpoint| {
+                let msg = format!("Breakpoint {}: {breakpoint}", self.breakpoints.len() + 1);
+                self.breakpoints.push(breakpoint);
+                self.breakpoint_pcs = None;
+                msg
+            }),
+            "d" | "delete" if args.is_empty() => {
+                self.breakpoints.clear();
+                self.breakpoint_pcs = None;
+                Ok("Deleted all breakpoints".to_string())
+            }
+            "d" | "delete" => match args.parse::<usize>() {
+                Ok(n @ 1..) if n <= self.breakpoints.len() => {
+                    let breakpoint = self.breakpoints.remove(n - 1);
+                    self.breakpoint_pcs = None;
+                    Ok(format!("Deleted breakpoint {n}: {breakpoint}"))
+                }
+                _ => Err(format!("No breakpoint {args}")),
+            },
+            "l" | "list" if self.breakpoints.is_empty() => Ok("No breakpoints".to_string()),
+            "l" | "list" => Ok(self
+                .breakpoints
+                .iter()
+                .enumerate()
+                .map(|(i, breakpoint)| format!("{}: {breakpoint}", i + 1))
+                .collect::<Vec<_>>()
+                .join(" | ")),
+            "n" | "next" => return self.run_to_breakpoint(true),
+            "p" | "prev" => return self.run_to_breakpoint(false),
+            _ => Err(format!("Unknown command: {name}")),
+        };
+        Some(res)
+    }
+
+    /// Moves to the next or previous step that hits a breakpoint, across all calls.
+    ///
+    /// Returns the message to show in the command line.
+    fn run_to_breakpoint(&mut self, forward: bool) -> Option<Result<String, String>> {
+        if self.breakpoints.is_empty() {
+            return Some(Err("No breakpoints set, add one with `:b <breakpoint>`".to_string()));
+        }
+        if self.breakpoint_pcs.is_none() {
+            let pcs = BreakpointPcs::new(&self.breakpoints, self.debug_arena(), |node, pc| {
+                let (element, source) = self.source_mapping(node, pc).ok()?;
+                Some((source.path.as_path(), source.source.as_str(), element.offset() as usize))
+            });
+            self.breakpoint_pcs = Some(pcs);
+        }
+
+        let Some((call, step, breakpoint)) = self.find_breakpoint_hit(forward) else {
+            let direction = if forward { "after" } else { "before" };
+            return Some(Err(format!("No breakpoint hit {direction} the current step")));
+        };
+        self.draw_memory.inner_call_index = call;
+        self.current_step = step;
+        Some(Ok(format!("Breakpoint {}: {}", breakpoint + 1, self.breakpoints[breakpoint])))
+    }
+
+    /// Finds the closest step after or before the current one that hits a breakpoint.
+    ///
+    /// Returns the call index, step index and breakpoint index.
+    fn find_breakpoint_hit(&self, forward: bool) -> Option<(usize, usize, usize)> {
+        let arena = self.debug_arena();
+        let (call, step) = (self.draw_memory.inner_call_index, self.current_step);
+        if forward {
+            for (i, node) in arena.iter().enumerate().skip(call) {
+                let start = if i == call { step + 1 } else { 0 };
+                for j in start..node.steps.len() {
+                    if let Some(breakpoint) = self.breakpoint_hit(node, j) {
+                        return Some((i, j, breakpoint));
+                    }
+                }
+            }
+        } else {
+            for i in (0..=call).rev() {
+                let end = if i == call { step } else { arena[i].steps.len() };
+                for j in (0..end).rev() {
+                    if let Some(breakpoint) = self.breakpoint_hit(&arena[i], j) {
+                        return Some((i, j, breakpoint));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns the index of the first breakpoint hit by the given step of a call.
+    fn breakpoint_hit(&self, node: &DebugNode, index: usize) -> Option<usize> {
+        let step = &node.steps[index];
+        let prev = index.checked_sub(1).map(|i| &node.steps[i]);
+        self.breakpoints.iter().enumerate().position(|(i, breakpoint)| {
+            let line_pcs = self.breakpoint_pcs.as_ref().and_then(|pcs| pcs.get(node, i));
+            breakpoint.matches(step, line_pcs) &&
+                !(breakpoint.fires_on_enter() &&
+                    prev.is_some_and(|prev| breakpoint.matches(prev, line_pcs)))
+        })
+    }
+
     fn handle_mouse_event(&mut self, event: MouseEvent) -> ControlFlow<ExitReason> {
         match event.kind {
             MouseEventKind::ScrollUp => self.step_back(),
@@ -1,6 +1,6 @@
 //! TUI draw implementation.
 
-use super::context::TUIContext;
+use super::{context::TUIContext, variables::VariableKind};
 use crate::op::OpcodeParam;
 use foundry_compilers::artifacts::sourcemap::SourceElement;
 use foundry_evm_core::buffer::{get_buffer_accesses, BufferKind};
@@ -32,12 +32,21 @@ fn draw_layout(&self, f: &mut Frame<'_>) {
             return;
         }
 
+        // Split off the command line.
+        let [app, command_line] =
+            Layout::new(Direction::Vertical, [Constraint::Min(0), Constraint::Length(1)])
+                .split(area)[..]
+        else {
+            unreachable!()
+        };
+        self.draw_command_line(f, command_line);
+
         // The horizontal layout draws these panes at 50% width.
         let min_column_width_for_horizontal = 200;
         if area.width >= min_column_width_for_horizontal {
-            self.horizontal_layout(f);
+            self.horizontal_layout(f, app);
         } else {
-            self.vertical_layout(f);
+            self.vertical_layout(f, app);
         }
     }
 
@@ -78,13 +87,13 @@ fn size_too_small(&self, f: &mut Frame<'_>, min_width: u16, min_height: u16) {
     /// |-----------------------------|
     /// |             buf             |
     /// |-----------------------------|
-    /// |                             |
-    /// |             src             |
-    /// |                             |
+    /// |                   |         |
+    /// |        src        |  vars   |
+    /// |                   |         |
     /// |-----------------------------|
+    /// |         command line        |
     /// ```
-    fn vertical_layout(&self, f: &mut Frame<'_>) {
-        let area = f.area();
+    fn vertical_layout(&self, f: &mut Frame<'_>, area: Rect) {
         let h_height = if self.show_shortcuts { 4 } else { 0 };
 
         // NOTE: `Layout::split` always returns a slice of the same length as the number of
@@ -100,7 +109,7 @@ fn vertical_layout(&self, f: &mut Frame<'_>) {
         };
 
         // Split the app in 4 vertically to construct all the panes.
-        let [op_pane, stack_pane, memory_pane, src_pane] = Layout::new(
+        let [op_pane, stack_pane, memory_pane, bottom] = Layout::new(
             Direction::Vertical,
             [
                 Constraint::Ratio(1, 6),
@@ -113,12 +122,21 @@ fn vertical_layout(&self, f: &mut Frame<'_>) {
             unreachable!()
         };
 
+        // Split the bottom pane horizontally to construct source and variables.
+        let [src_pane, variables_pane] =
+            Layout::new(Direction::Horizontal, [Constraint::Ratio(2, 3), Constraint::Ratio(1, 3)])
+                .split(bottom)[..]
+        else {
+            unreachable!()
+        };
+
         if self.show_shortcuts {
             self.draw_footer(f, footer);
         }
         self.draw_src(f, src_pane);
         self.draw_op_list(f, op_pane);
         self.draw_stack(f, stack_pane);
+        self.draw_variables(f, variables_pane);
         self.draw_buffer(f, memory_pane);
     }
 
@@ -128,13 +146,13 @@ fn vertical_layout(&self, f: &mut Frame<'_>) {
     /// |-----------------|-----------|
     /// |        op       |   stack   |
     /// |-----------------|-----------|
-    /// |                 |           |
-    /// |       src       |    buf    |
-    /// |                 |           |
+    /// |                 |   vars    |
+    /// |       src       |-----------|
+    /// |                 |    buf    |
     /// |-----------------|-----------|
+    /// |         command line        |
     /// ```
-    fn horizontal_layout(&self, f: &mut Frame<'_>) {
-        let area = f.area();
+    fn horizontal_layout(&self, f: &mut Frame<'_>, area: Rect) {
         let h_height = if self.show_shortcuts { 4 } else { 0 };
 
         // Split off footer.
@@ -162,11 +180,12 @@ fn horizontal_layout(&self, f: &mut Frame<'_>) {
             unreachable!()
         };
 
-        // Split right pane horizontally to construct stack and memory.
-        let [stack_pane, memory_pane] =
-            Layout::new(Direction::Vertical, [Constraint::Ratio(1, 4), Constraint::Ratio(3, 4)])
-                .split(app_right)[..]
-        else {
+        // Split right pane horizontally to construct stack, variables and memory.
+        let [stack_pane, variables_pane, memory_pane] = Layout::new(
+            Direction::Vertical,
+            [Constraint::Ratio(1, 4), Constraint::Ratio(1, 4), Constraint::Ratio(2, 4)],
+        )
+        .split(app_right)[..] else {
             unreachable!()
         };
 
@@ -176,12 +195,13 @@ fn horizontal_layout(&self, f: &mut Frame<'_>) {
         self.draw_src(f, src_pane);
         self.draw_op_list(f, op_pane);
         self.draw_stack(f, stack_pane);
+        self.draw_variables(f, variables_pane);
         self.draw_buffer(f, memory_pane);
     }
 
     fn draw_footer(&self, f: &mut Frame<'_>, area: Rect) {
         let l1 = "[q]: quit | [k/j]: prev/next op | [a/s]: prev/next jump | [c/C]: prev/next call | [g/G]: start/end | [b]: cycle memory/calldata/returndata buffers";
-        let l2 = "[t]: stack labels | [m]: buffer decoding | [shift + j/k]: scroll stack | [ctrl + j/k]: scroll buffer | ['<char>]: goto breakpoint | [h] toggle help";
+        let l2 = "[t]: stack labels | [m]: buffer decoding | [shift + j/k]: scroll stack | [ctrl + j/k]: scroll buffer | ['<char>]: goto breakpoint | [[/]]: prev/next breakpoint | [:]: command line | [h] toggle help";
         let dimmed = Style::new().add_modifier(Modifier::DIM);
         let lines =
             vec![Line::from(Span::styled(l1, dimmed)), Line::from(Span::styled(l2, dimmed))];
@@ -190,6 +210,26 @@ fn draw_footer(&self, f: &mut Frame<'_>, area: Rect) {
         f.render_widget(paragraph, area);
     }
 
+    fn draw_command_line(&self, f: &mut Frame<'_>, area: Rect) {
+        let line = if let Some(command) = &self.command {
+            Line::from(vec![Span::raw(":"), Span::raw(command.as_str()), Span::raw("█")])
+        } else {
+            match &self.status {
+                Some(Ok(msg)) => Line::from(Span::raw(msg.as_str())),
+                Some(Err(e)) => Line::from(Span::styled(e.as_str(), Style::new().fg(Color::Red))),
+                None => {
+                    let help = ":b <file>:<line> | :b op <opcode> | :b sstore [slot] | :b stack[i]/memory[offset] <cmp> <value> | :l | :d [n]";
+                    let n = self.breakpoints.len();
+                    Line::from(Span::styled(
+                        format!("{n} breakpoint(s) | {help}"),
+                        Style::new().add_modifier(Modifier::DIM),
+                    ))
+                }
+            }
+        };
+        f.render_widget(Paragraph::new(line), area);
+    }
+
     fn draw_src(&self, f: &mut Frame<'_>, area: Rect) {
         let (text_output, source_name) = self.src_text(area);
         let call_kind_text = match self.call_kind() {
@@ -342,19 +382,7 @@ fn src_text(&self, area: Rect) -> (Text<'_>, Option<&str>) {
 
     /// Returns source map, source code and source name of the current line.
     fn src_map(&self) -> Result<(SourceElement, &SourceData), String> {
-        let address = self.address();
-        let Some(contract_name) = self.debugger_context.identified_contracts.get(address) else {
-            return Err(format!("Unknown contract at address {address}"));
-        };
-
-        self.debugger_context
-            .contracts_sources
-            .find_source_mapping(
-                contract_name,
-                self.current_step().pc as u32,
-                self.debug_call().kind.is_any_create(),
-            )
-            .ok_or_else(|| format!("No source map for contract {contract_name}"))
+        self.source_mapping(self.debug_call(), self.current_step().pc)
     }
 
     fn draw_op_list(&self, f: &mut Frame<'_>, area: Rect) {
@@ -451,6 +479,38 @@ fn draw_stack(&self, f: &mut Frame<'_>, area: Rect) {
         f.render_widget(paragraph, area);
     }
 
+    fn draw_variables(&self, f: &mut Frame<'_>, area: Rect) {
+        let (title, text) = match self.variables() {
+            Ok((function, variables)) => {
+                let lines = variables
+                    .into_iter()
+                    .map(|var| {
+                        let kind_color = match var.kind {
+                            VariableKind::Parameter => Color::Cyan,
+                            VariableKind::Return => Color::Magenta,
+                            VariableKind::Local => Color::Yellow,
+                        };
+                        let value = match var.value {
+                            Some(value) => Span::raw(value),
+                            None => Span::styled("<unknown>", Style::new().fg(Color::DarkGray)),
+                        };
+                        Line::from(vec![
+                            Span::styled(format!("{: <6} ", var.kind), Style::new().fg(kind_color)),
+                            Span::raw(var.declaration),
+                            Span::raw(" = "),
+                            value,
+                        ])
+                    })
+                    .collect::<Vec<_>>();
+                (format!("Variables: {}", function.name), Text::from(lines))
+            }
+            Err(e) => ("Variables".to_string(), Text::from(e)),
+        };
+        let block = Block::default().title(title).borders(Borders::ALL);
+        let paragraph = Paragraph::new(text).block(block).wrap(Wrap { trim: true });
+        f.render_widget(paragraph, area);
+    }
+
     fn draw_buffer(&self, f: &mut Frame<'_>, area: Rect) {
         let call = self.debug_call();
         let step = self.current_step();
@@ -18,12 +18,16 @@
     time::{Duration, Instant},
 };
 
+mod breakpoint;
+
 mod context;
 use crate::debugger::DebuggerContext;
 use context::TUIContext;
 
 mod draw;
 
+mod variables;
+
 type DebuggerTerminal = Terminal<CrosstermBackend<io::Stdout>>;
 
 /// Debugger exit reason.
@@ -0,0 +1,506 @@
+//! Reconstruction of function parameters and local variables from the stack.
+
+use super::context::TUIContext;
+use alloy_dyn_abi::{DynSolType, DynSolValue};
+use alloy_primitives::U256;
+use foundry_common::fmt::format_token;
+use foundry_compilers::artifacts::sourcemap::Jump;
+use foundry_evm_traces::debug::{decode_from_memory, FunctionDefinition, VariableDeclaration};
+use revm::interpreter::OpCode;
+use revm_inspectors::tracing::types::CallTraceStep;
+use std::{fmt, path::Path};
+
+/// The kind of a [`Variable`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum VariableKind {
+    Parameter,
+    Return,
+    Local,
+}
+
+impl fmt::Display for VariableKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Parameter => "param",
+            Self::Return => "return",
+            Self::Local => "local",
+        })
+    }
+}
+
+/// A variable of the current function with its decoded value.
+#[derive(Clone, Debug)]
+pub(crate) struct Variable {
+    pub(crate) kind: VariableKind,
+    /// The declaration of the variable, e.g. `uint256[] memory values`.
+    pub(crate) declaration: String,
+    /// The decoded value, if the variable could be located on the stack.
+    pub(crate) value: Option<String>,
+}
+
+/// The type of a variable, split out of its declaration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct VariableType<'a> {
+    /// The type name, e.g. `uint256[]`.
+    ty: &'a str,
+    /// The data location, e.g. `memory`.
+    location: Option<&'a str>,
+}
+
+impl<'a> VariableType<'a> {
+    fn parse(var: &'a VariableDeclaration) -> Self {
+        let declaration = var.declaration.trim();
+        let declaration = if var.name.is_empty() {
+            declaration
+        } else {
+            declaration.strip_suffix(var.name.as_str()).unwrap_or(declaration).trim_end()
+        };
+        for location in ["memory", "storage", "calldata", "transient"] {
+            if let Some(ty) = declaration.strip_suffix(location) {
+                if ty.ends_with(char::is_whitespace) {
+                    return Self { ty: ty.trim_end(), location: Some(location) };
+                }
+            }
+        }
+        Self { ty: declaration, location: None }
+    }
+
+    /// Returns the ABI type, if this is an elementary type or an array of elementary types.
+    fn resolve(&self) -> Option<DynSolType> {
+        let ty = self.ty.strip_suffix(" payable").unwrap_or(self.ty);
+        let stem = ty.split('[').next()?;
+        let elementary = ["uint", "int", "bytes"].iter().any(|prefix| {
+            stem.strip_prefix(prefix).is_some_and(|bits| bits.chars().all(|c| c.is_ascii_digit()))
+        }) || matches!(stem, "address" | "bool" | "string");
+        if elementary {
+            DynSolType::parse(ty).ok()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of stack slots a variable of this type occupies.
+    fn stack_slots(&self) -> usize {
+        // Dynamic calldata arrays are stored as offset and length, external function pointers as
+        // address and selector.
+        let is_dynamic_calldata = self.location == Some("calldata") &&
+            (self.ty.ends_with("[]") || matches!(self.ty, "bytes" | "string"));
+        let is_external_function = self.ty.starts_with("function") && self.ty.contains("external");
+        if is_dynamic_calldata || is_external_function {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Decodes the value of a variable of this type from its stack slots.
+    fn decode(&self, slots: &[U256], step: &CallTraceStep, calldata: &[u8]) -> Option<String> {
+        let word = *slots.first()?;
+        let Some(ty) = self.resolve() else {
+            return Some(match self.location {
+                Some(location) => format!("{location} pointer {word:#x}"),
+                None => format!("{word:#x}"),
+            });
+        };
+
+        let value = match self.location {
+            None => ty.abi_decode(&word.to_be_bytes::<32>()).ok()?,
+            Some("memory") => {
+                decode_from_memory(&ty, step.memory.as_ref()?.as_bytes(), word.try_into().ok()?)?
+            }
+            Some("calldata") => {
+                let offset = word.try_into().ok()?;
+                let length = slots.get(1).and_then(|length| (*length).try_into().ok());
+                decode_from_calldata(&ty, calldata, offset, length)?
+            }
+            Some(location) => return Some(format!("{location} slot {word:#x}")),
+        };
+        Some(format_token(&value))
+    }
+}
+
+/// Decodes a calldata variable at `offset`, with the `length` taken from the stack for dynamic
+/// types.
+fn decode_from_calldata(
+    ty: &DynSolType,
+    calldata: &[u8],
+    offset: usize,
+    length: Option<usize>,
+) -> Option<DynSolValue> {
+    match ty {
+        DynSolType::Bytes | DynSolType::String => {
+            let data = calldata.get(offset..offset.checked_add(length?)?)?;
+            Some(if *ty == DynSolType::String {
+                DynSolValue::String(String::from_utf8_lossy(data).to_string())
+            } else {
+                DynSolValue::Bytes(data.to_vec())
+            })
+        }
+        DynSolType::Array(inner) | DynSolType::FixedArray(inner, _) => {
+            // Only arrays of value types are stored in place.
+            if is_dynamic(inner) ||
+                matches!(**inner, DynSolType::FixedArray(..) | DynSolType::Tuple(_))
+            {
+                return None;
+            }
+            let length = match ty {
+                DynSolType::FixedArray(_, length) => *length,
+                _ => length?,
+            };
+            let values = (0..length)
+                .map(|i| {
+                    let start = offset.checked_add(i.checked_mul(32)?)?;
+                    inner.abi_decode(calldata.get(start..start.checked_add(32)?)?).ok()
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(match ty {
+                DynSolType::FixedArray(..) => DynSolValue::FixedArray(values),
+                _ => DynSolValue::Array(values),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Returns whether `ty` is ABI-encoded with a dynamic size.
+fn is_dynamic(ty: &DynSolType) -> bool {
+    match ty {
+        DynSolType::Bytes | DynSolType::String | DynSolType::Array(_) => true,
+        DynSolType::FixedArray(inner, _) => is_dynamic(inner),
+        DynSolType::Tuple(types) => types.iter().any(is_dynamic),
+        _ => false,
+    }
+}
+
+/// The code generator that compiled a function, which determines where its variables are on the
+/// stack.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Codegen {
+    /// At the JUMPDEST entering the function, the parameters are on top of the stack, followed by
+    /// the return values and the local variables in declaration order.
+    Legacy,
+    /// At the JUMPDEST entering the function, the parameters are on top of the stack in reverse
+    /// order. The return values and local variables are placed by the Yul stack layout
+    /// generator, so they can't be located.
+    Ir,
+}
+
+impl Codegen {
+    /// Returns the stack indices of the slots of each variable, given the kind and number of
+    /// stack slots of the parameters, return values and local variables in order, and the stack
+    /// height at the function entry.
+    fn stack_indices(
+        self,
+        variables: impl IntoIterator<Item = (VariableKind, usize)>,
+        entry_stack_len: usize,
+    ) -> Vec<Option<Vec<usize>>> {
+        let variables = variables.into_iter().collect::<Vec<_>>();
+        let param_slots = variables
+            .iter()
+            .filter(|(kind, _)| *kind == VariableKind::Parameter)
+            .map(|(_, slots)| slots)
+            .sum::<usize>();
+
+        // The stack slots taken by the variables before the current one.
+        let mut taken = 0;
+        variables
+            .into_iter()
+            .map(|(kind, slots)| {
+                let indices: Option<Vec<usize>> = match self {
+                    Self::Legacy => entry_stack_len
+                        .checked_sub(param_slots)
+                        .map(|first| (first + taken..first + taken + slots).collect()),
+                    Self::Ir if kind == VariableKind::Parameter => (taken..taken + slots)
+                        .map(|slot| entry_stack_len.checked_sub(slot + 1))
+                        .collect(),
+                    Self::Ir => None,
+                };
+                taken += slots;
+                indices
+            })
+            .collect()
+    }
+}
+
+/// Finds the step at which the invocation of the function containing the step at `current` was
+/// entered.
+///
+/// Walks back the JUMPs, skipping internal calls that already returned, until the jump into the
+/// function. `jump` returns the jump type of the instruction at a program counter, and
+/// `in_function` whether a program counter is in the function.
+fn find_function_entry(
+    steps: &[CallTraceStep],
+    current: usize,
+    jump: impl Fn(usize) -> Option<Jump>,
+    in_function: impl Fn(usize) -> bool,
+) -> Option<usize> {
+    let mut depth = 0usize;
+    for i in (1..=current).rev() {
+        let prev = &steps[i - 1];
+        if prev.op != OpCode::JUMP {
+            continue;
+        }
+        match jump(prev.pc) {
+            Some(Jump::Out) => depth += 1,
+            Some(Jump::In) if depth > 0 => depth -= 1,
+            Some(Jump::In) => return in_function(steps[i].pc).then_some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+impl TUIContext<'_> {
+    /// Returns the function containing the current step and its parameters, return values and
+    /// local variables in scope.
+    ///
+    /// Variables are located relative to the stack at the JUMPDEST entering the function, in the
+    /// layout of the code generator the contract was compiled with, see [`Codegen`]. Values are
+    /// `None` when the entry of the function can't be found, e.g. because the optimizer inlined
+    /// it, or when the variable can't be located in code compiled via IR.
+    pub(crate) fn variables(&self) -> Result<(&FunctionDefinition, Vec<Variable>), String> {
+        let node = self.debug_call();
+        let step = self.current_step();
+        let (source_element, source) = self.source_mapping(node, step.pc)?;
+        let offset = source_element.offset() as usize;
+        let function =
+            source.find_function(offset).ok_or_else(|| "Not inside a function".to_string())?;
+        let codegen = match self.debugger_context.source_element(node, step.pc) {
+            Some((_, artifact)) if artifact.via_ir => Codegen::Ir,
+            _ => Codegen::Legacy,
+        };
+
+        let params = function.parameters.iter().map(|var| (VariableKind::Parameter, var));
+        let returns = function.returns.iter().map(|var| (VariableKind::Return, var));
+        let locals = function
+            .locals
+            .iter()
+            .filter(|var| var.scope.contains(&offset) && var.range.end <= offset)
+            .map(|var| (VariableKind::Local, var));
+        let variables = params
+            .chain(returns)
+            .chain(locals)
+            .map(|(kind, var)| (kind, var, VariableType::parse(var)))
+            .collect::<Vec<_>>();
+
+        let entry_stack = self
+            .function_entry(function, &source.path)
+            .and_then(|entry| self.debug_steps()[entry].stack.as_deref());
+        let indices = entry_stack.map(|entry_stack| {
+            codegen.stack_indices(
+                variables.iter().map(|(kind, _, ty)| (*kind, ty.stack_slots())),
+                entry_stack.len(),
+            )
+        });
+        // The Yul stack layout generator moves and pops parameters once they are no longer
+        // needed, so their values are read at the function entry.
+        let stack = match codegen {
+            Codegen::Legacy => step.stack.as_deref(),
+            Codegen::Ir => entry_stack,
+        }
+        .unwrap_or_default();
+
+        let variables = variables
+            .into_iter()
+            .enumerate()
+            .map(|(i, (kind, var, ty))| {
+                let value = indices
+                    .as_ref()
+                    .and_then(|indices| indices[i].as_ref())
+                    .and_then(|indices| {
+                        indices
+                            .iter()
+                            .map(|&index| stack.get(index).copied())
+                            .collect::<Option<Vec<_>>>()
+                    })
+                    .and_then(|slots| ty.decode(&slots, step, &node.calldata));
+                Variable { kind, declaration: var.declaration.clone(), value }
+            })
+            .collect();
+
+        Ok((function, variables))
+    }
+
+    /// Finds the step at which the current invocation of `function`, defined in the source file
+    /// at `path`, was entered.
+    fn function_entry(&self, function: &FunctionDefinition, path: &Path) -> Option<usize> {
+        let node = self.debug_call();
+        // Jumps into and out of the compiler generated utility functions have no source file,
+        // but still have to be counted.
+        let jump =
+            |pc| self.debugger_context.source_element(node, pc).map(|(element, _)| element.jump());
+        let in_function = |pc| {
+            self.source_mapping(node, pc).is_ok_and(|(element, source)| {
+                source.path == path && function.range.contains(&(element.offset() as usize))
+            })
+        };
+        find_function_entry(self.debug_steps(), self.current_step, jump, in_function)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{map::HashMap, Address, Bytes};
+    use revm::interpreter::InstructionResult;
+
+    fn step(pc: usize, op: OpCode) -> CallTraceStep {
+        CallTraceStep {
+            depth: 1,
+            pc,
+            code_section_idx: 0,
+            op,
+            contract: Address::ZERO,
+            stack: None,
+            push_stack: None,
+            memory: None,
+            returndata: Bytes::new(),
+            gas_remaining: 0,
+            gas_refund_counter: 0,
+            gas_used: 0,
+            gas_cost: 0,
+            storage_change: None,
+            status: InstructionResult::Continue,
+            immediate_bytes: None,
+            decoded: None,
+        }
+    }
+
+    fn declaration(declaration: &str, name: &str) -> VariableDeclaration {
+        VariableDeclaration {
+            declaration: declaration.to_string(),
+            name: name.to_string(),
+            range: 0..0,
+            scope: 0..0,
+        }
+    }
+
+    #[test]
+    fn parse_variable_types() {
+        let var = declaration("uint256[] memory values", "values");
+        let ty = VariableType::parse(&var);
+        assert_eq!(ty, VariableType { ty: "uint256[]", location: Some("memory") });
+        assert_eq!(ty.resolve(), Some(DynSolType::Array(Box::new(DynSolType::Uint(256)))));
+        assert_eq!(ty.stack_slots(), 1);
+
+        let var = declaration("bytes calldata", "");
+        let ty = VariableType::parse(&var);
+        assert_eq!(ty, VariableType { ty: "bytes", location: Some("calldata") });
+        assert_eq!(ty.stack_slots(), 2);
+
+        let var = declaration("address payable to", "to");
+        assert_eq!(VariableType::parse(&var).resolve(), Some(DynSolType::Address));
+
+        let var = declaration("IERC20 token", "token");
+        let ty = VariableType::parse(&var);
+        assert_eq!(ty, VariableType { ty: "IERC20", location: None });
+        assert_eq!(ty.resolve(), None);
+
+        // `memoryValue` is a name, not a data location.
+        let var = declaration("Info memoryValue", "memoryValue");
+        assert_eq!(VariableType::parse(&var), VariableType { ty: "Info", location: None });
+    }
+
+    #[test]
+    fn decode_calldata() {
+        let mut calldata = vec![0u8; 4];
+        calldata.extend(U256::from(1).to_be_bytes::<32>());
+        calldata.extend(U256::from(2).to_be_bytes::<32>());
+
+        let ty = DynSolType::Array(Box::new(DynSolType::Uint(256)));
+        assert_eq!(
+            decode_from_calldata(&ty, &calldata, 4, Some(2)),
+            Some(DynSolValue::Array(vec![
+                DynSolValue::Uint(U256::from(1), 256),
+                DynSolValue::Uint(U256::from(2), 256)
+            ]))
+        );
+        assert_eq!(decode_from_calldata(&ty, &calldata, 4, Some(3)), None);
+    }
+
+    #[test]
+    fn external_function_entry() {
+        use OpCode as Op;
+
+        // A public function entered through the dispatcher, which decodes the calldata with a
+        // compiler generated utility function. The function itself calls a utility function for
+        // checked arithmetic and a jump in a loop. Utility functions have no source file, so only
+        // their jump types are known.
+        let steps = [
+            step(0, Op::PUSH1),      // dispatcher
+            step(2, Op::JUMP),       // [in] to the ABI decoder
+            step(100, Op::JUMPDEST), // ABI decoder
+            step(101, Op::JUMP),     // [out] to the dispatcher
+            step(3, Op::JUMPDEST),   // dispatcher
+            step(4, Op::JUMP),       // [in] to the function
+            step(20, Op::JUMPDEST),  // function entry
+            step(21, Op::JUMP),      // [in] to the checked addition
+            step(110, Op::JUMPDEST), // checked addition
+            step(111, Op::JUMP),     // [out] to the function
+            step(22, Op::JUMPDEST),  // function
+            step(23, Op::JUMP),      // regular jump in a loop
+            step(22, Op::JUMPDEST),  // function
+        ];
+        let jumps = HashMap::<usize, Jump>::from_iter([
+            (2, Jump::In),
+            (101, Jump::Out),
+            (4, Jump::In),
+            (21, Jump::In),
+            (111, Jump::Out),
+            (23, Jump::Regular),
+        ]);
+        let jump = |pc| jumps.get(&pc).copied();
+        let in_function = |pc| (20..30).contains(&pc);
+
+        assert_eq!(find_function_entry(&steps, 12, jump, in_function), Some(6));
+        assert_eq!(find_function_entry(&steps, 6, jump, in_function), Some(6));
+        // Inside of the checked addition, not the function.
+        assert_eq!(find_function_entry(&steps, 8, jump, in_function), None);
+        // Inside of the dispatcher, which is not entered by a jump.
+        assert_eq!(find_function_entry(&steps, 4, jump, in_function), None);
+    }
+
+    #[test]
+    fn recursive_function_entry() {
+        use OpCode as Op;
+
+        let steps = [
+            step(0, Op::JUMP),      // [in] to the function
+            step(20, Op::JUMPDEST), // outer entry
+            step(21, Op::JUMP),     // [in] to the function
+            step(20, Op::JUMPDEST), // inner entry
+            step(25, Op::JUMP),     // [out] to the outer invocation
+            step(22, Op::JUMPDEST), // outer invocation
+        ];
+        let jumps =
+            HashMap::<usize, Jump>::from_iter([(0, Jump::In), (21, Jump::In), (25, Jump::Out)]);
+        let jump = |pc| jumps.get(&pc).copied();
+        let in_function = |pc| (20..30).contains(&pc);
+
+        assert_eq!(find_function_entry(&steps, 5, jump, in_function), Some(1));
+        assert_eq!(find_function_entry(&steps, 4, jump, in_function), Some(3));
+    }
+
+    #[test]
+    fn variable_stack_indices() {
+        // `f(uint256 a, bytes calldata b) returns (uint256 r)` with a local variable.
+        let variables = [
+            (VariableKind::Parameter, 1),
+            (VariableKind::Parameter, 2),
+            (VariableKind::Return, 1),
+            (VariableKind::Local, 1),
+        ];
+
+        assert_eq!(
+            Codegen::Legacy.stack_indices(variables, 6),
+            [Some(vec![3]), Some(vec![4, 5]), Some(vec![6]), Some(vec![7])]
+        );
+        assert_eq!(Codegen::Legacy.stack_indices(variables, 2), [None, None, None, None]);
+
+        assert_eq!(
+            Codegen::Ir.stack_indices(variables, 6),
+            [Some(vec![5]), Some(vec![4, 3]), None, None]
+        );
+        assert_eq!(Codegen::Ir.stack_indices(variables, 2), [Some(vec![1]), None, None, None]);
+    }
+}
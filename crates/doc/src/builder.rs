@@ -1,6 +1,6 @@
 use crate::{
-    document::DocumentContent, helpers::merge_toml_table, AsDoc, BufWriter, Document, ParseItem,
-    ParseSource, Parser, Preprocessor,
+    document::DocumentContent, helpers::merge_toml_table, lint, AsDoc, BufWriter, Document,
+    JsonDoc, LintDiagnostic, ParseItem, ParseSource, Parser, Preprocessor,
 };
 use alloy_primitives::map::HashMap;
 use forge_fmt::{FormatterConfig, Visitable};
@@ -11,11 +11,43 @@
 use rayon::prelude::*;
 use std::{
     cmp::Ordering,
-    fs,
+    fmt, fs,
     path::{Path, PathBuf},
+    str::FromStr,
 };
 use toml::value;
 
+/// The output format of the documentation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DocFormat {
+    /// An mdbook.
+    #[default]
+    Markdown,
+    /// A single JSON file, see [`JsonDoc`].
+    Json,
+}
+
+impl fmt::Display for DocFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Markdown => "markdown",
+            Self::Json => "json",
+        })
+    }
+}
+
+impl FromStr for DocFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "markdown" | "md" => Ok(Self::Markdown),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("unknown doc format `{s}`, expected `markdown` or `json`")),
+        }
+    }
+}
+
 /// Build Solidity documentation for a project from natspec comments.
 /// The builder parses the source files using [Parser],
 /// then formats and writes the elements as the output.
@@ -37,6 +69,8 @@ pub struct DocBuilder {
     pub fmt: FormatterConfig,
     /// Whether to include libraries to the output.
     pub include_libraries: bool,
+    /// The output format.
+    pub format: DocFormat,
 }
 
 // TODO: consider using `tfio`
@@ -45,6 +79,7 @@ impl DocBuilder {
     const SOL_EXT: &'static str = "sol";
     const README: &'static str = "README.md";
     const SUMMARY: &'static str = "SUMMARY.md";
+    const JSON: &'static str = "docs.json";
 
     /// Create new instance of builder.
     pub fn new(
@@ -62,6 +97,7 @@ pub fn new(
             config: DocConfig::default(),
             preprocessors: Default::default(),
             fmt: Default::default(),
+            format: Default::default(),
         }
     }
 
@@ -83,6 +119,12 @@ pub fn with_fmt(mut self, fmt: FormatterConfig) -> Self {
         self
     }
 
+    /// Set the output format on the builder.
+    pub fn with_format(mut self, format: DocFormat) -> Self {
+        self.format = format;
+        self
+    }
+
     /// Set preprocessors on the builder.
     pub fn with_preprocessor<P: Preprocessor + 'static>(mut self, preprocessor: P) -> Self {
         self.preprocessors.push(Box::new(preprocessor) as Box<dyn Preprocessor>);
@@ -96,6 +138,50 @@ pub fn out_dir(&self) -> PathBuf {
 
     /// Parse the sources and build the documentation.
     pub fn build(self) -> eyre::Result<()> {
+        let Some(documents) = self.documents()? else { return Ok(()) };
+
+        let documents = documents
+            .into_iter()
+            .filter(|d| !d.from_library || self.include_libraries)
+            .collect_vec();
+        if self.format == DocFormat::Json {
+            fs::create_dir_all(self.out_dir())?;
+            let doc = JsonDoc::new(&self.root, &documents);
+            fs::write(self.out_dir().join(Self::JSON), serde_json::to_string_pretty(&doc)?)?;
+            return Ok(())
+        }
+
+        // Write mdbook related files
+        self.write_mdbook(documents)?;
+
+        // Build the book if requested
+        if self.should_build {
+            MDBook::load(self.out_dir())
+                .and_then(|book| book.build())
+                .map_err(|err| eyre::eyre!("failed to build book: {err:?}"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Parse the sources and check their NatSpec comments against the configured lint rules.
+    ///
+    /// The paths of the returned diagnostics are relative to the project root.
+    pub fn lint(self) -> eyre::Result<Vec<LintDiagnostic>> {
+        let Some(documents) = self.documents()? else { return Ok(Vec::new()) };
+        let mut diagnostics = lint(&documents, &self.config.lint_rules)?;
+        for diagnostic in diagnostics.iter_mut() {
+            if let Ok(path) = diagnostic.path.strip_prefix(&self.root) {
+                diagnostic.path = path.to_path_buf();
+            }
+        }
+        Ok(diagnostics)
+    }
+
+    /// Parse the sources and apply the preprocessors, returning the sorted documents.
+    ///
+    /// Returns `None` if there are no sources.
+    fn documents(&self) -> eyre::Result<Option<Vec<Document>>> {
         // Expand ignore globs
         let ignored = expand_globs(&self.root, self.config.ignore.iter())?;
 
@@ -106,7 +192,7 @@ pub fn build(self) -> eyre::Result<()> {
 
         if sources.is_empty() {
             sh_println!("No sources detected at {}", self.sources.display())?;
-            return Ok(())
+            return Ok(None)
         }
 
         let library_sources = self
@@ -121,6 +207,8 @@ pub fn build(self) -> eyre::Result<()> {
             .chain(library_sources.iter().map(|path| (path, true)))
             .collect::<Vec<_>>();
 
+        // The preprocessors aren't `Sync`, so the parallel closure can't borrow the builder.
+        let (root, out, fmt) = (&self.root, &self.config.out, &self.fmt);
         let documents = combined_sources
             .par_iter()
             .enumerate()
@@ -148,7 +236,7 @@ pub fn build(self) -> eyre::Result<()> {
                 };
 
                 // Visit the parse tree
-                let mut doc = Parser::new(comments, source).with_fmt(self.fmt.clone());
+                let mut doc = Parser::new(comments, source).with_fmt(fmt.clone());
                 source_unit
                     .visit(&mut doc)
                     .map_err(|err| eyre::eyre!("Failed to parse source: {err}"))?;
@@ -180,16 +268,11 @@ pub fn build(self) -> eyre::Result<()> {
                 let mut files = remaining
                     .into_iter()
                     .map(|item| {
-                        let relative_path = path.strip_prefix(&self.root)?.join(item.filename());
-                        let target_path = self.config.out.join(Self::SRC).join(relative_path);
+                        let relative_path = path.strip_prefix(root)?.join(item.filename());
+                        let target_path = out.join(Self::SRC).join(relative_path);
                         let ident = item.source.ident();
-                        Ok(Document::new(
-                            path.clone(),
-                            target_path,
-                            from_library,
-                            self.config.out.clone(),
-                        )
-                        .with_content(DocumentContent::Single(item), ident))
+                        Ok(Document::new(path.clone(), target_path, from_library, out.clone())
+                            .with_content(DocumentContent::Single(item), ident))
                     })
                     .collect::<eyre::Result<Vec<_>>>()?;
 
@@ -205,8 +288,8 @@ pub fn build(self) -> eyre::Result<()> {
                         name.push_str(".md");
                         name
                     };
-                    let relative_path = path.strip_prefix(&self.root)?.join(filename);
-                    let target_path = self.config.out.join(Self::SRC).join(relative_path);
+                    let relative_path = path.strip_prefix(root)?.join(filename);
+                    let target_path = out.join(Self::SRC).join(relative_path);
 
                     let identity = match filestem {
                         Some(stem) if stem.to_lowercase().contains("constants") => stem.to_owned(),
@@ -215,13 +298,8 @@ pub fn build(self) -> eyre::Result<()> {
                     };
 
                     files.push(
-                        Document::new(
-                            path.clone(),
-                            target_path,
-                            from_library,
-                            self.config.out.clone(),
-                        )
-                        .with_content(DocumentContent::Constants(consts), identity),
+                        Document::new(path.clone(), target_path, from_library, out.clone())
+                            .with_content(DocumentContent::Constants(consts), identity),
                     )
                 }
 
@@ -229,16 +307,11 @@ pub fn build(self) -> eyre::Result<()> {
                 if !overloaded.is_empty() {
                     for (ident, funcs) in overloaded {
                         let filename = funcs.first().expect("no overloaded functions").filename();
-                        let relative_path = path.strip_prefix(&self.root)?.join(filename);
-                        let target_path = self.config.out.join(Self::SRC).join(relative_path);
+                        let relative_path = path.strip_prefix(root)?.join(filename);
+                        let target_path = out.join(Self::SRC).join(relative_path);
                         files.push(
-                            Document::new(
-                                path.clone(),
-                                target_path,
-                                from_library,
-                                self.config.out.clone(),
-                            )
-                            .with_content(DocumentContent::OverloadedFunctions(funcs), ident),
+                            Document::new(path.clone(), target_path, from_library, out.clone())
+                                .with_content(DocumentContent::OverloadedFunctions(funcs), ident),
                         );
                     }
                 }
@@ -256,23 +329,14 @@ pub fn build(self) -> eyre::Result<()> {
             })?;
 
         // Sort the results
-        let documents = documents.into_iter().sorted_by(|doc1, doc2| {
-            doc1.item_path.display().to_string().cmp(&doc2.item_path.display().to_string())
-        });
-
-        // Write mdbook related files
-        self.write_mdbook(
-            documents.filter(|d| !d.from_library || self.include_libraries).collect_vec(),
-        )?;
-
-        // Build the book if requested
-        if self.should_build {
-            MDBook::load(self.out_dir())
-                .and_then(|book| book.build())
-                .map_err(|err| eyre::eyre!("failed to build book: {err:?}"))?;
-        }
+        let documents = documents
+            .into_iter()
+            .sorted_by(|doc1, doc2| {
+                doc1.item_path.display().to_string().cmp(&doc2.item_path.display().to_string())
+            })
+            .collect();
 
-        Ok(())
+        Ok(Some(documents))
     }
 
     fn write_mdbook(&self, documents: Vec<Document>) -> eyre::Result<()> {
@@ -317,6 +381,9 @@ fn write_mdbook(&self, documents: Vec<Document>) -> eyre::Result<()> {
         // Write solidity syntax highlighting
         fs::write(out_dir.join("solidity.min.js"), include_str!("../static/solidity.min.js"))?;
 
+        // Write mermaid diagram rendering
+        fs::write(out_dir.join("mermaid-init.js"), include_str!("../static/mermaid-init.js"))?;
+
         // Write css files
         fs::write(out_dir.join("book.css"), include_str!("../static/book.css"))?;
 
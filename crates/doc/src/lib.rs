@@ -12,13 +12,16 @@
 extern crate tracing;
 
 mod builder;
-pub use builder::DocBuilder;
+pub use builder::{DocBuilder, DocFormat};
 
 mod document;
 pub use document::Document;
 
 mod helpers;
 
+mod lint;
+pub use lint::{lint, LintDiagnostic};
+
 mod parser;
 pub use parser::{
     error, Comment, CommentTag, Comments, CommentsRef, ParseItem, ParseSource, Parser,
@@ -28,6 +31,9 @@
 pub use preprocessor::*;
 
 mod writer;
-pub use writer::{AsDoc, AsDocResult, BufWriter, Markdown};
+pub use writer::{
+    AsDoc, AsDocResult, BufWriter, JsonAbiItem, JsonBase, JsonDoc, JsonItem, JsonNatSpec,
+    JsonParam, Markdown,
+};
 
 pub use mdbook;
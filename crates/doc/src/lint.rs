@@ -0,0 +1,372 @@
+//! NatSpec completeness checks, see [`lint`].
+
+use crate::{document::DocumentContent, CommentTag, Comments, Document, ParseItem, ParseSource};
+use alloy_primitives::map::HashMap;
+use forge_fmt::solang_ext::SafeUnwrap;
+use foundry_config::DocLintRule;
+use solang_parser::pt::{
+    ContractDefinition, ContractTy, FunctionAttribute, FunctionTy, Loc, Visibility,
+};
+use std::{fmt, fs, path::PathBuf};
+
+/// A violation of a [DocLintRule].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LintDiagnostic {
+    /// The violated rule.
+    pub rule: DocLintRule,
+    /// The path of the source file.
+    pub path: PathBuf,
+    /// The line of the item in the source file, starting at 1.
+    pub line: usize,
+    /// The description of the violation.
+    pub message: String,
+}
+
+impl fmt::Display for LintDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {} [{}]", self.path.display(), self.line, self.message, self.rule)
+    }
+}
+
+/// Checks the NatSpec comments of the documents that are not from libraries.
+///
+/// Library documents are only used to resolve `@inheritdoc` targets.
+pub fn lint(documents: &[Document], rules: &[DocLintRule]) -> eyre::Result<Vec<LintDiagnostic>> {
+    let items = documents.iter().filter_map(|document| match document.content {
+        DocumentContent::Single(ref item) => Some(item),
+        _ => None,
+    });
+    let mut linter = Linter::new(rules, items);
+    for document in documents.iter().filter(|document| !document.from_library) {
+        let src = fs::read_to_string(&document.item_path)?;
+        linter.lint_source(&document.item_path, &src, document.content.iter_items());
+    }
+
+    let mut diagnostics = linter.diagnostics;
+    diagnostics.sort_by(|a, b| (&a.path, a.line).cmp(&(&b.path, b.line)));
+    Ok(diagnostics)
+}
+
+/// The location of the items being checked.
+struct LintContext<'a> {
+    path: &'a PathBuf,
+    src: &'a str,
+    /// The contract of the items, if any.
+    contract: Option<&'a ContractDefinition>,
+}
+
+struct Linter<'a> {
+    rules: &'a [DocLintRule],
+    /// The documented contracts by name.
+    contracts: HashMap<String, &'a ParseItem>,
+    diagnostics: Vec<LintDiagnostic>,
+}
+
+impl<'a> Linter<'a> {
+    /// Creates a linter resolving `@inheritdoc` targets among the contracts of `items`.
+    fn new(rules: &'a [DocLintRule], items: impl IntoIterator<Item = &'a ParseItem>) -> Self {
+        let contracts = items
+            .into_iter()
+            .filter(|item| item.as_contract().is_some())
+            .map(|item| (item.source.ident(), item))
+            .collect();
+        Self { rules, contracts, diagnostics: Vec::new() }
+    }
+
+    /// Checks the items of a source file and the members of its contracts.
+    fn lint_source<'b>(
+        &mut self,
+        path: &PathBuf,
+        src: &str,
+        items: impl IntoIterator<Item = &'b ParseItem>,
+    ) {
+        for item in items {
+            let mut ctx = LintContext { path, src, contract: None };
+            self.lint_item(&ctx, item);
+            if let Some(contract) = item.as_contract() {
+                ctx.contract = Some(contract);
+                for child in item.children.iter() {
+                    self.lint_item(&ctx, child);
+                }
+            }
+        }
+    }
+
+    fn lint_item(&mut self, ctx: &LintContext<'_>, item: &ParseItem) {
+        let (kind, loc, params, external) = match &item.source {
+            ParseSource::Function(func) if func.ty == FunctionTy::Function => {
+                let external =
+                    ctx.contract.is_some_and(|c| matches!(c.ty, ContractTy::Interface(_))) ||
+                        func.attributes.iter().any(|attr| {
+                            matches!(
+                                attr,
+                                FunctionAttribute::Visibility(
+                                    Visibility::External(_) | Visibility::Public(_)
+                                )
+                            )
+                        });
+                let params = func
+                    .params
+                    .iter()
+                    .filter_map(|(_, param)| param.as_ref()?.name.as_ref())
+                    .map(|name| name.name.as_str())
+                    .collect::<Vec<_>>();
+                ("function", func.loc, params, external)
+            }
+            ParseSource::Event(event) => {
+                let params = event
+                    .fields
+                    .iter()
+                    .filter_map(|field| field.name.as_ref())
+                    .map(|name| name.name.as_str())
+                    .collect();
+                ("event", event.loc, params, true)
+            }
+            ParseSource::Error(error) => {
+                let params = error
+                    .fields
+                    .iter()
+                    .filter_map(|field| field.name.as_ref())
+                    .map(|name| name.name.as_str())
+                    .collect();
+                ("error", error.loc, params, true)
+            }
+            _ => return,
+        };
+        if !external {
+            return
+        }
+
+        let ident = item.source.ident();
+        let name = match ctx.contract {
+            Some(contract) => format!("{}.{ident}", contract.name.safe_unwrap().name),
+            None => ident,
+        };
+        // Items inheriting their documentation are checked with the inherited tags.
+        let line = line_of(ctx.src, loc);
+        let mut comments = item.comments.clone();
+        if let Some(inherited) = self.lint_inheritdoc(ctx, item, &name, line) {
+            for comment in inherited.iter() {
+                if !comments.contains_tag(comment) {
+                    comments.push(comment.clone());
+                }
+            }
+        }
+
+        let mut report = |rule: DocLintRule, message: String| {
+            if self.rules.contains(&rule) {
+                self.diagnostics.push(LintDiagnostic {
+                    rule,
+                    path: ctx.path.clone(),
+                    line,
+                    message,
+                });
+            }
+        };
+
+        if comments.include_tag(CommentTag::Notice).is_empty() {
+            report(DocLintRule::MissingNotice, format!("{kind} `{name}` is missing `@notice`"));
+        }
+
+        let tags = comments.include_tag(CommentTag::Param);
+        let documented = tags
+            .iter()
+            .filter_map(|comment| comment.value.split_whitespace().next())
+            .collect::<Vec<_>>();
+        for param in params.iter() {
+            if !documented.contains(param) {
+                report(
+                    DocLintRule::MissingParam,
+                    format!("{kind} `{name}` is missing `@param {param}`"),
+                );
+            }
+        }
+        for param in documented.iter() {
+            if !params.contains(param) {
+                report(
+                    DocLintRule::MismatchedParam,
+                    format!("{kind} `{name}` documents `@param {param}`, which is not a parameter"),
+                );
+            }
+        }
+    }
+
+    /// Checks the `@inheritdoc` tag of an item, if any, and returns the comments of the inherited
+    /// item.
+    fn lint_inheritdoc(
+        &mut self,
+        ctx: &LintContext<'_>,
+        item: &ParseItem,
+        name: &str,
+        line: usize,
+    ) -> Option<&'a Comments> {
+        let base = item.comments.find_inheritdoc_base()?;
+        let ident = item.source.ident();
+
+        let inherited = self.contracts.get(base).copied().and_then(|base_item| {
+            ctx.contract
+                .is_some_and(|contract| self.derives_from(contract, base))
+                .then(|| base_item.children.iter().find(|child| child.source.ident() == ident))
+                .flatten()
+        });
+        if inherited.is_none() && self.rules.contains(&DocLintRule::StaleInheritdoc) {
+            self.diagnostics.push(LintDiagnostic {
+                rule: DocLintRule::StaleInheritdoc,
+                path: ctx.path.clone(),
+                line,
+                message: format!(
+                    "`{name}` inherits the documentation of `{base}`, \
+                     which is not a base declaring `{ident}`"
+                ),
+            });
+        }
+        inherited.map(|item| &item.comments)
+    }
+
+    /// Returns whether the contract inherits from the named contract, directly or not.
+    fn derives_from(&self, contract: &ContractDefinition, base: &str) -> bool {
+        let mut stack = vec![contract];
+        let mut visited = Vec::new();
+        while let Some(contract) = stack.pop() {
+            for name in contract.base.iter().map(|b| &b.name.identifiers.last().unwrap().name) {
+                if name == base {
+                    return true
+                }
+                if visited.contains(name) {
+                    continue
+                }
+                visited.push(name.clone());
+                if let Some(contract) = self.contracts.get(name).and_then(|item| item.as_contract())
+                {
+                    stack.push(contract);
+                }
+            }
+        }
+        false
+    }
+}
+
+/// Returns the line of a location, starting at 1.
+fn line_of(src: &str, loc: Loc) -> usize {
+    src[..loc.start()].matches('\n').count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+    use forge_fmt::Visitable;
+
+    /// Lints a source file with all the rules, returning the violated rules and the messages.
+    fn lint_source(src: &str) -> Vec<(DocLintRule, String)> {
+        let (mut source, comments) = solang_parser::parse(src, 0).expect("failed to parse source");
+        let mut doc = Parser::new(comments, src.to_owned());
+        source.visit(&mut doc).expect("failed to visit source");
+        let items = doc.items();
+
+        let mut linter = Linter::new(&DocLintRule::ALL, items.iter());
+        linter.lint_source(&PathBuf::from("Test.sol"), src, items.iter());
+        linter.diagnostics.into_iter().map(|d| (d.rule, d.message)).collect()
+    }
+
+    #[test]
+    fn missing_notice() {
+        let src = r#"
+interface IVault {
+    function deposit() external;
+}
+contract Vault {
+    /// @notice Withdraws everything.
+    function withdraw() external {}
+    /// @dev Not part of the interface.
+    function sweep() internal {}
+    event Swept();
+}
+"#;
+        assert_eq!(
+            lint_source(src),
+            [
+                (
+                    DocLintRule::MissingNotice,
+                    "function `IVault.deposit` is missing `@notice`".into()
+                ),
+                (DocLintRule::MissingNotice, "event `Vault.Swept` is missing `@notice`".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_and_mismatched_params() {
+        let src = r#"
+/// @notice Thrown on a failed transfer.
+/// @param to The recipient.
+error TransferFailed(address to, uint256 amount);
+
+contract Token {
+    /// @notice Transfers tokens.
+    /// @param to The recipient.
+    /// @param value The amount.
+    function transfer(address to, uint256 amount) public {}
+    /// @notice Burns tokens.
+    /// @param amount The amount.
+    function burn(uint256 amount, bytes calldata) external {}
+}
+"#;
+        assert_eq!(
+            lint_source(src),
+            [
+                (
+                    DocLintRule::MissingParam,
+                    "error `TransferFailed` is missing `@param amount`".into()
+                ),
+                (
+                    DocLintRule::MissingParam,
+                    "function `Token.transfer` is missing `@param amount`".into()
+                ),
+                (
+                    DocLintRule::MismatchedParam,
+                    "function `Token.transfer` documents `@param value`, which is not a parameter"
+                        .into()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn stale_inheritdoc() {
+        let src = r#"
+interface IERC20 {
+    /// @notice Transfers tokens.
+    /// @param to The recipient.
+    /// @param amount The amount.
+    function transfer(address to, uint256 amount) external returns (bool);
+}
+interface IOther {
+    /// @notice Approves a spender.
+    /// @param spender The spender.
+    function approve(address spender) external;
+}
+contract Base is IERC20 {
+    /// @inheritdoc IERC20
+    function transfer(address to, uint256 amount) public virtual returns (bool) {}
+}
+contract Token is Base {
+    /// @inheritdoc IERC20
+    function transfer(address to, uint256 amount) public override returns (bool) {}
+    /// @inheritdoc IOther
+    /// @notice Approves a spender.
+    /// @param spender The spender.
+    function approve(address spender) external {}
+}
+"#;
+        assert_eq!(
+            lint_source(src),
+            [(
+                DocLintRule::StaleInheritdoc,
+                "`Token.approve` inherits the documentation of `IOther`, \
+                 which is not a base declaring `approve`"
+                    .into()
+            )]
+        );
+    }
+}
@@ -113,9 +113,9 @@ pub fn with_code(mut self, source: &str, config: FormatterConfig) -> ParserResul
         Ok(self)
     }
 
-    /// Format the item's filename.
-    pub fn filename(&self) -> String {
-        let prefix = match self.source {
+    /// Returns the kind of the item, e.g. `contract` or `function`.
+    pub fn kind(&self) -> &'static str {
+        match self.source {
             ParseSource::Contract(ref c) => match c.ty {
                 ContractTy::Contract(_) => "contract",
                 ContractTy::Abstract(_) => "abstract",
@@ -129,9 +129,12 @@ pub fn filename(&self) -> String {
             ParseSource::Struct(_) => "struct",
             ParseSource::Enum(_) => "enum",
             ParseSource::Type(_) => "type",
-        };
-        let ident = self.source.ident();
-        format!("{prefix}.{ident}.md")
+        }
+    }
+
+    /// Format the item's filename.
+    pub fn filename(&self) -> String {
+        format!("{}.{}.md", self.kind(), self.source.ident())
     }
 
     filter_children_fn!(pub fn variables(&self, Variable) -> VariableDefinition);
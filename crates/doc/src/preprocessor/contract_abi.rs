@@ -0,0 +1,46 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{document::DocumentContent, Document, ParseSource, PreprocessorOutput};
+use alloy_json_abi::JsonAbi;
+use forge_fmt::solang_ext::SafeUnwrap;
+use std::path::PathBuf;
+
+/// [ContractAbi] preprocessor id.
+pub const CONTRACT_ABI_ID: PreprocessorId = PreprocessorId("contract_abi");
+
+/// The contract ABI preprocessor.
+///
+/// It matches the documents with inner [`ParseSource::Contract`](crate::ParseSource) elements
+/// with the ABIs of the compiled contracts.
+///
+/// This preprocessor writes to [Document]'s context.
+#[derive(Debug, Default)]
+pub struct ContractAbi {
+    /// The source path, contract name and ABI of the compiled contracts.
+    pub abis: Vec<(PathBuf, String, JsonAbi)>,
+}
+
+impl Preprocessor for ContractAbi {
+    fn id(&self) -> PreprocessorId {
+        CONTRACT_ABI_ID
+    }
+
+    fn preprocess(&self, documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        for document in documents.iter() {
+            if let DocumentContent::Single(ref item) = document.content {
+                if let ParseSource::Contract(ref contract) = item.source {
+                    let name = &contract.name.safe_unwrap().name;
+                    // Artifact source paths may be relative to the project root.
+                    let abi = self.abis.iter().find(|(source, contract, _)| {
+                        contract == name && document.item_path.ends_with(source)
+                    });
+                    if let Some((_, _, abi)) = abi {
+                        document
+                            .add_context(self.id(), PreprocessorOutput::ContractAbi(abi.clone()));
+                    }
+                }
+            }
+        }
+
+        Ok(documents)
+    }
+}
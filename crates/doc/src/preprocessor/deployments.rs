@@ -21,7 +21,7 @@ pub struct Deployments {
 }
 
 /// A contract deployment.
-#[derive(Clone, Debug, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Deployment {
     /// The contract address
     pub address: Address,
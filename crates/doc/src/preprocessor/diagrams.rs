@@ -0,0 +1,452 @@
+use super::{Preprocessor, PreprocessorId};
+use crate::{document::DocumentContent, Document, ParseSource, PreprocessorOutput};
+use alloy_primitives::map::HashMap;
+use forge_fmt::solang_ext::SafeUnwrap;
+use solang_parser::pt::{
+    CatchClause, ContractDefinition, ContractPart, ContractTy, Expression, FunctionAttribute,
+    FunctionDefinition, FunctionTy, Statement, Visibility,
+};
+use std::fmt::Write;
+
+/// [Diagrams] preprocessor id.
+pub const DIAGRAMS_ID: PreprocessorId = PreprocessorId("diagrams");
+
+/// The diagrams preprocessor.
+///
+/// It matches the documents with inner [`ParseSource::Contract`](crate::ParseSource) elements
+/// and renders [Mermaid](https://mermaid.js.org) diagrams of their inheritance tree and,
+/// optionally, of the calls they make to external functions of the other project contracts.
+///
+/// This preprocessor writes to [Document]'s context.
+#[derive(Debug, Default)]
+pub struct Diagrams {
+    /// Whether to render the call graph of the contracts.
+    pub call_graph: bool,
+}
+
+/// The Mermaid diagrams of a contract.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ContractDiagrams {
+    /// The inheritance tree, if the contract has any bases.
+    pub inheritance: Option<String>,
+    /// The calls to external functions of other project contracts, if any.
+    pub call_graph: Option<String>,
+}
+
+impl Preprocessor for Diagrams {
+    fn id(&self) -> PreprocessorId {
+        DIAGRAMS_ID
+    }
+
+    fn preprocess(&self, documents: Vec<Document>) -> Result<Vec<Document>, eyre::Error> {
+        let contracts = documents
+            .iter()
+            .filter_map(|document| match document.content {
+                DocumentContent::Single(ref item) => item.as_contract(),
+                _ => None,
+            })
+            .map(|contract| (contract.name.safe_unwrap().name.as_str(), contract))
+            .collect::<HashMap<_, _>>();
+
+        for document in documents.iter() {
+            if let DocumentContent::Single(ref item) = document.content {
+                if let ParseSource::Contract(ref contract) = item.source {
+                    let diagrams = ContractDiagrams {
+                        inheritance: inheritance_graph(contract, &contracts),
+                        call_graph: self
+                            .call_graph
+                            .then(|| call_graph(contract, &contracts))
+                            .flatten(),
+                    };
+                    if diagrams != ContractDiagrams::default() {
+                        document.add_context(self.id(), PreprocessorOutput::Diagrams(diagrams));
+                    }
+                }
+            }
+        }
+
+        Ok(documents)
+    }
+}
+
+/// Returns the names of the direct bases of a contract.
+fn bases(contract: &ContractDefinition) -> impl Iterator<Item = &str> {
+    contract.base.iter().map(|base| base.name.identifiers.last().unwrap().name.as_str())
+}
+
+/// Returns the contract followed by its documented bases, each of them only once.
+fn linearize<'a>(
+    contract: &'a ContractDefinition,
+    contracts: &HashMap<&str, &'a ContractDefinition>,
+) -> Vec<&'a ContractDefinition> {
+    let mut all = vec![contract];
+    let mut i = 0;
+    while i < all.len() {
+        for base in bases(all[i]) {
+            if let Some(base) = contracts.get(base) {
+                if !all.iter().any(|c| std::ptr::eq(*c, *base)) {
+                    all.push(base);
+                }
+            }
+        }
+        i += 1;
+    }
+    all
+}
+
+/// Renders the inheritance tree of a contract, with an edge from each contract to its bases.
+fn inheritance_graph(
+    contract: &ContractDefinition,
+    contracts: &HashMap<&str, &ContractDefinition>,
+) -> Option<String> {
+    if contract.base.is_empty() {
+        return None
+    }
+
+    let mut nodes = Nodes::default();
+    let mut graph = String::from("graph BT\n");
+    for derived in linearize(contract, contracts) {
+        let name = &derived.name.safe_unwrap().name;
+        for base in bases(derived) {
+            let (derived, base) = (nodes.node(name), nodes.node(base));
+            writeln!(graph, "    {derived} --> {base}").unwrap();
+        }
+    }
+    Some(graph.trim_end().to_string())
+}
+
+/// Renders the calls from the functions of a contract to the external functions of the other
+/// project contracts.
+fn call_graph(
+    contract: &ContractDefinition,
+    contracts: &HashMap<&str, &ContractDefinition>,
+) -> Option<String> {
+    let name = &contract.name.safe_unwrap().name;
+
+    // The state variables of the contract and its bases with a contract type.
+    let mut state = HashMap::default();
+    for c in linearize(contract, contracts).into_iter().rev() {
+        for part in c.parts.iter() {
+            if let ContractPart::VariableDefinition(var) = part {
+                if let (Some(ident), Some(ty)) = (&var.name, contract_type(&var.ty, contracts)) {
+                    state.insert(ident.name.clone(), ty);
+                }
+            }
+        }
+    }
+
+    let mut edges = Vec::new();
+    for part in contract.parts.iter() {
+        let ContractPart::FunctionDefinition(func) = part else { continue };
+        let Some(body) = &func.body else { continue };
+
+        let mut calls = CallCollector { contracts, vars: state.clone(), calls: Vec::new() };
+        for (_, param) in func.params.iter().chain(func.returns.iter()) {
+            if let Some(param) = param {
+                calls.declare(param.name.as_ref().map(|n| n.name.as_str()), &param.ty);
+            }
+        }
+        calls.visit_statement(body);
+
+        let caller = format!("{name}.{}", function_name(func));
+        for callee in calls.calls {
+            let edge = (caller.clone(), callee);
+            if !edges.contains(&edge) {
+                edges.push(edge);
+            }
+        }
+    }
+
+    if edges.is_empty() {
+        return None
+    }
+
+    let mut nodes = Nodes::default();
+    let mut graph = String::from("graph LR\n");
+    for (caller, callee) in edges.iter() {
+        let (caller, callee) = (nodes.node(caller), nodes.node(callee));
+        writeln!(graph, "    {caller} --> {callee}").unwrap();
+    }
+    Some(graph.trim_end().to_string())
+}
+
+/// The nodes of a diagram.
+///
+/// Nodes have generated ids and quoted labels, since names like `Vault.withdraw` or `end` are not
+/// valid node ids.
+#[derive(Default)]
+struct Nodes<'a>(Vec<&'a str>);
+
+impl<'a> Nodes<'a> {
+    /// Returns the node with the given label, as written in an edge.
+    fn node(&mut self, label: &'a str) -> String {
+        let idx = self.0.iter().position(|n| *n == label).unwrap_or_else(|| {
+            self.0.push(label);
+            self.0.len() - 1
+        });
+        format!("n{idx}[\"{label}\"]")
+    }
+}
+
+/// Returns the name of a function as shown in the call graph.
+fn function_name(func: &FunctionDefinition) -> String {
+    func.name.as_ref().map_or(func.ty.to_string(), |n| n.name.to_owned())
+}
+
+/// Returns the name of the project contract of a type expression, if any.
+fn contract_type<'a>(
+    ty: &Expression,
+    contracts: &HashMap<&str, &'a ContractDefinition>,
+) -> Option<&'a ContractDefinition> {
+    match ty {
+        Expression::Variable(ident) => contracts.get(ident.name.as_str()).copied(),
+        _ => None,
+    }
+}
+
+/// Returns whether the contract or one of its bases has an external or public function with the
+/// given name.
+fn has_external_function(
+    contract: &ContractDefinition,
+    name: &str,
+    contracts: &HashMap<&str, &ContractDefinition>,
+) -> bool {
+    linearize(contract, contracts).into_iter().any(|c| {
+        c.parts.iter().any(|part| {
+            let ContractPart::FunctionDefinition(func) = part else { return false };
+            func.ty == FunctionTy::Function &&
+                func.name.as_ref().is_some_and(|n| n.name == name) &&
+                (matches!(c.ty, ContractTy::Interface(_)) ||
+                    func.attributes.iter().any(|attr| {
+                        matches!(
+                            attr,
+                            FunctionAttribute::Visibility(
+                                Visibility::External(_) | Visibility::Public(_)
+                            )
+                        )
+                    }))
+        })
+    })
+}
+
+/// Collects the external calls of a function body.
+struct CallCollector<'a, 'b> {
+    contracts: &'b HashMap<&'b str, &'a ContractDefinition>,
+    /// The variables in scope with a contract type.
+    vars: HashMap<String, &'a ContractDefinition>,
+    /// The called functions, as `Contract.function`.
+    calls: Vec<String>,
+}
+
+impl CallCollector<'_, '_> {
+    /// Records the type of a variable, shadowing any variable with the same name.
+    fn declare(&mut self, name: Option<&str>, ty: &Expression) {
+        let Some(name) = name else { return };
+        match contract_type(ty, self.contracts) {
+            Some(contract) => self.vars.insert(name.to_string(), contract),
+            None => self.vars.remove(name),
+        };
+    }
+
+    /// Returns the contract the member of an expression is accessed on, if it's a variable or a
+    /// conversion of a project contract type, or a library.
+    fn receiver(&self, expr: &Expression) -> Option<&ContractDefinition> {
+        match expr {
+            Expression::Variable(ident) => self.vars.get(&ident.name).copied().or_else(|| {
+                contract_type(expr, self.contracts)
+                    .filter(|contract| matches!(contract.ty, ContractTy::Library(_)))
+            }),
+            Expression::FunctionCall(_, ty, args) if args.len() == 1 => {
+                contract_type(ty, self.contracts)
+            }
+            Expression::Parenthesis(_, expr) => self.receiver(expr),
+            _ => None,
+        }
+    }
+
+    fn visit_call(&mut self, callee: &Expression) {
+        let callee = match callee {
+            Expression::FunctionCallBlock(_, callee, _) => callee.as_ref(),
+            callee => callee,
+        };
+        if let Expression::MemberAccess(_, receiver, member) = callee {
+            if let Some(contract) = self.receiver(receiver) {
+                if has_external_function(contract, &member.name, self.contracts) {
+                    let call = format!("{}.{}", contract.name.safe_unwrap().name, member.name);
+                    if !self.calls.contains(&call) {
+                        self.calls.push(call);
+                    }
+                }
+            }
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expression) {
+        match expr {
+            Expression::FunctionCall(_, callee, args) => {
+                self.visit_call(callee);
+                self.visit_expr(callee);
+                args.iter().for_each(|arg| self.visit_expr(arg));
+            }
+            Expression::NamedFunctionCall(_, callee, args) => {
+                self.visit_call(callee);
+                self.visit_expr(callee);
+                args.iter().for_each(|arg| self.visit_expr(&arg.expr));
+            }
+            Expression::FunctionCallBlock(_, callee, block) => {
+                self.visit_expr(callee);
+                self.visit_statement(block);
+            }
+            Expression::MemberAccess(_, expr, _) |
+            Expression::Parenthesis(_, expr) |
+            Expression::New(_, expr) => self.visit_expr(expr),
+            Expression::ArraySubscript(_, expr, index) => {
+                self.visit_expr(expr);
+                index.iter().for_each(|index| self.visit_expr(index));
+            }
+            Expression::ArraySlice(_, expr, start, end) => {
+                self.visit_expr(expr);
+                start.iter().chain(end.iter()).for_each(|expr| self.visit_expr(expr));
+            }
+            Expression::ConditionalOperator(_, cond, left, right) => {
+                self.visit_expr(cond);
+                self.visit_expr(left);
+                self.visit_expr(right);
+            }
+            Expression::ArrayLiteral(_, exprs) => {
+                exprs.iter().for_each(|expr| self.visit_expr(expr))
+            }
+            _ => {
+                let (left, right) = expr.components();
+                left.into_iter().chain(right).for_each(|expr| self.visit_expr(expr));
+            }
+        }
+    }
+
+    fn visit_statement(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::Block { statements, .. } => {
+                statements.iter().for_each(|stmt| self.visit_statement(stmt))
+            }
+            Statement::Args(_, args) => args.iter().for_each(|arg| self.visit_expr(&arg.expr)),
+            Statement::If(_, cond, then, otherwise) => {
+                self.visit_expr(cond);
+                self.visit_statement(then);
+                otherwise.iter().for_each(|stmt| self.visit_statement(stmt));
+            }
+            Statement::While(_, cond, body) => {
+                self.visit_expr(cond);
+                self.visit_statement(body);
+            }
+            Statement::DoWhile(_, body, cond) => {
+                self.visit_statement(body);
+                self.visit_expr(cond);
+            }
+            Statement::For(_, init, cond, next, body) => {
+                init.iter().for_each(|stmt| self.visit_statement(stmt));
+                cond.iter().chain(next.iter()).for_each(|expr| self.visit_expr(expr));
+                body.iter().for_each(|stmt| self.visit_statement(stmt));
+            }
+            Statement::Expression(_, expr) | Statement::Emit(_, expr) => self.visit_expr(expr),
+            Statement::VariableDefinition(_, decl, init) => {
+                init.iter().for_each(|expr| self.visit_expr(expr));
+                self.declare(decl.name.as_ref().map(|n| n.name.as_str()), &decl.ty);
+            }
+            Statement::Return(_, expr) => expr.iter().for_each(|expr| self.visit_expr(expr)),
+            Statement::Revert(_, _, args) => args.iter().for_each(|arg| self.visit_expr(arg)),
+            Statement::RevertNamedArgs(_, _, args) => {
+                args.iter().for_each(|arg| self.visit_expr(&arg.expr))
+            }
+            Statement::Try(_, expr, returns, catches) => {
+                self.visit_expr(expr);
+                if let Some((params, body)) = returns {
+                    for (_, param) in params.iter() {
+                        if let Some(param) = param {
+                            self.declare(param.name.as_ref().map(|n| n.name.as_str()), &param.ty);
+                        }
+                    }
+                    self.visit_statement(body);
+                }
+                for catch in catches.iter() {
+                    match catch {
+                        CatchClause::Simple(_, _, body) | CatchClause::Named(_, _, _, body) => {
+                            self.visit_statement(body)
+                        }
+                    }
+                }
+            }
+            Statement::Assembly { .. } |
+            Statement::Continue(_) |
+            Statement::Break(_) |
+            Statement::Error(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solang_parser::pt::SourceUnitPart;
+
+    fn diagrams(src: &str, name: &str) -> ContractDiagrams {
+        let (pt, _) = solang_parser::parse(src, 0).unwrap();
+        let contracts =
+            pt.0.iter()
+                .filter_map(|part| match part {
+                    SourceUnitPart::ContractDefinition(contract) => Some(&**contract),
+                    _ => None,
+                })
+                .map(|contract| (contract.name.safe_unwrap().name.as_str(), contract))
+                .collect::<HashMap<_, _>>();
+        let contract = contracts[name];
+        ContractDiagrams {
+            inheritance: inheritance_graph(contract, &contracts),
+            call_graph: call_graph(contract, &contracts),
+        }
+    }
+
+    #[test]
+    fn renders_diagrams() {
+        let src = r#"
+interface IERC20 {
+    function transfer(address to, uint256 amount) external returns (bool);
+}
+library Math {
+    function max(uint256 a, uint256 b) internal pure returns (uint256) {}
+}
+contract Owned {}
+contract Base is Owned, IERC20 {
+    IERC20 internal token;
+    function transfer(address, uint256) external returns (bool) {}
+}
+contract Vault is Base, Owned {
+    function withdraw(address to, uint256 amount) external {
+        require(token.transfer(to, Math.max(amount, 1)));
+    }
+    function sweep(address other) public {
+        IERC20(other).transfer(msg.sender, 1);
+        Base base = Base(other);
+        if (base.transfer(msg.sender, 2)) {}
+        token.transfer(msg.sender, 3);
+    }
+}
+"#;
+        assert_eq!(diagrams(src, "Owned"), ContractDiagrams::default());
+        let vault = diagrams(src, "Vault");
+        assert_eq!(
+            vault.inheritance.unwrap(),
+            r#"graph BT
+    n0["Vault"] --> n1["Base"]
+    n0["Vault"] --> n2["Owned"]
+    n1["Base"] --> n2["Owned"]
+    n1["Base"] --> n3["IERC20"]"#
+        );
+        assert_eq!(
+            vault.call_graph.unwrap(),
+            r#"graph LR
+    n0["Vault.withdraw"] --> n1["IERC20.transfer"]
+    n2["Vault.sweep"] --> n1["IERC20.transfer"]
+    n2["Vault.sweep"] --> n3["Base.transfer"]"#
+        );
+    }
+}
@@ -1,6 +1,7 @@
 //! Module containing documentation preprocessors.
 
 use crate::{Comments, Document};
+use alloy_json_abi::JsonAbi;
 use alloy_primitives::map::HashMap;
 use std::{fmt::Debug, path::PathBuf};
 
@@ -19,6 +20,12 @@
 mod deployments;
 pub use deployments::{Deployment, Deployments, DEPLOYMENTS_ID};
 
+mod contract_abi;
+pub use contract_abi::{ContractAbi, CONTRACT_ABI_ID};
+
+mod diagrams;
+pub use diagrams::{ContractDiagrams, Diagrams, DIAGRAMS_ID};
+
 /// The preprocessor id.
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct PreprocessorId(&'static str);
@@ -40,6 +47,12 @@ pub enum PreprocessorOutput {
     /// The deployments output.
     /// The deployment address of the item path.
     Deployments(Vec<Deployment>),
+    /// The contract ABI output.
+    /// The ABI of the compiled contract.
+    ContractAbi(JsonAbi),
+    /// The diagrams output.
+    /// The Mermaid diagrams of the contract.
+    Diagrams(ContractDiagrams),
 }
 
 /// Trait for preprocessing and/or modifying existing documents
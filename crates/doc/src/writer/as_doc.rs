@@ -3,7 +3,7 @@
     parser::ParseSource,
     writer::BufWriter,
     CommentTag, Comments, CommentsRef, Document, Markdown, PreprocessorOutput,
-    CONTRACT_INHERITANCE_ID, DEPLOYMENTS_ID, GIT_SOURCE_ID, INHERITDOC_ID,
+    CONTRACT_INHERITANCE_ID, DEPLOYMENTS_ID, DIAGRAMS_ID, GIT_SOURCE_ID, INHERITDOC_ID,
 };
 use forge_fmt::solang_ext::SafeUnwrap;
 use itertools::Itertools;
@@ -174,8 +174,24 @@ fn as_doc(&self) -> AsDocResult {
                             writer.writeln()?;
                         }
 
+                        let diagrams = read_context!(self, DIAGRAMS_ID, Diagrams);
+                        if let Some(inheritance) =
+                            diagrams.as_ref().and_then(|d| d.inheritance.as_ref())
+                        {
+                            writer.write_mermaid(inheritance)?;
+                            writer.writeln()?;
+                        }
+
                         writer.writeln_doc(&item.comments)?;
 
+                        if let Some(call_graph) =
+                            diagrams.as_ref().and_then(|d| d.call_graph.as_ref())
+                        {
+                            writer.write_subtitle("Call Graph")?;
+                            writer.write_mermaid(call_graph)?;
+                            writer.writeln()?;
+                        }
+
                         if let Some(state_vars) = item.variables() {
                             writer.write_subtitle("State Variables")?;
                             state_vars.into_iter().try_for_each(|(item, comments, code)| {
@@ -9,6 +9,9 @@
 /// Solidity language name.
 const SOLIDITY: &str = "solidity";
 
+/// Mermaid diagram language name.
+const MERMAID: &str = "mermaid";
+
 /// Headers and separator for rendering parameter table.
 const PARAM_TABLE_HEADERS: &[&str] = &["Name", "Type", "Description"];
 static PARAM_TABLE_SEPARATOR: LazyLock<String> =
@@ -109,6 +112,11 @@ pub fn write_code(&mut self, code: &str) -> fmt::Result {
         writeln!(self.buf, "{}", Markdown::CodeBlock(SOLIDITY, code))
     }
 
+    /// Writes a mermaid diagram block to the buffer.
+    pub fn write_mermaid(&mut self, diagram: &str) -> fmt::Result {
+        writeln!(self.buf, "{}", Markdown::CodeBlock(MERMAID, diagram))
+    }
+
     /// Write an item section to the buffer. First write comments, the item itself as code.
     pub fn write_section(&mut self, comments: &Comments, code: &str) -> fmt::Result {
         self.writeln_raw(comments.as_doc()?)?;
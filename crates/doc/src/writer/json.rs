@@ -0,0 +1,257 @@
+use crate::{
+    document::read_context, CommentTag, Comments, Deployment, Document, ParseItem,
+    PreprocessorOutput, CONTRACT_ABI_ID, CONTRACT_INHERITANCE_ID, DEPLOYMENTS_ID, GIT_SOURCE_ID,
+    INHERITDOC_ID,
+};
+use alloy_json_abi::JsonAbi;
+use serde::Serialize;
+use std::{collections::BTreeMap, path::Path};
+
+/// The JSON documentation of a project.
+///
+/// The schema is versioned with [`JsonDoc::VERSION`], which is bumped on breaking changes.
+#[derive(Clone, Debug, Serialize)]
+pub struct JsonDoc {
+    /// The version of the schema.
+    pub version: u32,
+    /// The documented items, in the order of their source paths.
+    pub items: Vec<JsonItem>,
+}
+
+/// A documented item, e.g. a contract or one of its functions.
+#[derive(Clone, Debug, Serialize)]
+pub struct JsonItem {
+    /// The kind of the item, e.g. `contract`, `interface` or `function`.
+    pub kind: &'static str,
+    /// The name of the item.
+    pub name: String,
+    /// The source path of the item, relative to the project root.
+    ///
+    /// Only set for top-level items.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// The link to the source file in the repository, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_url: Option<String>,
+    /// The formatted declaration of the item.
+    pub code: String,
+    /// The NatSpec comments of the item.
+    pub natspec: JsonNatSpec,
+    /// The contracts inherited by a contract.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub inherits: Vec<JsonBase>,
+    /// The deployments of a contract.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub deployments: Vec<Deployment>,
+    /// The ABI signatures of a contract.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub abi: Vec<JsonAbiItem>,
+    /// The members of a contract.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<Self>,
+}
+
+/// The NatSpec comments of an item.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct JsonNatSpec {
+    pub title: Option<String>,
+    pub author: Vec<String>,
+    pub notice: Option<String>,
+    pub dev: Option<String>,
+    /// The documented parameters, in order of appearance.
+    pub params: Vec<JsonParam>,
+    pub returns: Vec<String>,
+    /// The base contract of an `@inheritdoc` tag.
+    pub inheritdoc: Option<String>,
+    /// The `@custom:<name>` tags, keyed by name.
+    pub custom: BTreeMap<String, String>,
+}
+
+/// A documented parameter.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct JsonParam {
+    pub name: String,
+    pub description: String,
+}
+
+/// An inherited contract.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct JsonBase {
+    pub name: String,
+    /// The source path of the base contract relative to the project root, if it is documented.
+    pub path: Option<String>,
+}
+
+/// A function, event or error of a contract ABI.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct JsonAbiItem {
+    /// One of `function`, `event` or `error`.
+    pub kind: &'static str,
+    /// The signature, e.g. `transfer(address,uint256)`.
+    pub signature: String,
+    /// The hex-encoded selector, or topic for events.
+    pub selector: String,
+}
+
+impl JsonDoc {
+    /// The current version of the schema.
+    pub const VERSION: u32 = 1;
+
+    /// Creates the JSON documentation of the given documents.
+    pub fn new(root: &Path, documents: &[Document]) -> Self {
+        let relative = |path: &Path| path.strip_prefix(root).unwrap_or(path).display().to_string();
+
+        let mut items = Vec::new();
+        for document in documents {
+            let source_url = read_context!(document, GIT_SOURCE_ID, GitSource);
+            let deployments =
+                read_context!(document, DEPLOYMENTS_ID, Deployments).unwrap_or_default();
+            let linked = read_context!(document, CONTRACT_INHERITANCE_ID, ContractInheritance)
+                .unwrap_or_default();
+            let abi = read_context!(document, CONTRACT_ABI_ID, ContractAbi);
+            let inheritdocs = read_context!(document, INHERITDOC_ID, Inheritdoc);
+
+            for item in document.content.iter_items() {
+                let mut json = JsonItem::new(item, &item.comments);
+                json.path = Some(relative(&document.item_path));
+                json.source_url = source_url.clone();
+                json.deployments = deployments.clone();
+                json.abi = abi.as_ref().map(abi_items).unwrap_or_default();
+                if let Some(contract) = item.as_contract() {
+                    json.inherits = contract
+                        .base
+                        .iter()
+                        .map(|base| {
+                            let name = base.name.identifiers.last().unwrap().name.clone();
+                            // The inheritance links point to the output files of the bases.
+                            let path = linked.get(&name).and_then(|target| {
+                                documents
+                                    .iter()
+                                    .find(|doc| &doc.target_path == target)
+                                    .map(|doc| relative(&doc.item_path))
+                            });
+                            JsonBase { name, path }
+                        })
+                        .collect();
+                }
+                json.members = item
+                    .children
+                    .iter()
+                    .map(|child| {
+                        let comments = child
+                            .comments
+                            .merge_inheritdoc(&child.source.ident(), inheritdocs.clone());
+                        JsonItem::new(child, &comments)
+                    })
+                    .collect();
+                items.push(json);
+            }
+        }
+
+        Self { version: Self::VERSION, items }
+    }
+}
+
+impl JsonItem {
+    fn new(item: &ParseItem, comments: &Comments) -> Self {
+        Self {
+            kind: item.kind(),
+            name: item.source.ident(),
+            path: None,
+            source_url: None,
+            code: item.code.clone(),
+            natspec: JsonNatSpec::new(comments),
+            inherits: Vec::new(),
+            deployments: Vec::new(),
+            abi: Vec::new(),
+            members: Vec::new(),
+        }
+    }
+}
+
+impl JsonNatSpec {
+    /// Collects the NatSpec tags of the given comments.
+    pub fn new(comments: &Comments) -> Self {
+        let join = |tag: CommentTag| {
+            let tagged = comments.include_tag(tag);
+            let values = tagged.iter().map(|c| c.value.trim()).collect::<Vec<_>>();
+            (!values.is_empty()).then(|| values.join("\n"))
+        };
+
+        let mut natspec = Self {
+            title: join(CommentTag::Title),
+            notice: join(CommentTag::Notice),
+            dev: join(CommentTag::Dev),
+            ..Default::default()
+        };
+        for comment in comments.iter() {
+            match &comment.tag {
+                CommentTag::Author => natspec.author.push(comment.value.trim().to_string()),
+                CommentTag::Param => {
+                    let (name, description) =
+                        comment.split_first_word().unwrap_or((comment.value.trim(), ""));
+                    natspec.params.push(JsonParam {
+                        name: name.to_string(),
+                        description: description.trim().to_string(),
+                    });
+                }
+                CommentTag::Return => natspec.returns.push(comment.value.trim().to_string()),
+                CommentTag::Inheritdoc => {
+                    natspec.inheritdoc = comment.value.split_whitespace().next().map(str::to_string)
+                }
+                CommentTag::Custom(name) => {
+                    natspec.custom.insert(name.clone(), comment.value.trim().to_string());
+                }
+                CommentTag::Title | CommentTag::Notice | CommentTag::Dev => {}
+            }
+        }
+        natspec
+    }
+}
+
+/// Returns the functions, events and errors of an ABI.
+fn abi_items(abi: &JsonAbi) -> Vec<JsonAbiItem> {
+    let functions = abi.functions().map(|f| JsonAbiItem {
+        kind: "function",
+        signature: f.signature(),
+        selector: f.selector().to_string(),
+    });
+    let events = abi.events().map(|e| JsonAbiItem {
+        kind: "event",
+        signature: e.signature(),
+        selector: e.selector().to_string(),
+    });
+    let errors = abi.errors().map(|e| JsonAbiItem {
+        kind: "error",
+        signature: e.signature(),
+        selector: e.selector().to_string(),
+    });
+    functions.chain(events).chain(errors).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Comment;
+
+    #[test]
+    fn natspec() {
+        let mut comments = Comments::default();
+        assert_eq!(JsonNatSpec::new(&comments), JsonNatSpec::default());
+
+        comments.push(Comment::new(CommentTag::Notice, "Transfers tokens.".into()));
+        comments.push(Comment::new(CommentTag::Param, "to The recipient.".into()));
+        comments.push(Comment::new(CommentTag::Param, "amount The amount.".into()));
+        comments.push(Comment::new(CommentTag::Custom("security".into()), "audited".into()));
+        let natspec = JsonNatSpec::new(&comments);
+        assert_eq!(natspec.notice.as_deref(), Some("Transfers tokens."));
+        assert_eq!(
+            natspec.params,
+            [
+                JsonParam { name: "to".into(), description: "The recipient.".into() },
+                JsonParam { name: "amount".into(), description: "The amount.".into() },
+            ]
+        );
+        assert_eq!(natspec.custom["security"], "audited");
+    }
+}
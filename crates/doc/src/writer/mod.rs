@@ -2,10 +2,12 @@
 
 mod as_doc;
 mod buf_writer;
+mod json;
 mod markdown;
 
 pub use as_doc::{AsDoc, AsDocResult};
 pub use buf_writer::BufWriter;
+pub use json::{JsonAbiItem, JsonBase, JsonDoc, JsonItem, JsonNatSpec, JsonParam};
 pub use markdown::Markdown;
 
 mod traits;
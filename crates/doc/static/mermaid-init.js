@@ -0,0 +1,21 @@
+// Renders the `mermaid` code blocks when the book loads the Mermaid library, e.g. through the
+// `additional-js` of a custom `book.toml`, using the dark theme for the dark mdBook themes.
+(() => {
+    const blocks = document.querySelectorAll("code.language-mermaid");
+    if (blocks.length === 0 || typeof mermaid === "undefined") {
+        return;
+    }
+
+    const dark = ["coal", "navy", "ayu"].some((theme) =>
+        document.documentElement.classList.contains(theme)
+    );
+    for (const block of blocks) {
+        const diagram = document.createElement("pre");
+        diagram.className = "mermaid";
+        diagram.textContent = block.textContent;
+        block.parentElement.replaceWith(diagram);
+    }
+
+    mermaid.initialize({ startOnLoad: false, theme: dark ? "dark" : "default" });
+    mermaid.run({ querySelector: "pre.mermaid" });
+})();
@@ -9,7 +9,9 @@
 use foundry_compilers::artifacts::sourcemap::{Jump, SourceElement};
 use revm::interpreter::OpCode;
 use revm_inspectors::tracing::types::{CallTraceStep, DecodedInternalCall, DecodedTraceStep};
-pub use sources::{ArtifactData, ContractSources, SourceData};
+pub use sources::{
+    ArtifactData, ContractSources, FunctionDefinition, SourceData, VariableDeclaration,
+};
 
 #[derive(Clone, Debug)]
 pub struct DebugTraceIdentifier {
@@ -275,7 +277,7 @@ fn try_decode_args_from_step(args: &Parameters<'_>, step: &CallTraceStep) -> Opt
 }
 
 /// Decodes given [DynSolType] from memory.
-fn decode_from_memory(ty: &DynSolType, memory: &[u8], location: usize) -> Option<DynSolValue> {
+pub fn decode_from_memory(ty: &DynSolType, memory: &[u8], location: usize) -> Option<DynSolValue> {
     let first_word = memory.get(location..location + 32)?;
 
     match ty {
@@ -11,10 +11,14 @@
 use foundry_evm_core::utils::PcIcMap;
 use foundry_linking::Linker;
 use rayon::prelude::*;
-use solar_parse::{interface::Session, Parser};
+use solar_parse::{
+    ast::{self, visit::Visit, Span},
+    interface::{data_structures::Never, Session},
+    Parser,
+};
 use std::{
     collections::{BTreeMap, HashMap},
-    ops::Range,
+    ops::{ControlFlow, Range},
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -27,11 +31,42 @@ pub struct SourceData {
     /// Maps contract name to (start, end) of the contract definition in the source code.
     /// This is useful for determining which contract contains given function definition.
     contract_definitions: Vec<(String, Range<usize>)>,
+    /// Function definitions with the variables they declare, ordered by position.
+    function_definitions: Vec<FunctionDefinition>,
+}
+
+/// A function or modifier definition parsed from the source code.
+#[derive(Clone, Debug)]
+pub struct FunctionDefinition {
+    /// The name of the function, or its kind for unnamed functions such as `constructor`.
+    pub name: String,
+    /// Range of the definition in the source code.
+    pub range: Range<usize>,
+    /// The parameters, in order.
+    pub parameters: Vec<VariableDeclaration>,
+    /// The return parameters, in order.
+    pub returns: Vec<VariableDeclaration>,
+    /// The local variables, in declaration order.
+    pub locals: Vec<VariableDeclaration>,
+}
+
+/// A variable declaration parsed from the source code.
+#[derive(Clone, Debug)]
+pub struct VariableDeclaration {
+    /// The declaration without initializer, e.g. `uint256[] memory values`.
+    pub declaration: String,
+    /// The name of the variable, empty for unnamed parameters.
+    pub name: String,
+    /// Range of the declaration in the source code.
+    pub range: Range<usize>,
+    /// Range of the block in which the variable is visible.
+    pub scope: Range<usize>,
 }
 
 impl SourceData {
     pub fn new(source: Arc<String>, language: MultiCompilerLanguage, path: PathBuf) -> Self {
         let mut contract_definitions = Vec::new();
+        let mut function_definitions = Vec::new();
 
         match language {
             MultiCompilerLanguage::Vyper(_) => {
@@ -48,18 +83,22 @@ pub fn new(source: Arc<String>, language: MultiCompilerLanguage, path: PathBuf)
                     let mut parser =
                         Parser::from_source_code(&sess, &arena, filename, source.to_string())?;
                     let ast = parser.parse_file().map_err(|e| e.emit())?;
-                    for item in ast.items {
+                    for item in ast.items.iter() {
                         if let solar_parse::ast::ItemKind::Contract(contract) = &item.kind {
                             let range = item.span.lo().to_usize()..item.span.hi().to_usize();
                             contract_definitions.push((contract.name.to_string(), range));
                         }
                     }
+
+                    let mut collector = FunctionCollector::new(&source);
+                    let _ = collector.visit_source_unit(&ast);
+                    function_definitions = collector.functions;
                     Ok(())
                 });
             }
         }
 
-        Self { source, language, path, contract_definitions }
+        Self { source, language, path, contract_definitions, function_definitions }
     }
 
     /// Finds name of contract that contains given loc.
@@ -69,6 +108,113 @@ pub fn find_contract_name(&self, start: usize, end: usize) -> Option<&str> {
             .find(|(_, r)| start >= r.start && end <= r.end)
             .map(|(name, _)| name.as_str())
     }
+
+    /// Finds the function definition that contains given offset.
+    pub fn find_function(&self, offset: usize) -> Option<&FunctionDefinition> {
+        self.function_definitions.iter().find(|function| function.range.contains(&offset))
+    }
+}
+
+/// Collects [FunctionDefinition]s from a source unit.
+struct FunctionCollector<'a> {
+    source: &'a str,
+    functions: Vec<FunctionDefinition>,
+    /// Ranges of the blocks enclosing the visited statement, empty outside of function bodies.
+    scopes: Vec<Range<usize>>,
+}
+
+impl<'a> FunctionCollector<'a> {
+    fn new(source: &'a str) -> Self {
+        Self { source, functions: Vec::new(), scopes: Vec::new() }
+    }
+
+    fn declaration(
+        &self,
+        var: &ast::VariableDefinition<'_>,
+        scope: Range<usize>,
+    ) -> VariableDeclaration {
+        // Cut off the initializer of local variables.
+        let (name, end) = match var.name {
+            Some(name) => (name.to_string(), name.span.hi().to_usize()),
+            // Unnamed variables are parameters, which have no initializer.
+            None => (String::new(), var.span.hi().to_usize()),
+        };
+        let start = var.ty.span.lo().to_usize();
+        let declaration = self.source.get(start..end).unwrap_or_default().replace('\n', " ");
+        VariableDeclaration { declaration, name, range: span_range(var.span), scope }
+    }
+}
+
+impl<'ast> Visit<'ast> for FunctionCollector<'_> {
+    type BreakValue = Never;
+
+    fn visit_item(&mut self, item: &'ast ast::Item<'ast>) -> ControlFlow<Self::BreakValue> {
+        let ast::ItemKind::Function(func) = &item.kind else {
+            return self.walk_item(item);
+        };
+
+        let range = span_range(item.span);
+        let header = &func.header;
+        let declarations = |vars: &[ast::VariableDefinition<'_>]| {
+            vars.iter().map(|var| self.declaration(var, range.clone())).collect()
+        };
+        let function = FunctionDefinition {
+            name: header
+                .name
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| format!("{:?}", func.kind).to_lowercase()),
+            range: range.clone(),
+            parameters: declarations(header.parameters),
+            returns: declarations(header.returns),
+            locals: Vec::new(),
+        };
+        self.functions.push(function);
+
+        // Functions can't be nested, so only the body is left to visit.
+        if let Some(body) = &func.body {
+            self.scopes.push(range);
+            for stmt in body.iter() {
+                let _ = self.visit_stmt(stmt);
+            }
+            self.scopes.clear();
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn visit_stmt(&mut self, stmt: &'ast ast::Stmt<'ast>) -> ControlFlow<Self::BreakValue> {
+        let is_scope = matches!(
+            stmt.kind,
+            ast::StmtKind::Block { .. } |
+                ast::StmtKind::UncheckedBlock { .. } |
+                ast::StmtKind::For { .. }
+        );
+        if is_scope {
+            self.scopes.push(span_range(stmt.span));
+        }
+        let res = self.walk_stmt(stmt);
+        if is_scope {
+            self.scopes.pop();
+        }
+        res
+    }
+
+    fn visit_variable_definition(
+        &mut self,
+        var: &'ast ast::VariableDefinition<'ast>,
+    ) -> ControlFlow<Self::BreakValue> {
+        // Unnamed definitions inside of function bodies are function type parameters.
+        if let (Some(scope), Some(_)) = (self.scopes.last(), var.name) {
+            let declaration = self.declaration(var, scope.clone());
+            if let Some(function) = self.functions.last_mut() {
+                function.locals.push(declaration);
+            }
+        }
+        self.walk_variable_definition(var)
+    }
+}
+
+fn span_range(span: Span) -> Range<usize> {
+    span.lo().to_usize()..span.hi().to_usize()
 }
 
 #[derive(Clone, Debug)]
@@ -79,10 +225,18 @@ pub struct ArtifactData {
     pub pc_ic_map_runtime: Option<PcIcMap>,
     pub build_id: String,
     pub file_id: u32,
+    /// Whether the contract was compiled through the Yul IR, which lays out the stack
+    /// differently from the legacy code generator.
+    pub via_ir: bool,
 }
 
 impl ArtifactData {
-    fn new(bytecode: ContractBytecodeSome, build_id: String, file_id: u32) -> Result<Self> {
+    fn new(
+        bytecode: ContractBytecodeSome,
+        build_id: String,
+        file_id: u32,
+        via_ir: bool,
+    ) -> Result<Self> {
         let parse = |b: &Bytecode, name: &str| {
             // Only parse source map if it's not empty.
             let source_map = if b.source_map.as_ref().is_none_or(|s| s.is_empty()) {
@@ -109,7 +263,38 @@ fn new(bytecode: ContractBytecodeSome, build_id: String, file_id: u32) -> Result
             .map(|b| parse(&b, "runtime"))
             .unwrap_or_else(|| Ok((None, None)))?;
 
-        Ok(Self { source_map, source_map_runtime, pc_ic_map, pc_ic_map_runtime, build_id, file_id })
+        Ok(Self {
+            source_map,
+            source_map_runtime,
+            pc_ic_map,
+            pc_ic_map_runtime,
+            build_id,
+            file_id,
+            via_ir,
+        })
+    }
+
+    /// Returns the source element of the instruction at `pc`.
+    fn source_element(
+        &self,
+        language: &MultiCompilerLanguage,
+        pc: u32,
+        init_code: bool,
+    ) -> Option<&SourceElement> {
+        let source_map =
+            if init_code { self.source_map.as_ref() } else { self.source_map_runtime.as_ref() }?;
+
+        // Solc indexes source maps by instruction counter, but Vyper indexes by program
+        // counter.
+        if matches!(language, MultiCompilerLanguage::Solc(_)) {
+            let pc_ic_map =
+                if init_code { self.pc_ic_map.as_ref() } else { self.pc_ic_map_runtime.as_ref() }?;
+            let ic = pc_ic_map.get(pc)?;
+
+            source_map.get(ic as usize)
+        } else {
+            source_map.get(pc as usize)
+        }
     }
 }
 
@@ -155,6 +340,11 @@ pub fn insert<C: Compiler<CompilerContract = Contract>>(
             .map(|(id, artifact)| {
                 let mut new_artifact = None;
                 if let Some(file_id) = artifact.id {
+                    let via_ir = artifact
+                        .metadata
+                        .as_ref()
+                        .and_then(|metadata| metadata.settings.via_ir)
+                        .unwrap_or_default();
                     let artifact = if let Some((linker, libraries)) = link_data.as_ref() {
                         linker.link(id, libraries)?
                     } else {
@@ -164,7 +354,7 @@ pub fn insert<C: Compiler<CompilerContract = Contract>>(
 
                     new_artifact = Some((
                         id.name.clone(),
-                        ArtifactData::new(bytecode, id.build_id.clone(), file_id)?,
+                        ArtifactData::new(bytecode, id.build_id.clone(), file_id, via_ir)?,
                     ));
                 } else {
                     warn!(id = id.identifier(), "source not found");
@@ -249,26 +439,7 @@ pub fn find_source_mapping(
         init_code: bool,
     ) -> Option<(SourceElement, &SourceData)> {
         self.get_sources(contract_name)?.find_map(|(artifact, source)| {
-            let source_map = if init_code {
-                artifact.source_map.as_ref()
-            } else {
-                artifact.source_map_runtime.as_ref()
-            }?;
-
-            // Solc indexes source maps by instruction counter, but Vyper indexes by program
-            // counter.
-            let source_element = if matches!(source.language, MultiCompilerLanguage::Solc(_)) {
-                let pc_ic_map = if init_code {
-                    artifact.pc_ic_map.as_ref()
-                } else {
-                    artifact.pc_ic_map_runtime.as_ref()
-                }?;
-                let ic = pc_ic_map.get(pc)?;
-
-                source_map.get(ic as usize)
-            } else {
-                source_map.get(pc as usize)
-            }?;
+            let source_element = artifact.source_element(&source.language, pc, init_code)?;
             // if the source element has an index, find the sourcemap for that index
             let res = source_element
                 .index()
@@ -287,4 +458,65 @@ pub fn find_source_mapping(
             res
         })
     }
+
+    /// Returns the source element of the instruction at `pc` and the artifact containing it.
+    ///
+    /// Unlike [`Self::find_source_mapping`], this also returns elements of code without a known
+    /// source file, such as the compiler generated utility functions, e.g. to inspect their jump
+    /// types.
+    pub fn find_source_element(
+        &self,
+        contract_name: &str,
+        pc: u32,
+        init_code: bool,
+    ) -> Option<(&SourceElement, &ArtifactData)> {
+        self.get_sources(contract_name)?.find_map(|(artifact, source)| {
+            let source_element = artifact.source_element(&source.language, pc, init_code)?;
+            Some((source_element, artifact))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use foundry_compilers::solc::SolcLanguage;
+
+    #[test]
+    fn collects_function_variables() {
+        let source = r#"
+contract C {
+    function f(uint256 a, bytes calldata) internal returns (uint256 b) {
+        uint256 c = a + 1;
+        {
+            uint256 d = c;
+        }
+        for (uint256 i; i < 2; i++) {}
+        return c;
+    }
+}
+"#;
+        let data = SourceData::new(
+            Arc::new(source.to_string()),
+            MultiCompilerLanguage::Solc(SolcLanguage::Solidity),
+            PathBuf::from("C.sol"),
+        );
+
+        let offset = source.find("return c").unwrap();
+        let function = data.find_function(offset).unwrap();
+        assert_eq!(function.name, "f");
+
+        fn declarations(vars: &[VariableDeclaration]) -> Vec<&str> {
+            vars.iter().map(|var| var.declaration.as_str()).collect()
+        }
+        assert_eq!(declarations(&function.parameters), ["uint256 a", "bytes calldata"]);
+        assert_eq!(declarations(&function.returns), ["uint256 b"]);
+        assert_eq!(declarations(&function.locals), ["uint256 c", "uint256 d", "uint256 i"]);
+
+        // `d` and `i` are out of scope at the return statement.
+        let in_scope =
+            function.locals.iter().filter(|var| var.scope.contains(&offset)).collect::<Vec<_>>();
+        assert_eq!(in_scope.len(), 1);
+        assert_eq!(in_scope[0].name, "c");
+    }
 }
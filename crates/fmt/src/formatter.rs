@@ -1680,6 +1680,8 @@ fn write_function_header(
                 } else {
                     fmt.write_postfix_comments_before(attrs_loc.start())?;
                     fmt.write_whitespace_separator(multiline)?;
+                    // Sorting puts the attributes in the order of the style guide: visibility,
+                    // mutability, `virtual`, `override` and then the modifiers in their order.
                     let attributes =
                         fmt.items_to_chunks_sorted(attrs_end, func.attributes.iter_mut())?;
                     fmt.indented(1, |fmt| {
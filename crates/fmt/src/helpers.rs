@@ -1,11 +1,13 @@
 use crate::{
     inline_config::{InlineConfig, InvalidInlineConfigItem},
+    reorder,
+    solang_ext::CodeLocationExt,
     Comments, Formatter, FormatterConfig, FormatterError, Visitable,
 };
 use ariadne::{Color, Fmt, Label, Report, ReportKind, Source};
 use itertools::Itertools;
 use solang_parser::{diagnostics::Diagnostic, pt::*};
-use std::{fmt::Write, path::Path};
+use std::{fmt::Write, ops::Range, path::Path};
 
 /// Result of parsing the source code
 #[derive(Debug)]
@@ -22,6 +24,44 @@ pub struct Parsed<'a> {
     pub invalid_inline_config_items: Vec<(Loc, InvalidInlineConfigItem)>,
 }
 
+impl Parsed<'_> {
+    /// Restricts formatting to the items intersecting the byte `range`, leaving the rest of the
+    /// source code unchanged.
+    ///
+    /// Items are top-level definitions and, for contracts that are not fully in the range, the
+    /// definitions in the contract. An empty range selects the item containing its position.
+    pub fn restrict_to_range(&mut self, range: Range<usize>) {
+        let intersects =
+            |loc: Loc| loc.start() < range.end.max(range.start + 1) && range.start < loc.end();
+        let mut enabled = Vec::new();
+        for part in &self.pt.0 {
+            let loc = CodeLocationExt::loc(part);
+            if !intersects(loc) {
+                continue;
+            }
+            match part {
+                SourceUnitPart::ContractDefinition(contract)
+                    if range.start > loc.start() || range.end < loc.end() =>
+                {
+                    let parts = contract
+                        .parts
+                        .iter()
+                        .map(CodeLocationExt::loc)
+                        .filter(|loc| intersects(*loc))
+                        .collect::<Vec<_>>();
+                    if parts.is_empty() {
+                        enabled.push(loc);
+                    } else {
+                        enabled.extend(parts);
+                    }
+                }
+                _ => enabled.push(loc),
+            }
+        }
+        self.inline_config.disable_outside(&enabled, self.src);
+    }
+}
+
 /// Parse source code.
 pub fn parse(src: &str) -> Result<Parsed<'_>, FormatterError> {
     parse_raw(src).map_err(|diag| FormatterError::Parse(src.to_string(), None, diag))
@@ -44,11 +84,20 @@ pub fn parse_raw(src: &str) -> Result<Parsed<'_>, Vec<Diagnostic>> {
 }
 
 /// Format parsed code
+///
+/// If imports are grouped or contract members are sorted, the reordered source code is parsed
+/// again before formatting.
 pub fn format_to<W: Write>(
     writer: W,
     mut parsed: Parsed<'_>,
     config: FormatterConfig,
 ) -> Result<(), FormatterError> {
+    if let Some(src) = reorder::reorder(&parsed, &config) {
+        let parsed = parse(&src)?;
+        let config = FormatterConfig { group_imports: false, sort_members: false, ..config };
+        return format_to(writer, parsed, config)
+    }
+
     trace!(?parsed, ?config, "Formatting");
     let mut formatter =
         Formatter::new(writer, parsed.src, parsed.comments, parsed.inline_config, config);
@@ -65,6 +114,19 @@ pub fn format(src: &str) -> Result<String, FormatterError> {
     Ok(output)
 }
 
+/// Parse and format the items intersecting the byte `range` of a string with default settings
+///
+/// See [`Parsed::restrict_to_range`].
+pub fn format_range(src: &str, range: Range<usize>) -> Result<String, FormatterError> {
+    let mut parsed = parse(src)?;
+    parsed.restrict_to_range(range);
+
+    let mut output = String::new();
+    format_to(&mut output, parsed, FormatterConfig::default())?;
+
+    Ok(output)
+}
+
 /// Converts the start offset of a `Loc` to `(line, col)`
 pub fn offset_to_line_column(content: &str, start: usize) -> (usize, usize) {
     debug_assert!(content.len() > start);
@@ -132,4 +194,35 @@ fn test_interface_format() {
         let s = "interface I {\n    function increment() external;\n    function number() external view returns (uint256);\n    function setNumber(uint256 newNumber) external;\n}";
         let _formatted = format(s).unwrap();
     }
+
+    // Attributes are always written in the order of the style guide, keeping the order of the
+    // modifiers.
+    #[test]
+    fn test_function_attribute_order() {
+        let s = "contract C {\n    function f() modifier1 override virtual view public modifier2 returns (uint256) {}\n}\n";
+        let formatted = format(s).unwrap();
+        assert_eq!(
+            formatted,
+            "contract C {\n    function f() public view virtual override modifier1 modifier2 returns (uint256) {}\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_format_range() {
+        let s = "contract A {\n    function a( ) external {}\n\n    function b( ) external {}\n}\n\ncontract  B {}\n";
+        let b = s.find("function b").unwrap();
+
+        let formatted = format_range(s, b..b + 10).unwrap();
+        assert_eq!(
+            formatted,
+            "contract A {\n    function a( ) external {}\n\n    function b() external {}\n}\n\ncontract  B {}\n"
+        );
+
+        // An empty range formats the item at its position.
+        let formatted = format_range(s, s.len() - 2..s.len() - 2).unwrap();
+        assert_eq!(
+            formatted,
+            "contract A {\n    function a( ) external {}\n\n    function b( ) external {}\n}\n\ncontract B {}\n"
+        );
+    }
 }
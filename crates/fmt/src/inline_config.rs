@@ -1,7 +1,7 @@
 use crate::comments::{CommentState, CommentStringExt};
 use itertools::Itertools;
 use solang_parser::pt::Loc;
-use std::{fmt, str::FromStr};
+use std::{fmt, ops::Range, str::FromStr};
 
 /// An inline config item
 #[allow(clippy::enum_variant_names)]
@@ -149,8 +149,30 @@ pub fn new(items: impl IntoIterator<Item = (Loc, InlineConfigItem)>, src: &str)
         Self { disabled_ranges }
     }
 
+    /// Disables formatting for any code outside of the given locations, which must be sorted and
+    /// must not overlap
+    pub fn disable_outside(&mut self, enabled: &[Loc], src: &str) {
+        let mut start = 0;
+        for loc in enabled {
+            if loc.start() > start {
+                self.disabled_ranges.push(DisabledRange { start, end: loc.start(), loose: false });
+            }
+            start = start.max(loc.end());
+        }
+        if start < src.len() {
+            self.disabled_ranges.push(DisabledRange { start, end: src.len(), loose: false });
+        }
+    }
+
     /// Check if the location is in a disabled range
     pub fn is_disabled(&self, loc: Loc) -> bool {
         self.disabled_ranges.iter().any(|range| range.includes(loc))
     }
+
+    /// Check if any part of the byte range is in a disabled range
+    pub fn is_partially_disabled(&self, range: Range<usize>) -> bool {
+        self.disabled_ranges
+            .iter()
+            .any(|disabled| disabled.start < range.end && range.start < disabled.end)
+    }
 }
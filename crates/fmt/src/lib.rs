@@ -12,6 +12,7 @@
 mod helpers;
 pub mod inline_config;
 mod macros;
+mod reorder;
 pub mod solang_ext;
 mod string;
 pub mod visit;
@@ -21,7 +22,8 @@
 pub use comments::Comments;
 pub use formatter::{Formatter, FormatterError};
 pub use helpers::{
-    format, format_diagnostics_report, format_to, offset_to_line_column, parse, parse2, Parsed,
+    format, format_diagnostics_report, format_range, format_to, offset_to_line_column, parse,
+    parse2, Parsed,
 };
 pub use inline_config::InlineConfig;
 pub use visit::{Visitable, Visitor};
@@ -0,0 +1,269 @@
+//! Reordering of import directives and contract members.
+//!
+//! Items are moved in the source code before formatting, together with the comments preceding
+//! them, so that the formatter can then handle comments and blank lines as usual.
+
+use crate::{helpers::import_path_string, solang_ext::pt::*, FormatterConfig, Parsed};
+use std::ops::Range;
+
+/// Returns the source code with the imports grouped and the contract members sorted as configured,
+/// or `None` if nothing needs to be moved.
+///
+/// Items are only moved if each of them ends its line and none of them is in a disabled range.
+pub(crate) fn reorder(parsed: &Parsed<'_>, config: &FormatterConfig) -> Option<String> {
+    let mut edits = Vec::new();
+
+    if config.group_imports {
+        let parts = &parsed.pt.0;
+        let mut i = 0;
+        while i < parts.len() {
+            let start = i;
+            while parts
+                .get(i)
+                .is_some_and(|part| matches!(part, SourceUnitPart::ImportDirective(_)))
+            {
+                i += 1;
+            }
+            if i == start {
+                i += 1;
+                continue
+            }
+            let min = start.checked_sub(1).map_or(0, |prev| parts[prev].loc().end());
+            let limit = parts.get(i).map_or(parsed.src.len(), |next| next.loc().start());
+            edits.extend(group_imports(
+                parsed,
+                &parts[start..i],
+                min,
+                limit,
+                &config.import_groups,
+            ));
+        }
+    }
+
+    if config.sort_members {
+        for part in &parsed.pt.0 {
+            if let SourceUnitPart::ContractDefinition(contract) = part {
+                edits.extend(sort_members(parsed, contract));
+            }
+        }
+    }
+
+    if edits.is_empty() {
+        return None
+    }
+    edits.sort_by_key(|(range, _)| range.start);
+    let mut src = parsed.src.to_string();
+    for (range, replacement) in edits.into_iter().rev() {
+        src.replace_range(range, &replacement);
+    }
+    (src != parsed.src).then_some(src)
+}
+
+/// Groups consecutive import directives, keeping the order of the imports in each group.
+fn group_imports(
+    parsed: &Parsed<'_>,
+    imports: &[SourceUnitPart],
+    min: usize,
+    limit: usize,
+    groups: &[String],
+) -> Option<(Range<usize>, String)> {
+    let locs = imports.iter().map(|import| import.loc()).collect::<Vec<_>>();
+    let chunks = chunks(parsed, min, limit, &locs)?;
+
+    let mut imports = imports
+        .iter()
+        .zip(chunks.iter())
+        .map(|(import, chunk)| {
+            (import_group(import, groups), trim_blank_lines(&parsed.src[chunk.clone()]))
+        })
+        .collect::<Vec<_>>();
+    imports.sort_by_key(|(group, _)| *group);
+
+    let mut out = String::new();
+    for (i, (group, chunk)) in imports.iter().enumerate() {
+        if i > 0 && imports[i - 1].0 != *group {
+            out.push('\n');
+        }
+        out.push_str(chunk);
+    }
+    Some((chunks[0].start..chunks[chunks.len() - 1].end, out))
+}
+
+/// Returns the group of an import: the index of the longest matching prefix in `groups`, followed
+/// by other non-relative and then relative imports.
+fn import_group(import: &SourceUnitPart, groups: &[String]) -> usize {
+    let SourceUnitPart::ImportDirective(
+        Import::Plain(path, _) | Import::GlobalSymbol(path, _, _) | Import::Rename(path, _, _),
+    ) = import
+    else {
+        return groups.len()
+    };
+    let path = import_path_string(path);
+    groups
+        .iter()
+        .enumerate()
+        .filter(|(_, prefix)| path.starts_with(prefix.as_str()))
+        .max_by_key(|(_, prefix)| prefix.len())
+        .map(|(idx, _)| idx)
+        .unwrap_or(if path.starts_with('.') { groups.len() + 1 } else { groups.len() })
+}
+
+/// Sorts the members of a contract following the order of the Solidity style guide.
+fn sort_members(
+    parsed: &Parsed<'_>,
+    contract: &ContractDefinition,
+) -> Option<(Range<usize>, String)> {
+    let ranks = contract.parts.iter().map(member_rank).collect::<Option<Vec<_>>>()?;
+    if ranks.windows(2).all(|w| w[0] <= w[1]) {
+        return None
+    }
+
+    let header_end = contract
+        .base
+        .last()
+        .map(|base| base.loc.end())
+        .or_else(|| contract.name.as_ref().map(|name| name.loc.end()))?;
+    let locs = contract.parts.iter().map(|part| part.loc()).collect::<Vec<_>>();
+    let chunks = chunks(parsed, header_end, contract.loc.end(), &locs)?;
+
+    let mut members = ranks
+        .into_iter()
+        .zip(contract.parts.iter().map(is_spaced))
+        .zip(chunks.iter())
+        .map(|((rank, spaced), chunk)| (rank, spaced, &parsed.src[chunk.clone()]))
+        .collect::<Vec<_>>();
+    members.sort_by_key(|(rank, _, _)| *rank);
+
+    // Members that are separated by a blank line when formatted start with a single blank line,
+    // so that a moved member's comments stay attached to it.
+    let mut out = String::new();
+    for (i, (rank, spaced, chunk)) in members.iter().enumerate() {
+        if i == 0 {
+            out.push_str(trim_blank_lines(chunk));
+        } else {
+            let (prev_rank, prev_spaced, _) = members[i - 1];
+            if rank.0 != prev_rank.0 || *spaced || prev_spaced {
+                out.push('\n');
+                out.push_str(trim_blank_lines(chunk));
+            } else {
+                out.push_str(chunk);
+            }
+        }
+    }
+    Some((chunks[0].start..chunks[chunks.len() - 1].end, out))
+}
+
+/// Returns whether the member is always separated from the next one by a blank line.
+fn is_spaced(part: &ContractPart) -> bool {
+    match part {
+        ContractPart::FunctionDefinition(func) => func.body.is_some(),
+        ContractPart::StructDefinition(_) => true,
+        _ => false,
+    }
+}
+
+/// Returns the position of a contract member in the order of the Solidity style guide, and whether
+/// it is a view or pure function.
+///
+/// Returns `None` for members that can't be moved.
+fn member_rank(part: &ContractPart) -> Option<(u8, bool)> {
+    let rank = match part {
+        ContractPart::Using(_) => 0,
+        ContractPart::StructDefinition(_) |
+        ContractPart::EnumDefinition(_) |
+        ContractPart::TypeDefinition(_) => 1,
+        ContractPart::VariableDefinition(_) => 2,
+        ContractPart::EventDefinition(_) => 3,
+        ContractPart::ErrorDefinition(_) => 4,
+        ContractPart::FunctionDefinition(func) => {
+            let visibility = func.attributes.iter().find_map(|attr| match attr {
+                FunctionAttribute::Visibility(visibility) => Some(visibility),
+                _ => None,
+            });
+            let rank = match func.ty {
+                FunctionTy::Modifier => 5,
+                FunctionTy::Constructor => 6,
+                FunctionTy::Receive => 7,
+                FunctionTy::Fallback => 8,
+                FunctionTy::Function => match visibility {
+                    Some(Visibility::External(_)) => 9,
+                    Some(Visibility::Public(_)) | None => 10,
+                    Some(Visibility::Internal(_)) => 11,
+                    Some(Visibility::Private(_)) => 12,
+                },
+            };
+            let is_view = func.attributes.iter().any(|attr| {
+                matches!(
+                    attr,
+                    FunctionAttribute::Mutability(
+                        Mutability::View(_) | Mutability::Pure(_) | Mutability::Constant(_)
+                    )
+                )
+            });
+            return Some((rank, is_view))
+        }
+        ContractPart::Annotation(_) | ContractPart::StraySemicolon(_) => return None,
+    };
+    Some((rank, false))
+}
+
+/// Removes the leading blank lines of `s`.
+fn trim_blank_lines(mut s: &str) -> &str {
+    while let Some(idx) = s.find('\n') {
+        if !s[..idx].trim().is_empty() {
+            break
+        }
+        s = &s[idx + 1..];
+    }
+    s
+}
+
+/// Splits the source code of consecutive items into one chunk per item.
+///
+/// Each chunk ends at the end of the line of its item, and starts at the end of the previous chunk.
+/// The first chunk starts with the comments directly preceding the first item on their own lines,
+/// but not before `min`. Returns `None` if the items can't be split in lines before `limit`, or if
+/// any of them is in a disabled range.
+fn chunks(
+    parsed: &Parsed<'_>,
+    min: usize,
+    limit: usize,
+    locs: &[Loc],
+) -> Option<Vec<Range<usize>>> {
+    let src = parsed.src;
+    let line_start = |offset: usize| src[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+
+    let mut start = line_start(locs.first()?.start());
+    while let Some(comment) = parsed.comments.iter().filter(|c| c.loc.end() <= start).last() {
+        let comment_line_start = line_start(comment.loc.start());
+        if comment_line_start < min ||
+            !src[comment_line_start..comment.loc.start()].trim().is_empty() ||
+            !src[comment.loc.end()..start].trim().is_empty() ||
+            src[comment.loc.end()..start].matches('\n').count() > 1
+        {
+            break
+        }
+        start = comment_line_start;
+    }
+    if start < min {
+        return None
+    }
+
+    let mut chunks = Vec::with_capacity(locs.len());
+    for loc in locs {
+        if loc.start() < start {
+            return None
+        }
+        let newline = loc.end() + src[loc.end()..].find('\n')?;
+        if newline >= limit ||
+            parsed.comments.iter().any(|c| c.loc.start() < newline && newline < c.loc.end())
+        {
+            return None
+        }
+        chunks.push(start..newline + 1);
+        start = newline + 1;
+    }
+
+    let range = chunks[0].start..start;
+    (!parsed.inline_config.is_partially_disabled(range)).then_some(chunks)
+}
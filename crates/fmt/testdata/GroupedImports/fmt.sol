@@ -0,0 +1,14 @@
+// config: group_imports = true
+// config: import_groups = ["forge-std/", "@openzeppelin/"]
+import {Test} from "forge-std/Test.sol";
+import {console} from "forge-std/console.sol";
+
+// Token base contract
+import {ERC20} from "@openzeppelin/token/ERC20.sol";
+
+import "src/Bar.sol";
+
+import {Foo} from "./Foo.sol";
+import {Baz} from "../Baz.sol";
+
+contract A {}
@@ -0,0 +1,10 @@
+import {Foo} from "./Foo.sol";
+import {Test} from "forge-std/Test.sol";
+// Token base contract
+import {ERC20} from "@openzeppelin/token/ERC20.sol";
+import "src/Bar.sol";
+
+import {console} from "forge-std/console.sol";
+import {Baz} from "../Baz.sol";
+
+contract A {}
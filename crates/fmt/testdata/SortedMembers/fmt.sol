@@ -0,0 +1,29 @@
+// config: sort_members = true
+contract Sorted {
+    // the value
+    uint256 internal value;
+
+    /// @notice Emitted on update.
+    event Updated(uint256 value);
+
+    error Unauthorized();
+
+    modifier onlyOwner() {
+        _;
+    }
+
+    constructor() {}
+
+    function set(uint256 newValue) external {
+        _set(newValue);
+        emit Updated(newValue); // notify
+    }
+
+    function get() external view returns (uint256) {
+        return value;
+    }
+
+    function _set(uint256 newValue) internal {
+        value = newValue;
+    }
+}
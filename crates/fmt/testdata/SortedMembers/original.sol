@@ -0,0 +1,28 @@
+contract Sorted {
+    /// @notice Emitted on update.
+    event Updated(uint256 value);
+
+    function get() external view returns (uint256) {
+        return value;
+    }
+
+    // the value
+    uint256 internal value;
+
+    function _set(uint256 newValue) internal {
+        value = newValue;
+    }
+
+    constructor() {}
+
+    function set(uint256 newValue) external {
+        _set(newValue);
+        emit Updated(newValue); // notify
+    }
+
+    error Unauthorized();
+
+    modifier onlyOwner() {
+        _;
+    }
+}
@@ -235,3 +235,5 @@ macro_rules! test_directories {
 }
 
 test_dir!(SortedImports, TestConfig::skip_compare_ast_eq());
+test_dir!(GroupedImports, TestConfig::skip_compare_ast_eq());
+test_dir!(SortedMembers, TestConfig::skip_compare_ast_eq());
@@ -1,8 +1,8 @@
 use super::{install, watch::WatchArgs};
-use clap::Parser;
+use clap::{Parser, ValueHint};
 use eyre::Result;
 use foundry_cli::{opts::BuildOpts, utils::LoadConfig};
-use foundry_common::{compile::ProjectCompiler, shell};
+use foundry_common::{compile::ProjectCompiler, sarif, shell};
 use foundry_compilers::{
     compilers::{multi::MultiCompilerLanguage, Language},
     utils::source_files_iter,
@@ -56,6 +56,11 @@ pub struct BuildArgs {
     #[serde(skip)]
     pub ignore_eip_3860: bool,
 
+    /// Write the compiler diagnostics to the given file as a SARIF report.
+    #[arg(long, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    #[serde(skip)]
+    pub sarif: Option<PathBuf>,
+
     #[command(flatten)]
     #[serde(flatten)]
     pub build: BuildOpts,
@@ -95,10 +100,18 @@ pub fn run(self) -> Result<ProjectCompileOutput> {
             .print_names(self.names)
             .print_sizes(self.sizes)
             .ignore_eip_3860(self.ignore_eip_3860)
-            .bail(!format_json);
+            // The report must be written before bailing on errors.
+            .bail(!format_json && self.sarif.is_none());
 
         let output = compiler.compile(&project)?;
 
+        if let Some(path) = &self.sarif {
+            sarif::compiler_report(&config, &output.output().errors).write(path)?;
+            if !format_json && output.has_compiler_errors() {
+                eyre::bail!("Compilation failed");
+            }
+        }
+
         if format_json && !self.names && !self.sizes {
             sh_println!("{}", serde_json::to_string_pretty(&output.output())?)?;
         }
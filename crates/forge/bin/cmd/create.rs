@@ -180,6 +180,7 @@ pub async fn run(mut self) -> Result<()> {
             .await
         } else {
             // Deploy with signer
+            self.eth.wallet.ensure_chain_allowed(chain_id)?;
             let signer = self.eth.wallet.signer().await?;
             let deployer = signer.address();
             let provider = ProviderBuilder::<_, _, AnyNetwork>::default()
@@ -220,7 +221,7 @@ async fn verify_preflight_check(
         // NOTE: this does not represent the same `VerifyArgs` that would be sent after deployment,
         // since we don't know the address yet.
         let mut verify = VerifyArgs {
-            address: Default::default(),
+            address: None,
             contract: Some(self.contract.clone()),
             compiler_version: Some(id.version.to_string()),
             constructor_args,
@@ -244,6 +245,9 @@ async fn verify_preflight_check(
             show_standard_json_input: self.show_standard_json_input,
             guess_constructor_args: false,
             compilation_profile: Some(id.profile.to_string()),
+            broadcast: None,
+            jobs: 1,
+            proxy: false,
         };
 
         // Check config for Etherscan API Keys to avoid preflight check failing if no
@@ -403,7 +407,7 @@ async fn deploy<P: Provider<AnyNetwork>>(
             None
         };
         let verify = VerifyArgs {
-            address,
+            address: Some(address),
             contract: Some(self.contract),
             compiler_version: Some(id.version.to_string()),
             constructor_args,
@@ -424,8 +428,11 @@ async fn deploy<P: Provider<AnyNetwork>>(
             show_standard_json_input: self.show_standard_json_input,
             guess_constructor_args: false,
             compilation_profile: Some(id.profile.to_string()),
+            broadcast: None,
+            jobs: 1,
+            proxy: false,
         };
-        sh_println!("Waiting for {} to detect contract deployment...", verify.verifier.verifier)?;
+        sh_println!("Waiting for {} to detect contract deployment...", verify.verifier.provider())?;
         verify.run().await
     }
 
@@ -0,0 +1,390 @@
+use alloy_primitives::keccak256;
+use alloy_provider::Provider;
+use clap::{Parser, Subcommand};
+use comfy_table::{modifiers::UTF8_ROUND_CORNERS, Table};
+use eyre::Result;
+use forge_script_sequence::{bytecode_hash, Deployment, DeploymentRegistry};
+use forge_verify::proxy::Proxy;
+use foundry_cli::{
+    opts::{BuildOpts, ChainValueParser, ProjectPathOpts, RpcOpts},
+    utils::{self, LoadConfig},
+};
+use foundry_common::{compile::ProjectCompiler, shell, ContractsByArtifact};
+use foundry_compilers::artifacts::Libraries;
+use foundry_config::{impl_figment_convert, Chain, Config};
+use foundry_linking::Linker;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// CLI arguments for `forge deployments`.
+#[derive(Debug, Parser)]
+pub struct DeploymentsArgs {
+    #[command(subcommand)]
+    pub sub: DeploymentsSubcommands,
+}
+
+impl DeploymentsArgs {
+    pub async fn run(self) -> Result<()> {
+        match self.sub {
+            DeploymentsSubcommands::List(args) => args.run(),
+            DeploymentsSubcommands::Diff(args) => args.run(),
+            DeploymentsSubcommands::Verify(args) => args.run().await,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DeploymentsSubcommands {
+    /// List the deployments recorded by scripts in the `deployments` directory.
+    #[command(visible_alias = "ls")]
+    List(ListArgs),
+
+    /// Compare the recorded deployments with the current build, showing the contracts whose code
+    /// or functions changed since they were deployed and need an upgrade.
+    ///
+    /// Proxies are compared through their implementation.
+    Diff(DiffArgs),
+
+    /// Check that the recorded deployments match the code on chain.
+    Verify(VerifyArgs),
+}
+
+/// CLI arguments for `forge deployments list`.
+#[derive(Debug, Parser)]
+pub struct ListArgs {
+    /// The name of the deployment to list.
+    name: Option<String>,
+
+    /// The chain to list the deployments of, all chains by default.
+    #[arg(long, short, value_parser = ChainValueParser::default())]
+    chain: Option<Chain>,
+
+    /// List all the versions of the deployments instead of the latest one.
+    #[arg(long)]
+    all: bool,
+
+    #[command(flatten)]
+    project_paths: ProjectPathOpts,
+}
+
+impl ListArgs {
+    pub fn run(self) -> Result<()> {
+        let config = self.project_paths.load_config()?;
+        let registries = load_registries(&config, self.chain, self.name.as_deref())?;
+
+        let mut rows = vec![];
+        for registry in &registries {
+            for (name, versions) in &registry.deployments {
+                if self.name.as_ref().is_some_and(|n| n != name) {
+                    continue
+                }
+                let skip = if self.all { 0 } else { versions.len().saturating_sub(1) };
+                rows.extend(
+                    versions.iter().skip(skip).map(|deployment| (registry.chain, name, deployment)),
+                );
+            }
+        }
+
+        if shell::is_json() {
+            #[derive(Serialize)]
+            struct Row<'a> {
+                chain: u64,
+                name: &'a str,
+                #[serde(flatten)]
+                deployment: &'a Deployment,
+            }
+            let rows = rows
+                .into_iter()
+                .map(|(chain, name, deployment)| Row { chain, name, deployment })
+                .collect::<Vec<_>>();
+            sh_println!("{}", serde_json::to_string_pretty(&rows)?)?;
+            return Ok(())
+        }
+
+        let mut table = Table::new();
+        table.apply_modifier(UTF8_ROUND_CORNERS);
+        table.set_header(["Chain", "Name", "Version", "Contract", "Address", "Implementation"]);
+        for (chain, name, deployment) in rows {
+            let implementation = deployment.proxy.as_ref().map(|proxy| {
+                let name = proxy.implementation_name.as_deref().unwrap_or("unknown");
+                format!("{} ({name}, {})", proxy.implementation, proxy.kind)
+            });
+            table.add_row([
+                chain.to_string(),
+                name.clone(),
+                deployment.version.to_string(),
+                deployment.contract_name.clone(),
+                deployment.address.to_string(),
+                implementation.unwrap_or_default(),
+            ]);
+        }
+        sh_println!("{table}")?;
+        Ok(())
+    }
+}
+
+/// CLI arguments for `forge deployments diff`.
+#[derive(Debug, Parser)]
+pub struct DiffArgs {
+    /// The name of the deployment to compare.
+    name: Option<String>,
+
+    /// The chain to compare the deployments of, all chains by default.
+    #[arg(long, short, value_parser = ChainValueParser::default())]
+    chain: Option<Chain>,
+
+    #[command(flatten)]
+    build: BuildOpts,
+}
+
+/// The state of a deployment compared to the current build.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum DiffStatus {
+    Unchanged,
+    Changed,
+    /// The contract is not in the project anymore.
+    Removed,
+    /// The code of the deployment, or of the implementation of a proxy, was not recorded.
+    Unknown,
+}
+
+/// The difference between a deployment and the current build of its contract.
+#[derive(Debug, Serialize)]
+struct DeploymentDiff {
+    status: DiffStatus,
+    /// The functions added since the deployment.
+    added: Vec<String>,
+    /// The functions removed since the deployment.
+    removed: Vec<String>,
+}
+
+impl DeploymentDiff {
+    /// Compares a deployment with its contract in `contracts`, ignoring the metadata of the code.
+    fn new(deployment: Option<&Deployment>, contracts: &ContractsByArtifact) -> Result<Self> {
+        let mut diff = Self { status: DiffStatus::Unknown, added: Vec::new(), removed: Vec::new() };
+        let Some(deployment) = deployment else { return Ok(diff) };
+        let Some((_, contract)) =
+            contracts.find_by_name_or_identifier(&deployment.contract_name)?
+        else {
+            diff.status = DiffStatus::Removed;
+            return Ok(diff)
+        };
+        let (Some(code), Some(hash)) = (contract.deployed_bytecode(), deployment.bytecode_hash)
+        else {
+            return Ok(diff)
+        };
+
+        let functions =
+            contract.abi.functions().map(|function| function.signature()).collect::<Vec<_>>();
+        diff.added =
+            functions.iter().filter(|f| !deployment.functions.contains(f)).cloned().collect();
+        diff.removed =
+            deployment.functions.iter().filter(|f| !functions.contains(f)).cloned().collect();
+        diff.status =
+            if bytecode_hash(code) == hash && diff.added.is_empty() && diff.removed.is_empty() {
+                DiffStatus::Unchanged
+            } else {
+                DiffStatus::Changed
+            };
+        Ok(diff)
+    }
+}
+
+impl DiffArgs {
+    pub fn run(self) -> Result<()> {
+        let config = self.build.load_config()?;
+        let registries = load_registries(&config, self.chain, self.name.as_deref())?;
+
+        let project = config.project()?;
+        let output = ProjectCompiler::new().quiet(true).compile(&project)?;
+        let linker = Linker::new(project.root(), output.artifact_ids().collect());
+        // The contracts linked with each set of libraries used by the deployments.
+        let mut linked = BTreeMap::<Vec<String>, ContractsByArtifact>::new();
+
+        let mut rows = vec![];
+        for registry in &registries {
+            for (name, deployment) in registry.latest_deployments() {
+                if self.name.as_ref().is_some_and(|n| n != name) {
+                    continue
+                }
+                // Proxies are upgraded through their implementation.
+                let target = match &deployment.proxy {
+                    Some(proxy) => {
+                        proxy.implementation_name.as_ref().and_then(|name| registry.latest(name))
+                    }
+                    None => Some(deployment),
+                };
+                let libraries =
+                    target.map(|target| &target.libraries).unwrap_or(&deployment.libraries);
+                if !linked.contains_key(libraries) {
+                    let contracts = ContractsByArtifact::new(
+                        linker.get_linked_artifacts(&Libraries::parse(libraries)?)?,
+                    );
+                    linked.insert(libraries.clone(), contracts);
+                }
+                let diff = DeploymentDiff::new(target, &linked[libraries])?;
+                rows.push((registry.chain, name, deployment, diff));
+            }
+        }
+
+        if shell::is_json() {
+            #[derive(Serialize)]
+            struct Row<'a> {
+                chain: u64,
+                name: &'a str,
+                contract: &'a str,
+                address: String,
+                #[serde(flatten)]
+                diff: DeploymentDiff,
+            }
+            let rows = rows
+                .into_iter()
+                .map(|(chain, name, deployment, diff)| Row {
+                    chain,
+                    name,
+                    contract: &deployment.contract_name,
+                    address: deployment.address.to_string(),
+                    diff,
+                })
+                .collect::<Vec<_>>();
+            sh_println!("{}", serde_json::to_string_pretty(&rows)?)?;
+            return Ok(())
+        }
+
+        let mut table = Table::new();
+        table.apply_modifier(UTF8_ROUND_CORNERS);
+        table.set_header(["Chain", "Name", "Contract", "Address", "Status"]);
+        for (chain, name, deployment, diff) in rows {
+            let mut status = match diff.status {
+                DiffStatus::Unchanged => "unchanged",
+                DiffStatus::Changed => "changed",
+                DiffStatus::Removed => "removed from the project",
+                DiffStatus::Unknown => "unknown code",
+            }
+            .to_string();
+            for function in &diff.added {
+                status.push_str(&format!("\n+ {function}"));
+            }
+            for function in &diff.removed {
+                status.push_str(&format!("\n- {function}"));
+            }
+            table.add_row([
+                chain.to_string(),
+                name.clone(),
+                deployment.contract_name.clone(),
+                deployment.address.to_string(),
+                status,
+            ]);
+        }
+        sh_println!("{table}")?;
+        Ok(())
+    }
+}
+
+/// CLI arguments for `forge deployments verify`.
+#[derive(Debug, Parser)]
+pub struct VerifyArgs {
+    /// The name of the deployment to check.
+    name: Option<String>,
+
+    #[command(flatten)]
+    project_paths: ProjectPathOpts,
+
+    #[command(flatten)]
+    rpc: RpcOpts,
+}
+
+impl_figment_convert!(VerifyArgs, project_paths, rpc);
+
+impl VerifyArgs {
+    pub async fn run(self) -> Result<()> {
+        let config = self.load_config()?;
+        let provider = utils::get_provider(&config)?;
+        let chain_id = provider.get_chain_id().await?;
+        let registries = load_registries(&config, Some(chain_id.into()), self.name.as_deref())?;
+        let Some(registry) = registries.first().filter(|r| !r.deployments.is_empty()) else {
+            eyre::bail!("No deployments recorded for chain {chain_id}")
+        };
+
+        let mut table = Table::new();
+        table.apply_modifier(UTF8_ROUND_CORNERS);
+        table.set_header(["Name", "Address", "Status"]);
+        let mut mismatches = 0;
+        for (name, deployment) in registry.latest_deployments() {
+            if self.name.as_ref().is_some_and(|n| n != name) {
+                continue
+            }
+
+            let code = provider.get_code_at(deployment.address).await?;
+            let status = if code.is_empty() {
+                "no code".to_string()
+            } else if deployment.code_hash.is_some_and(|hash| hash != keccak256(&code)) {
+                "code mismatch".to_string()
+            } else if let Some(expected) = &deployment.proxy {
+                match Proxy::detect(&provider, deployment.address).await? {
+                    Some(proxy) if proxy.implementation == expected.implementation => {
+                        "ok".to_string()
+                    }
+                    Some(proxy) => format!(
+                        "implementation is {} instead of {}",
+                        proxy.implementation, expected.implementation
+                    ),
+                    None => "not a proxy anymore".to_string(),
+                }
+            } else {
+                "ok".to_string()
+            };
+            if status != "ok" {
+                mismatches += 1;
+            }
+            table.add_row([name.clone(), deployment.address.to_string(), status]);
+        }
+        sh_println!("{table}")?;
+
+        if mismatches > 0 {
+            eyre::bail!("{mismatches} deployments don't match the code on chain {chain_id}")
+        }
+        Ok(())
+    }
+}
+
+/// Loads the registries of `chain`, or of all chains if `None`, checking that `name` is recorded.
+fn load_registries(
+    config: &Config,
+    chain: Option<Chain>,
+    name: Option<&str>,
+) -> Result<Vec<DeploymentRegistry>> {
+    let registries = match chain {
+        Some(chain) => vec![DeploymentRegistry::load(config, chain.id())?],
+        None => DeploymentRegistry::load_all(config)?,
+    };
+    if let Some(name) = name {
+        if !registries.iter().any(|registry| registry.deployments.contains_key(name)) {
+            eyre::bail!("No deployment named `{name}` recorded")
+        }
+    }
+    Ok(registries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_deployments() {
+        let args = DeploymentsArgs::parse_from(["deployments", "ls", "Counter", "--chain", "1"]);
+        let DeploymentsSubcommands::List(args) = args.sub else { panic!("expected list") };
+        assert_eq!(args.name.as_deref(), Some("Counter"));
+        assert_eq!(args.chain, Some(Chain::mainnet()));
+
+        let args = DeploymentsArgs::parse_from([
+            "deployments",
+            "verify",
+            "--rpc-url",
+            "http://localhost:8545",
+        ]);
+        let DeploymentsSubcommands::Verify(args) = args.sub else { panic!("expected verify") };
+        assert_eq!(args.rpc.url.as_deref(), Some("http://localhost:8545"));
+    }
+}
@@ -2,7 +2,8 @@
 use clap::{Parser, ValueHint};
 use eyre::Result;
 use forge_doc::{
-    ContractInheritance, Deployments, DocBuilder, GitSource, InferInlineHyperlinks, Inheritdoc,
+    ContractAbi, ContractInheritance, Deployments, Diagrams, DocBuilder, DocFormat, GitSource,
+    InferInlineHyperlinks, Inheritdoc,
 };
 use foundry_cli::opts::GH_REPO_PREFIX_REGEX;
 use foundry_common::compile::ProjectCompiler;
@@ -63,15 +64,44 @@ pub struct DocArgs {
     /// Whether to create docs for external libraries.
     #[arg(long, short)]
     include_libraries: bool,
+
+    /// Whether to render the calls of each contract to the external functions of the other project
+    /// contracts as a diagram.
+    #[arg(long)]
+    call_graph: bool,
+
+    /// Check the NatSpec comments of the project against the `lint_rules` of the `[doc]` config,
+    /// instead of generating the documentation.
+    ///
+    /// Exits with a non-zero code if any rule is violated.
+    #[arg(long, conflicts_with_all = ["build", "serve", "call_graph"])]
+    lint: bool,
+
+    /// The output format: `markdown` for an mdbook, or `json` for a single `docs.json` file with
+    /// the NatSpec, inheritance, source links, deployments and ABI signatures of each item.
+    #[arg(long, value_name = "FORMAT", default_value = "markdown")]
+    format: DocFormat,
 }
 
 impl DocArgs {
     pub async fn run(self) -> Result<()> {
+        if self.format == DocFormat::Json && (self.build || self.serve) {
+            eyre::bail!("`--build` and `--serve` are only supported for the markdown format");
+        }
+        if self.format == DocFormat::Json && self.call_graph {
+            eyre::bail!("`--call-graph` is only supported for the markdown format");
+        }
+
         let config = self.config()?;
         let root = &config.root;
         let project = config.project()?;
-        let compiler = ProjectCompiler::new().quiet(true);
-        let _output = compiler.compile(&project)?;
+        // Linting only parses the sources, so that NatSpec errors are reported as lint issues
+        // rather than failing the compilation.
+        let output = if self.lint {
+            None
+        } else {
+            Some(ProjectCompiler::new().quiet(true).compile(&project)?)
+        };
 
         let mut doc_config = config.doc;
         if let Some(out) = self.out {
@@ -104,6 +134,7 @@ pub async fn run(self) -> Result<()> {
             self.include_libraries,
         )
         .with_should_build(self.build)
+        .with_format(self.format)
         .with_config(doc_config.clone())
         .with_fmt(config.fmt)
         .with_preprocessor(ContractInheritance { include_libraries: self.include_libraries })
@@ -120,6 +151,33 @@ pub async fn run(self) -> Result<()> {
             builder = builder.with_preprocessor(Deployments { root: root.clone(), deployments });
         }
 
+        if self.format == DocFormat::Markdown {
+            builder = builder.with_preprocessor(Diagrams { call_graph: self.call_graph });
+        }
+
+        if let Some(output) = output.filter(|_| self.format == DocFormat::Json) {
+            let abis = output
+                .artifact_ids()
+                .filter_map(|(id, artifact)| Some((id.source, id.name, artifact.abi.clone()?)))
+                .collect();
+            builder = builder.with_preprocessor(ContractAbi { abis });
+        }
+
+        if self.lint {
+            let diagnostics = builder.lint()?;
+            for diagnostic in diagnostics.iter() {
+                sh_println!("{diagnostic}")?;
+            }
+            if !diagnostics.is_empty() {
+                eyre::bail!(
+                    "found {} NatSpec issue{}",
+                    diagnostics.len(),
+                    if diagnostics.len() == 1 { "" } else { "s" }
+                );
+            }
+            return Ok(())
+        }
+
         builder.build()?;
 
         if self.serve {
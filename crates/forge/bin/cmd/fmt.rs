@@ -2,15 +2,17 @@
 use eyre::{Context, Result};
 use forge_fmt::{format_to, parse};
 use foundry_cli::utils::{FoundryPathExt, LoadConfig};
-use foundry_common::fs;
+use foundry_common::{fs, shell};
 use foundry_compilers::{compilers::solc::SolcLanguage, solc::SOLC_EXTENSIONS};
 use foundry_config::{filter::expand_globs, impl_figment_convert_basic};
 use rayon::prelude::*;
-use similar::{ChangeTag, TextDiff};
+use serde::Serialize;
+use similar::{ChangeTag, DiffTag, TextDiff};
 use std::{
     fmt::{self, Write},
     io,
     io::{Read, Write as _},
+    ops::Range,
     path::{Path, PathBuf},
 };
 use yansi::{Color, Paint, Style};
@@ -39,13 +41,47 @@ pub struct FmtArgs {
     /// In 'check' and stdin modes, outputs raw formatted code instead of the diff.
     #[arg(long, short)]
     raw: bool,
+
+    /// In 'check' and stdin modes, outputs a unified diff instead of the diff summary.
+    ///
+    /// With `--json`, outputs the edits to apply to each file instead.
+    #[arg(long, conflicts_with = "raw")]
+    diff: bool,
+
+    /// Only format the items intersecting the given byte range, e.g. `120:380`.
+    ///
+    /// Can only be used when formatting a single file or stdin.
+    #[arg(long, value_name = "START:END", value_parser = parse_range)]
+    range: Option<Range<usize>>,
+
+    /// Only format the items intersecting the given 1-based, inclusive line range, e.g. `10:24`.
+    ///
+    /// Can only be used when formatting a single file or stdin.
+    #[arg(
+        long,
+        value_name = "START:END",
+        value_parser = parse_line_range,
+        conflicts_with = "range"
+    )]
+    lines: Option<Range<usize>>,
 }
 
 impl_figment_convert_basic!(FmtArgs);
 
 impl FmtArgs {
     pub fn run(self) -> Result<()> {
-        let config = self.load_config()?;
+        let mut config = self.load_config()?;
+        if config.fmt.group_imports && config.fmt.import_groups.is_empty() {
+            let mut groups = config.get_all_remappings().map(|r| r.name).collect::<Vec<_>>();
+            groups.sort_unstable();
+            groups.dedup();
+            config.fmt.import_groups = groups;
+        }
+        if self.range.is_some() || self.lines.is_some() {
+            // Moving items would change code outside of the range.
+            config.fmt.group_imports = false;
+            config.fmt.sort_members = false;
+        }
 
         // Expand ignore globs and canonicalize from the get go
         let ignored = expand_globs(&config.root, config.fmt.ignore.iter())?
@@ -94,13 +130,21 @@ pub fn run(self) -> Result<()> {
             }
         };
 
+        if self.range.is_some() || self.lines.is_some() {
+            if let Input::Paths(paths) = &input {
+                if paths.len() != 1 {
+                    eyre::bail!("`--range` and `--lines` can only be used with a single file");
+                }
+            }
+        }
+
         let format = |source: String, path: Option<&Path>| -> Result<_> {
             let name = match path {
                 Some(path) => path.strip_prefix(&config.root).unwrap_or(path).display().to_string(),
                 None => "stdin".to_string(),
             };
 
-            let parsed = parse(&source).wrap_err_with(|| {
+            let mut parsed = parse(&source).wrap_err_with(|| {
                 format!("Failed to parse Solidity code for {name}. Leaving source unchanged.")
             })?;
 
@@ -113,6 +157,12 @@ pub fn run(self) -> Result<()> {
                 }
             }
 
+            if let Some(range) = self.range.clone() {
+                parsed.restrict_to_range(range);
+            } else if let Some(lines) = &self.lines {
+                parsed.restrict_to_range(line_range_to_bytes(&source, lines));
+            }
+
             let mut output = String::new();
             format_to(&mut output, parsed, config.fmt.clone()).unwrap();
 
@@ -130,9 +180,23 @@ pub fn run(self) -> Result<()> {
                     sh_print!("{output}")?;
                 }
 
-                // If new format then compute diff summary.
+                // If new format then compute the diff.
                 if new_format {
-                    return Ok(Some(format_diff_summary(&name, &diff)))
+                    let diff = if shell::is_json() {
+                        FmtDiff::Edits(FileEdits {
+                            path: name,
+                            edits: text_edits(&source, &output),
+                        })
+                    } else if self.diff {
+                        FmtDiff::Text(
+                            diff.unified_diff()
+                                .header(&format!("a/{name}"), &format!("b/{name}"))
+                                .to_string(),
+                        )
+                    } else {
+                        FmtDiff::Text(format_diff_summary(&name, &diff))
+                    };
+                    return Ok(Some(diff))
                 }
             } else if let Some(path) = path {
                 // If new format then write it on disk.
@@ -143,6 +207,7 @@ pub fn run(self) -> Result<()> {
             Ok(None)
         };
 
+        let is_stdin = matches!(input, Input::Stdin(_));
         let diffs = match input {
             Input::Stdin(source) => format(source, None).map(|diff| vec![diff]),
             Input::Paths(paths) => {
@@ -164,30 +229,131 @@ pub fn run(self) -> Result<()> {
             }
         }?;
 
-        let mut diffs = diffs.iter().flatten();
-        if let Some(first) = diffs.next() {
-            // This branch is only reachable with stdin or --check
-
-            if !self.raw {
-                let mut stdout = io::stdout().lock();
-                let first = std::iter::once(first);
-                for (i, diff) in first.chain(diffs).enumerate() {
+        let diffs = diffs.into_iter().flatten().collect::<Vec<_>>();
+        if shell::is_json() && !self.raw && (self.check || is_stdin) {
+            let edits = diffs
+                .iter()
+                .filter_map(|diff| match diff {
+                    FmtDiff::Edits(edits) => Some(edits),
+                    FmtDiff::Text(_) => None,
+                })
+                .collect::<Vec<_>>();
+            sh_println!("{}", serde_json::to_string_pretty(&edits)?)?;
+        } else if !self.raw {
+            // This is only reachable with stdin or --check
+            let mut stdout = io::stdout().lock();
+            for (i, diff) in diffs.iter().enumerate() {
+                if let FmtDiff::Text(diff) = diff {
                     if i > 0 {
                         let _ = stdout.write_all(b"\n");
                     }
                     let _ = stdout.write_all(diff.as_bytes());
                 }
             }
+        }
 
-            if self.check {
-                std::process::exit(1);
-            }
+        if self.check && !diffs.is_empty() {
+            std::process::exit(1);
         }
 
         Ok(())
     }
 }
 
+/// The changes needed to format a file.
+enum FmtDiff {
+    /// A diff summary or unified diff.
+    Text(String),
+    /// A list of edits, with `--json`.
+    Edits(FileEdits),
+}
+
+/// The edits needed to format a file.
+#[derive(Debug, Serialize)]
+struct FileEdits {
+    path: String,
+    edits: Vec<TextEdit>,
+}
+
+/// A text edit as defined by the Language Server Protocol.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TextEdit {
+    range: TextRange,
+    new_text: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct TextRange {
+    start: Position,
+    end: Position,
+}
+
+/// A 0-based line and UTF-16 character offset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+struct Position {
+    line: usize,
+    character: usize,
+}
+
+/// Returns the line-based edits that turn `old` into `new`.
+fn text_edits(old: &str, new: &str) -> Vec<TextEdit> {
+    let diff = TextDiff::from_lines(old, new);
+    let old_lines = diff.old_slices();
+    let new_lines = diff.new_slices();
+
+    // Returns the position of the start of the line at `idx`, or of the end of the text.
+    let position = |idx: usize| {
+        if idx < old_lines.len() || old.is_empty() || old.ends_with('\n') {
+            Position { line: idx, character: 0 }
+        } else {
+            let last = old_lines[old_lines.len() - 1];
+            Position { line: old_lines.len() - 1, character: last.encode_utf16().count() }
+        }
+    };
+
+    diff.ops()
+        .iter()
+        .filter(|op| op.tag() != DiffTag::Equal)
+        .map(|op| TextEdit {
+            range: TextRange {
+                start: position(op.old_range().start),
+                end: position(op.old_range().end),
+            },
+            new_text: new_lines[op.new_range()].concat(),
+        })
+        .collect()
+}
+
+/// Parses a `START:END` range.
+fn parse_range(s: &str) -> Result<Range<usize>, String> {
+    let (start, end) =
+        s.split_once(':').ok_or_else(|| format!("expected `START:END`, got `{s}`"))?;
+    let start = start.trim().parse::<usize>().map_err(|e| format!("invalid start: {e}"))?;
+    let end = end.trim().parse::<usize>().map_err(|e| format!("invalid end: {e}"))?;
+    if start > end {
+        return Err(format!("start ({start}) must not be greater than end ({end})"));
+    }
+    Ok(start..end)
+}
+
+/// Parses a 1-based `START:END` line range.
+fn parse_line_range(s: &str) -> Result<Range<usize>, String> {
+    let range = parse_range(s)?;
+    if range.start == 0 {
+        return Err("lines are 1-based, 0 is not a valid line".to_string());
+    }
+    Ok(range)
+}
+
+/// Converts a 1-based, inclusive line range to a byte range in `src`.
+fn line_range_to_bytes(src: &str, lines: &Range<usize>) -> Range<usize> {
+    let line_start = |line: usize| {
+        src.split_inclusive('\n').take(line.saturating_sub(1)).map(str::len).sum::<usize>()
+    };
+    line_start(lines.start)..line_start(lines.end + 1)
+}
+
 struct Line(Option<usize>);
 
 #[derive(Debug)]
@@ -247,3 +413,48 @@ fn format_diff_summary<'a>(name: &str, diff: &'a TextDiff<'a, 'a, '_, str>) -> S
 
     diff_summary
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_range() {
+        assert_eq!(parse_range("10:24").unwrap(), 10..24);
+        assert!(parse_range("24:10").is_err());
+        assert!(parse_range("10").is_err());
+
+        assert_eq!(parse_line_range("1:1").unwrap(), 1..1);
+        assert!(parse_line_range("0:10").is_err());
+    }
+
+    #[test]
+    fn converts_line_range() {
+        let src = "a\nbb\nccc\n";
+        assert_eq!(line_range_to_bytes(src, &(2..2)), 2..5);
+        assert_eq!(line_range_to_bytes(src, &(1..3)), 0..src.len());
+    }
+
+    #[test]
+    fn computes_text_edits() {
+        let pos = |line, character| Position { line, character };
+        let edits = text_edits("a\nb  \nc\n", "a\nb\nc\n");
+        assert_eq!(
+            edits,
+            [TextEdit {
+                range: TextRange { start: pos(1, 0), end: pos(2, 0) },
+                new_text: "b\n".into()
+            }]
+        );
+
+        // Edits at the end of text without a trailing newline.
+        let edits = text_edits("a\nb", "a\nb\n");
+        assert_eq!(
+            edits,
+            [TextEdit {
+                range: TextRange { start: pos(1, 0), end: pos(1, 1) },
+                new_text: "b\n".into()
+            }]
+        );
+    }
+}
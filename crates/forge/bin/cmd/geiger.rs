@@ -1,10 +1,17 @@
 use clap::{Parser, ValueHint};
 use eyre::{Result, WrapErr};
 use foundry_cli::utils::LoadConfig;
+use foundry_common::sarif::{
+    line_column, SarifLevel, SarifLocation, SarifReport, SarifResult, SarifRule,
+};
 use foundry_compilers::{resolver::parse::SolData, Graph};
 use foundry_config::{impl_figment_convert_basic, Config};
 use itertools::Itertools;
-use solar_parse::{ast, ast::visit::Visit, interface::Session};
+use solar_parse::{
+    ast,
+    ast::{visit::Visit, Span},
+    interface::Session,
+};
 use std::{
     ops::ControlFlow,
     path::{Path, PathBuf},
@@ -38,6 +45,10 @@ pub struct GeigerArgs {
     )]
     ignore: Vec<PathBuf>,
 
+    /// Write the findings to the given file as a SARIF report.
+    #[arg(long, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    sarif: Option<PathBuf>,
+
     #[arg(long, hide = true)]
     check: bool,
     #[arg(long, hide = true)]
@@ -111,25 +122,72 @@ pub fn run(self) -> Result<usize> {
             "setEnv".to_string(),
             "deriveKey".to_string(),
         ];
-        Ok(sess
-            .enter(|| sources.iter().map(|file| lint_file(&sess, unsafe_cheatcodes, file)).sum()))
+        let mut report = SarifReport::new("forge-geiger");
+        report.add_rule(SarifRule {
+            id: UNSAFE_CHEATCODE.to_string(),
+            description: "usage of a cheatcode that can access the file system or environment"
+                .to_string(),
+            level: SarifLevel::Error,
+            help_uri: None,
+        });
+        let count = sess.enter(|| {
+            sources
+                .iter()
+                .map(|file| lint_file(&sess, unsafe_cheatcodes, file, &mut report))
+                .sum::<usize>()
+        });
+
+        if let Some(path) = &self.sarif {
+            report.write(path)?;
+        }
+        Ok(count)
     }
 }
 
-fn lint_file(sess: &Session, unsafe_cheatcodes: &[String], path: &Path) -> usize {
-    try_lint_file(sess, unsafe_cheatcodes, path).unwrap_or(0)
+/// The SARIF rule id of geiger findings.
+const UNSAFE_CHEATCODE: &str = "unsafe-cheatcode";
+
+fn lint_file(
+    sess: &Session,
+    unsafe_cheatcodes: &[String],
+    path: &Path,
+    report: &mut SarifReport,
+) -> usize {
+    try_lint_file(sess, unsafe_cheatcodes, path, report).unwrap_or(0)
 }
 
 fn try_lint_file(
     sess: &Session,
     unsafe_cheatcodes: &[String],
     path: &Path,
+    report: &mut SarifReport,
 ) -> solar_parse::interface::Result<usize> {
     let arena = solar_parse::ast::Arena::new();
     let mut parser = solar_parse::Parser::from_file(sess, &arena, path)?;
     let ast = parser.parse_file().map_err(|e| e.emit())?;
     let mut visitor = Visitor::new(sess, unsafe_cheatcodes);
     visitor.visit_source_unit(&ast);
+
+    if !visitor.findings.is_empty() {
+        let source = std::fs::read_to_string(path).unwrap_or_default();
+        for (span, message) in visitor.findings {
+            let base = sess.source_map().lookup_source_file(span.lo()).start_pos.0 as usize;
+            let (start_line, start_column) = line_column(&source, span.lo().to_usize() - base);
+            let (end_line, end_column) = line_column(&source, span.hi().to_usize() - base);
+            report.add_result(SarifResult {
+                rule_id: UNSAFE_CHEATCODE.to_string(),
+                level: SarifLevel::Error,
+                message,
+                location: Some(SarifLocation {
+                    path: path.to_path_buf(),
+                    start_line,
+                    start_column,
+                    end_line,
+                    end_column,
+                }),
+            });
+        }
+    }
     Ok(visitor.count)
 }
 
@@ -137,11 +195,13 @@ struct Visitor<'a> {
     sess: &'a Session,
     count: usize,
     unsafe_cheatcodes: &'a [String],
+    /// The location and message of each finding.
+    findings: Vec<(Span, String)>,
 }
 
 impl<'a> Visitor<'a> {
     fn new(sess: &'a Session, unsafe_cheatcodes: &'a [String]) -> Self {
-        Self { sess, count: 0, unsafe_cheatcodes }
+        Self { sess, count: 0, unsafe_cheatcodes, findings: Vec::new() }
     }
 }
 
@@ -153,7 +213,8 @@ fn visit_expr(&mut self, expr: &'ast ast::Expr<'ast>) -> ControlFlow<Self::Break
             if let ast::ExprKind::Member(_lhs, member) = &lhs.kind {
                 if self.unsafe_cheatcodes.iter().any(|c| c.as_str() == member.as_str()) {
                     let msg = format!("usage of unsafe cheatcode `vm.{member}`");
-                    self.sess.dcx.err(msg).span(member.span).emit();
+                    self.sess.dcx.err(msg.clone()).span(member.span).emit();
+                    self.findings.push((member.span, msg));
                     self.count += 1;
                 }
             }
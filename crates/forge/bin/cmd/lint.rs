@@ -0,0 +1,138 @@
+use clap::{Parser, ValueHint};
+use eyre::{Result, WrapErr};
+use forge_lint::{Diagnostic, Linter, Severity};
+use foundry_cli::utils::LoadConfig;
+use foundry_common::shell;
+use foundry_compilers::{compilers::solc::SolcLanguage, solc::SOLC_EXTENSIONS};
+use foundry_config::{filter::expand_globs, impl_figment_convert_basic, Config};
+use itertools::Itertools;
+use std::path::PathBuf;
+
+/// CLI arguments for `forge lint`.
+#[derive(Clone, Debug, Parser)]
+pub struct LintArgs {
+    /// Paths to files or directories to lint.
+    #[arg(
+        conflicts_with = "root",
+        value_hint = ValueHint::FilePath,
+        value_name = "PATH",
+        num_args(1..),
+    )]
+    paths: Vec<PathBuf>,
+
+    /// The project's root path.
+    ///
+    /// By default root of the Git repository, if in one,
+    /// or the current working directory.
+    #[arg(long, value_hint = ValueHint::DirPath, value_name = "PATH")]
+    root: Option<PathBuf>,
+
+    /// Only report findings of at least this severity.
+    #[arg(long, value_name = "SEVERITY", default_value = "info")]
+    severity: Severity,
+
+    /// Exit with a non-zero code if there are findings of at least this severity.
+    #[arg(long, value_name = "SEVERITY")]
+    deny: Option<Severity>,
+
+    /// Write the findings to the given file as a SARIF report.
+    #[arg(long, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    sarif: Option<PathBuf>,
+
+    /// List the available lints and their severities, then exit.
+    #[arg(long)]
+    list: bool,
+}
+
+impl_figment_convert_basic!(LintArgs);
+
+impl LintArgs {
+    /// Returns the files to lint, excluding the ones ignored in the config.
+    pub fn sources(&self, config: &Config) -> Result<Vec<PathBuf>> {
+        let ignored = expand_globs(&config.root, config.lint.ignore.iter())?
+            .iter()
+            .flat_map(foundry_common::fs::canonicalize_path)
+            .collect::<Vec<_>>();
+
+        let sources = if self.paths.is_empty() {
+            config.project_paths::<SolcLanguage>().input_files_iter().collect::<Vec<_>>()
+        } else {
+            self.paths
+                .iter()
+                .flat_map(|path| foundry_compilers::utils::source_files_iter(path, SOLC_EXTENSIONS))
+                .unique()
+                .collect()
+        };
+
+        let cwd = std::env::current_dir()?;
+        Ok(sources
+            .into_iter()
+            .filter(|path| {
+                !foundry_common::fs::canonicalize_path(path)
+                    .is_ok_and(|path| ignored.contains(&path))
+            })
+            .map(|path| path.strip_prefix(&cwd).map(|p| p.to_path_buf()).unwrap_or(path))
+            .collect())
+    }
+
+    /// Runs the linter, returning the number of findings at or above the `--deny` severity plus
+    /// the number of files that could not be linted.
+    pub fn run(self) -> Result<usize> {
+        let config = self.load_config()?;
+        let linter = Linter::new(&config.lint)?;
+
+        if self.list {
+            for lint in forge_lint::rules::lints() {
+                let severity = linter.severity(lint);
+                sh_println!("{:<24} {:<8} {}", lint.id, severity.as_str(), lint.description)?;
+            }
+            return Ok(0);
+        }
+
+        let sources = self.sources(&config).wrap_err("Failed to resolve files")?;
+        let mut diagnostics = Vec::new();
+        let mut failed = 0;
+        for path in &sources {
+            match linter.lint_file(path) {
+                Ok(file_diagnostics) => diagnostics.extend(file_diagnostics),
+                Err(err) => {
+                    sh_err!("{err:?}")?;
+                    failed += 1;
+                }
+            }
+        }
+        diagnostics.retain(|d| d.severity >= self.severity);
+
+        if let Some(path) = &self.sarif {
+            linter.sarif_report(&diagnostics).write(path)?;
+        }
+
+        if shell::is_json() {
+            sh_println!("{}", serde_json::to_string_pretty(&diagnostics)?)?;
+        } else {
+            print_diagnostics(&diagnostics, sources.len())?;
+        }
+
+        let denied =
+            self.deny.map_or(0, |deny| diagnostics.iter().filter(|d| d.severity >= deny).count());
+        Ok(denied + failed)
+    }
+}
+
+fn print_diagnostics(diagnostics: &[Diagnostic], files: usize) -> Result<()> {
+    for diagnostic in diagnostics {
+        sh_println!("{diagnostic}")?;
+    }
+    let counts = [Severity::High, Severity::Medium, Severity::Low, Severity::Info]
+        .into_iter()
+        .map(|severity| (severity, diagnostics.iter().filter(|d| d.severity == severity).count()))
+        .filter(|(_, count)| *count > 0)
+        .map(|(severity, count)| format!("{count} {severity}"))
+        .join(", ");
+    if counts.is_empty() {
+        sh_println!("No findings in {files} files")?;
+    } else {
+        sh_println!("\nFound {counts} in {files} files")?;
+    }
+    Ok(())
+}
@@ -14,6 +14,7 @@
 pub mod config;
 pub mod coverage;
 pub mod create;
+pub mod deployments;
 pub mod doc;
 pub mod eip712;
 pub mod flatten;
@@ -23,6 +24,7 @@
 pub mod init;
 pub mod inspect;
 pub mod install;
+pub mod lint;
 pub mod remappings;
 pub mod remove;
 pub mod selectors;
@@ -39,7 +39,7 @@
     filter::GlobMatcher,
     Config,
 };
-use foundry_debugger::Debugger;
+use foundry_debugger::{DapTransport, Debugger};
 use foundry_evm::traces::identifier::TraceIdentifiers;
 use regex::Regex;
 use std::{
@@ -114,6 +114,13 @@ pub struct TestArgs {
     )]
     dump: Option<PathBuf>,
 
+    /// Serves the debugger over the Debug Adapter Protocol instead of opening the TUI.
+    ///
+    /// Takes a local port to listen on, or `stdio` to communicate over stdin and stdout, which
+    /// requires `--quiet`.
+    #[arg(long, requires = "debug", conflicts_with = "dump", value_name = "PORT|stdio")]
+    dap: Option<DapTransport>,
+
     /// Print a gas report.
     #[arg(long, env = "FORGE_GAS_REPORT")]
     gas_report: bool,
@@ -414,6 +421,8 @@ pub async fn execute_tests(mut self) -> Result<TestOutcome> {
             let mut debugger = builder.build();
             if let Some(dump_path) = self.dump {
                 debugger.dump_to_file(&dump_path)?;
+            } else if let Some(transport) = self.dap {
+                debugger.try_run_dap(transport, project.root())?;
             } else {
                 debugger.try_run_tui()?;
             }
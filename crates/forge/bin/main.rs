@@ -73,6 +73,7 @@ fn run() -> Result<()> {
             CacheSubcommands::Ls(cmd) => cmd.run(),
         },
         ForgeSubcommand::Create(cmd) => utils::block_on(cmd.run()),
+        ForgeSubcommand::Deployments(cmd) => utils::block_on(cmd.run()),
         ForgeSubcommand::Update(cmd) => cmd.run(),
         ForgeSubcommand::Install(cmd) => cmd.run(),
         ForgeSubcommand::Remove(cmd) => cmd.run(),
@@ -116,6 +117,12 @@ fn run() -> Result<()> {
             }
             Ok(())
         }
+        ForgeSubcommand::Lint(cmd) => {
+            if cmd.run()? > 0 {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
         ForgeSubcommand::Doc(cmd) => {
             if cmd.is_watch() {
                 utils::block_on(watch::watch_doc(cmd))
@@ -1,9 +1,9 @@
 use crate::cmd::{
     bind::BindArgs, bind_json, build::BuildArgs, cache::CacheArgs, clone::CloneArgs,
-    compiler::CompilerArgs, config, coverage, create::CreateArgs, doc::DocArgs, eip712, flatten,
-    fmt::FmtArgs, geiger, generate, init::InitArgs, inspect, install::InstallArgs,
-    remappings::RemappingArgs, remove::RemoveArgs, selectors::SelectorsSubcommands, snapshot,
-    soldeer, test, tree, update,
+    compiler::CompilerArgs, config, coverage, create::CreateArgs, deployments::DeploymentsArgs,
+    doc::DocArgs, eip712, flatten, fmt::FmtArgs, geiger, generate, init::InitArgs, inspect,
+    install::InstallArgs, lint, remappings::RemappingArgs, remove::RemoveArgs,
+    selectors::SelectorsSubcommands, snapshot, soldeer, test, tree, update,
 };
 use clap::{Parser, Subcommand, ValueHint};
 use forge_script::ScriptArgs;
@@ -90,6 +90,9 @@ pub enum ForgeSubcommand {
     #[command(visible_alias = "c")]
     Create(CreateArgs),
 
+    /// Inspect the deployment registry recorded by scripts.
+    Deployments(DeploymentsArgs),
+
     /// Create a new Forge project.
     Init(InitArgs),
 
@@ -144,6 +147,9 @@ pub enum ForgeSubcommand {
     /// Detects usage of unsafe cheat codes in a project and its dependencies.
     Geiger(geiger::GeigerArgs),
 
+    /// Lint Solidity source files.
+    Lint(lint::LintArgs),
+
     /// Generate documentation for the project.
     Doc(DocArgs),
 
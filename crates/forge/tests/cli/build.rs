@@ -70,6 +70,36 @@
 "#]].is_json());
 });
 
+// tests that compiler diagnostics are written as SARIF when --sarif is passed
+forgetest!(compile_sarif, |prj, cmd| {
+    prj.add_source(
+        "sarifError",
+        r"
+contract Dummy {
+    uint256 public number;
+    function something(uint256 newNumber) public {
+        number = newnumber; // error here
+    }
+}
+",
+    )
+    .unwrap();
+
+    cmd.args(["build", "--sarif", "report.sarif"]).assert_failure();
+
+    let report: serde_json::Value =
+        foundry_compilers::utils::read_json_file(&prj.root().join("report.sarif")).unwrap();
+    assert_eq!(report["version"], "2.1.0");
+    let run = &report["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "solc");
+    let result = &run["results"][0];
+    assert_eq!(result["ruleId"], "7576");
+    assert_eq!(result["level"], "error");
+    let location = &result["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "src/sarifError.sol");
+    assert_eq!(location["region"]["startLine"], 7);
+});
+
 forgetest!(initcode_size_exceeds_limit, |prj, cmd| {
     prj.add_source("LargeContract.sol", generate_large_init_contract(50_000).as_str()).unwrap();
     cmd.args(["build", "--sizes"]).assert_failure().stdout_eq(str![[r#"
@@ -144,6 +144,7 @@
         fmt: Default::default(),
         doc: Default::default(),
         bind_json: Default::default(),
+        lint: Default::default(),
         fs_permissions: Default::default(),
         labels: Default::default(),
         isolate: true,
@@ -1056,6 +1057,9 @@
 ignore = []
 contract_new_lines = false
 sort_imports = false
+group_imports = false
+import_groups = []
+sort_members = false
 
 [doc]
 out = "docs"
@@ -1063,6 +1067,12 @@
 book = "book.toml"
 homepage = "README.md"
 ignore = []
+lint_rules = [
+    "missing-notice",
+    "missing-param",
+    "mismatched-param",
+    "stale-inheritdoc",
+]
 
 [fuzz]
 runs = 256
@@ -1103,6 +1113,11 @@
 include = []
 exclude = []
 
+[lint]
+ignore = []
+
+[lint.severity]
+
 
 "#]]);
 
@@ -1253,20 +1268,33 @@
     "wrap_comments": false,
     "ignore": [],
     "contract_new_lines": false,
-    "sort_imports": false
+    "sort_imports": false,
+    "group_imports": false,
+    "import_groups": [],
+    "sort_members": false
   },
   "doc": {
     "out": "docs",
     "title": "",
     "book": "book.toml",
     "homepage": "README.md",
-    "ignore": []
+    "ignore": [],
+    "lint_rules": [
+      "missing-notice",
+      "missing-param",
+      "mismatched-param",
+      "stale-inheritdoc"
+    ]
   },
   "bind_json": {
     "out": "utils/JsonBindings.sol",
     "include": [],
     "exclude": []
   },
+  "lint": {
+    "severity": {},
+    "ignore": []
+  },
   "fs_permissions": [
     {
       "access": "read",
@@ -1,3 +1,4 @@
+use foundry_config::DocLintRule;
 use foundry_test_utils::util::{setup_forge_remote, RemoteProject};
 
 #[test]
@@ -6,3 +7,105 @@ fn can_generate_solmate_docs() {
         setup_forge_remote(RemoteProject::new("transmissions11/solmate").set_build(false));
     prj.forge_command().args(["doc", "--build"]).assert_success();
 }
+
+forgetest!(can_generate_json_docs, |prj, cmd| {
+    prj.add_source(
+        "Counter.sol",
+        r#"
+/// @title Counter
+/// @notice A counter.
+contract Counter {
+    uint256 public number;
+
+    /// @notice Increments the counter.
+    /// @param by The amount to add.
+    function increment(uint256 by) external {
+        number += by;
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    cmd.args(["doc", "--format", "json"]).assert_success();
+
+    let doc: serde_json::Value =
+        foundry_compilers::utils::read_json_file(&prj.root().join("docs/docs.json")).unwrap();
+    assert_eq!(doc["version"], 1);
+    let contract = &doc["items"][0];
+    assert_eq!(contract["kind"], "contract");
+    assert_eq!(contract["name"], "Counter");
+    assert_eq!(contract["path"], "src/Counter.sol");
+    assert_eq!(contract["natspec"]["title"], "Counter");
+    assert_eq!(contract["natspec"]["notice"], "A counter.");
+    assert!(contract["abi"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|item| item["signature"] == "increment(uint256)"));
+
+    let increment = contract["members"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|member| member["name"] == "increment")
+        .unwrap();
+    assert_eq!(increment["kind"], "function");
+    assert_eq!(increment["natspec"]["params"][0]["name"], "by");
+    assert_eq!(increment["natspec"]["params"][0]["description"], "The amount to add.");
+});
+
+forgetest!(can_lint_natspec, |prj, cmd| {
+    prj.add_source(
+        "Vault.sol",
+        r#"
+interface IVault {
+    /// @notice Deposits tokens.
+    /// @param amount The amount to deposit.
+    function deposit(uint256 amount) external;
+}
+
+contract Vault is IVault {
+    /// @notice Emitted on withdrawals.
+    event Withdraw(address to, uint256 amount);
+
+    error Empty();
+
+    /// @inheritdoc IVault
+    function deposit(uint256 amount) external {}
+
+    /// @inheritdoc IOther
+    function withdraw(uint256 amount) external {}
+
+    /// @notice Sweeps the tokens.
+    /// @param token The token to sweep.
+    function sweep(address to) public {}
+
+    function helper(uint256 amount) internal {}
+}
+"#,
+    )
+    .unwrap();
+
+    cmd.args(["doc", "--lint"]).assert_failure().stdout_eq(str![[r#"
+src/Vault.sol:[..]: event `Vault.Withdraw` is missing `@param to` [missing-param]
+src/Vault.sol:[..]: event `Vault.Withdraw` is missing `@param amount` [missing-param]
+src/Vault.sol:[..]: error `Vault.Empty` is missing `@notice` [missing-notice]
+src/Vault.sol:[..]: `Vault.withdraw` inherits the documentation of `IOther`, which is not a base declaring `withdraw` [stale-inheritdoc]
+src/Vault.sol:[..]: function `Vault.withdraw` is missing `@notice` [missing-notice]
+src/Vault.sol:[..]: function `Vault.withdraw` is missing `@param amount` [missing-param]
+src/Vault.sol:[..]: function `Vault.sweep` is missing `@param to` [missing-param]
+src/Vault.sol:[..]: function `Vault.sweep` documents `@param token`, which is not a parameter [mismatched-param]
+
+"#]]).stderr_eq(str![[r#"
+Error: found 8 NatSpec issues
+
+"#]]);
+
+    // Rules can be disabled in the config.
+    prj.update_config(|config| config.doc.lint_rules = vec![DocLintRule::MissingNotice]);
+    cmd.forge_fuse().args(["doc", "--lint"]).assert_failure().stderr_eq(str![[r#"
+Error: found 2 NatSpec issues
+
+"#]]);
+});
@@ -90,3 +90,29 @@
 
 "#]]);
 });
+
+forgetest!(sarif, |prj, cmd| {
+    prj.add_source(
+        "call.sol",
+        r#"
+        contract A is Test {
+            function do_ffi() public {
+                string[] memory inputs = new string[](1);
+                vm.ffi(inputs);
+            }
+        }
+    "#,
+    )
+    .unwrap();
+
+    cmd.args(["geiger", "--sarif", "geiger.sarif"]).assert_code(1);
+
+    let report: serde_json::Value =
+        foundry_compilers::utils::read_json_file(&prj.root().join("geiger.sarif")).unwrap();
+    let result = &report["runs"][0]["results"][0];
+    assert_eq!(result["ruleId"], "unsafe-cheatcode");
+    assert_eq!(result["message"]["text"], "usage of unsafe cheatcode `vm.ffi`");
+    let region = &result["locations"][0]["physicalLocation"]["region"];
+    assert_eq!(region["startLine"], 7);
+    assert_eq!(region["startColumn"], 20);
+});
@@ -0,0 +1,66 @@
+forgetest!(lint, |prj, cmd| {
+    prj.add_raw_source(
+        "Lint.sol",
+        r#"contract Lint {
+    address owner;
+
+    function f(address to) external {
+        to.call("");
+        require(tx.origin == owner);
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    cmd.args(["lint", "--deny", "high"]).assert_code(1).stdout_eq(str![[r#"
+src/Lint.sol:5:9: high[unchecked-call]: the return value of the low-level `call` is not checked
+src/Lint.sol:6:17: high[tx-origin]: `tx.origin` used for authorization, use `msg.sender`
+
+Found 2 high in 1 files
+
+"#]]);
+
+    // Lints can be turned off in the config or inline.
+    prj.update_config(|config| {
+        config.lint.severity.insert("tx-origin".to_string(), "off".parse().unwrap());
+    });
+    prj.add_raw_source(
+        "Lint.sol",
+        r#"contract Lint {
+    function f(address to) external {
+        // forgelint: disable-next-line
+        to.call("");
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    cmd.forge_fuse().args(["lint", "--deny", "high"]).assert_success().stdout_eq(str![[r#"
+No findings in 1 files
+
+"#]]);
+});
+
+// A file that fails to parse is reported without stopping the other files from being linted.
+forgetest!(lint_parse_error, |prj, cmd| {
+    prj.add_raw_source("Broken.sol", "contract Broken {").unwrap();
+    prj.add_raw_source(
+        "Lint.sol",
+        r#"contract Lint {
+    function f(address to) external {
+        to.call("");
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    cmd.args(["lint"]).assert_code(1).stdout_eq(str![[r#"
+src/Lint.sol:3:9: high[unchecked-call]: the return value of the low-level `call` is not checked
+
+Found 1 high in 2 files
+
+"#]]);
+});
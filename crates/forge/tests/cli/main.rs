@@ -19,6 +19,7 @@
 mod failure_assertions;
 mod geiger;
 mod inline_config;
+mod lint;
 mod multi_script;
 mod odyssey;
 mod script;
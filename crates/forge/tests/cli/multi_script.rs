@@ -1,7 +1,9 @@
 //! Contains various tests related to forge script
+use alloy_primitives::{address, U256};
 use anvil::{spawn, NodeConfig};
 
 use foundry_test_utils::{ScriptOutcome, ScriptTester};
+use serde_json::Value;
 
 forgetest_async!(can_deploy_multi_chain_script_without_lib, |prj, cmd| {
     let (api1, handle1) = spawn(NodeConfig::test()).await;
@@ -64,3 +66,89 @@
         .arg("--multi")
         .resume(ScriptOutcome::OkBroadcast);
 });
+
+// Tests that resuming a multi-chain deployment skips the completed stages and continues the stage
+// which stopped.
+forgetest_async!(can_resume_multi_chain_stages, |prj, cmd| {
+    foundry_test_utils::util::initialize(prj.root());
+    let (api1, handle1) = spawn(NodeConfig::test()).await;
+    let (api2, handle2) = spawn(NodeConfig::test().with_chain_id(Some(31338u64))).await;
+    let sender = address!("f39Fd6e51aad88F6F4ce6aB8827279cffFb92266");
+
+    prj.add_script(
+        "Multi.s.sol",
+        r#"
+import "forge-std/Script.sol";
+
+contract Empty {}
+
+contract MultiScript is Script {
+    function run(string memory rpc1, string memory rpc2) external {
+        vm.createSelectFork(rpc1);
+        vm.broadcast();
+        new Empty();
+
+        vm.createSelectFork(rpc2);
+        vm.broadcast();
+        new Empty();
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    // Once the first stage was included, drain the sender on the second chain before confirming
+    // the first stage, so that the second stage fails to be sent.
+    let balance = api2.balance(sender, None).await.unwrap();
+    let stopper = tokio::spawn(async move {
+        while api1.transaction_count(sender, None).await.unwrap().is_zero() {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        api2.anvil_set_balance(sender, U256::ZERO).await.unwrap();
+        api1.mine_one().await;
+        (api1, api2)
+    });
+
+    let args = [
+        "script",
+        "script/Multi.s.sol",
+        "--sig",
+        "run(string,string)",
+        &handle1.http_endpoint(),
+        &handle2.http_endpoint(),
+        "--private-key",
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        "--broadcast",
+        "--stage-confirmations",
+        "2",
+    ];
+    cmd.args(args).assert_failure().stderr_eq(str![[r#"
+...
+Error: Failed to send transaction
+...
+"#]]);
+    let (api1, api2) = stopper.await.unwrap();
+
+    let path = prj.root().join("broadcast/multi/Multi.s.sol-latest/run.json");
+    let sequence: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(sequence["stages"], serde_json::json!(["completed", "broadcasting"]));
+
+    api2.anvil_set_balance(sender, balance).await.unwrap();
+    cmd.forge_fuse().args(args).args(["--multi", "--resume"]).assert_success().stdout_eq(str![[
+        r#"
+...
+Skipping the sequence on chain 31337, completed by a previous run
+...
+ONCHAIN EXECUTION COMPLETE & SUCCESSFUL.
+...
+"#
+    ]]);
+
+    let sequence: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(sequence["stages"], serde_json::json!(["completed", "completed"]));
+    assert_eq!(sequence["deployments"][1]["receipts"].as_array().unwrap().len(), 1);
+
+    // The first stage wasn't broadcast again.
+    assert_eq!(api1.transaction_count(sender, None).await.unwrap().to::<u64>(), 1);
+    assert_eq!(api2.transaction_count(sender, None).await.unwrap().to::<u64>(), 1);
+});
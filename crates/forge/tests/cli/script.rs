@@ -1,8 +1,8 @@
 //! Contains various tests related to `forge script`.
 
 use crate::constants::TEMPLATE_CONTRACT;
-use alloy_primitives::{address, hex, Address, Bytes};
-use anvil::{spawn, NodeConfig};
+use alloy_primitives::{address, hex, Address, Bytes, B256, U256};
+use anvil::{spawn, EthereumHardfork, NodeConfig};
 use forge_script_sequence::ScriptSequence;
 use foundry_test_utils::{
     rpc::{self, next_http_rpc_endpoint},
@@ -2616,3 +2616,549 @@ struct Point {
 
 "#]]);
 });
+
+// Tests that the transactions of a Safe are batched into a signed Safe transaction instead of
+// being broadcast.
+forgetest_async!(can_propose_safe_transaction, |prj, cmd| {
+    foundry_test_utils::util::initialize(prj.root());
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let rpc = handle.http_endpoint();
+    let owner = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+    let owner_key = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    prj.add_source(
+        "MockSafe",
+        r#"
+contract MockSafe {
+    function nonce() external pure returns (uint256) {
+        return 7;
+    }
+
+    function getOwners() external pure returns (address[] memory owners) {
+        owners = new address[](1);
+        owners[0] = 0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266;
+    }
+
+    function getThreshold() external pure returns (uint256) {
+        return 1;
+    }
+}
+"#,
+    )
+    .unwrap();
+    prj.add_script(
+        "ProposeSafe.s.sol",
+        r#"
+import "forge-std/Script.sol";
+
+contract ProposeSafeScript is Script {
+    function run() external {
+        vm.startBroadcast(0x5FbDB2315678afecb367f032d93F642f64180aa3);
+        (bool success,) = address(0xbeef).call(abi.encodeWithSignature("ping()"));
+        require(success);
+        (success,) = address(0xcafe).call(abi.encodeWithSignature("pong()"));
+        require(success);
+        vm.stopBroadcast();
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    // The Safe is deployed by the owner's first transaction.
+    cmd.args([
+        "create",
+        "src/MockSafe.sol:MockSafe",
+        "--rpc-url",
+        &rpc,
+        "--private-key",
+        owner_key,
+        "--broadcast",
+    ])
+    .assert_success()
+    .stdout_eq(str![[r#"
+...
+Deployed to: 0x5FbDB2315678afecb367f032d93F642f64180aa3
+...
+"#]]);
+
+    cmd.forge_fuse()
+        .args([
+            "script",
+            "script/ProposeSafe.s.sol",
+            "--rpc-url",
+            &rpc,
+            "--safe",
+            "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+            "--private-key",
+            owner_key,
+        ])
+        .assert_success()
+        .stdout_eq(str![[r#"
+...
+Batched 2 transaction(s) of Safe 0x5FbDB2315678afecb367f032d93F642f64180aa3 on chain 31337 with nonce 7
+Safe transaction hash: [..]
+Signatures: 1/1
+The transaction can be executed with the `execTransaction` calldata.
+...
+"#]]);
+
+    let tx: Value = serde_json::from_str(
+        &std::fs::read_to_string(
+            prj.root().join("broadcast/ProposeSafe.s.sol/31337/dry-run/run-safe-tx-latest.json"),
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    // The calls go through MultiSend with a delegate call.
+    assert_eq!(tx["to"], "0x40A2aCCbd92BCA938b02010E17A5b8929b49130D");
+    assert_eq!(tx["operation"], 1);
+    assert_eq!(tx["nonce"], "0x7");
+    assert_eq!(tx["signatures"].as_array().unwrap().len(), 1);
+    assert_eq!(tx["signatures"][0]["owner"], owner);
+    assert!(tx["execTransaction"].is_string());
+});
+
+// Tests that the deployments of a script are only recorded with `--deployments`, and that they
+// can be listed and compared with the current build.
+forgetest_async!(can_record_deployments, |prj, cmd| {
+    foundry_test_utils::util::initialize(prj.root());
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let rpc = handle.http_endpoint();
+
+    prj.add_source(
+        "Box",
+        r#"
+contract Box {
+    uint256 public value;
+
+    function store(uint256 newValue) external {
+        value = newValue;
+    }
+}
+"#,
+    )
+    .unwrap();
+    prj.add_script(
+        "DeployBox.s.sol",
+        r#"
+import "forge-std/Script.sol";
+import {Box} from "../src/Box.sol";
+
+contract DeployBoxScript is Script {
+    function run() external {
+        vm.startBroadcast();
+        Box main = new Box();
+        vm.label(address(main), "MainBox");
+        new Box();
+        new Box();
+        vm.stopBroadcast();
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    let args = [
+        "script",
+        "script/DeployBox.s.sol",
+        "--rpc-url",
+        rpc.as_str(),
+        "--private-key",
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        "--broadcast",
+    ];
+    cmd.args(args).assert_success();
+    assert!(!prj.root().join("deployments").exists());
+
+    cmd.forge_fuse().args(args).arg("--deployments").assert_success().stdout_eq(str![[r#"
+...
+Deployments saved to: [..]
+...
+"#]]);
+
+    let registry: Value = serde_json::from_str(
+        &std::fs::read_to_string(prj.root().join("deployments/31337.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(registry["chain"], 31337);
+    let deployments = registry["deployments"].as_object().unwrap();
+    assert_eq!(
+        deployments.keys().map(String::as_str).collect::<Vec<_>>(),
+        ["Box", "Box_1", "MainBox"]
+    );
+    let main = &deployments["MainBox"][0];
+    assert_eq!(main["version"], 1);
+    assert_eq!(main["contractName"], "Box");
+    assert_eq!(main["constructorArgs"], "0x");
+    assert_eq!(main["functions"], serde_json::json!(["store(uint256)", "value()"]));
+    assert!(main["transactionHash"].is_string());
+    assert!(main["bytecodeHash"].is_string());
+    assert!(main.get("proxy").is_none());
+
+    cmd.forge_fuse().args(["deployments", "ls", "MainBox"]).assert_success().stdout_eq(str![[r#"
+...
+[..]MainBox[..]
+...
+"#]]);
+
+    cmd.forge_fuse().args(["deployments", "diff", "--json"]).assert_success().stdout_eq(
+        str![[r#"
+[
+  {
+    "chain": 31337,
+    "name": "Box",
+    "contract": "Box",
+    "address": "[..]",
+    "status": "unchanged",
+    "added": [],
+    "removed": []
+  },
+  {
+    "chain": 31337,
+    "name": "Box_1",
+    "contract": "Box",
+    "address": "[..]",
+    "status": "unchanged",
+    "added": [],
+    "removed": []
+  },
+  {
+    "chain": 31337,
+    "name": "MainBox",
+    "contract": "Box",
+    "address": "[..]",
+    "status": "unchanged",
+    "added": [],
+    "removed": []
+  }
+]
+
+"#]]
+        .is_json(),
+    );
+
+    // Only code changes are reported, not metadata changes.
+    prj.add_source(
+        "Box",
+        r#"
+// A comment changing the metadata.
+contract Box {
+    uint256 public value;
+
+    function store(uint256 newValue) external {
+        value = newValue;
+    }
+}
+"#,
+    )
+    .unwrap();
+    cmd.forge_fuse().args(["deployments", "diff", "MainBox", "--json"]).assert_success().stdout_eq(
+        str![[r#"
+...
+    "status": "unchanged",
+...
+"#]],
+    );
+
+    prj.add_source(
+        "Box",
+        r#"
+contract Box {
+    uint256 public value;
+
+    function set(uint256 newValue) external {
+        value = newValue;
+    }
+}
+"#,
+    )
+    .unwrap();
+    cmd.forge_fuse().args(["deployments", "diff", "MainBox", "--json"]).assert_success().stdout_eq(
+        str![[r#"
+[
+  {
+    "chain": 31337,
+    "name": "MainBox",
+    "contract": "Box",
+    "address": "[..]",
+    "status": "changed",
+    "added": [
+      "set(uint256)"
+    ],
+    "removed": [
+      "store(uint256)"
+    ]
+  }
+]
+
+"#]]
+        .is_json(),
+    );
+});
+
+// Tests that `--plan` shows the decoded state changes of the simulated transactions, and that
+// `--plan-out` writes them.
+forgetest_async!(can_show_plan, |prj, cmd| {
+    foundry_test_utils::util::initialize(prj.root());
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+
+    prj.add_source(
+        "Vault",
+        r#"
+contract Vault {
+    uint256 public total;
+    address public owner;
+
+    function deposit(uint256 amount) external {
+        total += amount;
+        owner = msg.sender;
+    }
+}
+"#,
+    )
+    .unwrap();
+    prj.add_script(
+        "Deposit.s.sol",
+        r#"
+import "forge-std/Script.sol";
+import {Vault} from "../src/Vault.sol";
+
+contract DepositScript is Script {
+    function run() external {
+        vm.startBroadcast();
+        Vault vault = new Vault();
+        vault.deposit(42);
+        vm.stopBroadcast();
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    cmd.args([
+        "script",
+        "script/Deposit.s.sol",
+        "--rpc-url",
+        &handle.http_endpoint(),
+        "--private-key",
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        "--plan",
+        "--plan-out",
+        "plan.json",
+    ])
+    .assert_success()
+    .stdout_eq(str![[r#"
+...
+== Plan ==
+
+Transaction 1: create Vault
+  from: 0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266
+  state changes:
+    0x5FbDB2315678afecb367f032d93F642f64180aa3 (Vault) [created]
+      nonce: 0 → 1
+    0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266
+      nonce: 0 → 1
+
+Transaction 2: Vault.deposit(42)
+  from: 0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266
+  state changes:
+    0x5FbDB2315678afecb367f032d93F642f64180aa3 (Vault)
+      total (uint256): 0 → 42
+      owner (address): 0x0000000000000000000000000000000000000000 → 0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266
+    0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266
+      nonce: 1 → 2
+
+...
+Plan saved to: plan.json
+...
+"#]]);
+
+    let plan: Value =
+        serde_json::from_str(&std::fs::read_to_string(prj.root().join("plan.json")).unwrap())
+            .unwrap();
+    let transactions = plan["transactions"].as_array().unwrap();
+    assert_eq!(transactions.len(), 2);
+    assert_eq!(transactions[1]["function"], "deposit(uint256)");
+    assert_eq!(transactions[1]["arguments"], serde_json::json!(["42"]));
+    let vault = &transactions[1]["stateChanges"][0];
+    assert_eq!(vault["address"], "0x5FbDB2315678afecb367f032d93F642f64180aa3");
+    assert_eq!(vault["created"], false);
+    assert_eq!(vault["storage"][0]["decoded"][0]["label"], "total");
+    assert_eq!(vault["storage"][0]["decoded"][0]["new"], "42");
+});
+
+// Tests that a transaction still pending after `--bump-timeout` is replaced with bumped fees, and
+// that the replacement is recorded in the broadcast.
+forgetest_async!(can_bump_pending_transaction, |prj, cmd| {
+    foundry_test_utils::util::initialize(prj.root());
+    let (api, handle) = spawn(NodeConfig::test().with_no_mining(true)).await;
+
+    prj.add_script(
+        "Deploy.s.sol",
+        r#"
+import "forge-std/Script.sol";
+
+contract Empty {}
+
+contract DeployScript is Script {
+    function run() external {
+        vm.broadcast();
+        new Empty();
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    // Only mine once the pending transaction was replaced.
+    let miner = tokio::spawn(async move {
+        let mut sent_price = None;
+        loop {
+            let inspect = api.txpool_inspect().await.unwrap();
+            let price =
+                inspect.pending.values().flat_map(|txs| txs.values()).next().map(|tx| tx.gas_price);
+            match (sent_price, price) {
+                (None, Some(price)) => sent_price = Some(price),
+                (Some(sent), Some(price)) if price > sent => {
+                    api.mine_one().await;
+                    return price
+                }
+                _ => {}
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+    });
+
+    cmd.args([
+        "script",
+        "script/Deploy.s.sol",
+        "--rpc-url",
+        &handle.http_endpoint(),
+        "--private-key",
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        "--broadcast",
+        "--with-gas-price",
+        "2gwei",
+        "--priority-gas-price",
+        "1gwei",
+        "--bump-timeout",
+        "1",
+        "--bump-percent",
+        "100",
+    ])
+    .assert_success()
+    .stderr_eq(str![[r#"
+...
+Warning: Transaction [..] is still pending, replaced it with [..]
+...
+"#]]);
+    assert_eq!(miner.await.unwrap(), 4_000_000_000);
+
+    let sequence: Value = serde_json::from_str(
+        &std::fs::read_to_string(prj.root().join("broadcast/Deploy.s.sol/31337/run-latest.json"))
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(sequence["pending"], serde_json::json!([]));
+    assert_eq!(sequence["transactions"][0]["hash"], sequence["receipts"][0]["transactionHash"]);
+});
+
+// Tests that transactions are batched through an ERC-7821 executor the sender delegates to.
+forgetest_async!(can_batch_delegated_transactions, |prj, cmd| {
+    foundry_test_utils::util::initialize(prj.root());
+    let (api, handle) =
+        spawn(NodeConfig::test().with_hardfork(Some(EthereumHardfork::Prague.into()))).await;
+
+    prj.add_script(
+        "Batch.s.sol",
+        r#"
+import "forge-std/Script.sol";
+
+contract Executor {
+    struct Call {
+        address to;
+        uint256 value;
+        bytes data;
+    }
+
+    function execute(bytes32, bytes calldata executionData) external payable {
+        require(msg.sender == address(this));
+        Call[] memory calls = abi.decode(executionData, (Call[]));
+        for (uint256 i; i < calls.length; i++) {
+            (bool success,) = calls[i].to.call{value: calls[i].value}(calls[i].data);
+            require(success);
+        }
+    }
+}
+
+contract Counter {
+    uint256 public number;
+
+    function increment() external {
+        number++;
+    }
+}
+
+contract BatchScript is Script {
+    function deploy() external {
+        vm.startBroadcast();
+        new Executor();
+        new Counter();
+        vm.stopBroadcast();
+    }
+
+    function run() external {
+        Counter counter = Counter(0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512);
+        vm.startBroadcast();
+        counter.increment();
+        counter.increment();
+        vm.stopBroadcast();
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    let args = [
+        "script",
+        "script/Batch.s.sol",
+        "--rpc-url",
+        &handle.http_endpoint(),
+        "--private-key",
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        "--broadcast",
+    ];
+    cmd.args(args).args(["--sig", "deploy()"]).assert_success();
+
+    cmd.forge_fuse()
+        .args(args)
+        .args(["--batch-delegate", "0x5FbDB2315678afecb367f032d93F642f64180aa3"])
+        .assert_success()
+        .stdout_eq(str![[r#"
+...
+Batched 2 transaction(s) of 0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266 on chain 31337 through executor 0x5FbDB2315678afecb367f032d93F642f64180aa3
+...
+"#]]);
+
+    let sequence: Value = serde_json::from_str(
+        &std::fs::read_to_string(prj.root().join("broadcast/Batch.s.sol/31337/run-latest.json"))
+            .unwrap(),
+    )
+    .unwrap();
+    let transactions = sequence["transactions"].as_array().unwrap();
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(transactions[0]["function"], "execute(bytes32,bytes)");
+    assert_eq!(transactions[0]["transaction"]["authorizationList"].as_array().unwrap().len(), 1);
+    assert_eq!(sequence["receipts"][0]["status"], "0x1");
+
+    // The sender is delegated to the executor and both increments were executed.
+    let code =
+        api.get_code(address!("f39Fd6e51aad88F6F4ce6aB8827279cffFb92266"), None).await.unwrap();
+    assert_eq!(code, Bytes::from(hex!("ef01005FbDB2315678afecb367f032d93F642f64180aa3").to_vec()));
+    let number = api
+        .storage_at(address!("e7f1725E7734CE288F8367e1Bb143E90bb3F0512"), U256::ZERO, None)
+        .await
+        .unwrap();
+    assert_eq!(number, B256::from(U256::from(2)));
+});
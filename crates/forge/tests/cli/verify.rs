@@ -2,11 +2,13 @@
 //! and Sourcify.
 
 use crate::utils::{self, EnvExternalities};
+use anvil::{spawn, NodeConfig};
 use foundry_common::retry::Retry;
 use foundry_test_utils::{
     forgetest,
     util::{OutputExt, TestCommand, TestProject},
 };
+use serde_json::Value;
 use std::time::Duration;
 
 /// Adds a `Unique` contract to the source directory of the project that can be imported as
@@ -284,3 +286,56 @@ fn create_verify_on_chain(info: Option<EnvExternalities>, prj: TestProject, mut
 forgetest!(can_verify_random_contract_sepolia_default_sourcify, |prj, cmd| {
     verify_on_chain(EnvExternalities::sepolia_empty_verifier(), prj, cmd);
 });
+
+// tests that `verify-contract --broadcast` verifies the contracts of a broadcast log and skips the
+// ones verified by a previous run
+forgetest_async!(can_verify_broadcast_and_resume, |prj, cmd| {
+    foundry_test_utils::util::initialize(prj.root());
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+
+    cmd.args([
+        "script",
+        "script/Counter.s.sol",
+        "--rpc-url",
+        &handle.http_endpoint(),
+        "--private-key",
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        "--broadcast",
+    ])
+    .assert_success();
+
+    let broadcast = prj.root().join("broadcast/Counter.s.sol/31337/run-latest.json");
+    let verify = |cmd: &mut TestCommand| {
+        cmd.forge_fuse()
+            .args(["verify-contract", "--broadcast"])
+            .arg(&broadcast)
+            // Nothing listens on this port, so verification requests fail.
+            .args(["--verifier", "sourcify", "--verifier-url", "http://127.0.0.1:1/"])
+            .args(["--retries", "1", "--delay", "0"]);
+    };
+
+    // The deployed contract is matched, but can't be verified.
+    verify(cmd);
+    cmd.assert_failure().stdout_eq(str![[r#"
+...
+Start verification for (1) contracts
+...
+"#]]);
+
+    // A contract verified by a previous run is skipped.
+    let log: Value = serde_json::from_str(&std::fs::read_to_string(&broadcast).unwrap()).unwrap();
+    let state = prj.root().join(format!("cache/verify/31337/{}.json", log["timestamp"]));
+    std::fs::create_dir_all(state.parent().unwrap()).unwrap();
+    let verified = serde_json::json!({ "verified": [log["receipts"][0]["contractAddress"]] });
+    std::fs::write(&state, verified.to_string()).unwrap();
+
+    verify(cmd);
+    cmd.assert_success().stdout_eq(str![[r#"
+...
+Skipping (1) contracts verified by a previous run
+##
+Start verification for (0) contracts
+All (0) contracts were verified!
+
+"#]]);
+});
@@ -0,0 +1,194 @@
+pub use forge_fmt::inline_config::{InlineConfigItem, InvalidInlineConfigItem};
+use std::ops::Range;
+
+/// A range in which the given lints, or all lints if `None`, are disabled.
+#[derive(Debug)]
+struct DisabledRange {
+    start: usize,
+    end: usize,
+    lints: Option<Vec<String>>,
+}
+
+impl DisabledRange {
+    fn includes(&self, offset: usize, lint: &str) -> bool {
+        (self.start..self.end).contains(&offset) &&
+            self.lints.as_ref().is_none_or(|lints| lints.iter().any(|l| l == lint))
+    }
+}
+
+/// An inline config. Keeps track of disabled ranges.
+///
+/// Built from the `forgelint:` comments of a source file, e.g.
+/// `// forgelint: disable-next-line` or `// forgelint: disable-line(tx-origin, unused-variable)`.
+/// The items are the same as the formatter's inline config items.
+#[derive(Debug, Default)]
+pub struct InlineConfig {
+    disabled_ranges: Vec<DisabledRange>,
+}
+
+impl InlineConfig {
+    /// Parses the inline config comments of a source file.
+    ///
+    /// `source_items` are the locations of the source items, used to resolve `disable-next-item`.
+    /// Invalid items are returned along with their location.
+    pub fn parse(
+        src: &str,
+        source_items: &[Range<usize>],
+    ) -> (Self, Vec<(Range<usize>, InvalidInlineConfigItem)>) {
+        let mut items = Vec::new();
+        let mut invalid = Vec::new();
+        for (loc, comment) in comments(src) {
+            let Some(item) = comment.trim().strip_prefix("forgelint:") else { continue };
+            let item = item.trim();
+            let (item, lints) = match item.split_once('(') {
+                Some((item, lints)) => {
+                    let lints = lints.trim_end().strip_suffix(')').unwrap_or(lints);
+                    let lints = lints.split(',').map(|l| l.trim().to_string()).collect();
+                    (item.trim_end(), Some(lints))
+                }
+                None => (item, None),
+            };
+            match item.parse() {
+                Ok(item) => items.push((loc, item, lints)),
+                Err(e) => invalid.push((loc, e)),
+            }
+        }
+        (Self::new(items, src, source_items), invalid)
+    }
+
+    /// Build a new inline config with an iterator of inline config items, the lints they apply to
+    /// and their locations in a source file, given the locations of the source items
+    pub fn new(
+        items: impl IntoIterator<Item = (Range<usize>, InlineConfigItem, Option<Vec<String>>)>,
+        src: &str,
+        source_items: &[Range<usize>],
+    ) -> Self {
+        let mut disabled_ranges = vec![];
+        let mut open = Vec::new();
+        for (loc, item, lints) in items {
+            match item {
+                InlineConfigItem::DisableNextItem => {
+                    let next = source_items
+                        .iter()
+                        .filter(|item| item.start >= loc.end)
+                        .min_by_key(|item| item.start);
+                    if let Some(next) = next {
+                        disabled_ranges.push(DisabledRange {
+                            start: loc.end,
+                            end: next.end,
+                            lints,
+                        });
+                    }
+                }
+                InlineConfigItem::DisableLine => {
+                    let start = src[..loc.start].rfind('\n').map_or(0, |idx| idx + 1);
+                    let end = src[loc.end..].find('\n').map_or(src.len(), |idx| loc.end + idx);
+                    disabled_ranges.push(DisabledRange { start, end, lints });
+                }
+                InlineConfigItem::DisableNextLine => {
+                    if let Some(idx) = src[loc.end..].find('\n') {
+                        let start = loc.end + idx + 1;
+                        let end = src[start..].find('\n').map_or(src.len(), |idx| start + idx);
+                        disabled_ranges.push(DisabledRange { start, end, lints });
+                    }
+                }
+                InlineConfigItem::DisableStart => open.push((loc.end, lints)),
+                InlineConfigItem::DisableEnd => {
+                    if let Some((start, lints)) = open.pop() {
+                        disabled_ranges.push(DisabledRange { start, end: loc.start, lints });
+                    }
+                }
+            }
+        }
+        for (start, lints) in open {
+            disabled_ranges.push(DisabledRange { start, end: src.len(), lints });
+        }
+        Self { disabled_ranges }
+    }
+
+    /// Check if the lint is disabled at the given offset
+    pub fn is_disabled(&self, offset: usize, lint: &str) -> bool {
+        self.disabled_ranges.iter().any(|range| range.includes(offset, lint))
+    }
+}
+
+/// Returns the location and the text of the comments in a Solidity source file.
+fn comments(src: &str) -> Vec<(Range<usize>, &str)> {
+    let bytes = src.as_bytes();
+    let mut comments = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'"' | b'\'') => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote && bytes[i] != b'\n' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                i += 1;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                let end = src[i..].find('\n').map_or(src.len(), |idx| i + idx);
+                comments.push((i..end, &src[i + 2..end]));
+                i = end;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                let end = src[i + 2..].find("*/").map_or(src.len(), |idx| i + 2 + idx + 2);
+                comments.push((i..end, src[i + 2..end].trim_end_matches("*/")));
+                i = end;
+            }
+            _ => i += 1,
+        }
+    }
+    comments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_ranges() {
+        let src = "\
+uint a; // forgelint: disable-line
+// forgelint: disable-next-line(tx-origin)
+uint b;
+string c = \"// forgelint: disable-line\";
+/* forgelint: disable-start */
+uint d;
+// forgelint: disable-end
+uint e;
+// forgelint: disable-next-item
+function f() {
+    uint g;
+}
+uint h;
+";
+        let range = |start: &str, end: &str| {
+            let start = src.find(start).unwrap();
+            start..start + src[start..].find(end).unwrap() + end.len()
+        };
+        let items = [range("function f", "\n}"), range("uint h", ";")];
+        let (config, invalid) = InlineConfig::parse(src, &items);
+        assert!(invalid.is_empty());
+        let offset = |s: &str| src.find(s).unwrap();
+
+        assert!(config.is_disabled(offset("uint a"), "unused-variable"));
+        assert!(config.is_disabled(offset("uint b"), "tx-origin"));
+        assert!(!config.is_disabled(offset("uint b"), "unused-variable"));
+        assert!(!config.is_disabled(offset("string c"), "unused-variable"));
+        assert!(config.is_disabled(offset("uint d"), "unused-variable"));
+        assert!(!config.is_disabled(offset("uint e"), "unused-variable"));
+        assert!(config.is_disabled(offset("uint g"), "unused-variable"));
+        assert!(!config.is_disabled(offset("uint h"), "unused-variable"));
+    }
+
+    #[test]
+    fn invalid_items() {
+        let (_, invalid) = InlineConfig::parse("// forgelint: disable-everything\n", &[]);
+        let invalid = invalid.iter().map(|(loc, err)| (loc.clone(), err.to_string()));
+        assert_eq!(
+            invalid.collect::<Vec<_>>(),
+            [(0..32, "Invalid inline config item: disable-everything".to_string())]
+        );
+    }
+}
@@ -0,0 +1,34 @@
+//! Solidity linter.
+//!
+//! See [`Linter`].
+
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+#[macro_use]
+extern crate foundry_common;
+
+mod inline_config;
+pub use inline_config::{InlineConfig, InlineConfigItem, InvalidInlineConfigItem};
+
+mod lint;
+pub use lint::{EmittedLint, Lint, LintContext, LintPass};
+
+mod linter;
+pub use linter::{Diagnostic, Linter};
+
+pub mod rules;
+
+pub use foundry_config::lint::Severity;
+
+#[cfg(test)]
+mod test_utils {
+    use crate::Linter;
+    use std::path::Path;
+
+    /// Returns the ids of the lints emitted for the given source, in order of appearance.
+    pub(crate) fn lint_ids(src: &str) -> Vec<&'static str> {
+        let diagnostics = Linter::default().lint_source(Path::new("Test.sol"), src).unwrap();
+        diagnostics.into_iter().map(|d| d.lint).collect()
+    }
+}
@@ -0,0 +1,98 @@
+use foundry_config::lint::Severity;
+use solar_parse::{
+    ast::{self, Span},
+    interface::Session,
+};
+use std::ops::Range;
+
+/// The metadata of a lint rule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Lint {
+    /// The unique id of the rule, e.g. `tx-origin`.
+    ///
+    /// Used in `foundry.toml` and inline config comments.
+    pub id: &'static str,
+    /// A short description of what the rule detects.
+    pub description: &'static str,
+    /// The severity of the rule, unless overridden in the config.
+    pub severity: Severity,
+}
+
+/// A lint emitted while visiting a source unit, before inline config and severities are applied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EmittedLint {
+    pub lint: &'static Lint,
+    /// The byte range in the source file.
+    pub range: Range<usize>,
+    pub message: String,
+}
+
+/// The state shared by all lint passes while linting a single source file.
+pub struct LintContext<'a> {
+    sess: &'a Session,
+    source: &'a str,
+    /// The position of the source file in the session's source map.
+    base: Option<usize>,
+    pub(crate) emitted: Vec<EmittedLint>,
+    /// The locations of the visited items.
+    pub(crate) items: Vec<Range<usize>>,
+}
+
+impl<'a> LintContext<'a> {
+    pub(crate) fn new(sess: &'a Session, source: &'a str) -> Self {
+        Self { sess, source, base: None, emitted: Vec::new(), items: Vec::new() }
+    }
+
+    /// Returns the source code of the file being linted.
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// Returns the byte range of `span` in the source file.
+    pub fn range(&mut self, span: Span) -> Range<usize> {
+        let base = *self.base.get_or_insert_with(|| {
+            self.sess.source_map().lookup_source_file(span.lo()).start_pos.0 as usize
+        });
+        span.lo().to_usize() - base..span.hi().to_usize() - base
+    }
+
+    /// Returns the source code of `span`.
+    pub fn snippet(&mut self, span: Span) -> &'a str {
+        let range = self.range(span);
+        self.source.get(range).unwrap_or_default()
+    }
+
+    /// Emits a lint at `span`.
+    pub fn emit(&mut self, lint: &'static Lint, span: Span, message: impl Into<String>) {
+        let range = self.range(span);
+        self.emitted.push(EmittedLint { lint, range, message: message.into() });
+    }
+}
+
+/// A set of lint rules that are checked while walking the AST.
+///
+/// All passes are run in a single walk. Each `check_*` method is called before the children of the
+/// node are visited.
+#[allow(unused_variables)]
+pub trait LintPass {
+    /// Returns the lints that this pass can emit.
+    fn lints(&self) -> &'static [&'static Lint];
+
+    fn check_source_unit<'ast>(&mut self, cx: &mut LintContext<'_>, unit: &ast::SourceUnit<'ast>) {}
+
+    fn check_item<'ast>(&mut self, cx: &mut LintContext<'_>, item: &'ast ast::Item<'ast>) {}
+
+    fn check_variable_definition<'ast>(
+        &mut self,
+        cx: &mut LintContext<'_>,
+        var: &'ast ast::VariableDefinition<'ast>,
+    ) {
+    }
+
+    fn check_stmt<'ast>(&mut self, cx: &mut LintContext<'_>, stmt: &'ast ast::Stmt<'ast>) {}
+
+    fn check_expr<'ast>(&mut self, cx: &mut LintContext<'_>, expr: &'ast ast::Expr<'ast>) {}
+
+    fn check_yul_expr<'ast>(&mut self, cx: &mut LintContext<'_>, expr: &'ast ast::yul::Expr<'ast>) {
+    }
+}
@@ -0,0 +1,297 @@
+use crate::{rules, InlineConfig, Lint, LintContext, LintPass, Severity};
+use eyre::{Context, Result};
+use foundry_common::sarif::{
+    line_column, SarifLevel, SarifLocation, SarifReport, SarifResult, SarifRule,
+};
+use foundry_config::LintConfig;
+use serde::Serialize;
+use solar_parse::{
+    ast::{self, visit::Visit},
+    interface::{data_structures::Never, Session},
+    Parser,
+};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+};
+
+/// A lint finding in a source file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    /// The id of the lint, e.g. `tx-origin`.
+    pub lint: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    pub path: PathBuf,
+    /// The 1-based line and column of the start of the finding.
+    pub line: usize,
+    pub column: usize,
+    /// The 1-based line and column of the end of the finding.
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl Diagnostic {
+    /// Converts the diagnostic to a SARIF result.
+    pub fn to_sarif(&self) -> SarifResult {
+        SarifResult {
+            rule_id: self.lint.to_string(),
+            level: sarif_level(self.severity),
+            message: self.message.clone(),
+            location: Some(SarifLocation {
+                path: self.path.clone(),
+                start_line: self.line,
+                start_column: self.column,
+                end_line: self.end_line,
+                end_column: self.end_column,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}[{}]: {}",
+            self.path.display(),
+            self.line,
+            self.column,
+            self.severity,
+            self.lint,
+            self.message
+        )
+    }
+}
+
+fn sarif_level(severity: Severity) -> SarifLevel {
+    match severity {
+        Severity::High => SarifLevel::Error,
+        Severity::Medium | Severity::Low => SarifLevel::Warning,
+        Severity::Info | Severity::Off => SarifLevel::Note,
+    }
+}
+
+/// Runs the built-in lint rules on Solidity source files.
+#[derive(Clone, Debug, Default)]
+pub struct Linter {
+    /// Severity overrides, keyed by lint id.
+    severities: BTreeMap<String, Severity>,
+}
+
+impl Linter {
+    /// Creates a new linter with the severity overrides of the given config.
+    ///
+    /// Returns an error if the config refers to an unknown lint.
+    pub fn new(config: &LintConfig) -> Result<Self> {
+        let lints = rules::lints();
+        for id in config.severity.keys() {
+            if !lints.iter().any(|lint| lint.id == id) {
+                eyre::bail!("unknown lint `{id}` in the `[lint.severity]` config");
+            }
+        }
+        Ok(Self { severities: config.severity.clone() })
+    }
+
+    /// Sets the severity of a lint.
+    pub fn with_severity(mut self, id: impl Into<String>, severity: Severity) -> Self {
+        self.severities.insert(id.into(), severity);
+        self
+    }
+
+    /// Returns the effective severity of a lint.
+    pub fn severity(&self, lint: &Lint) -> Severity {
+        self.severities.get(lint.id).copied().unwrap_or(lint.severity)
+    }
+
+    /// Returns the lints that are not turned off.
+    pub fn enabled_lints(&self) -> impl Iterator<Item = &'static Lint> + '_ {
+        rules::lints().into_iter().filter(|lint| self.severity(lint) != Severity::Off)
+    }
+
+    /// Lints the file at `path`.
+    pub fn lint_file(&self, path: &Path) -> Result<Vec<Diagnostic>> {
+        let source = foundry_common::fs::read_to_string(path)?;
+        self.lint_source(path, &source)
+    }
+
+    /// Lints the given source code of the file at `path`.
+    ///
+    /// Parse errors are emitted to stderr.
+    pub fn lint_source(&self, path: &Path, source: &str) -> Result<Vec<Diagnostic>> {
+        let mut passes = rules::passes();
+        passes.retain(|pass| pass.lints().iter().any(|lint| self.severity(lint) != Severity::Off));
+
+        let sess = Session::builder().with_stderr_emitter().build();
+        let (emitted, items) = sess
+            .enter(|| -> solar_parse::interface::Result<_> {
+                let arena = ast::Arena::new();
+                let mut parser = Parser::from_source_code(
+                    &sess,
+                    &arena,
+                    path.to_path_buf().into(),
+                    source.to_string(),
+                )?;
+                let ast = parser.parse_file().map_err(|e| e.emit())?;
+
+                let mut cx = LintContext::new(&sess, source);
+                let mut visitor = LintVisitor { cx: &mut cx, passes: &mut passes };
+                let _ = visitor.visit_source_unit(&ast);
+                Ok((cx.emitted, cx.items))
+            })
+            .map_err(|_| eyre::eyre!("failed to parse"))
+            .wrap_err_with(|| format!("failed to lint {}", path.display()))?;
+
+        let (inline_config, invalid) = InlineConfig::parse(source, &items);
+        for (loc, err) in invalid {
+            let (line, column) = line_column(source, loc.start);
+            let _ = sh_warn!("{}:{line}:{column}: {err}", path.display());
+        }
+
+        let mut diagnostics = emitted
+            .into_iter()
+            .filter(|emitted| !inline_config.is_disabled(emitted.range.start, emitted.lint.id))
+            .filter_map(|emitted| {
+                let severity = self.severity(emitted.lint);
+                if severity == Severity::Off {
+                    return None;
+                }
+                let (line, column) = line_column(source, emitted.range.start);
+                let (end_line, end_column) = line_column(source, emitted.range.end);
+                Some(Diagnostic {
+                    lint: emitted.lint.id,
+                    severity,
+                    message: emitted.message,
+                    path: path.to_path_buf(),
+                    line,
+                    column,
+                    end_line,
+                    end_column,
+                })
+            })
+            .collect::<Vec<_>>();
+        diagnostics.sort_by_key(|d| (d.line, d.column));
+        Ok(diagnostics)
+    }
+
+    /// Creates a SARIF report of the given diagnostics.
+    pub fn sarif_report(&self, diagnostics: &[Diagnostic]) -> SarifReport {
+        let mut report = SarifReport::new("forge-lint");
+        for lint in self.enabled_lints() {
+            report.add_rule(SarifRule {
+                id: lint.id.to_string(),
+                description: lint.description.to_string(),
+                level: sarif_level(self.severity(lint)),
+                help_uri: None,
+            });
+        }
+        for diagnostic in diagnostics {
+            report.add_result(diagnostic.to_sarif());
+        }
+        report
+    }
+}
+
+/// Runs all lint passes in a single walk of the AST.
+struct LintVisitor<'a, 'b> {
+    cx: &'a mut LintContext<'b>,
+    passes: &'a mut [Box<dyn LintPass>],
+}
+
+impl<'ast> Visit<'ast> for LintVisitor<'_, '_> {
+    type BreakValue = Never;
+
+    fn visit_source_unit(&mut self, unit: &ast::SourceUnit<'ast>) -> ControlFlow<Self::BreakValue> {
+        for pass in self.passes.iter_mut() {
+            pass.check_source_unit(self.cx, unit);
+        }
+        self.walk_source_unit(unit)
+    }
+
+    fn visit_item(&mut self, item: &'ast ast::Item<'ast>) -> ControlFlow<Self::BreakValue> {
+        let range = self.cx.range(item.span);
+        self.cx.items.push(range);
+        for pass in self.passes.iter_mut() {
+            pass.check_item(self.cx, item);
+        }
+        self.walk_item(item)
+    }
+
+    fn visit_variable_definition(
+        &mut self,
+        var: &'ast ast::VariableDefinition<'ast>,
+    ) -> ControlFlow<Self::BreakValue> {
+        for pass in self.passes.iter_mut() {
+            pass.check_variable_definition(self.cx, var);
+        }
+        self.walk_variable_definition(var)
+    }
+
+    fn visit_stmt(&mut self, stmt: &'ast ast::Stmt<'ast>) -> ControlFlow<Self::BreakValue> {
+        for pass in self.passes.iter_mut() {
+            pass.check_stmt(self.cx, stmt);
+        }
+        self.walk_stmt(stmt)
+    }
+
+    fn visit_expr(&mut self, expr: &'ast ast::Expr<'ast>) -> ControlFlow<Self::BreakValue> {
+        for pass in self.passes.iter_mut() {
+            pass.check_expr(self.cx, expr);
+        }
+        self.walk_expr(expr)
+    }
+
+    fn visit_yul_expr(
+        &mut self,
+        expr: &'ast ast::yul::Expr<'ast>,
+    ) -> ControlFlow<Self::BreakValue> {
+        for pass in self.passes.iter_mut() {
+            pass.check_yul_expr(self.cx, expr);
+        }
+        self.walk_yul_expr(expr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRC: &str = r#"
+contract C {
+    function f(address to) external {
+        // forgelint: disable-next-line(unchecked-call)
+        to.call("");
+        to.call("");
+        require(tx.origin == to);
+    }
+}
+"#;
+
+    #[test]
+    fn inline_config_and_severities() {
+        let path = Path::new("C.sol");
+        let diagnostics = Linter::default().lint_source(path, SRC).unwrap();
+        let lints = diagnostics.iter().map(|d| (d.lint, d.line)).collect::<Vec<_>>();
+        assert_eq!(lints, [("unchecked-call", 6), ("tx-origin", 7)]);
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "C.sol:6:9: high[unchecked-call]: the return value of the low-level `call` is not checked"
+        );
+
+        let linter = Linter::default()
+            .with_severity("unchecked-call", Severity::Off)
+            .with_severity("tx-origin", Severity::Low);
+        let diagnostics = linter.lint_source(path, SRC).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Low);
+    }
+
+    #[test]
+    fn unknown_lint() {
+        let mut config = LintConfig::default();
+        config.severity.insert("not-a-lint".to_string(), Severity::High);
+        assert!(Linter::new(&config).is_err());
+    }
+}
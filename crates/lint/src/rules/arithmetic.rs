@@ -0,0 +1,108 @@
+use crate::{Lint, LintContext, LintPass, Severity};
+use solar_parse::ast;
+
+pub const DIVIDE_BEFORE_MULTIPLY: Lint = Lint {
+    id: "divide-before-multiply",
+    description: "multiplication is performed on the result of a division, losing precision",
+    severity: Severity::Medium,
+};
+
+pub const INCORRECT_SHIFT: Lint = Lint {
+    id: "incorrect-shift",
+    description: "the operands of an assembly shift are in the wrong order",
+    severity: Severity::High,
+};
+
+/// Checks arithmetic operations.
+#[derive(Debug, Default)]
+pub struct Arithmetic;
+
+impl LintPass for Arithmetic {
+    fn lints(&self) -> &'static [&'static Lint] {
+        &[&DIVIDE_BEFORE_MULTIPLY, &INCORRECT_SHIFT]
+    }
+
+    fn check_expr<'ast>(&mut self, cx: &mut LintContext<'_>, expr: &'ast ast::Expr<'ast>) {
+        let ast::ExprKind::Binary(lhs, op, rhs) = &expr.kind else { return };
+        let divides_first = match op.kind {
+            ast::BinOpKind::Mul => [lhs, rhs].into_iter().any(|operand| is_division(operand)),
+            // The parser associates operators of the same precedence to the right, so `a / b * c`
+            // is parsed as a division by a multiplication without parentheses.
+            ast::BinOpKind::Div => matches!(
+                &rhs.kind,
+                ast::ExprKind::Binary(_, op, _) if matches!(op.kind, ast::BinOpKind::Mul)
+            ),
+            _ => false,
+        };
+        if divides_first {
+            cx.emit(
+                &DIVIDE_BEFORE_MULTIPLY,
+                expr.span,
+                "multiplication on the result of a division, consider multiplying first",
+            );
+        }
+    }
+
+    fn check_yul_expr<'ast>(&mut self, cx: &mut LintContext<'_>, expr: &'ast ast::yul::Expr<'ast>) {
+        let ast::yul::ExprKind::Call(call) = &expr.kind else { return };
+        let name = call.name.as_str();
+        if !matches!(name, "shl" | "shr" | "sar") {
+            return;
+        }
+        // `shl(shift, value)` shifts `value`, the shift amount usually is the constant.
+        if let [shift, value] = &call.arguments[..] {
+            let is_lit =
+                |expr: &ast::yul::Expr<'_>| matches!(expr.kind, ast::yul::ExprKind::Lit(_));
+            if is_lit(value) && !is_lit(shift) {
+                let msg = format!(
+                    "`{name}` shifts the constant, the shift amount comes first in assembly"
+                );
+                cx.emit(&INCORRECT_SHIFT, expr.span, msg);
+            }
+        }
+    }
+}
+
+/// Returns `true` if `expr` is a division, possibly in parentheses.
+fn is_division(expr: &ast::Expr<'_>) -> bool {
+    match &expr.kind {
+        ast::ExprKind::Binary(_, op, _) => matches!(op.kind, ast::BinOpKind::Div),
+        ast::ExprKind::Tuple(elements) => match &elements[..] {
+            [Some(inner)] => is_division(inner),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::lint_ids;
+
+    #[test]
+    fn divide_before_multiply() {
+        let src = r#"
+contract C {
+    function f(uint256 a, uint256 b) external pure returns (uint256, uint256, uint256, uint256) {
+        return (a / b * 10, 10 * (a / b), a * 10 / b, a / (b * 10));
+    }
+}
+"#;
+        assert_eq!(lint_ids(src), ["divide-before-multiply", "divide-before-multiply"]);
+    }
+
+    #[test]
+    fn incorrect_shift() {
+        let src = r#"
+contract C {
+    function f(uint256 a) external pure returns (uint256 b, uint256 c) {
+        assembly {
+            b := shr(a, 8)
+            c := shl(8, a)
+        }
+    }
+}
+"#;
+        assert_eq!(lint_ids(src), ["incorrect-shift"]);
+    }
+}
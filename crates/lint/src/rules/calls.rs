@@ -0,0 +1,110 @@
+use crate::{Lint, LintContext, LintPass, Severity};
+use solar_parse::ast;
+
+pub const UNCHECKED_CALL: Lint = Lint {
+    id: "unchecked-call",
+    description: "the return value of a low-level call is not checked",
+    severity: Severity::High,
+};
+
+pub const TX_ORIGIN: Lint = Lint {
+    id: "tx-origin",
+    description: "`tx.origin` is used for authorization",
+    severity: Severity::High,
+};
+
+/// Checks the use of low-level calls and `tx.origin`.
+#[derive(Debug, Default)]
+pub struct Calls;
+
+impl LintPass for Calls {
+    fn lints(&self) -> &'static [&'static Lint] {
+        &[&UNCHECKED_CALL, &TX_ORIGIN]
+    }
+
+    fn check_stmt<'ast>(&mut self, cx: &mut LintContext<'_>, stmt: &'ast ast::Stmt<'ast>) {
+        // The result of an expression statement is discarded.
+        if let ast::StmtKind::Expr(expr) = &stmt.kind {
+            if let Some(member) = low_level_call(expr) {
+                let msg = format!("the return value of the low-level `{member}` is not checked");
+                cx.emit(&UNCHECKED_CALL, expr.span, msg);
+            }
+        }
+    }
+
+    fn check_expr<'ast>(&mut self, cx: &mut LintContext<'_>, expr: &'ast ast::Expr<'ast>) {
+        let ast::ExprKind::Binary(lhs, op, rhs) = &expr.kind else { return };
+        if !matches!(op.kind, ast::BinOpKind::Eq | ast::BinOpKind::Ne) {
+            return;
+        }
+        for (side, other) in [(lhs, rhs), (rhs, lhs)] {
+            // `tx.origin == msg.sender` checks that the caller is an EOA, not who it is.
+            if is_member(side, "tx", "origin") && !is_member(other, "msg", "sender") {
+                cx.emit(
+                    &TX_ORIGIN,
+                    side.span,
+                    "`tx.origin` used for authorization, use `msg.sender`",
+                );
+            }
+        }
+    }
+}
+
+/// Returns the name of the low-level call if `expr` is one, e.g. `call` for
+/// `target.call{value: 1}("")`.
+fn low_level_call<'ast>(expr: &'ast ast::Expr<'ast>) -> Option<&'ast str> {
+    let ast::ExprKind::Call(callee, _) = &expr.kind else { return None };
+    let callee = match &callee.kind {
+        ast::ExprKind::CallOptions(callee, _) => callee,
+        _ => callee,
+    };
+    let ast::ExprKind::Member(_, member) = &callee.kind else { return None };
+    let member = member.as_str();
+    matches!(member, "call" | "delegatecall" | "staticcall" | "send").then_some(member)
+}
+
+/// Returns `true` if `expr` is `base.member`, e.g. `tx.origin`.
+fn is_member(expr: &ast::Expr<'_>, base: &str, member: &str) -> bool {
+    match &expr.kind {
+        ast::ExprKind::Member(lhs, m) => {
+            m.as_str() == member &&
+                matches!(&lhs.kind, ast::ExprKind::Ident(ident) if ident.as_str() == base)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::lint_ids;
+
+    #[test]
+    fn unchecked_calls() {
+        let src = r#"
+contract C {
+    function f(address payable to) external {
+        to.call{value: 1}("");
+        to.send(1);
+        (bool ok,) = to.call("");
+        require(ok);
+        require(to.send(1));
+    }
+}
+"#;
+        assert_eq!(lint_ids(src), ["unchecked-call", "unchecked-call"]);
+    }
+
+    #[test]
+    fn tx_origin() {
+        let src = r#"
+contract C {
+    address owner;
+    function f() external {
+        require(tx.origin == owner);
+        require(tx.origin == msg.sender);
+    }
+}
+"#;
+        assert_eq!(lint_ids(src), ["tx-origin"]);
+    }
+}
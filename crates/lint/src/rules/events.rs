@@ -0,0 +1,201 @@
+use crate::{Lint, LintContext, LintPass, Severity};
+use solar_parse::{
+    ast::{self, visit::Visit},
+    interface::data_structures::Never,
+};
+use std::ops::ControlFlow;
+
+pub const MISSING_EVENT: Lint = Lint {
+    id: "missing-event",
+    description: "a public function changes state variables without emitting an event",
+    severity: Severity::Low,
+};
+
+/// Checks that public functions which change state emit events.
+#[derive(Debug, Default)]
+pub struct MissingEvent;
+
+impl LintPass for MissingEvent {
+    fn lints(&self) -> &'static [&'static Lint] {
+        &[&MISSING_EVENT]
+    }
+
+    fn check_item<'ast>(&mut self, cx: &mut LintContext<'_>, item: &'ast ast::Item<'ast>) {
+        let ast::ItemKind::Contract(contract) = &item.kind else { return };
+
+        // Constants and immutables can't be changed after construction.
+        let state_variables = contract
+            .body
+            .iter()
+            .filter_map(|item| match &item.kind {
+                ast::ItemKind::Variable(var) if var.mutability.is_none() => var.name,
+                _ => None,
+            })
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>();
+        if state_variables.is_empty() {
+            return;
+        }
+
+        for item in contract.body.iter() {
+            let ast::ItemKind::Function(func) = &item.kind else { continue };
+            let is_public = !matches!(
+                func.header.visibility,
+                Some(ast::Visibility::Internal | ast::Visibility::Private)
+            );
+            let (Some(name), Some(body), ast::FunctionKind::Function, true) =
+                (func.header.name, &func.body, func.kind, is_public)
+            else {
+                continue;
+            };
+
+            let mut visitor = StateChanges {
+                state_variables: &state_variables,
+                shadowed: func
+                    .header
+                    .parameters
+                    .iter()
+                    .chain(func.header.returns.iter())
+                    .filter_map(|var| var.name.map(|name| name.to_string()))
+                    .collect(),
+                changed: None,
+                emits: false,
+            };
+            for stmt in body.iter() {
+                let _ = visitor.visit_stmt(stmt);
+            }
+            if let (Some(changed), false) = (visitor.changed, visitor.emits) {
+                let msg = format!("`{name}` changes `{changed}` without emitting an event");
+                cx.emit(&MISSING_EVENT, name.span, msg);
+            }
+        }
+    }
+}
+
+/// Finds changes to state variables and `emit` statements in a function body.
+struct StateChanges<'a> {
+    state_variables: &'a [String],
+    /// The names of parameters and local variables.
+    shadowed: Vec<String>,
+    /// The first state variable that is changed.
+    changed: Option<String>,
+    emits: bool,
+}
+
+impl StateChanges<'_> {
+    fn check_target(&mut self, mut expr: &ast::Expr<'_>) {
+        // `balances[to].amount = x` changes `balances`.
+        loop {
+            match &expr.kind {
+                ast::ExprKind::Index(base, _) | ast::ExprKind::Member(base, _) => expr = base,
+                ast::ExprKind::Ident(ident) => {
+                    let name = ident.as_str();
+                    if self.changed.is_none() &&
+                        self.state_variables.iter().any(|var| var == name) &&
+                        !self.shadowed.iter().any(|var| var == name)
+                    {
+                        self.changed = Some(name.to_string());
+                    }
+                    return;
+                }
+                _ => return,
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for StateChanges<'_> {
+    type BreakValue = Never;
+
+    fn visit_stmt(&mut self, stmt: &'ast ast::Stmt<'ast>) -> ControlFlow<Self::BreakValue> {
+        match stmt.kind {
+            ast::StmtKind::Emit(..) => self.emits = true,
+            // Variables declared in these statements go out of scope at their end.
+            ast::StmtKind::Block(_) |
+            ast::StmtKind::UncheckedBlock(_) |
+            ast::StmtKind::For { .. } |
+            ast::StmtKind::Try(_) => {
+                let len = self.shadowed.len();
+                let _ = self.walk_stmt(stmt);
+                self.shadowed.truncate(len);
+                return ControlFlow::Continue(());
+            }
+            _ => {}
+        }
+        self.walk_stmt(stmt)
+    }
+
+    fn visit_variable_definition(
+        &mut self,
+        var: &'ast ast::VariableDefinition<'ast>,
+    ) -> ControlFlow<Self::BreakValue> {
+        if let Some(name) = var.name {
+            self.shadowed.push(name.to_string());
+        }
+        self.walk_variable_definition(var)
+    }
+
+    fn visit_expr(&mut self, expr: &'ast ast::Expr<'ast>) -> ControlFlow<Self::BreakValue> {
+        match &expr.kind {
+            ast::ExprKind::Assign(lhs, _, _) => self.check_target(lhs),
+            ast::ExprKind::Delete(target) => self.check_target(target),
+            ast::ExprKind::Unary(op, target)
+                if matches!(
+                    op.kind,
+                    ast::UnOpKind::PreInc |
+                        ast::UnOpKind::PreDec |
+                        ast::UnOpKind::PostInc |
+                        ast::UnOpKind::PostDec
+                ) =>
+            {
+                self.check_target(target)
+            }
+            _ => {}
+        }
+        self.walk_expr(expr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::lint_ids;
+
+    #[test]
+    fn missing_event() {
+        let src = r#"
+contract C {
+    event OwnerChanged(address owner);
+
+    address owner;
+    mapping(address => uint256) balances;
+
+    function setOwner(address newOwner) external {
+        owner = newOwner;
+        emit OwnerChanged(newOwner);
+    }
+
+    function deposit() external payable {
+        balances[msg.sender] += msg.value;
+    }
+
+    function shadow(address owner) external pure returns (address) {
+        owner = address(0);
+        return owner;
+    }
+
+    function scoped(address newOwner) external {
+        {
+            address owner = newOwner;
+            owner;
+        }
+        owner = newOwner;
+    }
+
+    function update() internal {
+        owner = address(0);
+    }
+}
+"#;
+        assert_eq!(lint_ids(src), ["missing-event", "missing-event"]);
+    }
+}
@@ -0,0 +1,36 @@
+//! The built-in lint rules.
+
+use crate::{Lint, LintPass};
+
+mod arithmetic;
+pub use arithmetic::{Arithmetic, DIVIDE_BEFORE_MULTIPLY, INCORRECT_SHIFT};
+
+mod calls;
+pub use calls::{Calls, TX_ORIGIN, UNCHECKED_CALL};
+
+mod events;
+pub use events::{MissingEvent, MISSING_EVENT};
+
+mod naming;
+pub use naming::{
+    Naming, MIXED_CASE_FUNCTION, MIXED_CASE_VARIABLE, PASCAL_CASE, SCREAMING_SNAKE_CASE,
+};
+
+mod unused;
+pub use unused::{Unused, UNUSED_IMPORT, UNUSED_VARIABLE};
+
+/// Returns a new instance of every built-in lint pass.
+pub fn passes() -> Vec<Box<dyn LintPass>> {
+    vec![
+        Box::new(Calls),
+        Box::new(Arithmetic),
+        Box::new(MissingEvent),
+        Box::new(Unused),
+        Box::new(Naming),
+    ]
+}
+
+/// Returns all built-in lints.
+pub fn lints() -> Vec<&'static Lint> {
+    passes().iter().flat_map(|pass| pass.lints().iter().copied()).collect()
+}
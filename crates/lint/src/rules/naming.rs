@@ -0,0 +1,147 @@
+use crate::{Lint, LintContext, LintPass, Severity};
+use solar_parse::ast;
+
+pub const PASCAL_CASE: Lint = Lint {
+    id: "pascal-case",
+    description: "contracts, structs, enums, events, errors and types should use PascalCase",
+    severity: Severity::Info,
+};
+
+pub const MIXED_CASE_FUNCTION: Lint = Lint {
+    id: "mixed-case-function",
+    description: "functions should use mixedCase",
+    severity: Severity::Info,
+};
+
+pub const MIXED_CASE_VARIABLE: Lint = Lint {
+    id: "mixed-case-variable",
+    description: "variables should use mixedCase",
+    severity: Severity::Info,
+};
+
+pub const SCREAMING_SNAKE_CASE: Lint = Lint {
+    id: "screaming-snake-case",
+    description: "constants should use SCREAMING_SNAKE_CASE",
+    severity: Severity::Info,
+};
+
+/// Checks the naming conventions of the Solidity style guide.
+#[derive(Debug, Default)]
+pub struct Naming;
+
+impl LintPass for Naming {
+    fn lints(&self) -> &'static [&'static Lint] {
+        &[&PASCAL_CASE, &MIXED_CASE_FUNCTION, &MIXED_CASE_VARIABLE, &SCREAMING_SNAKE_CASE]
+    }
+
+    fn check_item<'ast>(&mut self, cx: &mut LintContext<'_>, item: &'ast ast::Item<'ast>) {
+        let name = match &item.kind {
+            ast::ItemKind::Contract(contract) => contract.name,
+            ast::ItemKind::Struct(strukt) => strukt.name,
+            ast::ItemKind::Enum(enm) => enm.name,
+            ast::ItemKind::Udvt(udvt) => udvt.name,
+            ast::ItemKind::Event(event) => event.name,
+            ast::ItemKind::Error(error) => error.name,
+            ast::ItemKind::Function(func) => {
+                if let (ast::FunctionKind::Function, Some(name)) = (func.kind, func.header.name) {
+                    if !is_mixed_case(name.as_str()) && !is_test_function(name.as_str()) {
+                        let msg = format!("function `{name}` should use mixedCase");
+                        cx.emit(&MIXED_CASE_FUNCTION, name.span, msg);
+                    }
+                }
+                return;
+            }
+            _ => return,
+        };
+        if !is_pascal_case(name.as_str()) {
+            cx.emit(&PASCAL_CASE, name.span, format!("`{name}` should use PascalCase"));
+        }
+    }
+
+    fn check_variable_definition<'ast>(
+        &mut self,
+        cx: &mut LintContext<'_>,
+        var: &'ast ast::VariableDefinition<'ast>,
+    ) {
+        let Some(name) = var.name else { return };
+        let s = name.as_str();
+        match var.mutability {
+            Some(ast::VarMut::Constant) => {
+                if !is_screaming_snake_case(s) {
+                    let msg = format!("constant `{name}` should use SCREAMING_SNAKE_CASE");
+                    cx.emit(&SCREAMING_SNAKE_CASE, name.span, msg);
+                }
+            }
+            // The style guide doesn't cover immutables, both conventions are common.
+            Some(ast::VarMut::Immutable) if is_screaming_snake_case(s) => {}
+            _ => {
+                if !is_mixed_case(s) {
+                    let msg = format!("variable `{name}` should use mixedCase");
+                    cx.emit(&MIXED_CASE_VARIABLE, name.span, msg);
+                }
+            }
+        }
+    }
+}
+
+/// Returns `true` if `s` is PascalCase, e.g. `ERC20` or `TokenVault`.
+fn is_pascal_case(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_uppercase()) && !s.contains('_')
+}
+
+/// Returns `true` if `s` is mixedCase, ignoring leading and trailing underscores, e.g. `_balance`.
+fn is_mixed_case(s: &str) -> bool {
+    let s = s.trim_matches('_');
+    s.is_empty() || (!s.starts_with(|c: char| c.is_ascii_uppercase()) && !s.contains('_'))
+}
+
+/// Returns `true` if `s` is SCREAMING_SNAKE_CASE, e.g. `MAX_SUPPLY`.
+fn is_screaming_snake_case(s: &str) -> bool {
+    s.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Returns `true` if `s` is a test function, which use underscores to separate the parts of their
+/// name, e.g. `test_RevertIf_Zero`.
+fn is_test_function(s: &str) -> bool {
+    ["test", "invariant", "statefulFuzz"].iter().any(|prefix| s.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::lint_ids;
+
+    #[test]
+    fn cases() {
+        assert!(is_pascal_case("ERC20"));
+        assert!(!is_pascal_case("erc20"));
+        assert!(!is_pascal_case("Token_Vault"));
+        assert!(is_mixed_case("_balance"));
+        assert!(is_mixed_case("balanceOf"));
+        assert!(!is_mixed_case("BalanceOf"));
+        assert!(!is_mixed_case("balance_of"));
+        assert!(is_screaming_snake_case("MAX_SUPPLY_2"));
+        assert!(!is_screaming_snake_case("maxSupply"));
+    }
+
+    #[test]
+    fn naming() {
+        let src = r#"
+contract token {
+    uint256 constant maxSupply = 1;
+    uint256 immutable DECIMALS;
+    uint256 immutable initialSupply;
+    uint256 Total_supply;
+
+    struct Info { uint256 value; }
+
+    function Transfer() external {}
+    function test_RevertIf_Zero() external {}
+}
+"#;
+        assert_eq!(
+            lint_ids(src),
+            ["pascal-case", "screaming-snake-case", "mixed-case-variable", "mixed-case-function"]
+        );
+    }
+}
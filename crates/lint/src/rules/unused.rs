@@ -0,0 +1,185 @@
+use crate::{Lint, LintContext, LintPass, Severity};
+use solar_parse::{
+    ast::{self, visit::Visit, Ident},
+    interface::data_structures::Never,
+};
+use std::ops::ControlFlow;
+
+pub const UNUSED_IMPORT: Lint = Lint {
+    id: "unused-import",
+    description: "an imported symbol is never used",
+    severity: Severity::Info,
+};
+
+pub const UNUSED_VARIABLE: Lint = Lint {
+    id: "unused-variable",
+    description: "a local variable is never used",
+    severity: Severity::Low,
+};
+
+/// Checks for unused imports and local variables.
+///
+/// Uses are found by the identifiers in the AST, without resolving them, so a local variable
+/// shadowed by another one is used if the other one is.
+#[derive(Debug, Default)]
+pub struct Unused;
+
+impl LintPass for Unused {
+    fn lints(&self) -> &'static [&'static Lint] {
+        &[&UNUSED_IMPORT, &UNUSED_VARIABLE]
+    }
+
+    fn check_source_unit<'ast>(&mut self, cx: &mut LintContext<'_>, unit: &ast::SourceUnit<'ast>) {
+        let mut symbols = Vec::new();
+        for item in unit.items.iter() {
+            let ast::ItemKind::Import(import) = &item.kind else { continue };
+            match &import.items {
+                ast::ImportItems::Aliases(aliases) => {
+                    symbols.extend(aliases.iter().map(|(name, alias)| alias.unwrap_or(*name)))
+                }
+                ast::ImportItems::Plain(Some(alias)) | ast::ImportItems::Glob(Some(alias)) => {
+                    symbols.push(*alias)
+                }
+                // Unaliased imports can't be checked without resolving the imported file.
+                ast::ImportItems::Plain(None) | ast::ImportItems::Glob(None) => {}
+            }
+        }
+        if symbols.is_empty() {
+            return;
+        }
+
+        let mut idents = Idents::default();
+        let _ = idents.visit_source_unit(unit);
+        for symbol in symbols {
+            if !idents.0.iter().any(|ident| ident.name == symbol.name) {
+                let msg = format!("`{symbol}` is imported but never used");
+                cx.emit(&UNUSED_IMPORT, symbol.span, msg);
+            }
+        }
+    }
+
+    fn check_item<'ast>(&mut self, cx: &mut LintContext<'_>, item: &'ast ast::Item<'ast>) {
+        let ast::ItemKind::Function(func) = &item.kind else { return };
+        let Some(stmts) = &func.body else { return };
+
+        let mut locals = Locals::default();
+        let mut idents = Idents::default();
+        for stmt in stmts.iter() {
+            let _ = locals.visit_stmt(stmt);
+            let _ = idents.visit_stmt(stmt);
+        }
+
+        for local in locals.0 {
+            let used = idents
+                .0
+                .iter()
+                .any(|ident| ident.name == local.name && ident.span.lo() > local.span.lo());
+            if !used {
+                let msg = format!("local variable `{local}` is never used");
+                cx.emit(&UNUSED_VARIABLE, local.span, msg);
+            }
+        }
+    }
+}
+
+/// Collects the names of local variables.
+#[derive(Default)]
+struct Locals(Vec<Ident>);
+
+impl<'ast> Visit<'ast> for Locals {
+    type BreakValue = Never;
+
+    fn visit_variable_definition(
+        &mut self,
+        var: &'ast ast::VariableDefinition<'ast>,
+    ) -> ControlFlow<Self::BreakValue> {
+        // Unnamed definitions are parameters of function types.
+        if let Some(name) = var.name {
+            self.0.push(name);
+        }
+        self.walk_variable_definition(var)
+    }
+}
+
+/// Collects the identifiers that may refer to a declaration, in order of appearance.
+///
+/// Imports, member names and the names of named arguments are skipped, as they don't refer to
+/// the declarations in scope.
+#[derive(Default)]
+struct Idents(Vec<Ident>);
+
+impl<'ast> Visit<'ast> for Idents {
+    type BreakValue = Never;
+
+    fn visit_import_directive(
+        &mut self,
+        _import: &'ast ast::ImportDirective<'ast>,
+    ) -> ControlFlow<Self::BreakValue> {
+        ControlFlow::Continue(())
+    }
+
+    fn visit_expr(&mut self, expr: &'ast ast::Expr<'ast>) -> ControlFlow<Self::BreakValue> {
+        match &expr.kind {
+            ast::ExprKind::Member(base, _) => self.visit_expr(base),
+            _ => self.walk_expr(expr),
+        }
+    }
+
+    fn visit_named_args(
+        &mut self,
+        args: &'ast ast::NamedArgList<'ast>,
+    ) -> ControlFlow<Self::BreakValue> {
+        for arg in args.iter() {
+            self.visit_expr(arg.value)?;
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn visit_ident(&mut self, ident: &'ast Ident) -> ControlFlow<Self::BreakValue> {
+        self.0.push(*ident);
+        ControlFlow::Continue(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::lint_ids;
+
+    #[test]
+    fn unused() {
+        let src = r#"
+import {IERC20, SafeERC20 as Safe} from "./IERC20.sol";
+import "./Other.sol";
+
+contract C {
+    function f(IERC20 token) external returns (uint256 result) {
+        uint256 balance = token.balanceOf(address(this));
+        uint256 unused = 1;
+        result = balance;
+    }
+}
+"#;
+        assert_eq!(lint_ids(src), ["unused-import", "unused-variable"]);
+    }
+
+    #[test]
+    fn mentions_are_not_uses() {
+        let src = r#"
+import {IERC20} from "./IERC20.sol";
+
+contract C {
+    struct S {
+        uint256 amount;
+    }
+
+    /// @notice Transfers an `IERC20` token.
+    function f(S memory s) external pure returns (uint256) {
+        uint256 amount = 1;
+        // Returns the `amount` of `s`.
+        return s.amount;
+    }
+}
+"#;
+        assert_eq!(lint_ids(src), ["unused-import", "unused-variable"]);
+    }
+}
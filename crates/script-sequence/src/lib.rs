@@ -4,9 +4,11 @@
 extern crate foundry_common;
 
 pub mod reader;
+pub mod registry;
 pub mod sequence;
 pub mod transaction;
 
 pub use reader::*;
+pub use registry::*;
 pub use sequence::*;
 pub use transaction::*;
@@ -0,0 +1,223 @@
+use alloy_primitives::{keccak256, Address, Bytes, TxHash, B256};
+use eyre::{Result, WrapErr};
+use foundry_common::fs;
+use foundry_config::Config;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::PathBuf};
+
+pub const DEPLOYMENTS_DIR: &str = "deployments";
+
+/// Registry of the contracts deployed by the project's scripts on a chain, saved to
+/// `deployments/[chain_id].json`.
+///
+/// Each deployment name maps to its versions, oldest first: a new version is recorded whenever the
+/// name is deployed to a new address or, for proxies, upgraded to a new implementation.
+///
+/// Deployments are named after their `vm.label` if the script labeled them, or after their
+/// contract otherwise.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DeploymentRegistry {
+    pub chain: u64,
+    pub deployments: BTreeMap<String, Vec<Deployment>>,
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+/// A version of a deployment in the [DeploymentRegistry].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Deployment {
+    /// The version of the deployment, starting at 1.
+    pub version: usize,
+    /// The name of the deployed contract.
+    pub contract_name: String,
+    pub address: Address,
+    /// The hash of the transaction which deployed or upgraded the contract.
+    pub transaction_hash: Option<TxHash>,
+    pub block_number: Option<u64>,
+    /// The [bytecode_hash] of the runtime code of the contract artifact.
+    pub bytecode_hash: Option<B256>,
+    /// The hash of the runtime code when the deployment was recorded.
+    pub code_hash: Option<B256>,
+    pub constructor_args: Bytes,
+    /// The signatures of the functions of the contract artifact.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub functions: Vec<String>,
+    /// The libraries the contract was linked with.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub libraries: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<ProxyDeployment>,
+    pub timestamp: u64,
+    pub commit: Option<String>,
+}
+
+/// The implementation of a proxy [Deployment].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyDeployment {
+    /// The kind of the proxy, e.g. `transparent` or `UUPS`.
+    pub kind: String,
+    pub implementation: Address,
+    /// The name of the implementation in the registry, if it was deployed by a script.
+    pub implementation_name: Option<String>,
+}
+
+impl DeploymentRegistry {
+    /// Returns the path of the registry of a chain.
+    pub fn path(config: &Config, chain_id: u64) -> PathBuf {
+        config.root.join(DEPLOYMENTS_DIR).join(format!("{chain_id}.json"))
+    }
+
+    /// Loads the registry of a chain, or returns an empty one if none exists yet.
+    pub fn load(config: &Config, chain_id: u64) -> Result<Self> {
+        let path = Self::path(config, chain_id);
+        let mut registry = if path.exists() {
+            fs::read_json_file(&path)
+                .wrap_err_with(|| format!("failed to read deployments `{}`", path.display()))?
+        } else {
+            Self { chain: chain_id, ..Default::default() }
+        };
+        registry.path = path;
+        Ok(registry)
+    }
+
+    /// Loads the registries of all the chains the project deployed to.
+    pub fn load_all(config: &Config) -> Result<Vec<Self>> {
+        let dir = config.root.join(DEPLOYMENTS_DIR);
+        if !dir.exists() {
+            return Ok(vec![])
+        }
+        let mut registries = vec![];
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let Some(chain_id) = path
+                .file_name()
+                .and_then(|name| name.to_str()?.strip_suffix(".json")?.parse().ok())
+            else {
+                continue
+            };
+            registries.push(Self::load(config, chain_id)?);
+        }
+        registries.sort_by_key(|registry| registry.chain);
+        Ok(registries)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write_json_file(&self.path, self)?;
+        Ok(())
+    }
+
+    /// Returns the latest version of a deployment.
+    pub fn latest(&self, name: &str) -> Option<&Deployment> {
+        self.deployments.get(name)?.last()
+    }
+
+    /// Returns the latest versions of all the deployments.
+    pub fn latest_deployments(&self) -> impl Iterator<Item = (&String, &Deployment)> {
+        self.deployments.iter().filter_map(|(name, versions)| Some((name, versions.last()?)))
+    }
+
+    /// Returns the name of the latest deployment at `address`.
+    pub fn name_of(&self, address: Address) -> Option<&str> {
+        self.latest_deployments()
+            .find(|(_, deployment)| deployment.address == address)
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Records a new version of the deployment `name`.
+    ///
+    /// Returns `false` without recording anything if the latest version is at the same address
+    /// with the same implementation, e.g. when resuming a broadcast.
+    pub fn add(&mut self, name: String, mut deployment: Deployment) -> bool {
+        let versions = self.deployments.entry(name).or_default();
+        if let Some(latest) = versions.last() {
+            let implementation = |d: &Deployment| d.proxy.as_ref().map(|p| p.implementation);
+            if latest.address == deployment.address &&
+                implementation(latest) == implementation(&deployment)
+            {
+                return false
+            }
+        }
+        deployment.version = versions.len() + 1;
+        versions.push(deployment);
+        true
+    }
+}
+
+/// Returns the hash of a runtime code without its trailing CBOR metadata, so that it only changes
+/// with the compiled code and not with e.g. comments or the source paths.
+///
+/// Immutable references are zeroed in artifacts, so only artifact code should be compared.
+pub fn bytecode_hash(code: &[u8]) -> B256 {
+    keccak256(strip_metadata(code))
+}
+
+/// Strips the CBOR-encoded metadata appended by solc to a runtime code, if any.
+fn strip_metadata(code: &[u8]) -> &[u8] {
+    let Some((rest, len)) = code.split_last_chunk::<2>() else { return code };
+    let len = u16::from_be_bytes(*len) as usize;
+    match rest.len().checked_sub(len) {
+        // The metadata is a CBOR map of at most 23 entries.
+        Some(start) if len > 0 && (0xa1..=0xb7).contains(&rest[start]) => &rest[..start],
+        _ => code,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deployment(address: Address, implementation: Option<Address>) -> Deployment {
+        Deployment {
+            version: 0,
+            contract_name: "Counter".to_string(),
+            address,
+            transaction_hash: None,
+            block_number: None,
+            bytecode_hash: None,
+            code_hash: None,
+            constructor_args: Bytes::new(),
+            functions: vec![],
+            libraries: vec![],
+            proxy: implementation.map(|implementation| ProxyDeployment {
+                kind: "UUPS".to_string(),
+                implementation,
+                implementation_name: None,
+            }),
+            timestamp: 0,
+            commit: None,
+        }
+    }
+
+    #[test]
+    fn can_version_deployments() {
+        let mut registry = DeploymentRegistry::default();
+        let (a, b) = (Address::with_last_byte(1), Address::with_last_byte(2));
+
+        assert!(registry.add("Counter".to_string(), deployment(a, None)));
+        assert!(!registry.add("Counter".to_string(), deployment(a, None)));
+        assert!(registry.add("Counter".to_string(), deployment(b, None)));
+        assert_eq!(registry.latest("Counter").unwrap().version, 2);
+
+        assert!(registry.add("Proxy".to_string(), deployment(a, Some(a))));
+        assert!(!registry.add("Proxy".to_string(), deployment(a, Some(a))));
+        assert!(registry.add("Proxy".to_string(), deployment(a, Some(b))));
+        assert_eq!(registry.latest("Proxy").unwrap().version, 2);
+        assert_eq!(registry.name_of(b), Some("Counter"));
+    }
+
+    #[test]
+    fn can_strip_metadata() {
+        let code = [0x60, 0x80];
+        // `{"solc": 0x000818}`
+        let metadata = [0xa1, 0x64, b's', b'o', b'l', b'c', 0x43, 0x00, 0x08, 0x18, 0x00, 0x0a];
+        assert_eq!(strip_metadata(&[&code[..], &metadata[..]].concat()), code);
+        assert_eq!(strip_metadata(&code), code);
+        assert_eq!(strip_metadata(&[0x00, 0x00, 0x00]), [0x00, 0x00, 0x00]);
+        assert_ne!(bytecode_hash(&code), bytecode_hash(&[0x60, 0x40]));
+    }
+}
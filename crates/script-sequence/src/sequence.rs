@@ -1,13 +1,13 @@
 use crate::transaction::TransactionWithMetadata;
 use alloy_network::AnyTransactionReceipt;
-use alloy_primitives::{hex, map::HashMap, TxHash};
+use alloy_primitives::{hex, map::HashMap, Address, TxHash};
 use eyre::{ContextCompat, Result, WrapErr};
 use foundry_common::{fs, shell, TransactionMaybeSigned, SELECTOR_LEN};
 use foundry_compilers::ArtifactId;
 use foundry_config::Config;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::VecDeque,
+    collections::{BTreeMap, VecDeque},
     io::{BufWriter, Write},
     path::PathBuf,
     time::{Duration, SystemTime, UNIX_EPOCH},
@@ -34,6 +34,9 @@ pub struct ScriptSequence {
     /// None if sequence should not be saved to disk (e.g. part of a multi-chain sequence)
     pub paths: Option<(PathBuf, PathBuf)>,
     pub returns: HashMap<String, NestedValue>,
+    /// The labels of the contracts created by the transactions, set with `vm.label`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<Address, String>,
     pub timestamp: u64,
     pub chain: u64,
     pub commit: Option<String>,
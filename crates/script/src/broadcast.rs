@@ -1,6 +1,7 @@
 use crate::{
-    build::LinkedBuildData, progress::ScriptProgress, sequence::ScriptSequenceKind,
-    verify::BroadcastedState, ScriptArgs, ScriptConfig,
+    build::LinkedBuildData, delegation::batch_delegated, multi_sequence::StageStatus,
+    progress::ScriptProgress, sequence::ScriptSequenceKind, verify::BroadcastedState, ScriptArgs,
+    ScriptConfig,
 };
 use alloy_chains::Chain;
 use alloy_consensus::TxEnvelope;
@@ -11,7 +12,7 @@
     utils::format_units,
     Address, TxHash,
 };
-use alloy_provider::{utils::Eip1559Estimation, Provider};
+use alloy_provider::Provider;
 use alloy_rpc_types::TransactionRequest;
 use alloy_serde::WithOtherFields;
 use eyre::{bail, Context, Result};
@@ -120,6 +121,42 @@ pub async fn send_transaction(
     Ok(*pending.tx_hash())
 }
 
+/// Waits until a sent transaction is included, replacing it with bumped fees every
+/// `--bump-timeout` seconds, and returns the hash of the included transaction.
+///
+/// Signed transactions can't be replaced, so their hash is returned right away.
+async fn replace_until_included(
+    args: &ScriptArgs,
+    provider: Arc<RetryProvider>,
+    kind: &SendTransactionKind<'_>,
+    tx_hash: TxHash,
+    is_fixed_gas_limit: bool,
+    estimate_via_rpc: bool,
+    timeout: u64,
+) -> Result<TxHash> {
+    let tx = match kind {
+        SendTransactionKind::Unlocked(tx) | SendTransactionKind::Raw(tx, _) => tx.clone(),
+        SendTransactionKind::Signed(_) => return Ok(tx_hash),
+    };
+    args.gas
+        .wait_for_inclusion(&provider, tx, tx_hash, timeout, |tx| {
+            let kind = match kind {
+                SendTransactionKind::Raw(_, signer) => SendTransactionKind::Raw(tx, signer),
+                _ => SendTransactionKind::Unlocked(tx),
+            };
+            // The nonce is already used by the pending transaction, so it's not checked.
+            send_transaction(
+                provider.clone(),
+                kind,
+                false,
+                is_fixed_gas_limit,
+                estimate_via_rpc,
+                args.gas_estimate_multiplier,
+            )
+        })
+        .await
+}
+
 /// How to send a single transaction
 #[derive(Clone)]
 pub enum SendTransactionKind<'a> {
@@ -231,7 +268,18 @@ pub async fn broadcast(mut self) -> Result<BroadcastedState> {
         let send_kind = if self.args.unlocked {
             SendTransactionsKind::Unlocked(required_addresses.clone())
         } else {
-            let signers = self.script_wallets.into_multi_wallet().into_signers()?;
+            let mut wallet = self.script_wallets.into_multi_wallet();
+            for sequence in self.sequence.sequences() {
+                for tx in sequence.transactions().skip(sequence.receipts.len()) {
+                    if tx.is_unsigned() {
+                        wallet.ensure_chain_allowed(
+                            tx.from().expect("missing from"),
+                            sequence.chain,
+                        )?;
+                    }
+                }
+            }
+            let signers = wallet.into_signers()?;
             let mut missing_addresses = Vec::new();
 
             for addr in &required_addresses {
@@ -248,6 +296,21 @@ pub async fn broadcast(mut self) -> Result<BroadcastedState> {
                 );
             }
 
+            if let Some(executor) = self.args.batch_delegate {
+                for sequence in self.sequence.sequences_mut() {
+                    if sequence.receipts.is_empty() {
+                        batch_delegated(
+                            sequence,
+                            executor,
+                            &signers,
+                            self.args.gas_estimate_multiplier,
+                        )
+                        .await?;
+                    }
+                }
+                self.sequence.save(true, false)?;
+            }
+
             let signers = signers
                 .into_iter()
                 .map(|(addr, signer)| (addr, EthereumWallet::new(signer)))
@@ -259,6 +322,28 @@ pub async fn broadcast(mut self) -> Result<BroadcastedState> {
         let progress = ScriptProgress::default();
 
         for i in 0..self.sequence.sequences().len() {
+            // Don't start a stage before the stages it depends on are confirmed, and skip the
+            // stages completed by a previous run.
+            if let ScriptSequenceKind::Multi(multi) = &mut self.sequence {
+                match multi.stage(i) {
+                    StageStatus::Waiting => {
+                        multi.wait_for_dependencies(i).await?;
+                        multi.set_stage(i, StageStatus::Broadcasting);
+                        multi.save(true, false)?;
+                    }
+                    StageStatus::Broadcasting => {}
+                    StageStatus::Completed => {
+                        if !shell::is_json() {
+                            sh_println!(
+                                "\nSkipping the sequence on chain {}, completed by a previous run",
+                                multi.deployments[i].chain
+                            )?;
+                        }
+                        continue;
+                    }
+                }
+            }
+
             let mut sequence = self.sequence.sequences_mut().get_mut(i).unwrap();
 
             let provider = Arc::new(try_get_http_provider(sequence.rpc_url())?);
@@ -269,34 +354,16 @@ pub async fn broadcast(mut self) -> Result<BroadcastedState> {
             if already_broadcasted < sequence.transactions.len() {
                 let is_legacy = Chain::from(sequence.chain).is_legacy() || self.args.legacy;
                 // Make a one-time gas price estimation
-                let (gas_price, eip1559_fees) = match (
-                    is_legacy,
-                    self.args.with_gas_price,
-                    self.args.priority_gas_price,
-                ) {
-                    (true, Some(gas_price), _) => (Some(gas_price.to()), None),
-                    (true, None, _) => (Some(provider.get_gas_price().await?), None),
-                    (false, Some(max_fee_per_gas), Some(max_priority_fee_per_gas)) => (
-                        None,
-                        Some(Eip1559Estimation {
-                            max_fee_per_gas: max_fee_per_gas.to(),
-                            max_priority_fee_per_gas: max_priority_fee_per_gas.to(),
-                        }),
-                    ),
-                    (false, _, _) => {
-                        let mut fees = provider.estimate_eip1559_fees(None).await.wrap_err("Failed to estimate EIP1559 fees. This chain might not support EIP1559, try adding --legacy to your command.")?;
-
-                        if let Some(gas_price) = self.args.with_gas_price {
-                            fees.max_fee_per_gas = gas_price.to();
-                        }
-
-                        if let Some(priority_gas_price) = self.args.priority_gas_price {
-                            fees.max_priority_fee_per_gas = priority_gas_price.to();
-                        }
-
-                        (None, Some(fees))
-                    }
-                };
+                let fees = self
+                    .args
+                    .gas
+                    .fees(
+                        &provider,
+                        is_legacy,
+                        self.args.with_gas_price,
+                        self.args.priority_gas_price,
+                    )
+                    .await?;
 
                 // Iterate through transactions, matching the `from` field with the associated
                 // wallet. Then send the transaction. Panics if we find a unknown `from`
@@ -322,15 +389,7 @@ pub async fn broadcast(mut self) -> Result<BroadcastedState> {
                                     tx.set_create();
                                 }
 
-                                if let Some(gas_price) = gas_price {
-                                    tx.set_gas_price(gas_price);
-                                } else {
-                                    let eip1559_fees = eip1559_fees.expect("was set above");
-                                    tx.set_max_priority_fee_per_gas(
-                                        eip1559_fees.max_priority_fee_per_gas,
-                                    );
-                                    tx.set_max_fee_per_gas(eip1559_fees.max_fee_per_gas);
-                                }
+                                fees.apply(&mut tx);
 
                                 send_kind.for_sender(&from, tx)?
                             }
@@ -379,6 +438,7 @@ pub async fn broadcast(mut self) -> Result<BroadcastedState> {
 
                     if !pending_transactions.is_empty() {
                         let mut buffer = futures::stream::iter(pending_transactions).buffered(7);
+                        let mut sent = vec![];
 
                         while let Some(tx_hash) = buffer.next().await {
                             let tx_hash = tx_hash.wrap_err("Failed to send transaction")?;
@@ -389,9 +449,40 @@ pub async fn broadcast(mut self) -> Result<BroadcastedState> {
                             sequence = self.sequence.sequences_mut().get_mut(i).unwrap();
 
                             seq_progress.inner.write().tx_sent(tx_hash);
+                            sent.push((index, tx_hash));
                             index += 1;
                         }
 
+                        // Replace the transactions still pending after `--bump-timeout` with
+                        // bumped fees. They are waited on in nonce order, so that transactions
+                        // only pending behind a stuck one aren't replaced, and each replacement
+                        // is saved before waiting on the next one.
+                        if self.args.gas.bump_timeout.is_some() {
+                            for ((kind, is_fixed_gas_limit), (index, tx_hash)) in
+                                batch.iter().zip(sent)
+                            {
+                                let included = replace_until_included(
+                                    &self.args,
+                                    provider.clone(),
+                                    kind,
+                                    tx_hash,
+                                    *is_fixed_gas_limit,
+                                    estimate_via_rpc,
+                                    self.script_config.config.transaction_timeout,
+                                )
+                                .await?;
+                                if tx_hash != included {
+                                    sequence.remove_pending(tx_hash);
+                                    sequence.add_pending(index, included);
+                                    seq_progress.inner.write().tx_replaced(tx_hash, included);
+
+                                    // Checkpoint save
+                                    self.sequence.save(true, false)?;
+                                    sequence = self.sequence.sequences_mut().get_mut(i).unwrap();
+                                }
+                            }
+                        }
+
                         // Checkpoint save
                         self.sequence.save(true, false)?;
                         sequence = self.sequence.sequences_mut().get_mut(i).unwrap();
@@ -428,6 +519,11 @@ pub async fn broadcast(mut self) -> Result<BroadcastedState> {
                 avg_gas_price.trim_end_matches('0').trim_end_matches('.')
             ));
             seq_progress.inner.write().finish();
+
+            if let ScriptSequenceKind::Multi(multi) = &mut self.sequence {
+                multi.set_stage(i, StageStatus::Completed);
+                multi.save(true, false)?;
+            }
         }
 
         if !shell::is_json() {
@@ -445,7 +541,7 @@ pub async fn broadcast(mut self) -> Result<BroadcastedState> {
 
     pub fn verify_preflight_check(&self) -> Result<()> {
         for sequence in self.sequence.sequences() {
-            if self.args.verifier.verifier == VerificationProviderType::Etherscan &&
+            if self.args.verifier.provider() == VerificationProviderType::Etherscan &&
                 self.script_config
                     .config
                     .get_etherscan_api_key(Some(sequence.chain.into()))
@@ -262,7 +262,7 @@ pub async fn resume(self) -> Result<BundledState> {
             Some(provider.get_chain_id().await?)
         };
 
-        let sequence = match self.try_load_sequence(chain, false) {
+        let mut sequence = match self.try_load_sequence(chain, false) {
             Ok(sequence) => sequence,
             Err(_) => {
                 // If the script was simulated, but there was no attempt to broadcast yet,
@@ -312,6 +312,11 @@ pub async fn resume(self) -> Result<BundledState> {
             (self.args, self.build_data, self.script_wallets, self.script_config)
         };
 
+        // Stages given on the command line take precedence over the ones of the saved sequence.
+        if let ScriptSequenceKind::Multi(multi) = &mut sequence {
+            multi.configure_stages(&args.chain_dependency, args.stage_confirmations);
+        }
+
         // Collect libraries from sequence and link contracts with them.
         let libraries = match sequence {
             ScriptSequenceKind::Single(ref seq) => Libraries::parse(&seq.libraries)?,
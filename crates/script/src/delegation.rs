@@ -0,0 +1,192 @@
+//! Batching of a sender's transactions into a single EIP-7702 transaction executed through a
+//! delegated executor, see [`ScriptArgs::batch_delegate`](crate::ScriptArgs::batch_delegate).
+
+use crate::broadcast::estimate_gas;
+use alloy_eips::eip7702::Authorization;
+use alloy_network::TransactionBuilder;
+use alloy_primitives::{b256, map::AddressHashMap, Address, Bytes, B256, U256};
+use alloy_provider::Provider;
+use alloy_signer::Signer;
+use alloy_sol_types::{sol, SolCall, SolValue};
+use eyre::{Context, Result};
+use forge_script_sequence::{AdditionalContract, ScriptSequence, TransactionWithMetadata};
+use foundry_common::provider::try_get_http_provider;
+use foundry_wallets::WalletSigner;
+use revm_inspectors::tracing::types::CallKind;
+
+/// The ERC-7821 execution mode of a batch of calls without `opData`.
+const BATCH_MODE: B256 = b256!("0100000000000000000000000000000000000000000000000000000000000000");
+
+/// The prefix of the code of an account delegated with EIP-7702.
+const DELEGATION_DESIGNATOR: [u8; 3] = [0xef, 0x01, 0x00];
+
+sol! {
+    /// A call of an ERC-7821 batch.
+    #[derive(Debug, PartialEq, Eq)]
+    struct Call {
+        address to;
+        uint256 value;
+        bytes data;
+    }
+
+    interface IERC7821 {
+        function execute(bytes32 mode, bytes calldata executionData) external payable;
+    }
+}
+
+/// Encodes the `execute` call of the executor running a batch of calls.
+fn execute_calldata(calls: &[Call]) -> Bytes {
+    IERC7821::executeCall { mode: BATCH_MODE, executionData: calls.abi_encode().into() }
+        .abi_encode()
+        .into()
+}
+
+/// Replaces the transactions of `sequence` by a single transaction of their sender to itself,
+/// executing them as a batch through `executor`.
+///
+/// Unless the sender is already delegated to `executor`, the transaction includes an authorization
+/// signed by the sender delegating to it. Sequences with a single transaction are left as is.
+pub async fn batch_delegated(
+    sequence: &mut ScriptSequence,
+    executor: Address,
+    signers: &AddressHashMap<WalletSigner>,
+    estimate_multiplier: u64,
+) -> Result<()> {
+    if sequence.transactions.len() < 2 {
+        return Ok(());
+    }
+
+    let first = sequence.transactions.front().expect("not empty");
+    let sender = first.tx().from().expect("no sender");
+    let nonce = first.tx().nonce().expect("no nonce");
+    let calls = batch_calls(sequence, sender)?;
+
+    let provider = try_get_http_provider(sequence.rpc_url())?;
+    if provider.get_code_at(executor).await?.is_empty() {
+        eyre::bail!("No executor contract deployed at {executor} on chain {}", sequence.chain);
+    }
+
+    let code = provider.get_code_at(sender).await?;
+    let authorization_list = if code.starts_with(&DELEGATION_DESIGNATOR) &&
+        code[DELEGATION_DESIGNATOR.len()..] == executor[..]
+    {
+        None
+    } else {
+        let signer = signers
+            .get(&sender)
+            .ok_or_else(|| eyre::eyre!("No associated wallet for sender {sender}"))?;
+        if !signer.can_sign_hash() {
+            eyre::bail!(
+                "The signer of {sender} can't sign EIP-7702 authorizations: use a local, KMS or \
+                 plugin signer, or delegate {sender} to {executor} beforehand"
+            )
+        }
+        // The sender's nonce is incremented by the transaction before the authorization is
+        // processed.
+        let authorization = Authorization {
+            chain_id: U256::from(sequence.chain),
+            address: executor,
+            nonce: nonce + 1,
+        };
+        let signature = signer
+            .sign_hash(&authorization.signature_hash())
+            .await
+            .wrap_err_with(|| format!("failed to sign the authorization of {sender}"))?;
+        Some(vec![authorization.into_signed(signature)])
+    };
+
+    let mut batched_tx = first.tx().clone();
+    let tx = batched_tx.as_unsigned_mut().expect("checked above");
+    tx.set_to(sender);
+    tx.set_value(U256::ZERO);
+    tx.set_input(execute_calldata(&calls));
+    tx.access_list = None;
+    tx.authorization_list = authorization_list;
+    // The gas limits of the batched transactions don't account for the executor, so simulate the
+    // batch as a whole, which also checks it doesn't revert.
+    estimate_gas(tx, &provider, estimate_multiplier)
+        .await
+        .wrap_err_with(|| format!("failed to simulate the batch of {sender}"))?;
+
+    // Keep track of the contracts deployed by the batch.
+    let additional_contracts = sequence
+        .transactions
+        .iter()
+        .flat_map(|tx| {
+            let create2 = tx.is_create2().then(|| AdditionalContract {
+                opcode: CallKind::Create2,
+                address: tx.contract_address.unwrap_or_default(),
+                init_code: tx
+                    .tx()
+                    .input()
+                    .and_then(|input| input.get(32..))
+                    .map(Bytes::copy_from_slice)
+                    .unwrap_or_default(),
+            });
+            create2.into_iter().chain(tx.additional_contracts.iter().cloned())
+        })
+        .collect();
+
+    let mut batched = TransactionWithMetadata::from_tx_request(batched_tx);
+    batched.rpc = first.rpc.clone();
+    batched.opcode = CallKind::Call;
+    batched.contract_address = Some(sender);
+    batched.function = Some(IERC7821::executeCall::SIGNATURE.to_string());
+    batched.additional_contracts = additional_contracts;
+
+    sh_println!(
+        "\nBatched {} transaction(s) of {sender} on chain {} through executor {executor}",
+        calls.len(),
+        sequence.chain
+    )?;
+
+    sequence.transactions = [batched].into();
+    Ok(())
+}
+
+/// Returns the calls made by the transactions of a sequence, which must all be calls from
+/// `sender` to be executed by it in a batch.
+fn batch_calls(sequence: &ScriptSequence, sender: Address) -> Result<Vec<Call>> {
+    sequence
+        .transactions()
+        .map(|tx| {
+            let from = tx.from().expect("no sender");
+            if from != sender || !tx.is_unsigned() {
+                eyre::bail!(
+                    "Transactions from {from} can't be batched into a transaction of {sender}"
+                )
+            }
+            let Some(to) = tx.to().and_then(|kind| kind.to().copied()) else {
+                eyre::bail!(
+                    "Batched transactions can't deploy contracts directly, deploy them through a \
+                     CREATE2 factory instead"
+                )
+            };
+            Ok(Call {
+                to,
+                value: tx.value().unwrap_or_default(),
+                data: tx.input().map(Bytes::copy_from_slice).unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+
+    #[test]
+    fn can_encode_batch() {
+        let target = address!("000000000000000000000000000000000000bEEF");
+        let calls = vec![
+            Call { to: target, value: U256::from(1), data: Bytes::from_static(&[0xab, 0xcd]) },
+            Call { to: target, value: U256::ZERO, data: Bytes::new() },
+        ];
+
+        let calldata = execute_calldata(&calls);
+        let decoded = IERC7821::executeCall::abi_decode(&calldata, true).unwrap();
+        assert_eq!(decoded.mode, BATCH_MODE);
+        assert_eq!(<Vec<Call>>::abi_decode(&decoded.executionData, true).unwrap(), calls);
+    }
+}
@@ -0,0 +1,229 @@
+//! Recording of the broadcasted deployments in the project's [DeploymentRegistry].
+
+use crate::verify::BroadcastedState;
+use alloy_primitives::{keccak256, map::HashMap, Address, Bytes, TxHash, TxKind};
+use alloy_provider::Provider;
+use eyre::{Context, Result};
+use forge_script_sequence::{
+    bytecode_hash, Deployment, DeploymentRegistry, ProxyDeployment, ScriptSequence,
+};
+use forge_verify::proxy::Proxy;
+use foundry_common::{
+    provider::{try_get_http_provider, RetryProvider},
+    shell, ContractsByArtifact,
+};
+
+/// A contract created by a broadcasted transaction.
+struct CreatedContract<'a> {
+    address: Address,
+    init_code: &'a [u8],
+    fallback_name: Option<&'a str>,
+    transaction_hash: TxHash,
+    block_number: Option<u64>,
+}
+
+impl BroadcastedState {
+    /// Records the contracts created by the broadcast, and the upgrades of the proxies in the
+    /// registry, in the deployment registries of their chains.
+    pub async fn update_registry(&self) -> Result<()> {
+        for sequence in self.sequence.sequences() {
+            if sequence.receipts.is_empty() {
+                continue
+            }
+            let provider = try_get_http_provider(sequence.rpc_url())
+                .wrap_err_with(|| format!("bad fork_url provider: {}", sequence.rpc_url()))?;
+            let mut registry =
+                DeploymentRegistry::load(&self.script_config.config, sequence.chain)?;
+
+            let mut updated = record_deployments(
+                &mut registry,
+                sequence,
+                &self.build_data.known_contracts,
+                &provider,
+            )
+            .await?;
+            updated |= record_upgrades(&mut registry, sequence, &provider).await?;
+
+            if updated {
+                registry.save()?;
+                if !shell::is_json() {
+                    sh_println!("Deployments saved to: {}\n", registry.path.display())?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Records the contracts created by the transactions of `sequence`.
+///
+/// Deployments are named after their label, or after their contract suffixed with their index
+/// when an unlabeled contract is deployed several times by the same script.
+async fn record_deployments(
+    registry: &mut DeploymentRegistry,
+    sequence: &ScriptSequence,
+    known_contracts: &ContractsByArtifact,
+    provider: &RetryProvider,
+) -> Result<bool> {
+    let mut updated = false;
+    let mut deployed = HashMap::<String, usize>::default();
+    for contract in created_contracts(sequence) {
+        // Already recorded, e.g. when resuming a broadcast.
+        if registry.name_of(contract.address).is_some() {
+            continue
+        }
+
+        let matched = known_contracts.iter().find_map(|(id, data)| {
+            let args = contract.init_code.strip_prefix(data.bytecode()?.as_ref())?;
+            let name = id.name.strip_suffix(&format!(".{}", id.profile)).unwrap_or(&id.name);
+            Some((
+                name.to_string(),
+                data.deployed_bytecode().map(|code| bytecode_hash(code)),
+                Bytes::copy_from_slice(args),
+                data.abi.functions().map(|function| function.signature()).collect(),
+            ))
+        });
+        let (contract_name, bytecode_hash, constructor_args, functions) = match matched {
+            Some(matched) => matched,
+            None => match contract.fallback_name.filter(|name| !name.is_empty()) {
+                Some(name) => (name.to_string(), None, Bytes::new(), vec![]),
+                None => {
+                    sh_warn!(
+                        "Not recording the deployment of unknown contract at {}",
+                        contract.address
+                    )?;
+                    continue
+                }
+            },
+        };
+
+        let code = provider.get_code_at(contract.address).await?;
+        let proxy = Proxy::detect(provider, contract.address).await?.map(|proxy| ProxyDeployment {
+            kind: proxy.kind.to_string(),
+            implementation: proxy.implementation,
+            implementation_name: registry.name_of(proxy.implementation).map(str::to_string),
+        });
+
+        let name = match sequence.labels.get(&contract.address) {
+            Some(label) => label.clone(),
+            None => {
+                let count = deployed.entry(contract_name.clone()).or_default();
+                let name = if *count == 0 {
+                    contract_name.clone()
+                } else {
+                    format!("{contract_name}_{count}")
+                };
+                *count += 1;
+                name
+            }
+        };
+
+        updated |= registry.add(
+            name,
+            Deployment {
+                version: 0,
+                contract_name,
+                address: contract.address,
+                transaction_hash: Some(contract.transaction_hash),
+                block_number: contract.block_number,
+                bytecode_hash,
+                code_hash: Some(keccak256(&code)),
+                constructor_args,
+                functions,
+                libraries: sequence.libraries.clone(),
+                proxy,
+                timestamp: sequence.timestamp,
+                commit: sequence.commit.clone(),
+            },
+        );
+    }
+    Ok(updated)
+}
+
+/// Records a new version of the proxies in the registry whose implementation changed.
+///
+/// Only the proxies the transactions of `sequence` called or passed as an argument, e.g. to a proxy
+/// admin, are checked.
+async fn record_upgrades(
+    registry: &mut DeploymentRegistry,
+    sequence: &ScriptSequence,
+    provider: &RetryProvider,
+) -> Result<bool> {
+    let proxies = registry
+        .latest_deployments()
+        .filter(|(_, deployment)| {
+            deployment.proxy.is_some() && is_touched(sequence, deployment.address)
+        })
+        .map(|(name, deployment)| (name.clone(), deployment.clone()))
+        .collect::<Vec<_>>();
+
+    let mut updated = false;
+    for (name, mut deployment) in proxies {
+        let Some(proxy) = Proxy::detect(provider, deployment.address).await? else { continue };
+        if deployment.proxy.as_ref().is_some_and(|p| p.implementation == proxy.implementation) {
+            continue
+        }
+        deployment.proxy = Some(ProxyDeployment {
+            kind: proxy.kind.to_string(),
+            implementation: proxy.implementation,
+            implementation_name: registry.name_of(proxy.implementation).map(str::to_string),
+        });
+        // The upgrade may have been sent through an admin contract, so we can't tell which
+        // transaction performed it.
+        deployment.transaction_hash = None;
+        deployment.block_number = None;
+        deployment.timestamp = sequence.timestamp;
+        deployment.commit.clone_from(&sequence.commit);
+        updated |= registry.add(name, deployment);
+    }
+    Ok(updated)
+}
+
+/// Returns whether a transaction of `sequence` called `address` or passed it in its calldata.
+fn is_touched(sequence: &ScriptSequence, address: Address) -> bool {
+    sequence.transactions.iter().any(|tx| {
+        tx.tx().to() == Some(TxKind::Call(address)) ||
+            tx.tx().input().is_some_and(|input| {
+                input.windows(Address::len_bytes()).any(|window| window == address.as_slice())
+            })
+    })
+}
+
+/// Returns the contracts created by the successful transactions of `sequence`, in order.
+fn created_contracts(sequence: &ScriptSequence) -> Vec<CreatedContract<'_>> {
+    let mut contracts = vec![];
+    for tx in &sequence.transactions {
+        let Some(receipt) =
+            sequence.receipts.iter().find(|receipt| Some(receipt.transaction_hash) == tx.hash)
+        else {
+            continue
+        };
+        if !receipt.inner.inner.inner.receipt.status.coerce_status() {
+            continue
+        }
+
+        let created = if tx.is_create2() { tx.contract_address } else { receipt.contract_address };
+        if let (Some(address), Some(data)) = (created, tx.tx().input()) {
+            // CREATE2 transactions are calls to the factory starting with a 32-byte salt.
+            let offset = if tx.is_create2() { 32 } else { 0 };
+            contracts.push(CreatedContract {
+                address,
+                init_code: data.get(offset..).unwrap_or_default(),
+                fallback_name: tx.contract_name.as_deref(),
+                transaction_hash: receipt.transaction_hash,
+                block_number: receipt.block_number,
+            });
+        }
+
+        for additional in &tx.additional_contracts {
+            contracts.push(CreatedContract {
+                address: additional.address,
+                init_code: additional.init_code.as_ref(),
+                fallback_name: None,
+                transaction_hash: receipt.transaction_hash,
+                block_number: receipt.block_number,
+            });
+        }
+    }
+    contracts
+}
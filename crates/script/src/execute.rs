@@ -21,7 +21,7 @@
     ContractsByArtifact,
 };
 use foundry_config::{Config, NamedChain};
-use foundry_debugger::Debugger;
+use foundry_debugger::{DapTransport, Debugger};
 use foundry_evm::{
     decode::decode_console_logs,
     inspectors::cheatcodes::BroadcastableTransactions,
@@ -495,6 +495,12 @@ pub fn run_debugger(self) -> Result<()> {
         Ok(())
     }
 
+    pub fn run_dap(self, transport: DapTransport) -> Result<()> {
+        let root = self.script_config.config.root.clone();
+        self.create_debugger().try_run_dap(transport, &root)?;
+        Ok(())
+    }
+
     pub fn dump_debugger(self, path: &Path) -> Result<()> {
         self.create_debugger().dump_to_file(path)?;
         Ok(())
@@ -27,7 +27,7 @@
 use forge_script_sequence::{AdditionalContract, NestedValue};
 use forge_verify::{RetryArgs, VerifierArgs};
 use foundry_cli::{
-    opts::{BuildOpts, GlobalArgs},
+    opts::{BuildOpts, GasStrategyOpts, GlobalArgs},
     utils::LoadConfig,
 };
 use foundry_common::{
@@ -35,7 +35,7 @@
     evm::{Breakpoints, EvmArgs},
     shell, ContractsByArtifact, CONTRACT_MAX_SIZE, SELECTOR_LEN,
 };
-use foundry_compilers::ArtifactId;
+use foundry_compilers::{artifacts::output_selection::ContractOutputSelection, ArtifactId};
 use foundry_config::{
     figment,
     figment::{
@@ -44,6 +44,7 @@
     },
     Config,
 };
+use foundry_debugger::DapTransport;
 use foundry_evm::{
     backend::Backend,
     executors::ExecutorBuilder,
@@ -55,17 +56,23 @@
     traces::{TraceMode, Traces},
 };
 use foundry_wallets::MultiWalletOpts;
+use multi_sequence::{parse_chain_dependency, StageConfirmations};
+use plan::AccountChange;
 use serde::Serialize;
 use std::path::PathBuf;
 
 mod broadcast;
 mod build;
+mod delegation;
+mod deployments;
 mod execute;
 mod multi_sequence;
+mod plan;
 mod progress;
 mod providers;
 mod receipts;
 mod runner;
+mod safe;
 mod sequence;
 mod simulate;
 mod transaction;
@@ -145,6 +152,9 @@ pub struct ScriptArgs {
     ///
     /// Example: If transaction N has a nonce of 22, then the account should have a nonce of 22,
     /// otherwise it fails.
+    ///
+    /// Multi chain deployments skip the chains whose transactions were all included, and don't wait
+    /// again for the chains that were already confirmed.
     #[arg(long)]
     pub resume: bool,
 
@@ -152,6 +162,21 @@ pub struct ScriptArgs {
     #[arg(long)]
     pub multi: bool,
 
+    /// Makes the transactions of a multi chain deployment on a chain wait for the ones sent
+    /// before them on another chain, e.g. `10:1` for transactions on chain 10 to wait for the
+    /// ones on chain 1.
+    ///
+    /// By default, transactions wait for the ones sent before them on all other chains.
+    #[arg(long, value_name = "CHAIN:DEPENDENCY", value_parser = parse_chain_dependency)]
+    pub chain_dependency: Vec<(u64, u64)>,
+
+    /// How long the transactions of a multi chain deployment wait for the ones they depend on,
+    /// either a number of confirmations or `finalized`.
+    ///
+    /// Defaults to the value of the resumed deployment, or 1 confirmation.
+    #[arg(long, value_name = "CONFIRMATIONS")]
+    pub stage_confirmations: Option<StageConfirmations>,
+
     /// Open the script in the debugger.
     ///
     /// Takes precedence over broadcast.
@@ -167,6 +192,13 @@ pub struct ScriptArgs {
     )]
     pub dump: Option<PathBuf>,
 
+    /// Serves the debugger over the Debug Adapter Protocol instead of opening the TUI.
+    ///
+    /// Takes a local port to listen on, or `stdio` to communicate over stdin and stdout, which
+    /// requires `--quiet`.
+    #[arg(long, requires = "debug", conflicts_with = "dump", value_name = "PORT|stdio")]
+    pub dap: Option<DapTransport>,
+
     /// Makes sure a transaction is sent,
     /// only after its previous one has been confirmed and succeeded.
     #[arg(long)]
@@ -178,6 +210,25 @@ pub struct ScriptArgs {
     #[arg(long)]
     pub non_interactive: bool,
 
+    /// Shows the plan of the changes made by the simulated transactions before broadcasting them:
+    /// the decoded calls, the emitted events and the balance, nonce and storage changes.
+    ///
+    /// Asks for confirmation before broadcasting, unless `--non-interactive` is passed.
+    #[arg(long, conflicts_with = "skip_simulation")]
+    pub plan: bool,
+
+    /// Writes the plan of the changes made by the simulated transactions to a JSON file.
+    ///
+    /// Storage changes are only decoded with `--plan`, or if the storage layouts are already
+    /// part of the `extra_output`.
+    #[arg(
+        long,
+        conflicts_with = "skip_simulation",
+        value_hint = ValueHint::FilePath,
+        value_name = "PATH"
+    )]
+    pub plan_out: Option<PathBuf>,
+
     /// The Etherscan (or equivalent) API key
     #[arg(long, env = "ETHERSCAN_API_KEY", value_name = "KEY")]
     pub etherscan_api_key: Option<String>,
@@ -186,6 +237,13 @@ pub struct ScriptArgs {
     #[arg(long)]
     pub verify: bool,
 
+    /// Records the deployed contracts and the upgrades of the recorded proxies in the deployment
+    /// registry of the chain, `deployments/<chain>.json`.
+    ///
+    /// Deployments are named after their `vm.label`, or their contract otherwise.
+    #[arg(long)]
+    pub deployments: bool,
+
     /// Gas price for legacy transactions, or max fee per gas for EIP1559 transactions, either
     /// specified in wei, or as a string with a unit type.
     ///
@@ -202,9 +260,46 @@ pub struct ScriptArgs {
     #[arg(long, env = "ETH_TIMEOUT")]
     pub timeout: Option<u64>,
 
+    /// Batches the transactions sent by this Safe multisig into a single Safe transaction instead
+    /// of broadcasting them, through MultiSend for multiple calls.
+    ///
+    /// Writes a Safe Transaction Builder batch and the Safe transaction with its EIP-712 hash.
+    /// The wallets owning the Safe sign the transaction, and once its threshold is met the
+    /// `execTransaction` calldata is written as well.
+    #[arg(
+        long,
+        value_name = "ADDRESS",
+        conflicts_with_all = ["broadcast", "resume", "verify", "unlocked"]
+    )]
+    pub safe: Option<Address>,
+
+    /// The nonce of the Safe transaction, defaults to the current nonce of the Safe.
+    #[arg(long, requires = "safe", value_name = "NONCE")]
+    pub safe_nonce: Option<u64>,
+
+    /// The MultiSend contract batching the calls of the Safe transaction, defaults to the
+    /// canonical `MultiSendCallOnly` v1.3.0 deployment.
+    #[arg(long, requires = "safe", value_name = "ADDRESS")]
+    pub safe_multisend: Option<Address>,
+
+    /// Broadcasts the transactions of each chain as a single EIP-7702 transaction of their
+    /// sender, executing them atomically through the ERC-7821 batch executor at this address.
+    ///
+    /// The sender signs an authorization delegating to the executor, unless it's already
+    /// delegated to it. Contracts must be deployed through a CREATE2 factory.
+    #[arg(
+        long,
+        value_name = "EXECUTOR",
+        conflicts_with_all = ["safe", "resume", "legacy", "unlocked"]
+    )]
+    pub batch_delegate: Option<Address>,
+
     #[command(flatten)]
     pub build: BuildOpts,
 
+    #[command(flatten)]
+    pub gas: GasStrategyOpts,
+
     #[command(flatten)]
     pub wallets: MultiWalletOpts,
 
@@ -222,7 +317,14 @@ impl ScriptArgs {
     pub async fn preprocess(self) -> Result<PreprocessedState> {
         let script_wallets = Wallets::new(self.wallets.get_multi_wallet().await?, self.evm.sender);
 
-        let (config, mut evm_opts) = self.load_config_and_evm_opts()?;
+        let (mut config, mut evm_opts) = self.load_config_and_evm_opts()?;
+
+        // Storage layouts are needed to decode the storage changes of the plan.
+        if self.plan &&
+            !config.extra_output.contains(&ContractOutputSelection::StorageLayout)
+        {
+            config.extra_output.push(ContractOutputSelection::StorageLayout);
+        }
 
         if let Some(sender) = self.maybe_load_private_key()? {
             evm_opts.sender = sender;
@@ -259,6 +361,9 @@ pub async fn run_script(self) -> Result<()> {
                 .await?;
 
             if pre_simulation.args.debug {
+                if let Some(transport) = pre_simulation.args.dap {
+                    return pre_simulation.run_dap(transport);
+                }
                 return match pre_simulation.args.dump.clone() {
                     Some(path) => pre_simulation.dump_debugger(&path),
                     None => pre_simulation.run_debugger(),
@@ -300,6 +405,10 @@ pub async fn run_script(self) -> Result<()> {
             pre_simulation.fill_metadata().await?.bundle().await?
         };
 
+        if let Some(safe) = bundled.args.safe {
+            return bundled.safe(safe).await;
+        }
+
         // Exit early in case user didn't provide any broadcast/verify related flags.
         if !bundled.args.should_broadcast() {
             if !shell::is_json() {
@@ -321,6 +430,12 @@ pub async fn run_script(self) -> Result<()> {
         // Wait for pending txes and broadcast others.
         let broadcasted = bundled.wait_for_pending().await?.broadcast().await?;
 
+        if broadcasted.args.deployments {
+            if let Err(err) = broadcasted.update_registry().await {
+                sh_warn!("Failed to record the deployments: {err:#}")?;
+            }
+        }
+
         if broadcasted.args.verify {
             broadcasted.verify().await?;
         }
@@ -513,6 +628,9 @@ pub struct ScriptResult {
     pub address: Option<Address>,
     #[serde(skip)]
     pub breakpoints: Breakpoints,
+    /// The changes made by a simulated transaction, if recorded for the plan.
+    #[serde(skip)]
+    pub state_changes: Vec<AccountChange>,
 }
 
 impl ScriptResult {
@@ -650,6 +768,7 @@ async fn _get_runner(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloy_primitives::address;
     use foundry_config::{NamedChain, UnresolvedEnvVarError};
     use std::fs;
     use tempfile::tempdir;
@@ -661,6 +780,31 @@ fn can_parse_sig() {
         assert_eq!(args.sig, sig);
     }
 
+    #[test]
+    fn can_parse_safe() {
+        let args = ScriptArgs::parse_from([
+            "foundry-cli",
+            "Contract.sol",
+            "--sender",
+            "0x4e59b44847b379578588920ca78fbf26c0b4956c",
+            "--safe",
+            "0x4e59b44847b379578588920ca78fbf26c0b4956c",
+            "--safe-nonce",
+            "3",
+        ]);
+        assert_eq!(args.safe, Some(address!("4e59b44847b379578588920ca78fbf26c0b4956c")));
+        assert_eq!(args.safe_nonce, Some(3));
+
+        let args = ScriptArgs::try_parse_from([
+            "foundry-cli",
+            "Contract.sol",
+            "--safe",
+            "0x4e59b44847b379578588920ca78fbf26c0b4956c",
+            "--broadcast",
+        ]);
+        assert!(args.is_err());
+    }
+
     #[test]
     fn can_parse_unlocked() {
         let args = ScriptArgs::parse_from([
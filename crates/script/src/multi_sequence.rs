@@ -1,16 +1,75 @@
+use alloy_provider::Provider;
+use alloy_rpc_types::{BlockNumberOrTag, BlockTransactionsKind};
 use eyre::{ContextCompat, Result, WrapErr};
 use forge_script_sequence::{
     now, sig_to_file_name, ScriptSequence, SensitiveScriptSequence, DRY_RUN_DIR,
 };
-use foundry_common::{fs, shell};
+use foundry_common::{fs, provider::try_get_http_provider, shell};
 use foundry_compilers::ArtifactId;
-use foundry_config::Config;
+use foundry_config::{Chain, Config};
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::{BTreeMap, BTreeSet},
     io::{BufWriter, Write},
     path::PathBuf,
+    str::FromStr,
+    time::Duration,
 };
 
+/// Interval at which the chains are polled when waiting for the sequences a sequence depends on.
+const STAGE_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// How long a sequence waits for the transactions of the sequences it depends on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StageConfirmations {
+    /// Until the last transaction has this many confirmations.
+    Blocks(u64),
+    /// Until the last transaction is in a finalized block.
+    Finalized,
+}
+
+impl Default for StageConfirmations {
+    fn default() -> Self {
+        Self::Blocks(1)
+    }
+}
+
+impl FromStr for StageConfirmations {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s == "finalized" {
+            return Ok(Self::Finalized);
+        }
+        let blocks = s.parse().wrap_err_with(|| {
+            format!("invalid confirmations `{s}`, expected a number of blocks or `finalized`")
+        })?;
+        Ok(Self::Blocks(blocks))
+    }
+}
+
+/// The progress of the stage of a sequence, saved so that `--resume` continues where a run
+/// stopped.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StageStatus {
+    /// Waiting for the sequences it depends on.
+    #[default]
+    Waiting,
+    /// The sequences it depends on are confirmed, its transactions are being broadcast.
+    Broadcasting,
+    /// All of its transactions were included.
+    Completed,
+}
+
+/// Parses a `CHAIN:DEPENDENCY` pair of chains, given by name or id.
+pub fn parse_chain_dependency(s: &str) -> Result<(u64, u64)> {
+    let (chain, dependency) =
+        s.split_once(':').wrap_err_with(|| format!("expected `CHAIN:DEPENDENCY`, got `{s}`"))?;
+    Ok((Chain::from_str(chain)?.id(), Chain::from_str(dependency)?.id()))
+}
+
 /// Holds the sequences of multiple chain deployments.
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct MultiChainSequence {
@@ -20,6 +79,16 @@ pub struct MultiChainSequence {
     #[serde(skip)]
     pub sensitive_path: PathBuf,
     pub timestamp: u64,
+    /// The chains whose sequences the sequences of each chain wait for. Sequences wait for all
+    /// other chains if empty.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub dependencies: BTreeMap<u64, BTreeSet<u64>>,
+    /// How long sequences wait for the sequences they depend on.
+    #[serde(default)]
+    pub confirmations: StageConfirmations,
+    /// The progress of the stage of each sequence.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stages: Vec<StageStatus>,
 }
 
 /// Sensitive values from script sequences.
@@ -46,7 +115,134 @@ pub fn new(
     ) -> Result<Self> {
         let (path, sensitive_path) = Self::get_paths(config, sig, target, dry_run)?;
 
-        Ok(Self { deployments, path, sensitive_path, timestamp: now().as_secs() })
+        Ok(Self {
+            deployments,
+            path,
+            sensitive_path,
+            timestamp: now().as_secs(),
+            dependencies: Default::default(),
+            confirmations: Default::default(),
+            stages: Default::default(),
+        })
+    }
+
+    /// Sets the dependencies between chains and the confirmations to wait for between them,
+    /// keeping the ones of the sequence if unset, e.g. when resuming.
+    ///
+    /// Stages which didn't send any transaction yet wait again for their dependencies if these
+    /// changed.
+    pub fn configure_stages(
+        &mut self,
+        dependencies: &[(u64, u64)],
+        confirmations: Option<StageConfirmations>,
+    ) {
+        let previous = (self.dependencies.clone(), self.confirmations);
+        if !dependencies.is_empty() {
+            self.dependencies.clear();
+            for (chain, dependency) in dependencies {
+                self.dependencies.entry(*chain).or_default().insert(*dependency);
+            }
+        }
+        if let Some(confirmations) = confirmations {
+            self.confirmations = confirmations;
+        }
+
+        if previous != (self.dependencies.clone(), self.confirmations) {
+            for (stage, sequence) in self.stages.iter_mut().zip(&self.deployments) {
+                if *stage == StageStatus::Broadcasting &&
+                    sequence.receipts.is_empty() &&
+                    sequence.pending.is_empty()
+                {
+                    *stage = StageStatus::Waiting;
+                }
+            }
+        }
+    }
+
+    /// Returns the progress of the stage of the sequence at `index`.
+    pub fn stage(&self, index: usize) -> StageStatus {
+        self.stages.get(index).copied().unwrap_or_default()
+    }
+
+    /// Sets the progress of the stage of the sequence at `index`.
+    pub fn set_stage(&mut self, index: usize, status: StageStatus) {
+        if self.stages.len() < self.deployments.len() {
+            self.stages.resize(self.deployments.len(), StageStatus::Waiting);
+        }
+        self.stages[index] = status;
+    }
+
+    /// Returns the indices of the sequences the sequence at `index` depends on: the sequences
+    /// broadcast before it on the chains it depends on.
+    pub fn dependencies_of(&self, index: usize) -> Vec<usize> {
+        let chain = self.deployments[index].chain;
+        let depends_on = |dependency: u64| {
+            dependency != chain &&
+                (self.dependencies.is_empty() ||
+                    self.dependencies
+                        .get(&chain)
+                        .is_some_and(|deps| deps.contains(&dependency)))
+        };
+        self.deployments[..index]
+            .iter()
+            .enumerate()
+            .filter(|(_, sequence)| depends_on(sequence.chain))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Waits until the transactions of the sequences the sequence at `index` depends on have
+    /// enough confirmations.
+    pub async fn wait_for_dependencies(&self, index: usize) -> Result<()> {
+        for dependency in self.dependencies_of(index) {
+            let sequence = &self.deployments[dependency];
+            if sequence.receipts.len() < sequence.transactions.len() {
+                eyre::bail!(
+                    "The transactions on chain {} the sequence on chain {} depends on were not all \
+                     included",
+                    sequence.chain,
+                    self.deployments[index].chain
+                );
+            }
+            let Some(block) = sequence.receipts.iter().filter_map(|r| r.block_number).max() else {
+                continue
+            };
+
+            let provider = try_get_http_provider(sequence.rpc_url())?;
+            let mut waiting = false;
+            loop {
+                let confirmed = match self.confirmations {
+                    StageConfirmations::Blocks(confirmations) => {
+                        provider.get_block_number().await? + 1 >= block + confirmations
+                    }
+                    StageConfirmations::Finalized => provider
+                        .get_block_by_number(
+                            BlockNumberOrTag::Finalized,
+                            BlockTransactionsKind::Hashes,
+                        )
+                        .await?
+                        .is_some_and(|finalized| finalized.header.number >= block),
+                };
+                if confirmed {
+                    break
+                }
+                if !waiting && !shell::is_json() {
+                    let until = match self.confirmations {
+                        StageConfirmations::Blocks(n) => format!("{n} confirmations"),
+                        StageConfirmations::Finalized => "finality".to_string(),
+                    };
+                    sh_println!(
+                        "\nWaiting for {until} of block {block} on chain {} before broadcasting \
+                         on chain {}",
+                        sequence.chain,
+                        self.deployments[index].chain
+                    )?;
+                    waiting = true;
+                }
+                tokio::time::sleep(STAGE_POLL_INTERVAL).await;
+            }
+        }
+        Ok(())
     }
 
     /// Gets paths in the formats
@@ -164,3 +360,74 @@ pub fn save(&mut self, silent: bool, save_ts: bool) -> Result<()> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn multi_sequence(chains: &[u64]) -> MultiChainSequence {
+        MultiChainSequence {
+            deployments: chains
+                .iter()
+                .map(|chain| ScriptSequence { chain: *chain, ..Default::default() })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn can_resolve_dependencies() {
+        let mut sequence = multi_sequence(&[1, 10, 1, 8453]);
+        assert_eq!(sequence.dependencies_of(0), Vec::<usize>::new());
+        assert_eq!(sequence.dependencies_of(2), vec![1]);
+        assert_eq!(sequence.dependencies_of(3), vec![0, 1, 2]);
+
+        sequence.configure_stages(&[(8453, 1), (10, 1)], Some(StageConfirmations::Finalized));
+        assert_eq!(sequence.dependencies_of(1), vec![0]);
+        assert_eq!(sequence.dependencies_of(2), Vec::<usize>::new());
+        assert_eq!(sequence.dependencies_of(3), vec![0, 2]);
+        assert_eq!(sequence.confirmations, StageConfirmations::Finalized);
+
+        // Resuming without stages keeps the ones of the sequence.
+        sequence.configure_stages(&[], None);
+        assert_eq!(sequence.dependencies_of(3), vec![0, 2]);
+    }
+
+    #[test]
+    fn can_track_stages() {
+        let mut sequence = multi_sequence(&[1, 10, 8453]);
+        assert_eq!(sequence.stage(2), StageStatus::Waiting);
+
+        sequence.set_stage(0, StageStatus::Completed);
+        sequence.set_stage(1, StageStatus::Broadcasting);
+        sequence.set_stage(2, StageStatus::Broadcasting);
+        sequence.deployments[2].pending.push(Default::default());
+        let json = serde_json::to_value(&sequence).unwrap();
+        assert_eq!(
+            json["stages"],
+            serde_json::json!(["completed", "broadcasting", "broadcasting"])
+        );
+
+        // Unchanged stages are kept.
+        sequence.configure_stages(&[], Some(StageConfirmations::Blocks(1)));
+        assert_eq!(sequence.stage(1), StageStatus::Broadcasting);
+
+        // Stages which didn't send any transaction wait again for their new dependencies.
+        sequence.configure_stages(&[], Some(StageConfirmations::Finalized));
+        assert_eq!(sequence.stage(0), StageStatus::Completed);
+        assert_eq!(sequence.stage(1), StageStatus::Waiting);
+        assert_eq!(sequence.stage(2), StageStatus::Broadcasting);
+    }
+
+    #[test]
+    fn can_parse_stages() {
+        assert_eq!(parse_chain_dependency("10:1").unwrap(), (10, 1));
+        assert_eq!(parse_chain_dependency("optimism:mainnet").unwrap(), (10, 1));
+        assert!(parse_chain_dependency("10").is_err());
+        assert_eq!(
+            "finalized".parse::<StageConfirmations>().unwrap(),
+            StageConfirmations::Finalized
+        );
+        assert_eq!("12".parse::<StageConfirmations>().unwrap(), StageConfirmations::Blocks(12));
+    }
+}
@@ -0,0 +1,460 @@
+//! Plan of the changes made by the simulated transactions, see [`ScriptArgs::plan`].
+
+use crate::ScriptArgs;
+use alloy_primitives::{
+    map::HashMap, utils::format_units, Address, Bytes, Log, TxKind, B256, I256, U256,
+};
+use eyre::Result;
+use forge_script_sequence::TransactionWithMetadata;
+use foundry_common::fs;
+use foundry_compilers::{artifacts::StorageLayout, ProjectCompileOutput};
+use foundry_evm::{
+    backend::Backend,
+    revm::{primitives::KECCAK_EMPTY, DatabaseRef},
+    traces::CallTraceDecoder,
+    utils::StateChangeset,
+};
+use serde::Serialize;
+use std::fmt::{self, Write};
+use yansi::Paint;
+
+/// The changes made by the simulated transactions of a script.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Plan {
+    pub transactions: Vec<PlannedTransaction>,
+}
+
+/// A simulated transaction and its changes.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedTransaction {
+    pub from: Address,
+    pub to: Option<Address>,
+    pub contract_name: Option<String>,
+    pub function: Option<String>,
+    pub arguments: Vec<String>,
+    pub value: U256,
+    pub events: Vec<PlannedEvent>,
+    pub state_changes: Vec<AccountChange>,
+}
+
+/// An event emitted by a simulated transaction.
+#[derive(Clone, Debug, Serialize)]
+pub struct PlannedEvent {
+    pub address: Address,
+    /// The name of the event, if it could be decoded.
+    pub name: Option<String>,
+    /// The decoded parameters of the event, as name and value pairs.
+    pub params: Vec<(String, String)>,
+    pub topics: Vec<B256>,
+    pub data: Bytes,
+}
+
+/// The changes made to an account by a transaction.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountChange {
+    pub address: Address,
+    /// The name of the contract at the address, if known.
+    pub contract: Option<String>,
+    /// Whether the account was created by the transaction.
+    pub created: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<Change<U256>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<Change<u64>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub storage: Vec<StorageChange>,
+}
+
+/// A storage slot changed by a transaction.
+#[derive(Clone, Debug, Serialize)]
+pub struct StorageChange {
+    pub slot: B256,
+    pub previous: B256,
+    pub new: B256,
+    /// The changed state variables stored in the slot, if known from the storage layout.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub decoded: Vec<VariableChange>,
+}
+
+/// A state variable changed by a transaction.
+#[derive(Clone, Debug, Serialize)]
+pub struct VariableChange {
+    pub label: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub previous: String,
+    pub new: String,
+}
+
+/// The previous and new values of a field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Change<T> {
+    pub previous: T,
+    pub new: T,
+}
+
+impl<T: PartialEq> Change<T> {
+    fn new(previous: T, new: T) -> Option<Self> {
+        (previous != new).then_some(Self { previous, new })
+    }
+}
+
+/// Returns the accounts changed by an executed transaction, with their storage changes still
+/// undecoded, by comparing its changeset with the state `db` it was executed on.
+pub fn state_changes(db: &Backend, changeset: &StateChangeset) -> Result<Vec<AccountChange>> {
+    let mut changes = vec![];
+    for (address, account) in changeset {
+        if !account.is_touched() {
+            continue
+        }
+        let previous = db.basic_ref(*address)?.unwrap_or_default();
+        let created = account.is_created() ||
+            (previous.code_hash == KECCAK_EMPTY && account.info.code_hash != KECCAK_EMPTY);
+        let balance = Change::new(previous.balance, account.info.balance);
+        let nonce = Change::new(previous.nonce, account.info.nonce);
+        let mut storage = account
+            .storage
+            .iter()
+            .filter(|(_, slot)| slot.is_changed())
+            .map(|(slot, value)| StorageChange {
+                slot: B256::from(*slot),
+                previous: B256::from(value.original_value),
+                new: B256::from(value.present_value),
+                decoded: vec![],
+            })
+            .collect::<Vec<_>>();
+        storage.sort_by_key(|change| change.slot);
+
+        if created || balance.is_some() || nonce.is_some() || !storage.is_empty() {
+            changes.push(AccountChange {
+                address: *address,
+                contract: None,
+                created,
+                balance,
+                nonce,
+                storage,
+            });
+        }
+    }
+    changes.sort_by_key(|change| change.address);
+    Ok(changes)
+}
+
+/// Builds the [Plan] of the simulated transactions.
+pub struct PlanBuilder<'a> {
+    decoder: &'a CallTraceDecoder,
+    /// The storage layouts of the contracts, by address.
+    layouts: HashMap<Address, &'a StorageLayout>,
+    plan: Plan,
+}
+
+impl<'a> PlanBuilder<'a> {
+    pub fn new(decoder: &'a CallTraceDecoder, output: &'a ProjectCompileOutput) -> Self {
+        let layouts = decoder
+            .contracts
+            .iter()
+            .filter_map(|(address, id)| {
+                let (_, artifact) = output.artifact_ids().find(|(artifact_id, _)| {
+                    artifact_id.identifier() == *id || artifact_id.name == *id
+                })?;
+                let layout = artifact.storage_layout.as_ref()?;
+                Some((*address, layout))
+            })
+            .collect();
+        Self { decoder, layouts, plan: Plan::default() }
+    }
+
+    /// Adds a simulated transaction with the logs it emitted and the changes it made.
+    pub async fn add(
+        &mut self,
+        tx: &TransactionWithMetadata,
+        logs: &[Log],
+        mut state_changes: Vec<AccountChange>,
+    ) {
+        let mut events = Vec::with_capacity(logs.len());
+        for log in logs {
+            let decoded = self.decoder.decode_event(&log.data).await;
+            events.push(PlannedEvent {
+                address: log.address,
+                name: decoded.name,
+                params: decoded.params.unwrap_or_default(),
+                topics: log.topics().to_vec(),
+                data: log.data.data.clone(),
+            });
+        }
+
+        for change in &mut state_changes {
+            change.contract = self.contract_name(change.address);
+            if let Some(layout) = self.layouts.get(&change.address) {
+                for slot in &mut change.storage {
+                    slot.decoded = decode_slot(layout, slot);
+                }
+            }
+        }
+
+        let to = match tx.tx().to() {
+            Some(TxKind::Call(to)) => Some(to),
+            _ => None,
+        };
+        self.plan.transactions.push(PlannedTransaction {
+            from: tx.tx().from().unwrap_or_default(),
+            to,
+            contract_name: tx.contract_name.clone().filter(|name| !name.is_empty()),
+            function: tx.function.clone().filter(|function| !function.is_empty()),
+            arguments: tx.arguments.clone().unwrap_or_default(),
+            value: tx.tx().value().unwrap_or_default(),
+            events,
+            state_changes,
+        });
+    }
+
+    fn contract_name(&self, address: Address) -> Option<String> {
+        if let Some(label) = self.decoder.labels.get(&address) {
+            return Some(label.clone())
+        }
+        let id = self.decoder.contracts.get(&address)?;
+        Some(id.rsplit(':').next().unwrap_or(id).to_string())
+    }
+
+    pub fn build(self) -> Plan {
+        self.plan
+    }
+}
+
+impl Plan {
+    /// Shows the plan and writes it to `--plan-out`, if set.
+    pub fn save(&self, args: &ScriptArgs) -> Result<()> {
+        if args.plan && !foundry_common::shell::is_json() {
+            sh_println!("\n{self}")?;
+        }
+        if let Some(path) = &args.plan_out {
+            fs::write_json_file(path, self)?;
+            sh_println!("\nPlan saved to: {}", path.display())?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", "== Plan ==".bold())?;
+        for (i, tx) in self.transactions.iter().enumerate() {
+            writeln!(f)?;
+            let call = match (&tx.to, &tx.function) {
+                (None, _) => {
+                    format!("create {}", tx.contract_name.as_deref().unwrap_or("contract"))
+                }
+                (Some(to), function) => {
+                    let contract = tx.contract_name.clone().unwrap_or_else(|| to.to_string());
+                    let function = function.as_deref().unwrap_or("fallback()");
+                    let name = function.split('(').next().unwrap_or(function);
+                    format!("{contract}.{name}({})", tx.arguments.join(", "))
+                }
+            };
+            writeln!(f, "{} {call}", format!("Transaction {}:", i + 1).bold())?;
+            writeln!(f, "  from: {}", tx.from)?;
+            if !tx.value.is_zero() {
+                writeln!(f, "  value: {} ETH", format_ether(tx.value))?;
+            }
+
+            if !tx.events.is_empty() {
+                writeln!(f, "  events:")?;
+                for event in &tx.events {
+                    match &event.name {
+                        Some(name) => {
+                            let params = event
+                                .params
+                                .iter()
+                                .map(|(name, value)| format!("{name}: {value}"))
+                                .collect::<Vec<_>>()
+                                .join(", ");
+                            writeln!(f, "    {}: {name}({params})", event.address)?;
+                        }
+                        None => writeln!(
+                            f,
+                            "    {}: topics {:?}, data {}",
+                            event.address, event.topics, event.data
+                        )?,
+                    }
+                }
+            }
+
+            if !tx.state_changes.is_empty() {
+                writeln!(f, "  state changes:")?;
+                for change in &tx.state_changes {
+                    let mut account = change.address.to_string();
+                    if let Some(contract) = &change.contract {
+                        write!(account, " ({contract})")?;
+                    }
+                    if change.created {
+                        account.push_str(" [created]");
+                    }
+                    writeln!(f, "    {account}")?;
+                    if let Some(Change { previous, new }) = change.balance {
+                        writeln!(
+                            f,
+                            "      balance: {} → {} ETH",
+                            format_ether(previous),
+                            format_ether(new)
+                        )?;
+                    }
+                    if let Some(Change { previous, new }) = change.nonce {
+                        writeln!(f, "      nonce: {previous} → {new}")?;
+                    }
+                    for slot in &change.storage {
+                        if slot.decoded.is_empty() {
+                            writeln!(
+                                f,
+                                "      slot {}: {} → {}",
+                                slot.slot, slot.previous, slot.new
+                            )?;
+                        }
+                        for variable in &slot.decoded {
+                            writeln!(
+                                f,
+                                "      {} ({}): {} → {}",
+                                variable.label, variable.ty, variable.previous, variable.new
+                            )?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn format_ether(value: U256) -> String {
+    let value = format_units(value, 18).unwrap_or_else(|_| value.to_string());
+    value.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Decodes the state variables of `layout` changed in a storage slot.
+///
+/// Only value types stored directly in their declared slot are decoded: the slots of mappings and
+/// dynamic arrays are derived from keys which are unknown here.
+fn decode_slot(layout: &StorageLayout, change: &StorageChange) -> Vec<VariableChange> {
+    let slot = U256::from_be_bytes(change.slot.0);
+    let mut decoded = vec![];
+    for variable in &layout.storage {
+        if variable.slot.parse::<U256>().ok() != Some(slot) {
+            continue
+        }
+        let Some(ty) = layout.types.get(&variable.storage_type) else { continue };
+        let Ok(size) = ty.number_of_bytes.parse::<usize>() else { continue };
+        let Ok(offset) = usize::try_from(variable.offset) else { continue };
+        if ty.encoding != "inplace" || size == 0 || offset + size > 32 {
+            continue
+        }
+
+        let extract = |word: B256| {
+            let value = U256::from_be_bytes(word.0) >> (offset * 8);
+            if size == 32 {
+                value
+            } else {
+                value & ((U256::from(1) << (size * 8)) - U256::from(1))
+            }
+        };
+        let (previous, new) = (extract(change.previous), extract(change.new));
+        if previous == new {
+            continue
+        }
+        decoded.push(VariableChange {
+            label: variable.label.clone(),
+            ty: ty.label.clone(),
+            previous: format_value(&ty.label, previous, size),
+            new: format_value(&ty.label, new, size),
+        });
+    }
+    decoded
+}
+
+/// Formats a value type of `size` bytes stored in a slot.
+fn format_value(ty: &str, value: U256, size: usize) -> String {
+    if ty == "bool" {
+        (!value.is_zero()).to_string()
+    } else if ty == "address" || ty.starts_with("contract ") || ty == "address payable" {
+        Address::from_word(value.into()).to_string()
+    } else if ty.starts_with("uint") || ty.starts_with("enum ") {
+        value.to_string()
+    } else if ty.starts_with("int") {
+        // Sign-extend the value to 256 bits.
+        let bits = size * 8;
+        let value =
+            if bits < 256 && value.bit(bits - 1) { value | (U256::MAX << bits) } else { value };
+        I256::from_raw(value).to_string()
+    } else {
+        let bytes = B256::from(value);
+        format!("0x{}", alloy_primitives::hex::encode(&bytes[32 - size..]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use foundry_compilers::artifacts::{Storage, StorageType};
+    use std::collections::BTreeMap;
+
+    fn layout() -> StorageLayout {
+        let variable = |label: &str, slot: &str, offset: i64, ty: &str| Storage {
+            ast_id: 0,
+            contract: "src/Counter.sol:Counter".to_string(),
+            label: label.to_string(),
+            offset,
+            slot: slot.to_string(),
+            storage_type: ty.to_string(),
+        };
+        let ty = |label: &str, size: &str| StorageType {
+            encoding: "inplace".to_string(),
+            key: None,
+            label: label.to_string(),
+            number_of_bytes: size.to_string(),
+            value: None,
+            other: BTreeMap::new(),
+        };
+        StorageLayout {
+            storage: vec![
+                variable("number", "0", 0, "t_uint256"),
+                variable("owner", "1", 0, "t_address"),
+                variable("paused", "1", 20, "t_bool"),
+                variable("delta", "2", 0, "t_int8"),
+            ],
+            types: BTreeMap::from([
+                ("t_uint256".to_string(), ty("uint256", "32")),
+                ("t_address".to_string(), ty("address", "20")),
+                ("t_bool".to_string(), ty("bool", "1")),
+                ("t_int8".to_string(), ty("int8", "1")),
+            ]),
+        }
+    }
+
+    #[test]
+    fn can_decode_storage_changes() {
+        let layout = layout();
+        let change = |slot: u64, previous: U256, new: U256| StorageChange {
+            slot: B256::from(U256::from(slot)),
+            previous: B256::from(previous),
+            new: B256::from(new),
+            decoded: vec![],
+        };
+
+        let decoded = decode_slot(&layout, &change(0, U256::ZERO, U256::from(42)));
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].label, "number");
+        assert_eq!((decoded[0].previous.as_str(), decoded[0].new.as_str()), ("0", "42"));
+
+        // Only the packed variable which changed is decoded.
+        let owner = U256::from(0xbeef);
+        let decoded = decode_slot(&layout, &change(1, owner, owner | (U256::from(1) << 160)));
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].label, "paused");
+        assert_eq!((decoded[0].previous.as_str(), decoded[0].new.as_str()), ("false", "true"));
+
+        let decoded = decode_slot(&layout, &change(2, U256::from(1), U256::from(0xff)));
+        assert_eq!((decoded[0].previous.as_str(), decoded[0].new.as_str()), ("1", "-1"));
+
+        assert!(decode_slot(&layout, &change(3, U256::ZERO, U256::from(1))).is_empty());
+    }
+}
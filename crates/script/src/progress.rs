@@ -101,6 +101,14 @@ pub fn tx_sent(&mut self, tx_hash: B256) {
         self.txs.inc(1);
     }
 
+    /// Moves the pending transaction spinner to the transaction replacing it.
+    pub fn tx_replaced(&mut self, tx_hash: B256, replacement: B256) {
+        if let Some(spinner) = self.tx_spinners.remove(&tx_hash) {
+            spinner.set_message(format!("{} {}", "[Pending]".yellow(), replacement));
+            self.tx_spinners.insert(replacement, spinner);
+        }
+    }
+
     /// Removes the pending transaction spinner and advances confirmed transactions progress bar.
     pub fn finish_tx_spinner(&mut self, tx_hash: B256) {
         if let Some(spinner) = self.tx_spinners.remove(&tx_hash) {
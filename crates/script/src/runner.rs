@@ -1,5 +1,5 @@
 use super::ScriptResult;
-use crate::build::ScriptPredeployLibraries;
+use crate::{build::ScriptPredeployLibraries, plan::state_changes};
 use alloy_eips::eip7702::SignedAuthorization;
 use alloy_primitives::{Address, Bytes, TxKind, U256};
 use alloy_rpc_types::TransactionRequest;
@@ -20,11 +20,13 @@
 pub struct ScriptRunner {
     pub executor: Executor,
     pub evm_opts: EvmOpts,
+    /// Whether to record the state changes of the simulated transactions, for `--plan`.
+    pub record_state_changes: bool,
 }
 
 impl ScriptRunner {
     pub fn new(executor: Executor, evm_opts: EvmOpts) -> Self {
-        Self { executor, evm_opts }
+        Self { executor, evm_opts, record_state_changes: false }
     }
 
     /// Deploys the libraries and broadcast contract. Calls setUp method if requested.
@@ -246,13 +248,16 @@ pub fn simulate(
                 true,
             )
         } else if to.is_none() {
+            // Deployments are committed right away, so their changes are diffed against a copy
+            // of the previous state.
+            let previous = self.record_state_changes.then(|| self.executor.backend().clone());
             let res = self.executor.deploy(
                 from,
                 calldata.expect("No data for create transaction"),
                 value.unwrap_or(U256::ZERO),
                 None,
             );
-            let (address, RawCallResult { gas_used, logs, traces, .. }) = match res {
+            let (address, raw) = match res {
                 Ok(DeployResult { address, raw }) => (address, raw),
                 Err(EvmError::Execution(err)) => {
                     let ExecutionErr { raw, reason } = *err;
@@ -261,6 +266,11 @@ pub fn simulate(
                 }
                 Err(e) => eyre::bail!("Failed deploying contract: {e:?}"),
             };
+            let changes = match &previous {
+                Some(previous) => state_changes(previous, &raw.state_changeset)?,
+                None => vec![],
+            };
+            let RawCallResult { gas_used, logs, traces, .. } = raw;
 
             Ok(ScriptResult {
                 returned: Bytes::new(),
@@ -272,6 +282,7 @@ pub fn simulate(
                     .map(|traces| vec![(TraceKind::Execution, traces)])
                     .unwrap_or_default(),
                 address: Some(address),
+                state_changes: changes,
                 ..Default::default()
             })
         } else {
@@ -306,6 +317,7 @@ fn call(
             self.executor.call_raw(from, to, calldata.clone(), value)?
         };
         let mut gas_used = res.gas_used;
+        let mut changes = vec![];
 
         // We should only need to calculate realistic gas costs when preparing to broadcast
         // something. This happens during the onchain simulation stage, where we commit each
@@ -314,6 +326,10 @@ fn call(
         // Otherwise don't re-execute, or some usecases might be broken: https://github.com/foundry-rs/foundry/issues/3921
         if commit {
             gas_used = self.search_optimal_gas_usage(&res, from, to, &calldata, value)?;
+            // The changes of the call are diffed against the state before committing it.
+            if self.record_state_changes {
+                changes = state_changes(self.executor.backend(), &res.state_changeset)?;
+            }
             res = self.executor.transact_raw(from, to, calldata, value)?;
         }
 
@@ -336,6 +352,7 @@ fn call(
             transactions,
             address: None,
             breakpoints,
+            state_changes: changes,
         })
     }
 
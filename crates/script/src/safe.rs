@@ -0,0 +1,424 @@
+//! Batching of the transactions of a Safe multisig into a single Safe transaction, see
+//! [`ScriptArgs::safe`](crate::ScriptArgs::safe).
+
+use crate::broadcast::BundledState;
+use alloy_dyn_abi::TypedData;
+use alloy_primitives::{address, map::AddressHashMap, Address, Bytes, B256, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::TransactionRequest;
+use alloy_serde::WithOtherFields;
+use alloy_signer::Signer;
+use alloy_sol_types::{eip712_domain, sol, SolCall, SolStruct};
+use eyre::{Context, Result};
+use forge_script_sequence::{sig_to_file_name, ScriptSequence};
+use foundry_common::{
+    fs,
+    provider::{try_get_http_provider, RetryProvider},
+};
+use foundry_wallets::WalletSigner;
+use serde::Serialize;
+
+/// The canonical `MultiSendCallOnly` deployment of Safe v1.3.0.
+pub const MULTISEND_CALL_ONLY: Address = address!("40A2aCCbd92BCA938b02010E17A5b8929b49130D");
+
+/// The version of the Safe Transaction Builder batch files.
+const TX_BUILDER_VERSION: &str = "1.17.1";
+
+sol! {
+    interface ISafe {
+        function nonce() external view returns (uint256);
+        function getOwners() external view returns (address[] memory);
+        function getThreshold() external view returns (uint256);
+        function execTransaction(
+            address to,
+            uint256 value,
+            bytes calldata data,
+            uint8 operation,
+            uint256 safeTxGas,
+            uint256 baseGas,
+            uint256 gasPrice,
+            address gasToken,
+            address refundReceiver,
+            bytes memory signatures
+        ) external payable returns (bool success);
+    }
+
+    interface IMultiSend {
+        function multiSend(bytes memory transactions) external payable;
+    }
+
+    #[derive(Debug, Serialize)]
+    struct SafeTx {
+        address to;
+        uint256 value;
+        bytes data;
+        uint8 operation;
+        uint256 safeTxGas;
+        uint256 baseGas;
+        uint256 gasPrice;
+        address gasToken;
+        address refundReceiver;
+        uint256 nonce;
+    }
+}
+
+/// The operation of a Safe transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+enum Operation {
+    Call = 0,
+    DelegateCall = 1,
+}
+
+/// A call made by a Safe.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct SafeCall {
+    to: Address,
+    value: U256,
+    data: Bytes,
+}
+
+/// Encodes calls as the packed `transactions` argument of `multiSend`.
+fn encode_multisend(calls: &[SafeCall]) -> Bytes {
+    let mut encoded = Vec::new();
+    for call in calls {
+        encoded.push(Operation::Call as u8);
+        encoded.extend_from_slice(call.to.as_slice());
+        encoded.extend_from_slice(&call.value.to_be_bytes::<32>());
+        encoded.extend_from_slice(&U256::from(call.data.len()).to_be_bytes::<32>());
+        encoded.extend_from_slice(&call.data);
+    }
+    encoded.into()
+}
+
+/// Returns the Safe transaction making the calls, directly for a single call or through
+/// `multisend` otherwise.
+fn safe_tx(calls: &[SafeCall], multisend: Address, nonce: U256) -> SafeTx {
+    let (to, value, data, operation) = match calls {
+        [call] => (call.to, call.value, call.data.clone(), Operation::Call),
+        _ => (
+            multisend,
+            U256::ZERO,
+            IMultiSend::multiSendCall { transactions: encode_multisend(calls) }.abi_encode().into(),
+            Operation::DelegateCall,
+        ),
+    };
+    SafeTx {
+        to,
+        value,
+        data,
+        operation: operation as u8,
+        safeTxGas: U256::ZERO,
+        baseGas: U256::ZERO,
+        gasPrice: U256::ZERO,
+        gasToken: Address::ZERO,
+        refundReceiver: Address::ZERO,
+        nonce,
+    }
+}
+
+/// A batch file of the Safe Transaction Builder, which can be imported in the Safe web app.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TransactionBuilderBatch {
+    version: &'static str,
+    chain_id: String,
+    created_at: u128,
+    meta: TransactionBuilderMeta,
+    transactions: Vec<TransactionBuilderTransaction>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TransactionBuilderMeta {
+    name: String,
+    description: String,
+    tx_builder_version: &'static str,
+    created_from_safe_address: Address,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TransactionBuilderTransaction {
+    to: Address,
+    value: String,
+    data: Bytes,
+}
+
+/// A Safe transaction with its hash and the signatures collected for it.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SafeTransactionOutput {
+    safe: Address,
+    chain_id: u64,
+    to: Address,
+    value: U256,
+    data: Bytes,
+    operation: u8,
+    safe_tx_gas: U256,
+    base_gas: U256,
+    gas_price: U256,
+    gas_token: Address,
+    refund_receiver: Address,
+    nonce: U256,
+    safe_tx_hash: B256,
+    threshold: U256,
+    /// The signatures by owner, ordered by owner address.
+    signatures: Vec<SafeSignature>,
+    /// The `execTransaction` calldata, once enough owners signed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exec_transaction: Option<Bytes>,
+}
+
+#[derive(Debug, Serialize)]
+struct SafeSignature {
+    owner: Address,
+    signature: Bytes,
+}
+
+impl BundledState {
+    /// Batches the transactions of the Safe into a Safe transaction per chain instead of
+    /// broadcasting them, and signs it with the owners among the wallets.
+    pub async fn safe(self, safe: Address) -> Result<()> {
+        let Self { args, script_config, script_wallets, build_data, sequence: sequences } = self;
+        let multisend = args.safe_multisend.unwrap_or(MULTISEND_CALL_ONLY);
+        let mut wallet = script_wallets.into_multi_wallet();
+
+        for sequence in sequences.sequences() {
+            let calls = safe_calls(sequence, safe)?;
+            if calls.is_empty() {
+                continue
+            }
+
+            let provider = try_get_http_provider(sequence.rpc_url())?;
+            let nonce = match args.safe_nonce {
+                Some(nonce) => U256::from(nonce),
+                None => call(&provider, safe, ISafe::nonceCall {}).await?._0,
+            };
+            let tx = safe_tx(&calls, multisend, nonce);
+            let domain = eip712_domain! {
+                chain_id: sequence.chain,
+                verifying_contract: safe,
+            };
+            let safe_tx_hash = tx.eip712_signing_hash(&domain);
+
+            let owners = call(&provider, safe, ISafe::getOwnersCall {}).await?._0;
+            let threshold = call(&provider, safe, ISafe::getThresholdCall {}).await?._0;
+            for owner in &owners {
+                wallet.ensure_chain_allowed(*owner, sequence.chain)?;
+            }
+            let signatures = sign(&tx, &domain, wallet.signers()?, &owners).await?;
+            let exec_transaction = (U256::from(signatures.len()) >= threshold).then(|| {
+                let signatures = signatures
+                    .iter()
+                    .flat_map(|signature| signature.signature.iter().copied())
+                    .collect::<Vec<_>>();
+                Bytes::from(
+                    ISafe::execTransactionCall {
+                        to: tx.to,
+                        value: tx.value,
+                        data: tx.data.clone(),
+                        operation: tx.operation,
+                        safeTxGas: tx.safeTxGas,
+                        baseGas: tx.baseGas,
+                        gasPrice: tx.gasPrice,
+                        gasToken: tx.gasToken,
+                        refundReceiver: tx.refundReceiver,
+                        signatures: signatures.into(),
+                    }
+                    .abi_encode(),
+                )
+            });
+
+            let (path, _) = ScriptSequence::get_paths(
+                &script_config.config,
+                &args.sig,
+                &build_data.build_data.target,
+                sequence.chain,
+                true,
+            )?;
+            let name = sig_to_file_name(&args.sig);
+            let batch_path = path.with_file_name(format!("{name}-safe-batch-latest.json"));
+            let tx_path = path.with_file_name(format!("{name}-safe-tx-latest.json"));
+            let batch = TransactionBuilderBatch {
+                version: "1.0",
+                chain_id: sequence.chain.to_string(),
+                created_at: forge_script_sequence::now().as_millis(),
+                meta: TransactionBuilderMeta {
+                    name: format!("{} ({})", build_data.build_data.target.name, args.sig),
+                    description: String::new(),
+                    tx_builder_version: TX_BUILDER_VERSION,
+                    created_from_safe_address: safe,
+                },
+                transactions: calls
+                    .iter()
+                    .map(|call| TransactionBuilderTransaction {
+                        to: call.to,
+                        value: call.value.to_string(),
+                        data: call.data.clone(),
+                    })
+                    .collect(),
+            };
+            fs::write_json_file(&batch_path, &batch)?;
+
+            let num_signatures = signatures.len();
+            let output = SafeTransactionOutput {
+                safe,
+                chain_id: sequence.chain,
+                to: tx.to,
+                value: tx.value,
+                data: tx.data,
+                operation: tx.operation,
+                safe_tx_gas: tx.safeTxGas,
+                base_gas: tx.baseGas,
+                gas_price: tx.gasPrice,
+                gas_token: tx.gasToken,
+                refund_receiver: tx.refundReceiver,
+                nonce: tx.nonce,
+                safe_tx_hash,
+                threshold,
+                signatures,
+                exec_transaction,
+            };
+            fs::write_json_file(&tx_path, &output)?;
+
+            sh_println!(
+                "\nBatched {} transaction(s) of Safe {safe} on chain {} with nonce {nonce}",
+                calls.len(),
+                sequence.chain
+            )?;
+            sh_println!("Safe transaction hash: {safe_tx_hash}")?;
+            sh_println!("Signatures: {num_signatures}/{threshold}")?;
+            if output.exec_transaction.is_some() {
+                sh_println!(
+                    "The transaction can be executed with the `execTransaction` calldata."
+                )?;
+            }
+            sh_println!("Transaction Builder batch saved to: {}", batch_path.display())?;
+            sh_println!("Safe transaction saved to: {}", tx_path.display())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the calls made by the Safe in a sequence.
+///
+/// Fails if the sequence has other transactions, since they would be reordered.
+fn safe_calls(sequence: &ScriptSequence, safe: Address) -> Result<Vec<SafeCall>> {
+    sequence
+        .transactions()
+        .map(|tx| {
+            let Some(from) = tx.from() else {
+                eyre::bail!(
+                    "Transactions without a sender can't be batched into a Safe transaction"
+                )
+            };
+            if from != safe || !tx.is_unsigned() {
+                eyre::bail!(
+                    "Transactions from {from} can't be batched into a transaction of Safe {safe}"
+                )
+            }
+            let Some(to) = tx.to().and_then(|kind| kind.to().copied()) else {
+                eyre::bail!(
+                    "Safe {safe} can't deploy contracts directly, deploy them through a CREATE2 \
+                     factory instead"
+                )
+            };
+            Ok(SafeCall {
+                to,
+                value: tx.value().unwrap_or_default(),
+                data: tx.input().map(Bytes::copy_from_slice).unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Signs a Safe transaction with the signers owning the Safe, ordered by owner address as
+/// expected by `execTransaction`.
+///
+/// The transaction is signed as dynamic typed data, so that remote signers receive the full
+/// `eth_signTypedData_v4` payload instead of a bare hash.
+async fn sign(
+    tx: &SafeTx,
+    domain: &alloy_sol_types::Eip712Domain,
+    signers: &AddressHashMap<WalletSigner>,
+    owners: &[Address],
+) -> Result<Vec<SafeSignature>> {
+    let typed_data = TypedData::from_struct(tx, Some(domain.clone()));
+    let mut signatures = Vec::new();
+    for owner in owners {
+        let Some(signer) = signers.get(owner) else { continue };
+        let signature = signer
+            .sign_dynamic_typed_data(&typed_data)
+            .await
+            .wrap_err_with(|| format!("failed to sign the Safe transaction with {owner}"))?;
+        signatures.push(SafeSignature { owner: *owner, signature: signature.as_bytes().into() });
+    }
+    signatures.sort_by_key(|signature| signature.owner);
+    Ok(signatures)
+}
+
+/// Calls a view function of a contract.
+async fn call<C: SolCall>(provider: &RetryProvider, to: Address, call: C) -> Result<C::Return> {
+    let tx = TransactionRequest::default().to(to).input(Bytes::from(call.abi_encode()).into());
+    let output = provider.call(&WithOtherFields::new(tx)).await?;
+    C::abi_decode_returns(&output, true)
+        .wrap_err_with(|| format!("failed to decode the result of `{}` on {to}", C::SIGNATURE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::hex;
+
+    #[test]
+    fn can_batch_safe_calls() {
+        let target = address!("000000000000000000000000000000000000bEEF");
+        let calls = [
+            SafeCall { to: target, value: U256::from(1), data: Bytes::from_static(&[0xab, 0xcd]) },
+            SafeCall { to: target, value: U256::ZERO, data: Bytes::new() },
+        ];
+
+        let tx = safe_tx(&calls[..1], MULTISEND_CALL_ONLY, U256::from(7));
+        assert_eq!(tx.to, target);
+        assert_eq!(tx.value, U256::from(1));
+        assert_eq!(tx.data, calls[0].data);
+        assert_eq!(tx.operation, Operation::Call as u8);
+        assert_eq!(tx.nonce, U256::from(7));
+
+        let encoded = encode_multisend(&calls);
+        assert_eq!(encoded.len(), 2 * (1 + 20 + 32 + 32) + 2);
+        assert_eq!(
+            hex::encode(&encoded[..1 + 20 + 32 + 32 + 2]),
+            format!("00{}{:064x}{:064x}abcd", hex::encode(target), 1, 2)
+        );
+
+        let tx = safe_tx(&calls, MULTISEND_CALL_ONLY, U256::ZERO);
+        assert_eq!(tx.to, MULTISEND_CALL_ONLY);
+        assert_eq!(tx.value, U256::ZERO);
+        assert_eq!(tx.operation, Operation::DelegateCall as u8);
+        assert_eq!(
+            IMultiSend::multiSendCall::abi_decode(&tx.data, true).unwrap().transactions,
+            encoded
+        );
+    }
+
+    #[test]
+    fn can_hash_safe_tx_as_typed_data() {
+        let calls = [SafeCall {
+            to: address!("000000000000000000000000000000000000bEEF"),
+            value: U256::from(1),
+            data: Bytes::from_static(&[0xab, 0xcd]),
+        }];
+        let tx = safe_tx(&calls, MULTISEND_CALL_ONLY, U256::from(7));
+        let domain = eip712_domain! {
+            chain_id: 1,
+            verifying_contract: address!("000000000000000000000000000000000000cafE"),
+        };
+
+        let typed_data = TypedData::from_struct(&tx, Some(domain.clone()));
+        assert_eq!(typed_data.primary_type, "SafeTx");
+        assert_eq!(typed_data.eip712_signing_hash().unwrap(), tx.eip712_signing_hash(&domain));
+    }
+}
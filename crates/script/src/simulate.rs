@@ -6,6 +6,7 @@
     broadcast::{estimate_gas, BundledState},
     build::LinkedBuildData,
     execute::{ExecutionArtifacts, ExecutionData},
+    plan::PlanBuilder,
     sequence::get_commit_hash,
     ScriptArgs, ScriptConfig, ScriptResult,
 };
@@ -112,6 +113,7 @@ pub async fn simulate_and_fill(
         );
 
         let mut final_txs = VecDeque::new();
+        let show_plan = self.args.plan || self.args.plan_out.is_some();
 
         // Executes all transactions from the different forks concurrently.
         let futs = transactions
@@ -121,7 +123,7 @@ pub async fn simulate_and_fill(
                 let tx = transaction.tx_mut();
 
                 let to = if let Some(TxKind::Call(to)) = tx.to() { Some(to) } else { None };
-                let result = runner
+                let mut result = runner
                     .simulate(
                         tx.from()
                             .expect("transaction doesn't have a `from` address at execution time"),
@@ -131,9 +133,10 @@ pub async fn simulate_and_fill(
                         tx.authorization_list(),
                     )
                     .wrap_err("Internal EVM error during simulation")?;
+                let state_changes = std::mem::take(&mut result.state_changes);
 
                 if !result.success {
-                    return Ok((None, false, result.traces));
+                    return Ok((None, false, result.traces, vec![], state_changes));
                 }
 
                 // Simulate mining the transaction if the user passes `--slow`.
@@ -151,7 +154,7 @@ pub async fn simulate_and_fill(
                     .with_execution_result(&result, self.args.gas_estimate_multiplier)
                     .build();
 
-                eyre::Ok((Some(transaction), is_noop_tx, result.traces))
+                eyre::Ok((Some(transaction), is_noop_tx, result.traces, result.logs, state_changes))
             })
             .collect::<Vec<_>>();
 
@@ -160,9 +163,11 @@ pub async fn simulate_and_fill(
             sh_println!("Simulated On-chain Traces:\n")?;
         }
 
+        let mut plan =
+            PlanBuilder::new(&self.execution_artifacts.decoder, &self.build_data.build_data.output);
         let mut abort = false;
         for res in join_all(futs).await {
-            let (tx, is_noop_tx, mut traces) = res?;
+            let (tx, is_noop_tx, mut traces, logs, state_changes) = res?;
 
             // Transaction will be `None`, if execution didn't pass.
             if tx.is_none() || self.script_config.evm_opts.verbosity > 3 {
@@ -190,6 +195,9 @@ pub async fn simulate_and_fill(
                     }
                 }
 
+                if show_plan {
+                    plan.add(&tx, &logs, state_changes).await;
+                }
                 final_txs.push_back(tx);
             } else {
                 abort = true;
@@ -200,6 +208,22 @@ pub async fn simulate_and_fill(
             eyre::bail!("Simulated execution failed.")
         }
 
+        if show_plan {
+            plan.build().save(&self.args)?;
+
+            // Only prompt if we're broadcasting and we've not disabled interactivity.
+            if self.args.plan &&
+                self.args.should_broadcast() &&
+                !self.args.non_interactive &&
+                !shell::is_json() &&
+                !Confirm::new()
+                    .with_prompt("Do you wish to broadcast these transactions?".to_string())
+                    .interact()?
+            {
+                eyre::bail!("User canceled the script.");
+            }
+        }
+
         Ok(final_txs)
     }
 
@@ -233,7 +257,8 @@ async fn build_runners(&self) -> Result<Vec<(String, ScriptRunner)>> {
         let futs = rpcs.into_iter().map(|rpc| async move {
             let mut script_config = self.script_config.clone();
             script_config.evm_opts.fork_url = Some(rpc.clone());
-            let runner = script_config.get_runner().await?;
+            let mut runner = script_config.get_runner().await?;
+            runner.record_state_changes = self.args.plan || self.args.plan_out.is_some();
             Ok((rpc.clone(), runner))
         });
         try_join_all(futs).await
@@ -388,13 +413,15 @@ pub async fn bundle(self) -> Result<BundledState> {
         let sequence = if sequences.len() == 1 {
             ScriptSequenceKind::Single(sequences.pop().expect("empty sequences"))
         } else {
-            ScriptSequenceKind::Multi(MultiChainSequence::new(
+            let mut multi = MultiChainSequence::new(
                 sequences,
                 &self.args.sig,
                 &self.build_data.build_data.target,
                 &self.script_config.config,
                 !self.args.broadcast,
-            )?)
+            )?;
+            multi.configure_stages(&self.args.chain_dependency, self.args.stage_confirmations);
+            ScriptSequenceKind::Multi(multi)
         };
 
         Ok(BundledState {
@@ -440,9 +467,25 @@ fn create_sequence(
             })
             .collect();
 
+        // Only keep the labels of the created contracts, which name their deployments.
+        let labels = transactions
+            .iter()
+            .flat_map(|tx| {
+                tx.contract_address
+                    .filter(|_| tx.opcode.is_any_create())
+                    .into_iter()
+                    .chain(tx.additional_contracts.iter().map(|contract| contract.address))
+            })
+            .filter_map(|address| {
+                let label = self.execution_artifacts.decoder.labels.get(&address)?;
+                Some((address, label.clone()))
+            })
+            .collect();
+
         let sequence = ScriptSequence {
             transactions,
             returns: self.execution_artifacts.returns.clone(),
+            labels,
             receipts: vec![],
             pending: vec![],
             paths,
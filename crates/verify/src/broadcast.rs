@@ -0,0 +1,314 @@
+//! Verification of the contracts deployed by a script broadcast.
+
+use crate::{RetryArgs, VerifierArgs, VerifyArgs};
+use alloy_primitives::{hex, Address};
+use eyre::{Context, Result};
+use forge_script_sequence::{AdditionalContract, ScriptSequence};
+use foundry_cli::opts::{EtherscanOpts, ProjectPathOpts};
+use foundry_common::{compile::ProjectCompiler, fs, ContractsByArtifact};
+use foundry_compilers::{artifacts::Libraries, info::ContractInfo, Project};
+use foundry_config::{Chain, Config};
+use foundry_linking::Linker;
+use futures::{stream, StreamExt};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Data struct to help `ScriptSequence` verify contracts on `etherscan`.
+#[derive(Clone)]
+pub struct VerifyBundle {
+    pub num_of_optimizations: Option<usize>,
+    pub known_contracts: ContractsByArtifact,
+    pub project_paths: ProjectPathOpts,
+    pub etherscan: EtherscanOpts,
+    pub retry: RetryArgs,
+    pub verifier: VerifierArgs,
+    pub via_ir: bool,
+}
+
+impl VerifyBundle {
+    pub fn new(
+        project: &Project,
+        config: &Config,
+        known_contracts: ContractsByArtifact,
+        retry: RetryArgs,
+        verifier: VerifierArgs,
+    ) -> Self {
+        let num_of_optimizations =
+            if config.optimizer == Some(true) { config.optimizer_runs } else { None };
+
+        let config_path = config.get_config_path();
+
+        let project_paths = ProjectPathOpts {
+            root: Some(project.paths.root.clone()),
+            contracts: Some(project.paths.sources.clone()),
+            remappings: project.paths.remappings.clone(),
+            remappings_env: None,
+            cache_path: Some(project.paths.cache.clone()),
+            lib_paths: project.paths.libraries.clone(),
+            hardhat: config.profile == Config::HARDHAT_PROFILE,
+            config_path: if config_path.exists() { Some(config_path) } else { None },
+        };
+
+        let via_ir = config.via_ir;
+
+        Self {
+            num_of_optimizations,
+            known_contracts,
+            etherscan: Default::default(),
+            project_paths,
+            retry,
+            verifier,
+            via_ir,
+        }
+    }
+
+    /// Configures the chain and sets the etherscan key, if available
+    pub fn set_chain(&mut self, config: &Config, chain: Chain) {
+        // If dealing with multiple chains, we need to be able to change in between the config
+        // chain_id.
+        self.etherscan.key = config.get_etherscan_api_key(Some(chain));
+        self.etherscan.chain = Some(chain);
+    }
+
+    /// Given a `VerifyBundle` and contract details, it tries to generate a valid `VerifyArgs` to
+    /// use against the `contract_address`.
+    pub fn get_verify_args(
+        &self,
+        contract_address: Address,
+        create2_offset: usize,
+        data: &[u8],
+        libraries: &[String],
+    ) -> Option<VerifyArgs> {
+        for (artifact, contract) in self.known_contracts.iter() {
+            let Some(bytecode) = contract.bytecode() else { continue };
+            // If it's a CREATE2, the tx.data comes with a 32-byte salt in the beginning
+            // of the transaction
+            if data.split_at(create2_offset).1.starts_with(bytecode) {
+                let constructor_args = data.split_at(create2_offset + bytecode.len()).1.to_vec();
+
+                if artifact.source.extension().is_some_and(|e| e.to_str() == Some("vy")) {
+                    warn!("Skipping verification of Vyper contract: {}", artifact.name);
+                }
+
+                // Strip artifact profile from contract name when creating contract info.
+                let contract = ContractInfo {
+                    path: Some(artifact.source.to_string_lossy().to_string()),
+                    name: artifact
+                        .name
+                        .strip_suffix(&format!(".{}", &artifact.profile))
+                        .unwrap_or_else(|| &artifact.name)
+                        .to_string(),
+                };
+
+                // We strip the build metadadata information, since it can lead to
+                // etherscan not identifying it correctly. eg:
+                // `v0.8.10+commit.fc410830.Linux.gcc` != `v0.8.10+commit.fc410830`
+                let version = Version::new(
+                    artifact.version.major,
+                    artifact.version.minor,
+                    artifact.version.patch,
+                );
+
+                let verify = VerifyArgs {
+                    address: Some(contract_address),
+                    contract: Some(contract),
+                    compiler_version: Some(version.to_string()),
+                    constructor_args: Some(hex::encode(constructor_args)),
+                    constructor_args_path: None,
+                    num_of_optimizations: self.num_of_optimizations,
+                    etherscan: self.etherscan.clone(),
+                    rpc: Default::default(),
+                    flatten: false,
+                    force: false,
+                    skip_is_verified_check: true,
+                    watch: true,
+                    retry: self.retry,
+                    libraries: libraries.to_vec(),
+                    root: None,
+                    verifier: self.verifier.clone(),
+                    via_ir: self.via_ir,
+                    evm_version: None,
+                    show_standard_json_input: false,
+                    guess_constructor_args: false,
+                    compilation_profile: Some(artifact.profile.to_string()),
+                    broadcast: None,
+                    jobs: 1,
+                    proxy: false,
+                };
+
+                return Some(verify)
+            }
+        }
+        None
+    }
+
+    /// Matches the contracts created by the transactions of a broadcast sequence, including the
+    /// ones created during their execution, with the known contracts.
+    ///
+    /// Returns the verification arguments of the matched contracts and the addresses of the
+    /// unmatched ones.
+    pub fn get_sequence_verify_args(
+        &self,
+        sequence: &mut ScriptSequence,
+    ) -> (Vec<VerifyArgs>, Vec<Address>) {
+        let mut verifications = Vec::with_capacity(sequence.receipts.len());
+        let mut unverifiable_contracts = vec![];
+
+        // Make sure the receipts have the right order first.
+        sequence.sort_receipts();
+
+        for (receipt, tx) in sequence.receipts.iter_mut().zip(sequence.transactions.iter()) {
+            // create2 hash offset
+            let mut offset = 0;
+
+            if tx.is_create2() {
+                receipt.contract_address = tx.contract_address;
+                offset = 32;
+            }
+
+            // Verify contract created directly from the transaction
+            if let (Some(address), Some(data)) = (receipt.contract_address, tx.tx().input()) {
+                match self.get_verify_args(address, offset, data, &sequence.libraries) {
+                    Some(verify) => verifications.push(verify),
+                    None => unverifiable_contracts.push(address),
+                };
+            }
+
+            // Verify potential contracts created during the transaction execution
+            for AdditionalContract { address, init_code, .. } in &tx.additional_contracts {
+                match self.get_verify_args(*address, 0, init_code.as_ref(), &sequence.libraries) {
+                    Some(verify) => verifications.push(verify),
+                    None => unverifiable_contracts.push(*address),
+                };
+            }
+        }
+
+        (verifications, unverifiable_contracts)
+    }
+}
+
+/// The addresses of the contracts of a broadcast that were successfully verified.
+///
+/// Saved after each verification so that an interrupted or partially failed batch can be resumed.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BatchState {
+    verified: Vec<Address>,
+}
+
+impl BatchState {
+    /// Returns the path of the state of a broadcast sequence.
+    fn path(config: &Config, sequence: &ScriptSequence) -> PathBuf {
+        config
+            .cache_path
+            .join("verify")
+            .join(sequence.chain.to_string())
+            .join(format!("{}.json", sequence.timestamp))
+    }
+
+    fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default())
+        }
+        fs::read_json_file(path).wrap_err("failed to read the verification state")
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write_json_file(path, self)?;
+        Ok(())
+    }
+}
+
+impl VerifyArgs {
+    /// Verifies all the contracts deployed by the broadcast at `path`, `self.jobs` at a time.
+    ///
+    /// Successfully verified contracts are skipped when running again for the same broadcast.
+    pub(crate) async fn run_broadcast(self, path: &Path, config: Config) -> Result<()> {
+        let mut sequence: ScriptSequence = fs::read_json_file(path)
+            .wrap_err_with(|| format!("failed to read broadcast `{}`", path.display()))?;
+
+        let project = config.project()?;
+        let output = ProjectCompiler::new().compile(&project)?;
+        let libraries = Libraries::parse(&sequence.libraries)?;
+        let linker = Linker::new(project.root(), output.artifact_ids().collect());
+        let known_contracts = ContractsByArtifact::new(linker.get_linked_artifacts(&libraries)?);
+
+        let mut bundle =
+            VerifyBundle::new(&project, &config, known_contracts, self.retry, self.verifier);
+        bundle.set_chain(&config, sequence.chain.into());
+
+        let (verifications, unverifiable_contracts) =
+            bundle.get_sequence_verify_args(&mut sequence);
+        if !unverifiable_contracts.is_empty() {
+            sh_warn!(
+                "We haven't found any matching bytecode for the following contracts: {:?}.\n\n\
+                 This may occur when the underlying source code or compiler version has changed \
+                 since the broadcast.",
+                unverifiable_contracts
+            )?;
+        }
+
+        let state_path = BatchState::path(&config, &sequence);
+        let mut state = BatchState::load(&state_path)?;
+        let (skipped, verifications): (Vec<_>, Vec<_>) = verifications
+            .into_iter()
+            .partition(|verify| state.verified.contains(&verify.address()));
+        if !skipped.is_empty() {
+            sh_println!("Skipping ({}) contracts verified by a previous run", skipped.len())?;
+        }
+
+        let num_verifications = verifications.len();
+        sh_println!("##\nStart verification for ({num_verifications}) contracts")?;
+        let skip_is_verified_check = self.skip_is_verified_check;
+        let mut results = stream::iter(verifications.into_iter().map(|mut verify| {
+            verify.skip_is_verified_check = skip_is_verified_check;
+            async move {
+                let address = verify.address();
+                // Boxed since `run` calls this function.
+                (address, Box::pin(verify.run()).await)
+            }
+        }))
+        .buffer_unordered(self.jobs.max(1));
+
+        let mut num_of_successful_verifications = 0;
+        while let Some((address, result)) = results.next().await {
+            match result {
+                Ok(()) => {
+                    num_of_successful_verifications += 1;
+                    state.verified.push(address);
+                    state.save(&state_path)?;
+                }
+                Err(err) => sh_err!("Failed to verify contract {address}: {err:#}")?,
+            }
+        }
+
+        if num_of_successful_verifications < num_verifications {
+            eyre::bail!(
+                "Not all ({num_of_successful_verifications} / {num_verifications}) contracts were \
+                 verified! Run the command again to retry the failed verifications."
+            )
+        }
+
+        sh_println!("All ({num_verifications}) contracts were verified!")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+
+    #[test]
+    fn batch_state_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("verify").join("1").join("1700000000.json");
+        assert!(BatchState::load(&path).unwrap().verified.is_empty());
+
+        let state =
+            BatchState { verified: vec![address!("5FbDB2315678afecb367f032d93F642f64180aa3")] };
+        state.save(&path).unwrap();
+        assert_eq!(BatchState::load(&path).unwrap().verified, state.verified);
+    }
+}
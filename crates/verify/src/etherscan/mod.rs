@@ -4,7 +4,7 @@
     verify::{VerifyArgs, VerifyCheckArgs},
 };
 use alloy_json_abi::Function;
-use alloy_primitives::hex;
+use alloy_primitives::{hex, Address};
 use alloy_provider::Provider;
 use alloy_rpc_types::TransactionTrait;
 use eyre::{eyre, Context, OptionExt, Result};
@@ -21,6 +21,7 @@
 use foundry_evm::constants::DEFAULT_CREATE2_DEPLOYER;
 use regex::Regex;
 use semver::{BuildMetadata, Version};
+use serde::Deserialize;
 use std::{fmt::Debug, sync::LazyLock};
 
 mod flatten;
@@ -126,7 +127,7 @@ async fn verify(&mut self, args: VerifyArgs, context: VerificationContext) -> Re
                 "Submitted contract for verification:\n\tResponse: `{}`\n\tGUID: `{}`\n\tURL: {}",
                 resp.message,
                 resp.result,
-                etherscan.address_url(args.address)
+                etherscan.address_url(args.address())
             )?;
 
             if args.watch {
@@ -199,6 +200,14 @@ async fn check(&self, args: VerifyCheckArgs) -> Result<()> {
     }
 }
 
+/// The response of the proxy verification endpoints, whose result is either the submission GUID
+/// or the status message.
+#[derive(Debug, Deserialize)]
+struct ProxyVerificationResponse {
+    status: String,
+    result: String,
+}
+
 impl EtherscanVerificationProvider {
     /// Create a source provider
     fn source_provider(&self, args: &VerifyArgs) -> Box<dyn EtherscanSourceProvider> {
@@ -245,6 +254,92 @@ async fn is_contract_verified(
         Ok(true)
     }
 
+    /// Links a proxy to its verified implementation, i.e. the "Is this a proxy?" flow of
+    /// Etherscan, and waits for the result.
+    pub(crate) async fn verify_proxy(
+        &self,
+        args: &VerifyArgs,
+        proxy: Address,
+        implementation: Address,
+    ) -> Result<()> {
+        let config = args.load_config()?;
+        let etherscan = self.client(
+            args.etherscan.chain.unwrap_or_default(),
+            args.verifier.verifier_url.as_deref(),
+            args.etherscan.key().as_deref(),
+            &config,
+        )?;
+        let api_url = etherscan.etherscan_api_url().clone();
+        let api_key = args.etherscan.key().unwrap_or_default();
+        let (proxy_str, implementation_str) = (proxy.to_string(), implementation.to_string());
+        let client = reqwest::Client::new();
+
+        sh_println!("\nSubmitting proxy {proxy} with implementation {implementation}.")?;
+        let resp: ProxyVerificationResponse = args
+            .retry
+            .into_retry()
+            .run_async(|| async {
+                client
+                    .post(api_url.clone())
+                    .form(&[
+                        ("apikey", api_key.as_str()),
+                        ("module", "contract"),
+                        ("action", "verifyproxycontract"),
+                        ("address", proxy_str.as_str()),
+                        ("expectedimplementation", implementation_str.as_str()),
+                    ])
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await
+                    .wrap_err("Failed to submit proxy verification")
+            })
+            .await?;
+        trace!(?resp, "Received proxy verification response");
+        if resp.status == "0" {
+            eyre::bail!("Failed to submit proxy verification: {}", resp.result)
+        }
+
+        let guid = resp.result;
+        args.retry
+            .into_retry()
+            .run_async_until_break(|| async {
+                let resp: ProxyVerificationResponse = client
+                    .get(api_url.clone())
+                    .query(&[
+                        ("apikey", api_key.as_str()),
+                        ("module", "contract"),
+                        ("action", "checkproxyverification"),
+                        ("guid", guid.as_str()),
+                    ])
+                    .send()
+                    .await
+                    .and_then(|resp| resp.error_for_status())
+                    .map_err(|err| RetryError::Retry(err.into()))?
+                    .json()
+                    .await
+                    .wrap_err("Failed to request proxy verification status")
+                    .map_err(RetryError::Retry)?;
+
+                trace!(?resp, "Received proxy verification status");
+
+                if resp.result == "Pending in queue" {
+                    return Err(RetryError::Retry(eyre!("Proxy verification is still pending...")))
+                }
+                if resp.status == "0" {
+                    return Err(RetryError::Break(eyre!("Proxy failed to verify: {}", resp.result)))
+                }
+
+                let _ = sh_println!("{}", resp.result);
+                let _ =
+                    sh_println!("Proxy successfully verified: {}", etherscan.address_url(proxy));
+                Ok(())
+            })
+            .await
+            .wrap_err("Checking proxy verification result failed")
+    }
+
     /// Create an Etherscan client.
     pub(crate) fn client(
         &self,
@@ -309,7 +404,7 @@ pub async fn create_verify_request(
             format!("v{}", ensure_solc_build_metadata(context.compiler_version.clone()).await?);
         let constructor_args = self.constructor_args(args, context).await?;
         let mut verify_args =
-            VerifyContract::new(args.address, contract_name, source, compiler_version)
+            VerifyContract::new(args.address(), contract_name, source, compiler_version)
                 .constructor_arguments(constructor_args)
                 .code_format(code_format);
 
@@ -387,7 +482,7 @@ async fn guess_constructor_args(
             &context.config,
         )?;
 
-        let creation_data = client.contract_creation_data(args.address).await?;
+        let creation_data = client.contract_creation_data(args.address()).await?;
         let transaction = provider
             .get_transaction_by_hash(creation_data.transaction_hash)
             .await?
@@ -397,7 +492,7 @@ async fn guess_constructor_args(
             .await?
             .ok_or_eyre("Couldn't fetch transaction receipt from RPC")?;
 
-        let maybe_creation_code = if receipt.contract_address == Some(args.address) {
+        let maybe_creation_code = if receipt.contract_address == Some(args.address()) {
             transaction.inner.inner.input()
         } else if transaction.to() == Some(DEFAULT_CREATE2_DEPLOYER) {
             &transaction.inner.inner.input()[32..]
@@ -9,10 +9,15 @@
 #[macro_use]
 extern crate tracing;
 
+mod broadcast;
+pub use broadcast::VerifyBundle;
+
 mod etherscan;
 
 pub mod provider;
 
+pub mod proxy;
+
 pub mod bytecode;
 pub use bytecode::VerifyBytecodeArgs;
 
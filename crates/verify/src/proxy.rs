@@ -0,0 +1,240 @@
+//! Verification of proxy contracts, see [`VerifyArgs::proxy`].
+
+use crate::{
+    etherscan::EtherscanVerificationProvider, provider::VerificationProviderType, VerifyArgs,
+};
+use alloy_primitives::{b256, hex, Address, Bytes, B256};
+use alloy_provider::Provider;
+use alloy_rpc_types::TransactionRequest;
+use alloy_serde::WithOtherFields;
+use eyre::{Context, Result};
+use foundry_cli::utils;
+use foundry_common::provider::RetryProvider;
+use foundry_config::Config;
+use std::fmt;
+
+/// `bytes32(uint256(keccak256('eip1967.proxy.implementation')) - 1)`
+const IMPLEMENTATION_SLOT: B256 =
+    b256!("360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+
+/// `bytes32(uint256(keccak256('eip1967.proxy.admin')) - 1)`
+const ADMIN_SLOT: B256 = b256!("b53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103");
+
+/// `bytes32(uint256(keccak256('eip1967.proxy.beacon')) - 1)`
+const BEACON_SLOT: B256 = b256!("a3f0ad74e5423aebfd80d3ef4346578335a9a72aeaee59ff6cb3582b35133d50");
+
+/// The selector of `implementation()`, implemented by beacons.
+const IMPLEMENTATION_SELECTOR: [u8; 4] = hex!("5c60da1b");
+
+/// The runtime code of an EIP-1167 minimal proxy, around the implementation address.
+const MINIMAL_PROXY_PREFIX: [u8; 10] = hex!("363d3d373d3d3d363d73");
+const MINIMAL_PROXY_SUFFIX: [u8; 15] = hex!("5af43d82803e903d91602b57fd5bf3");
+
+/// The kind of a proxy contract.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProxyKind {
+    /// EIP-1967 proxy with an admin, e.g. OpenZeppelin's `TransparentUpgradeableProxy`.
+    Transparent,
+    /// EIP-1967 proxy upgraded through its implementation.
+    Uups,
+    /// EIP-1967 proxy reading its implementation from a beacon.
+    Beacon,
+    /// EIP-1167 minimal proxy.
+    Minimal,
+}
+
+impl fmt::Display for ProxyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transparent => f.write_str("transparent"),
+            Self::Uups => f.write_str("UUPS"),
+            Self::Beacon => f.write_str("beacon"),
+            Self::Minimal => f.write_str("EIP-1167 minimal"),
+        }
+    }
+}
+
+/// A proxy contract detected on chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Proxy {
+    pub kind: ProxyKind,
+    /// The address of the implementation.
+    pub implementation: Address,
+}
+
+impl Proxy {
+    /// Detects the proxy deployed at `address`, if any.
+    pub async fn detect(provider: &RetryProvider, address: Address) -> Result<Option<Self>> {
+        let code = provider.get_code_at(address).await?;
+        if let Some(implementation) = minimal_proxy_implementation(&code) {
+            return Ok(Some(Self { kind: ProxyKind::Minimal, implementation }))
+        }
+
+        let read_slot = |slot: B256| async move {
+            let value = provider.get_storage_at(address, slot.into()).await?;
+            Ok::<_, eyre::Report>(Address::from_word(value.into()))
+        };
+
+        let implementation = read_slot(IMPLEMENTATION_SLOT).await?;
+        if !implementation.is_zero() {
+            let kind = if read_slot(ADMIN_SLOT).await?.is_zero() {
+                ProxyKind::Uups
+            } else {
+                ProxyKind::Transparent
+            };
+            return Ok(Some(Self { kind, implementation }))
+        }
+
+        let beacon = read_slot(BEACON_SLOT).await?;
+        if !beacon.is_zero() {
+            let tx = TransactionRequest::default()
+                .to(beacon)
+                .input(Bytes::from(IMPLEMENTATION_SELECTOR).into());
+            let output = provider
+                .call(&WithOtherFields::new(tx))
+                .await
+                .wrap_err_with(|| format!("failed to get the implementation of beacon {beacon}"))?;
+            if output.len() != 32 {
+                eyre::bail!("beacon {beacon} returned an invalid implementation: {output}")
+            }
+            let implementation = Address::from_word(B256::from_slice(&output));
+            return Ok(Some(Self { kind: ProxyKind::Beacon, implementation }))
+        }
+
+        Ok(None)
+    }
+}
+
+/// Returns the implementation of an EIP-1167 minimal proxy given its runtime code.
+fn minimal_proxy_implementation(code: &[u8]) -> Option<Address> {
+    let address =
+        code.strip_prefix(&MINIMAL_PROXY_PREFIX[..])?.strip_suffix(&MINIMAL_PROXY_SUFFIX[..])?;
+    (address.len() == 20).then(|| Address::from_slice(address))
+}
+
+impl VerifyArgs {
+    /// Verifies the implementation of the proxy at the address, then links the proxy to it.
+    ///
+    /// Only Etherscan links proxies through its API, Sourcify and Blockscout detect them on their
+    /// own.
+    pub(crate) async fn run_proxy(mut self, config: Config) -> Result<()> {
+        let verifier = self.verifier.provider();
+        if matches!(verifier, VerificationProviderType::Oklink | VerificationProviderType::Custom) {
+            eyre::bail!("--proxy is not supported with the {verifier} verifier")
+        }
+        if config.get_rpc_url().is_none() {
+            eyre::bail!("You have to provide a valid RPC URL to use --proxy")
+        }
+        let provider = utils::get_provider(&config)?;
+        let chain = utils::get_chain(config.chain, provider.clone()).await?;
+
+        let address = self.address();
+        let Some(proxy) = Proxy::detect(&provider, address).await? else {
+            eyre::bail!(
+                "No proxy found at {address}: the EIP-1967 implementation and beacon slots are \
+                 empty and the code is not an EIP-1167 minimal proxy"
+            )
+        };
+        sh_println!(
+            "Detected {} proxy at {address} with implementation {}",
+            proxy.kind,
+            proxy.implementation
+        )?;
+
+        self.proxy = false;
+        let mut implementation = self.clone();
+        implementation.address = Some(proxy.implementation);
+        // The explorer only links a proxy to an implementation that is already verified.
+        implementation.watch = true;
+        // Boxed since `run` calls this function.
+        Box::pin(implementation.run()).await?;
+
+        self.etherscan.chain = Some(chain);
+        self.etherscan.key = config.get_etherscan_config_with_chain(Some(chain))?.map(|c| c.key);
+        if self.uses_sourcify() {
+            sh_println!("Sourcify detects proxies on its own, skipping linking the proxy.")?;
+            return Ok(())
+        }
+        if verifier == VerificationProviderType::Blockscout {
+            sh_println!("Blockscout detects proxies on its own, skipping linking the proxy.")?;
+            return Ok(())
+        }
+
+        EtherscanVerificationProvider::default()
+            .verify_proxy(&self, address, proxy.implementation)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, TxKind};
+    use anvil::{spawn, NodeConfig};
+    use foundry_common::provider::get_http_provider;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn can_detect_proxies() {
+        let (api, handle) = spawn(NodeConfig::test()).await;
+        let provider = get_http_provider(handle.http_endpoint());
+        let implementation = address!("bebebebebebebebebebebebebebebebebebebebe");
+        api.anvil_set_code(implementation, Bytes::from_static(&[0x00])).await.unwrap();
+
+        // EIP-1167 minimal proxy, deployed with the init code of the EIP.
+        let runtime = [&MINIMAL_PROXY_PREFIX[..], &implementation[..], &MINIMAL_PROXY_SUFFIX[..]];
+        let tx = TransactionRequest {
+            from: handle.dev_accounts().next(),
+            to: Some(TxKind::Create),
+            input: Bytes::from([&hex!("3d602d80600a3d3981f3")[..], &runtime.concat()].concat())
+                .into(),
+            ..Default::default()
+        };
+        let receipt = provider
+            .send_transaction(WithOtherFields::new(tx))
+            .await
+            .unwrap()
+            .get_receipt()
+            .await
+            .unwrap();
+        let minimal = receipt.contract_address.unwrap();
+        assert_eq!(
+            Proxy::detect(&provider, minimal).await.unwrap(),
+            Some(Proxy { kind: ProxyKind::Minimal, implementation })
+        );
+
+        // EIP-1967 proxy, UUPS without an admin and transparent with one.
+        let eip1967 = address!("1000000000000000000000000000000000000002");
+        api.anvil_set_code(eip1967, Bytes::from_static(&[0x00])).await.unwrap();
+        let set_slot = |slot: B256, value: Address| {
+            api.anvil_set_storage_at(eip1967, slot.into(), value.into_word())
+        };
+        set_slot(IMPLEMENTATION_SLOT, implementation).await.unwrap();
+        assert_eq!(
+            Proxy::detect(&provider, eip1967).await.unwrap(),
+            Some(Proxy { kind: ProxyKind::Uups, implementation })
+        );
+        set_slot(ADMIN_SLOT, address!("a000000000000000000000000000000000000000")).await.unwrap();
+        assert_eq!(
+            Proxy::detect(&provider, eip1967).await.unwrap(),
+            Some(Proxy { kind: ProxyKind::Transparent, implementation })
+        );
+
+        // Other contracts aren't proxies.
+        assert_eq!(Proxy::detect(&provider, implementation).await.unwrap(), None);
+    }
+
+    #[test]
+    fn can_parse_minimal_proxy() {
+        let code = hex!(
+            "363d3d373d3d3d363d73bebebebebebebebebebebebebebebebebebebebe5af43d82803e903d91602b57fd5bf3"
+        );
+        assert_eq!(
+            minimal_proxy_implementation(&code),
+            Some(address!("bebebebebebebebebebebebebebebebebebebebe"))
+        );
+
+        assert_eq!(minimal_proxy_implementation(&code[1..]), None);
+        assert_eq!(minimal_proxy_implementation(&code[..code.len() - 1]), None);
+        assert_eq!(minimal_proxy_implementation(&[]), None);
+    }
+}
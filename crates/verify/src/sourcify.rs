@@ -44,10 +44,10 @@ async fn verify(&mut self, args: VerifyArgs, context: VerificationContext) -> Re
                     sh_println!(
                         "\nSubmitting verification for [{}] {:?}.",
                         context.target_name,
-                        args.address.to_string()
+                        args.address().to_string()
                     )?;
                     let response = client
-                        .post(args.verifier.verifier_url.as_deref().unwrap_or(SOURCIFY_URL))
+                        .post(sourcify_url(args.verifier.verifier_url.as_deref())?)
                         .header("Content-Type", "application/json")
                         .body(serde_json::to_string(&body)?)
                         .send()
@@ -60,7 +60,7 @@ async fn verify(&mut self, args: VerifyArgs, context: VerificationContext) -> Re
                             "Sourcify verification request for address ({}) \
                              failed with status code {status}\n\
                              Details: {error:#}",
-                            args.address,
+                            args.address(),
                         );
                     }
 
@@ -80,9 +80,7 @@ async fn check(&self, args: VerifyCheckArgs) -> Result<()> {
             .into_retry()
             .run_async(|| {
                 async {
-                    let url = Url::from_str(
-                        args.verifier.verifier_url.as_deref().unwrap_or(SOURCIFY_URL),
-                    )?;
+                    let url = sourcify_url(args.verifier.verifier_url.as_deref())?;
                     let query = format!(
                         "check-by-addresses?addresses={}&chainIds={}",
                         args.id,
@@ -132,7 +130,7 @@ fn prepare_request(
         }
 
         let req = SourcifyVerifyRequest {
-            address: args.address.to_string(),
+            address: args.address().to_string(),
             chain: args.etherscan.chain.unwrap_or_default().id().to_string(),
             files,
             chosen_contract: None,
@@ -164,6 +162,18 @@ fn process_sourcify_response(
     }
 }
 
+/// Returns the URL of the Sourcify server, by default the public one.
+///
+/// The URL always ends with a slash so that the API endpoints can be joined to it, e.g. for a local
+/// instance at `http://localhost:5555` or behind a path like `https://example.com/server`.
+fn sourcify_url(url: Option<&str>) -> Result<Url> {
+    let mut url = Url::from_str(url.unwrap_or(SOURCIFY_URL))?;
+    if !url.path().ends_with('/') {
+        url.set_path(&format!("{}/", url.path()));
+    }
+    Ok(url)
+}
+
 #[derive(Debug, Serialize)]
 pub struct SourcifyVerifyRequest {
     address: String,
@@ -198,4 +208,18 @@ fn test_check_addresses_url() {
             "https://server-verify.hashscan.io/check-by-addresses?addresses=0x1234&chainIds=1"
         );
     }
+
+    #[test]
+    fn test_sourcify_url() {
+        assert_eq!(sourcify_url(None).unwrap().as_str(), SOURCIFY_URL);
+        assert_eq!(
+            sourcify_url(Some("http://localhost:5555")).unwrap().as_str(),
+            "http://localhost:5555/"
+        );
+        let url = sourcify_url(Some("http://10.0.0.2:5555/server")).unwrap();
+        assert_eq!(
+            url.join("check-by-addresses?addresses=0x1234&chainIds=1337").unwrap().as_str(),
+            "http://10.0.0.2:5555/server/check-by-addresses?addresses=0x1234&chainIds=1337"
+        );
+    }
 }
@@ -29,8 +29,10 @@
 #[derive(Clone, Debug, Parser)]
 pub struct VerifierArgs {
     /// The contract verification provider to use.
-    #[arg(long, help_heading = "Verifier options", default_value = "sourcify", value_enum)]
-    pub verifier: VerificationProviderType,
+    ///
+    /// Defaults to Sourcify, or to Etherscan if an Etherscan API key is set.
+    #[arg(long, help_heading = "Verifier options", value_enum)]
+    pub verifier: Option<VerificationProviderType>,
 
     /// The verifier API KEY, if using a custom provider.
     #[arg(long, help_heading = "Verifier options", env = "VERIFIER_API_KEY")]
@@ -43,11 +45,17 @@ pub struct VerifierArgs {
 
 impl Default for VerifierArgs {
     fn default() -> Self {
-        Self {
-            verifier: VerificationProviderType::Sourcify,
-            verifier_api_key: None,
-            verifier_url: None,
-        }
+        Self { verifier: None, verifier_api_key: None, verifier_url: None }
+    }
+}
+
+impl VerifierArgs {
+    /// Returns the verification provider, Sourcify if none is given.
+    ///
+    /// Note that [`VerificationProviderType::client`] uses Etherscan instead of Sourcify when
+    /// given an Etherscan API key.
+    pub fn provider(&self) -> VerificationProviderType {
+        self.verifier.clone().unwrap_or_default()
     }
 }
 
@@ -55,7 +63,10 @@ fn default() -> Self {
 #[derive(Clone, Debug, Parser)]
 pub struct VerifyArgs {
     /// The address of the contract to verify.
-    pub address: Address,
+    ///
+    /// Not used when verifying a broadcast with `--broadcast`.
+    #[arg(required_unless_present = "broadcast")]
+    pub address: Option<Address>,
 
     /// The contract identifier in the form `<path>:<contractname>`.
     pub contract: Option<ContractInfo>,
@@ -105,6 +116,38 @@ pub struct VerifyArgs {
     #[arg(long)]
     pub watch: bool,
 
+    /// Verify all the contracts deployed by a script broadcast, e.g.
+    /// `broadcast/Deploy.s.sol/1/run-latest.json`, instead of a single contract.
+    ///
+    /// This includes linked libraries and contracts deployed through a CREATE2 factory. Verified
+    /// contracts are skipped when running again for the same broadcast.
+    #[arg(
+        long,
+        value_hint = ValueHint::FilePath,
+        value_name = "PATH",
+        conflicts_with_all = [
+            "contract",
+            "constructor_args",
+            "constructor_args_path",
+            "guess_constructor_args",
+            "flatten",
+            "show_standard_json_input",
+        ]
+    )]
+    pub broadcast: Option<PathBuf>,
+
+    /// The number of contracts to verify concurrently when verifying a broadcast.
+    #[arg(long, requires = "broadcast", default_value_t = 4, value_name = "NUM")]
+    pub jobs: usize,
+
+    /// Treat the address as a proxy: detect its implementation on chain, verify it, and link the
+    /// proxy to it on the explorer.
+    ///
+    /// Supports EIP-1967 (Transparent and UUPS), beacon and EIP-1167 minimal proxies. The
+    /// contract and constructor arguments are the ones of the implementation. Requires an RPC URL.
+    #[arg(long, conflicts_with_all = ["broadcast", "show_standard_json_input"])]
+    pub proxy: bool,
+
     /// Set pre-linked libraries.
     #[arg(long, help_heading = "Linker options", env = "DAPP_LIBRARIES")]
     pub libraries: Vec<String>,
@@ -189,6 +232,14 @@ impl VerifyArgs {
     pub async fn run(mut self) -> Result<()> {
         let config = self.load_config()?;
 
+        if let Some(broadcast) = self.broadcast.take() {
+            return self.run_broadcast(&broadcast, config).await
+        }
+
+        if self.proxy {
+            return self.run_proxy(config).await
+        }
+
         if self.guess_constructor_args && config.get_rpc_url().is_none() {
             eyre::bail!(
                 "You have to provide a valid RPC URL to use --guess-constructor-args feature"
@@ -220,7 +271,7 @@ pub async fn run(mut self) -> Result<()> {
         }
 
         let verifier_url = self.verifier.verifier_url.clone();
-        sh_println!("Start verifying contract `{}` deployed on {chain}", self.address)?;
+        sh_println!("Start verifying contract `{}` deployed on {chain}", self.address())?;
         if let Some(version) = &self.compiler_version {
             sh_println!("Compiler version: {version}")?;
         }
@@ -232,8 +283,9 @@ pub async fn run(mut self) -> Result<()> {
                 sh_println!("Constructor args: {args}")?
             }
         }
-        self.verifier.verifier.client(&self.etherscan.key())?.verify(self, context).await.map_err(|err| {
-            if let Some(verifier_url) = verifier_url {
+        let is_sourcify = self.uses_sourcify();
+        self.verification_provider()?.verify(self, context).await.map_err(|err| {
+            if let Some(verifier_url) = verifier_url.filter(|_| !is_sourcify) {
                  match Url::parse(&verifier_url) {
                     Ok(url) => {
                         if is_host_only(&url) {
@@ -254,9 +306,33 @@ pub async fn run(mut self) -> Result<()> {
         })
     }
 
+    /// Returns the address of the contract to verify.
+    ///
+    /// It's only missing when verifying a broadcast, which verifies each contract with its own
+    /// arguments.
+    pub fn address(&self) -> Address {
+        self.address.expect("the address is required unless verifying a broadcast")
+    }
+
     /// Returns the configured verification provider
     pub fn verification_provider(&self) -> Result<Box<dyn VerificationProvider>> {
-        self.verifier.verifier.client(&self.etherscan.key())
+        if self.uses_sourcify() {
+            return VerificationProviderType::Sourcify.client(&None)
+        }
+        self.verifier.provider().client(&self.etherscan.key())
+    }
+
+    /// Returns whether the contract is verified on Sourcify.
+    ///
+    /// An Etherscan key selects Etherscan over the default Sourcify provider, unless
+    /// `--verifier sourcify` is passed explicitly, e.g. to use a local instance for a chain
+    /// without an explorer.
+    pub(crate) fn uses_sourcify(&self) -> bool {
+        match self.verifier.verifier {
+            Some(VerificationProviderType::Sourcify) => true,
+            Some(_) => false,
+            None => self.etherscan.key().is_none_or(|key| key.is_empty()),
+        }
     }
 
     /// Resolves [VerificationContext] object either from entered contract name or by trying to
@@ -368,7 +444,7 @@ pub async fn resolve_context(&self) -> Result<VerificationContext> {
                 eyre::bail!("You have to provide a contract name or a valid RPC URL")
             }
             let provider = utils::get_provider(&config)?;
-            let code = provider.get_code_at(self.address).await?;
+            let code = provider.get_code_at(self.address()).await?;
 
             let output = ProjectCompiler::new().compile(&project)?;
             let contracts = ContractsByArtifact::new(
@@ -378,7 +454,7 @@ pub async fn resolve_context(&self) -> Result<VerificationContext> {
             let Some((artifact_id, _)) = contracts.find_by_deployed_code_exact(&code) else {
                 eyre::bail!(format!(
                     "Bytecode at {} does not match any local contracts",
-                    self.address
+                    self.address()
                 ))
             };
 
@@ -429,7 +505,7 @@ pub async fn run(self) -> Result<()> {
             "Checking verification status on {}",
             self.etherscan.chain.unwrap_or_default()
         )?;
-        self.verifier.verifier.client(&self.etherscan.key())?.check(self).await
+        self.verifier.provider().client(&self.etherscan.key())?.check(self).await
     }
 }
 
@@ -459,4 +535,46 @@ fn can_parse_verify_contract() {
         ]);
         assert!(args.via_ir);
     }
+
+    #[test]
+    fn can_parse_verify_broadcast() {
+        let args: VerifyArgs = VerifyArgs::parse_from([
+            "foundry-cli",
+            "--broadcast",
+            "broadcast/Deploy.s.sol/1/run-latest.json",
+            "--jobs",
+            "8",
+        ]);
+        assert_eq!(args.broadcast, Some(PathBuf::from("broadcast/Deploy.s.sol/1/run-latest.json")));
+        assert_eq!(args.jobs, 8);
+
+        assert!(VerifyArgs::try_parse_from(["foundry-cli"]).is_err());
+        assert!(VerifyArgs::try_parse_from([
+            "foundry-cli",
+            "--broadcast",
+            "run-latest.json",
+            "--constructor-args",
+            "0x",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn can_parse_verify_proxy() {
+        let args: VerifyArgs = VerifyArgs::parse_from([
+            "foundry-cli",
+            "0x0000000000000000000000000000000000000000",
+            "src/Counter.sol:Counter",
+            "--proxy",
+        ]);
+        assert!(args.proxy);
+
+        assert!(VerifyArgs::try_parse_from([
+            "foundry-cli",
+            "--broadcast",
+            "run-latest.json",
+            "--proxy",
+        ])
+        .is_err());
+    }
 }
@@ -1,3 +1,4 @@
+use crate::{plugin::PluginSignerError, remote::RemoteSignerError};
 use alloy_primitives::hex::FromHexError;
 use alloy_signer::k256::ecdsa;
 use alloy_signer_ledger::LedgerError;
@@ -35,6 +36,10 @@ pub enum WalletSignerError {
     #[cfg(feature = "gcp-kms")]
     Gcp(#[from] GcpSignerError),
     #[error(transparent)]
+    Remote(#[from] RemoteSignerError),
+    #[error(transparent)]
+    Plugin(#[from] PluginSignerError),
+    #[error(transparent)]
     Io(#[from] std::io::Error),
     #[error(transparent)]
     InvalidHex(#[from] FromHexError),
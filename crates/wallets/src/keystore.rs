@@ -0,0 +1,233 @@
+//! Keystore management: metadata of the accounts of a keystores folder and encryption of keys into
+//! keystores with custom parameters.
+
+use alloy_primitives::{hex, keccak256, Address};
+use alloy_signer_local::PrivateKeySigner;
+use ctr::cipher::{KeyIvInit, StreamCipher};
+use eyre::{Context, Result};
+use rand::{CryptoRng, Rng};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+type Aes128Ctr = ctr::Ctr128BE<aes::Aes128>;
+
+/// The folder of the metadata files, inside a keystores folder.
+pub const METADATA_DIR: &str = ".metadata";
+
+/// Metadata of a keystore account, stored in the [`METADATA_DIR`] folder next to the keystore.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeystoreMetadata {
+    /// A human readable label for the account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// The chains the account may sign transactions for, or any chain if empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chains: Vec<u64>,
+}
+
+impl KeystoreMetadata {
+    /// Returns the path of the metadata of the account `name` of the keystores folder `dir`.
+    pub fn path(dir: &Path, name: &str) -> PathBuf {
+        dir.join(METADATA_DIR).join(format!("{name}.json"))
+    }
+
+    /// Loads the metadata of the account `name`, if any.
+    pub fn load(dir: &Path, name: &str) -> Result<Option<Self>> {
+        let path = Self::path(dir, name);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let metadata = fs::read_to_string(&path)
+            .wrap_err_with(|| format!("Failed to read keystore metadata {path:?}"))?;
+        Ok(Some(
+            serde_json::from_str(&metadata)
+                .wrap_err_with(|| format!("Failed to parse keystore metadata {path:?}"))?,
+        ))
+    }
+
+    /// Loads the metadata of the keystore at `path`, if any.
+    pub fn load_for_keystore(path: &Path) -> Result<Option<Self>> {
+        match (path.parent(), path.file_name().and_then(|name| name.to_str())) {
+            (Some(dir), Some(name)) => Self::load(dir, name),
+            _ => Ok(None),
+        }
+    }
+
+    /// Saves the metadata of the account `name`, removing the metadata file if it's empty.
+    pub fn save(&self, dir: &Path, name: &str) -> Result<()> {
+        let path = Self::path(dir, name);
+        if self.is_empty() {
+            if path.exists() {
+                fs::remove_file(&path)?;
+            }
+            return Ok(());
+        }
+        fs::create_dir_all(dir.join(METADATA_DIR))?;
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .wrap_err_with(|| format!("Failed to write keystore metadata {path:?}"))
+    }
+
+    /// Returns `true` if no metadata is set.
+    pub fn is_empty(&self) -> bool {
+        self.label.is_none() && self.chains.is_empty()
+    }
+
+    /// Returns an error if the account is restricted to other chains than `chain_id`.
+    pub fn ensure_chain_allowed(&self, chain_id: u64) -> Result<()> {
+        if !self.chains.is_empty() && !self.chains.contains(&chain_id) {
+            eyre::bail!(
+                "Keystore account{} is restricted to chains {:?} and can't be used on chain \
+                 {chain_id}",
+                self.label.as_ref().map(|label| format!(" `{label}`")).unwrap_or_default(),
+                self.chains
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Reads the address recorded in the keystore at `path`, if any, without decrypting it.
+pub fn keystore_address(path: &Path) -> Result<Option<Address>> {
+    let keystore =
+        fs::read_to_string(path).wrap_err_with(|| format!("Failed to read keystore {path:?}"))?;
+    let keystore: Value = serde_json::from_str(&keystore)
+        .wrap_err_with(|| format!("Failed to parse keystore {path:?}"))?;
+    keystore["address"]
+        .as_str()
+        .map(|address| {
+            address.parse().wrap_err_with(|| format!("Invalid address in keystore {path:?}"))
+        })
+        .transpose()
+}
+
+/// The scrypt parameters used to derive the encryption key of a keystore from its password.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScryptParams {
+    /// The base 2 logarithm of the CPU/memory cost `n`.
+    pub log_n: u8,
+    /// The block size.
+    pub r: u32,
+    /// The parallelization.
+    pub p: u32,
+}
+
+impl Default for ScryptParams {
+    /// The parameters used by geth.
+    fn default() -> Self {
+        Self { log_n: 18, r: 8, p: 1 }
+    }
+}
+
+impl ScryptParams {
+    /// Derives the 32 bytes key of `password`.
+    fn derive_key(&self, password: &[u8], salt: &[u8]) -> Result<[u8; 32]> {
+        let params = scrypt::Params::new(self.log_n, self.r, self.p)
+            .map_err(|e| eyre::eyre!("Invalid scrypt parameters: {e}"))?;
+        let mut key = [0u8; 32];
+        scrypt::scrypt(password, salt, &params, &mut key)
+            .map_err(|e| eyre::eyre!("Failed to derive the keystore key: {e}"))?;
+        Ok(key)
+    }
+
+    /// Returns the `kdfparams` of a keystore.
+    fn to_json(self, salt: &[u8]) -> Value {
+        json!({
+            "dklen": 32,
+            "n": 1u64 << self.log_n,
+            "p": self.p,
+            "r": self.r,
+            "salt": hex::encode(salt),
+        })
+    }
+}
+
+/// Encrypts the key of `signer` into a version 3 Web3 Secret Storage keystore, as written by
+/// `cast wallet new` and `cast wallet import`, derived with the given scrypt parameters.
+pub fn encrypt_keystore_v3<R: Rng + CryptoRng>(
+    rng: &mut R,
+    signer: &PrivateKeySigner,
+    password: &str,
+    params: ScryptParams,
+) -> Result<Value> {
+    let salt: [u8; 32] = rng.gen();
+    let iv: [u8; 16] = rng.gen();
+    let key = params.derive_key(password.as_bytes(), &salt)?;
+
+    let mut ciphertext = signer.credential().to_bytes().to_vec();
+    aes_128_ctr(&key[..16], &iv, &mut ciphertext);
+    let mac = keccak256([&key[16..], &ciphertext[..]].concat());
+
+    Ok(json!({
+        "address": hex::encode(signer.address()),
+        "crypto": {
+            "cipher": "aes-128-ctr",
+            "cipherparams": { "iv": hex::encode(iv) },
+            "ciphertext": hex::encode(&ciphertext),
+            "kdf": "scrypt",
+            "kdfparams": params.to_json(&salt),
+            "mac": hex::encode(mac),
+        },
+        "id": random_uuid(rng),
+        "version": 3,
+    }))
+}
+
+fn aes_128_ctr(key: &[u8], iv: &[u8], data: &mut [u8]) {
+    Aes128Ctr::new_from_slices(key, iv).expect("valid key and iv lengths").apply_keystream(data);
+}
+
+/// Returns a random version 4 UUID.
+fn random_uuid<R: Rng>(rng: &mut R) -> String {
+    let mut bytes: [u8; 16] = rng.gen();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex::encode(bytes);
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Cheap parameters to keep the tests fast.
+    const PARAMS: ScryptParams = ScryptParams { log_n: 4, r: 8, p: 1 };
+
+    #[test]
+    fn can_decrypt_keystore_v3() {
+        let dir = tempfile::tempdir().unwrap();
+        let signer = PrivateKeySigner::random();
+
+        let keystore =
+            encrypt_keystore_v3(&mut rand::thread_rng(), &signer, "secret", PARAMS).unwrap();
+        assert_eq!(keystore["crypto"]["kdfparams"]["n"], 16);
+        let path = dir.path().join("keystore");
+        fs::write(&path, keystore.to_string()).unwrap();
+
+        let decrypted = PrivateKeySigner::decrypt_keystore(&path, "secret").unwrap();
+        assert_eq!(decrypted.address(), signer.address());
+        assert_eq!(keystore_address(&path).unwrap(), Some(signer.address()));
+        assert!(PrivateKeySigner::decrypt_keystore(&path, "wrong").is_err());
+    }
+
+    #[test]
+    fn can_restrict_chains() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(KeystoreMetadata::load(dir.path(), "deployer").unwrap(), None);
+
+        let metadata = KeystoreMetadata { label: Some("Deployer".into()), chains: vec![1, 10] };
+        metadata.save(dir.path(), "deployer").unwrap();
+        let loaded = KeystoreMetadata::load_for_keystore(&dir.path().join("deployer")).unwrap();
+        assert_eq!(loaded, Some(metadata.clone()));
+
+        assert!(metadata.ensure_chain_allowed(10).is_ok());
+        assert!(metadata.ensure_chain_allowed(8453).is_err());
+        assert!(KeystoreMetadata::default().ensure_chain_allowed(8453).is_ok());
+
+        KeystoreMetadata::default().save(dir.path(), "deployer").unwrap();
+        assert!(!KeystoreMetadata::path(dir.path(), "deployer").exists());
+    }
+}
@@ -9,8 +9,11 @@
 extern crate tracing;
 
 pub mod error;
+pub mod keystore;
 pub mod multi_wallet;
+pub mod plugin;
 pub mod raw_wallet;
+pub mod remote;
 pub mod utils;
 pub mod wallet;
 pub mod wallet_signer;
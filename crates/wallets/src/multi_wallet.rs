@@ -1,4 +1,6 @@
 use crate::{
+    keystore::KeystoreMetadata,
+    remote::RemoteSigner,
     utils,
     wallet_signer::{PendingSigner, WalletSigner},
 };
@@ -19,22 +21,41 @@ pub struct MultiWallet {
     pending_signers: Vec<PendingSigner>,
     /// Contains unlocked signers.
     signers: AddressHashMap<WalletSigner>,
+    /// Metadata of the keystore signers, which may restrict the chains they sign for.
+    keystore_metadata: AddressHashMap<KeystoreMetadata>,
 }
 
 impl MultiWallet {
     pub fn new(pending_signers: Vec<PendingSigner>, signers: Vec<WalletSigner>) -> Self {
         let signers = signers.into_iter().map(|signer| (signer.address(), signer)).collect();
-        Self { pending_signers, signers }
+        Self { pending_signers, signers, keystore_metadata: Default::default() }
     }
 
     fn maybe_unlock_pending(&mut self) -> Result<()> {
         for pending in self.pending_signers.drain(..) {
+            let metadata = match &pending {
+                PendingSigner::Keystore(path) => KeystoreMetadata::load_for_keystore(path)?,
+                PendingSigner::Interactive => None,
+            };
             let signer = pending.unlock()?;
+            if let Some(metadata) = metadata {
+                self.keystore_metadata.insert(signer.address(), metadata);
+            }
             self.signers.insert(signer.address(), signer);
         }
         Ok(())
     }
 
+    /// Returns an error if the signer of `address` is a keystore restricted to other chains than
+    /// `chain_id` by its [`KeystoreMetadata`].
+    pub fn ensure_chain_allowed(&mut self, address: Address, chain_id: u64) -> Result<()> {
+        self.maybe_unlock_pending()?;
+        if let Some(metadata) = self.keystore_metadata.get(&address) {
+            metadata.ensure_chain_allowed(chain_id)?;
+        }
+        Ok(())
+    }
+
     pub fn signers(&mut self) -> Result<&AddressHashMap<WalletSigner>> {
         self.maybe_unlock_pending()?;
         Ok(&self.signers)
@@ -86,6 +107,8 @@ macro_rules! create_hw_wallets {
 /// 5. Private Keys (cleartext in CLI)
 /// 6. Private Keys (interactively via secure prompt)
 /// 7. AWS KMS
+/// 8. Remote signer (via URL)
+/// 9. Signer plugins (via commands)
 #[derive(Builder, Clone, Debug, Default, Serialize, Parser)]
 #[command(next_help_heading = "Wallet options", about = None, long_about = None)]
 pub struct MultiWalletOpts {
@@ -221,6 +244,31 @@ pub struct MultiWalletOpts {
     /// Use AWS Key Management Service.
     #[arg(long, help_heading = "Wallet options - remote", hide = !cfg!(feature = "aws-kms"))]
     pub aws: bool,
+
+    /// Use a remote signer speaking the JSON-RPC signing methods, e.g. Web3Signer or Clef.
+    ///
+    /// Signs with the --froms accounts, or all the accounts of the signer.
+    #[arg(
+        long,
+        help_heading = "Wallet options - remote",
+        value_name = "URL",
+        env = "ETH_REMOTE_SIGNER_URL"
+    )]
+    #[builder(default = "None")]
+    pub remote_signer_url: Option<String>,
+
+    /// Use external programs as signers, e.g. bridges to HashiCorp Vault or HSMs.
+    ///
+    /// Each command is run through the shell and signs requests received over stdio, see the
+    /// `foundry_wallets::plugin` documentation for the protocol.
+    #[arg(
+        long = "signer-cmd",
+        help_heading = "Wallet options - remote",
+        value_name = "COMMAND",
+        env = "ETH_SIGNER_CMD"
+    )]
+    #[builder(default = "None")]
+    pub signer_cmds: Option<Vec<String>>,
 }
 
 impl MultiWalletOpts {
@@ -238,9 +286,21 @@ pub async fn get_multi_wallet(&self) -> Result<MultiWallet> {
         if let Some(aws_signers) = self.aws_signers().await? {
             signers.extend(aws_signers);
         }
+        if let Some(remote_signers) = self.remote_signers().await? {
+            signers.extend(remote_signers);
+        }
+        if let Some(plugin_signers) = self.plugin_signers().await? {
+            signers.extend(plugin_signers);
+        }
+        let mut keystore_metadata = AddressHashMap::default();
         if let Some((pending_keystores, unlocked)) = self.keystores()? {
             pending.extend(pending_keystores);
-            signers.extend(unlocked);
+            for (signer, metadata) in unlocked {
+                if let Some(metadata) = metadata {
+                    keystore_metadata.insert(signer.address(), metadata);
+                }
+                signers.push(signer);
+            }
         }
         if let Some(pks) = self.private_keys()? {
             signers.extend(pks);
@@ -255,7 +315,9 @@ pub async fn get_multi_wallet(&self) -> Result<MultiWallet> {
             ));
         }
 
-        Ok(MultiWallet::new(pending, signers))
+        let mut wallet = MultiWallet::new(pending, signers);
+        wallet.keystore_metadata = keystore_metadata;
+        Ok(wallet)
     }
 
     pub fn private_keys(&self) -> Result<Option<Vec<WalletSigner>>> {
@@ -296,10 +358,14 @@ fn keystore_paths(&self) -> Result<Option<Vec<PathBuf>>> {
         Ok(None)
     }
 
-    /// Returns all wallets read from the provided keystores arguments
+    /// Returns all wallets read from the provided keystores arguments, along with the metadata of
+    /// the unlocked ones
     ///
     /// Returns `Ok(None)` if no keystore provided.
-    pub fn keystores(&self) -> Result<Option<(Vec<PendingSigner>, Vec<WalletSigner>)>> {
+    #[allow(clippy::type_complexity)]
+    pub fn keystores(
+        &self,
+    ) -> Result<Option<(Vec<PendingSigner>, Vec<(WalletSigner, Option<KeystoreMetadata>)>)>> {
         if let Some(keystore_paths) = self.keystore_paths()? {
             let mut pending = Vec::new();
             let mut signers = Vec::new();
@@ -319,7 +385,7 @@ pub fn keystores(&self) -> Result<Option<(Vec<PendingSigner>, Vec<WalletSigner>)
                 if let Some(pending_signer) = maybe_pending {
                     pending.push(pending_signer);
                 } else if let Some(signer) = maybe_signer {
-                    signers.push(signer);
+                    signers.push((signer, KeystoreMetadata::load_for_keystore(path)?));
                 }
             }
             return Ok(Some((pending, signers)));
@@ -397,6 +463,33 @@ pub async fn aws_signers(&self) -> Result<Option<Vec<WalletSigner>>> {
 
         Ok(None)
     }
+
+    /// Returns the signers of the `--froms` accounts from the remote signer, or of all its
+    /// accounts if none is given.
+    pub async fn remote_signers(&self) -> Result<Option<Vec<WalletSigner>>> {
+        let Some(url) = &self.remote_signer_url else { return Ok(None) };
+        let signers = match &self.froms {
+            Some(froms) if !froms.is_empty() => {
+                let mut signers = vec![];
+                for from in froms {
+                    signers.push(WalletSigner::from_remote(url, Some(*from)).await?);
+                }
+                signers
+            }
+            _ => RemoteSigner::all(url).await?.into_iter().map(WalletSigner::Remote).collect(),
+        };
+        Ok(Some(signers))
+    }
+
+    /// Spawns the `--signer-cmd` plugins.
+    pub async fn plugin_signers(&self) -> Result<Option<Vec<WalletSigner>>> {
+        let Some(cmds) = &self.signer_cmds else { return Ok(None) };
+        let mut signers = vec![];
+        for cmd in cmds {
+            signers.push(WalletSigner::from_plugin(cmd).await?);
+        }
+        Ok(Some(signers))
+    }
 }
 
 #[cfg(test)]
@@ -441,7 +534,7 @@ fn parse_keystore_password_file() {
         let (_, unlocked) = args.keystores().unwrap().unwrap();
         assert_eq!(unlocked.len(), 1);
         assert_eq!(
-            unlocked[0].address(),
+            unlocked[0].0.address(),
             Address::from_str("0xec554aeafe75601aaab43bd4621a22284db566c2").unwrap()
         );
     }
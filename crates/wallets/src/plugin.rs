@@ -0,0 +1,304 @@
+//! Signer delegating to an external program, e.g. a bridge to HashiCorp Vault Transit, a YubiHSM
+//! or an in-house HSM.
+//!
+//! The program is spawned once and exchanges newline-delimited JSON messages over its standard
+//! input and output; its standard error is shown to the user. Each request is an object
+//! `{"id": <number>, "method": <string>, "params": <object>}` answered by either
+//! `{"id": <number>, "result": <value>}` or `{"id": <number>, "error": <string>}`:
+//!
+//! | method             | params                | result                            |
+//! |--------------------|-----------------------|-----------------------------------|
+//! | `address`          | `{}`                  | the address of the key            |
+//! | `sign_hash`        | `{hash}`              | the 65 bytes signature of `hash`  |
+//! | `sign_transaction` | `{transaction, hash}` | the 65 bytes signature of `hash`  |
+//! | `sign_typed_data`  | `{typed_data, hash}`  | the 65 bytes signature of `hash`  |
+//!
+//! `transaction` is a JSON-RPC transaction request and `typed_data` is an EIP-712 typed data
+//! object, given so that the program can check what it signs. Messages and typed data structs known
+//! at compile time are signed with `sign_hash` over their EIP-191 and EIP-712 hashes. Signatures
+//! are checked against the address of the key.
+
+use crate::remote::{check_signature, transaction_json};
+use alloy_consensus::SignableTransaction;
+use alloy_dyn_abi::TypedData;
+use alloy_network::TxSigner;
+use alloy_primitives::{Address, Bytes, ChainId, PrimitiveSignature, B256};
+use alloy_signer::Signer;
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use std::{fmt, process::Stdio, sync::Arc};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
+    process::{Child, Command},
+    sync::Mutex,
+};
+
+/// Errors of a [PluginSigner].
+#[derive(Debug, thiserror::Error)]
+pub enum PluginSignerError {
+    #[error("failed to run signer plugin: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("signer plugin error: {0}")]
+    Plugin(String),
+    #[error("signer plugin exited")]
+    Exited,
+    #[error("invalid response from signer plugin: {0}")]
+    InvalidResponse(String),
+}
+
+/// A signer backed by an external program, see the [module documentation](self).
+#[derive(Clone)]
+pub struct PluginSigner {
+    io: Arc<Mutex<PluginIo>>,
+    address: Address,
+    chain_id: Option<ChainId>,
+}
+
+impl fmt::Debug for PluginSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PluginSigner")
+            .field("address", &self.address)
+            .field("chain_id", &self.chain_id)
+            .finish_non_exhaustive()
+    }
+}
+
+struct PluginIo {
+    reader: BufReader<Box<dyn AsyncRead + Send + Unpin>>,
+    writer: Box<dyn AsyncWrite + Send + Unpin>,
+    next_id: u64,
+    /// The plugin process, killed on drop.
+    _child: Option<Child>,
+}
+
+#[derive(Deserialize)]
+struct PluginResponse {
+    id: u64,
+    result: Option<Value>,
+    error: Option<String>,
+}
+
+impl PluginSigner {
+    /// Spawns `cmd` through the shell and asks it for the address of its key.
+    pub async fn spawn(cmd: &str) -> Result<Self, PluginSignerError> {
+        let mut command = if cfg!(windows) {
+            let mut command = Command::new("cmd");
+            command.arg("/C");
+            command
+        } else {
+            let mut command = Command::new("sh");
+            command.arg("-c");
+            command
+        };
+        let mut child = command
+            .arg(cmd)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()?;
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let stdin = child.stdin.take().expect("stdin is piped");
+        Self::new(Box::new(stdout), Box::new(stdin), Some(child)).await
+    }
+
+    /// Talks to a plugin reading requests from `writer` and writing responses to `reader`.
+    async fn new(
+        reader: Box<dyn AsyncRead + Send + Unpin>,
+        writer: Box<dyn AsyncWrite + Send + Unpin>,
+        child: Option<Child>,
+    ) -> Result<Self, PluginSignerError> {
+        let io = PluginIo { reader: BufReader::new(reader), writer, next_id: 1, _child: child };
+        let mut signer =
+            Self { io: Arc::new(Mutex::new(io)), address: Address::ZERO, chain_id: None };
+        signer.address = signer.request("address", json!({})).await?;
+        Ok(signer)
+    }
+
+    /// Sends a request to the plugin and waits for its response.
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<T, PluginSignerError> {
+        let mut io = self.io.lock().await;
+        let id = io.next_id;
+        io.next_id += 1;
+
+        let mut request = json!({ "id": id, "method": method, "params": params }).to_string();
+        request.push('\n');
+        io.writer.write_all(request.as_bytes()).await?;
+        io.writer.flush().await?;
+
+        let mut line = String::new();
+        if io.reader.read_line(&mut line).await? == 0 {
+            return Err(PluginSignerError::Exited)
+        }
+        let response: PluginResponse = serde_json::from_str(&line)
+            .map_err(|err| PluginSignerError::InvalidResponse(err.to_string()))?;
+        if response.id != id {
+            return Err(PluginSignerError::InvalidResponse(format!(
+                "expected response {id}, got {}",
+                response.id
+            )))
+        }
+        if let Some(error) = response.error {
+            return Err(PluginSignerError::Plugin(error))
+        }
+        let result = response
+            .result
+            .ok_or_else(|| PluginSignerError::InvalidResponse("missing result".to_string()))?;
+        serde_json::from_value(result)
+            .map_err(|err| PluginSignerError::InvalidResponse(err.to_string()))
+    }
+
+    /// Requests a signature and checks that it was made by the plugin's key over `hash`.
+    async fn request_signature(
+        &self,
+        method: &str,
+        mut params: Value,
+        hash: B256,
+    ) -> alloy_signer::Result<PrimitiveSignature> {
+        params["hash"] = json!(hash);
+        let signature: Bytes =
+            self.request(method, params).await.map_err(alloy_signer::Error::other)?;
+        let signature = PrimitiveSignature::try_from(signature.as_ref())?;
+        check_signature(self.address, &signature, hash)?;
+        Ok(signature)
+    }
+}
+
+#[async_trait]
+impl Signer for PluginSigner {
+    async fn sign_hash(&self, hash: &B256) -> alloy_signer::Result<PrimitiveSignature> {
+        self.request_signature("sign_hash", json!({}), *hash).await
+    }
+
+    async fn sign_dynamic_typed_data(
+        &self,
+        payload: &TypedData,
+    ) -> alloy_signer::Result<PrimitiveSignature> {
+        let hash = payload.eip712_signing_hash()?;
+        self.request_signature("sign_typed_data", json!({ "typed_data": payload }), hash).await
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> Option<ChainId> {
+        self.chain_id
+    }
+
+    fn set_chain_id(&mut self, chain_id: Option<ChainId>) {
+        self.chain_id = chain_id;
+    }
+}
+
+#[async_trait]
+impl TxSigner<PrimitiveSignature> for PluginSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    async fn sign_transaction(
+        &self,
+        tx: &mut dyn SignableTransaction<PrimitiveSignature>,
+    ) -> alloy_signer::Result<PrimitiveSignature> {
+        if let Some(chain_id) = self.chain_id {
+            if !tx.set_chain_id_checked(chain_id) {
+                return Err(alloy_signer::Error::TransactionChainIdMismatch {
+                    signer: chain_id,
+                    tx: tx.chain_id().unwrap(),
+                })
+            }
+        }
+        let transaction = transaction_json(self.address, tx);
+        self.request_signature(
+            "sign_transaction",
+            json!({ "transaction": transaction }),
+            tx.signature_hash(),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::TxEip1559;
+    use alloy_network::TxSignerSync;
+    use alloy_primitives::{TxKind, U256};
+    use alloy_signer::SignerSync;
+    use alloy_signer_local::PrivateKeySigner;
+
+    alloy_sol_types::sol! {
+        struct Mail {
+            string contents;
+        }
+    }
+
+    /// Connects to a plugin signing with a local key, refusing to sign transactions to `denied`.
+    async fn connect(signer: PrivateKeySigner, denied: Address) -> PluginSigner {
+        let (client, plugin) = tokio::io::duplex(1 << 16);
+        let (reader, mut writer) = tokio::io::split(plugin);
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(reader).lines();
+            while let Some(line) = lines.next_line().await.unwrap() {
+                let request: Value = serde_json::from_str(&line).unwrap();
+                let params = &request["params"];
+                let hash = || {
+                    let hash: B256 = serde_json::from_value(params["hash"].clone()).unwrap();
+                    json!(Bytes::from(signer.sign_hash_sync(&hash).unwrap().as_bytes()))
+                };
+                let mut response = match request["method"].as_str().unwrap() {
+                    "address" => json!({ "result": signer.address() }),
+                    "sign_transaction" if params["transaction"]["to"] == json!(denied) => {
+                        json!({ "error": "denied" })
+                    }
+                    "sign_hash" | "sign_transaction" | "sign_typed_data" => {
+                        json!({ "result": hash() })
+                    }
+                    method => panic!("unexpected method {method}"),
+                };
+                response["id"] = request["id"].clone();
+                writer.write_all(format!("{response}\n").as_bytes()).await.unwrap();
+            }
+        });
+        let (reader, writer) = tokio::io::split(client);
+        PluginSigner::new(Box::new(reader), Box::new(writer), None).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn can_sign_with_plugin() {
+        let local = PrivateKeySigner::random();
+        let denied = Address::with_last_byte(2);
+        let plugin = connect(local.clone(), denied).await;
+        assert_eq!(Signer::address(&plugin), local.address());
+
+        let signature = plugin.sign_message(b"hello").await.unwrap();
+        assert_eq!(signature, local.sign_message_sync(b"hello").unwrap());
+
+        let domain = alloy_sol_types::eip712_domain! { name: "Test", chain_id: 1, };
+        let payload = Mail { contents: "hello".to_string() };
+        let signature = plugin.sign_typed_data(&payload, &domain).await.unwrap();
+        assert_eq!(signature, local.sign_typed_data_sync(&payload, &domain).unwrap());
+
+        let tx = |to| TxEip1559 {
+            chain_id: 1,
+            to: TxKind::Call(to),
+            value: U256::from(1),
+            gas_limit: 21_000,
+            max_fee_per_gas: 2,
+            max_priority_fee_per_gas: 1,
+            ..Default::default()
+        };
+        let mut allowed = tx(Address::with_last_byte(1));
+        let signature = plugin.sign_transaction(&mut allowed).await.unwrap();
+        assert_eq!(signature, local.sign_transaction_sync(&mut allowed).unwrap());
+
+        let err = plugin.sign_transaction(&mut tx(denied)).await.unwrap_err();
+        assert!(err.to_string().contains("denied"), "{err}");
+    }
+}
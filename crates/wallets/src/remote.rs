@@ -0,0 +1,469 @@
+//! Signer delegating to a remote signing service over JSON-RPC, e.g. Web3Signer or Clef.
+
+use alloy_consensus::{SignableTransaction, TxEnvelope};
+use alloy_dyn_abi::{eip712::Resolver, DynSolValue, TypedData};
+use alloy_eips::eip2718::Decodable2718;
+use alloy_network::TxSigner;
+use alloy_primitives::{hex, Address, Bytes, ChainId, PrimitiveSignature, B256, U128, U64};
+use alloy_signer::{Signer, UnsupportedSignerOperation};
+use alloy_sol_types::{Eip712Domain, SolStruct, SolType};
+use async_trait::async_trait;
+use reqwest::Url;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+
+/// Errors of a [RemoteSigner].
+#[derive(Debug, thiserror::Error)]
+pub enum RemoteSignerError {
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error("invalid remote signer URL: {0}")]
+    InvalidUrl(String),
+    #[error("remote signer error {code}: {message}")]
+    Rpc { code: i64, message: String },
+    #[error("invalid response from remote signer: {0}")]
+    InvalidResponse(String),
+    #[error("remote signer does not manage any account")]
+    NoAccounts,
+    #[error("remote signer does not manage account {0}")]
+    UnknownAccount(Address),
+}
+
+/// A signer backed by a remote signing service speaking the Ethereum JSON-RPC signing methods:
+/// `eth_accounts`, `eth_signTransaction`, `eth_sign` and `eth_signTypedData_v4`.
+///
+/// The key never leaves the service, which signs hashes only as part of a transaction, message or
+/// typed data it can inspect.
+#[derive(Clone, Debug)]
+pub struct RemoteSigner {
+    client: reqwest::Client,
+    url: Url,
+    address: Address,
+    chain_id: Option<ChainId>,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+/// The result of `eth_signTransaction`: the raw transaction for Web3Signer, or an object with the
+/// raw transaction for Clef.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SignedTransaction {
+    Raw(Bytes),
+    Object { raw: Bytes },
+}
+
+impl RemoteSigner {
+    /// Connects to the remote signer at `url` for `address`, or for its first account if `None`.
+    pub async fn new(url: &str, address: Option<Address>) -> Result<Self, RemoteSignerError> {
+        let mut signer = Self {
+            client: reqwest::Client::new(),
+            url: Url::parse(url).map_err(|err| RemoteSignerError::InvalidUrl(err.to_string()))?,
+            address: Address::ZERO,
+            chain_id: None,
+        };
+        let accounts = signer.accounts().await?;
+        signer.address = match address {
+            Some(address) if accounts.contains(&address) => address,
+            Some(address) => return Err(RemoteSignerError::UnknownAccount(address)),
+            None => *accounts.first().ok_or(RemoteSignerError::NoAccounts)?,
+        };
+        Ok(signer)
+    }
+
+    /// Returns the accounts managed by the remote signer.
+    pub async fn accounts(&self) -> Result<Vec<Address>, RemoteSignerError> {
+        self.request("eth_accounts", json!([])).await
+    }
+
+    /// Returns a signer for each account managed by the remote signer at `url`.
+    pub async fn all(url: &str) -> Result<Vec<Self>, RemoteSignerError> {
+        let signer = Self::new(url, None).await?;
+        let accounts = signer.accounts().await?;
+        Ok(accounts.into_iter().map(|address| Self { address, ..signer.clone() }).collect())
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<T, RemoteSignerError> {
+        trace!(method, %params, "sending remote signer request");
+        let response: RpcResponse<T> = self
+            .client
+            .post(self.url.clone())
+            .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        match (response.result, response.error) {
+            (_, Some(RpcError { code, message })) => Err(RemoteSignerError::Rpc { code, message }),
+            (Some(result), None) => Ok(result),
+            (None, None) => {
+                Err(RemoteSignerError::InvalidResponse(format!("missing result for `{method}`")))
+            }
+        }
+    }
+
+    /// Requests a signature and checks that it was made by this signer's account over `hash`.
+    async fn request_signature(
+        &self,
+        method: &str,
+        params: Value,
+        hash: B256,
+    ) -> alloy_signer::Result<PrimitiveSignature> {
+        let signature: Bytes =
+            self.request(method, params).await.map_err(alloy_signer::Error::other)?;
+        let signature = PrimitiveSignature::try_from(signature.as_ref())?;
+        check_signature(self.address, &signature, hash)?;
+        Ok(signature)
+    }
+
+    async fn sign_transaction_inner(
+        &self,
+        tx: &dyn SignableTransaction<PrimitiveSignature>,
+    ) -> alloy_signer::Result<PrimitiveSignature> {
+        if tx.blob_versioned_hashes().is_some() {
+            return Err(alloy_signer::Error::other(
+                "remote signers do not support blob transactions",
+            ))
+        }
+
+        let request = transaction_json(self.address, tx);
+
+        let signed: SignedTransaction = self
+            .request("eth_signTransaction", json!([request]))
+            .await
+            .map_err(alloy_signer::Error::other)?;
+        let raw = match signed {
+            SignedTransaction::Raw(raw) | SignedTransaction::Object { raw } => raw,
+        };
+        let envelope = TxEnvelope::decode_2718(&mut raw.as_ref()).map_err(|err| {
+            alloy_signer::Error::other(RemoteSignerError::InvalidResponse(err.to_string()))
+        })?;
+
+        // The signature must be over our transaction, which the signer could have changed.
+        let signature = *envelope.signature();
+        check_signature(self.address, &signature, tx.signature_hash())?;
+        Ok(signature)
+    }
+}
+
+/// Returns the JSON-RPC transaction request object of a transaction signed by `from`.
+pub(crate) fn transaction_json(
+    from: Address,
+    tx: &dyn SignableTransaction<PrimitiveSignature>,
+) -> Value {
+    let mut request = json!({
+        "from": from,
+        "nonce": U64::from(tx.nonce()),
+        "gas": U64::from(tx.gas_limit()),
+        "value": tx.value(),
+        "data": tx.input(),
+        "type": U64::from(tx.ty()),
+    });
+    if let Some(to) = tx.to() {
+        request["to"] = json!(to);
+    }
+    if let Some(chain_id) = tx.chain_id() {
+        request["chainId"] = json!(U64::from(chain_id));
+    }
+    if let Some(gas_price) = tx.gas_price() {
+        request["gasPrice"] = json!(U128::from(gas_price));
+    } else {
+        request["maxFeePerGas"] = json!(U128::from(tx.max_fee_per_gas()));
+        request["maxPriorityFeePerGas"] =
+            json!(U128::from(tx.max_priority_fee_per_gas().unwrap_or_default()));
+    }
+    if let Some(access_list) = tx.access_list() {
+        request["accessList"] = json!(access_list);
+    }
+    if let Some(authorization_list) = tx.authorization_list() {
+        request["authorizationList"] = json!(authorization_list);
+    }
+    request
+}
+
+/// Returns the typed data of a struct known at compile time.
+///
+/// The message is decoded from the ABI encoding of the struct, which doesn't need to implement
+/// `Serialize` as [`TypedData::from_struct`] requires.
+pub(crate) fn typed_data_from_struct<T: SolStruct>(
+    payload: &T,
+    domain: &Eip712Domain,
+) -> alloy_dyn_abi::Result<TypedData> {
+    let mut resolver = Resolver::from_struct::<T>();
+    resolver.ingest_string(domain.encode_type())?;
+    let message = resolver.resolve(T::NAME)?.abi_decode(&abi_encode::<T>(payload))?;
+    Ok(TypedData {
+        domain: domain.clone(),
+        resolver,
+        primary_type: T::NAME.into(),
+        message: typed_data_message(&message),
+    })
+}
+
+/// ABI-encodes a value of `S`.
+///
+/// The Rust type of a [`SolType`] is always encodable, but a generic [`SolStruct`] is only known
+/// to be its own Rust type, so it's encoded through this function.
+fn abi_encode<S: SolType>(value: &S::RustType) -> Vec<u8> {
+    S::abi_encode(value)
+}
+
+/// Returns the JSON message of a decoded EIP-712 value.
+fn typed_data_message(value: &DynSolValue) -> Value {
+    match value {
+        DynSolValue::Bool(value) => json!(value),
+        DynSolValue::Int(value, _) => json!(value.to_string()),
+        DynSolValue::Uint(value, _) => json!(value.to_string()),
+        DynSolValue::FixedBytes(word, size) => json!(hex::encode_prefixed(&word[..*size])),
+        DynSolValue::Address(address) => json!(address),
+        DynSolValue::Function(function) => json!(hex::encode_prefixed(function)),
+        DynSolValue::Bytes(bytes) => json!(hex::encode_prefixed(bytes)),
+        DynSolValue::String(string) => json!(string),
+        DynSolValue::Array(values) |
+        DynSolValue::FixedArray(values) |
+        DynSolValue::Tuple(values) => Value::Array(values.iter().map(typed_data_message).collect()),
+        DynSolValue::CustomStruct { prop_names, tuple, .. } => Value::Object(
+            prop_names.iter().cloned().zip(tuple.iter().map(typed_data_message)).collect(),
+        ),
+    }
+}
+
+/// Checks that a signature over `hash` was made by `address`.
+pub(crate) fn check_signature(
+    address: Address,
+    signature: &PrimitiveSignature,
+    hash: B256,
+) -> alloy_signer::Result<()> {
+    let signer = signature.recover_address_from_prehash(&hash)?;
+    if signer != address {
+        return Err(alloy_signer::Error::other(format!(
+            "signature is from {signer} instead of {address}"
+        )))
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl Signer for RemoteSigner {
+    async fn sign_hash(&self, _hash: &B256) -> alloy_signer::Result<PrimitiveSignature> {
+        Err(alloy_signer::Error::UnsupportedOperation(UnsupportedSignerOperation::SignHash))
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> alloy_signer::Result<PrimitiveSignature> {
+        let hash = alloy_primitives::eip191_hash_message(message);
+        self.request_signature(
+            "eth_sign",
+            json!([self.address, hex::encode_prefixed(message)]),
+            hash,
+        )
+        .await
+    }
+
+    async fn sign_typed_data<T: SolStruct + Send + Sync>(
+        &self,
+        payload: &T,
+        domain: &Eip712Domain,
+    ) -> alloy_signer::Result<PrimitiveSignature>
+    where
+        Self: Sized,
+    {
+        self.sign_dynamic_typed_data(&typed_data_from_struct(payload, domain)?).await
+    }
+
+    async fn sign_dynamic_typed_data(
+        &self,
+        payload: &TypedData,
+    ) -> alloy_signer::Result<PrimitiveSignature> {
+        let hash = payload.eip712_signing_hash()?;
+        let params = json!([self.address, payload]);
+        match self.request_signature("eth_signTypedData_v4", params.clone(), hash).await {
+            // Web3Signer only serves the unversioned method.
+            Err(alloy_signer::Error::Other(err))
+                if matches!(
+                    err.downcast_ref(),
+                    Some(RemoteSignerError::Rpc { code: -32601, .. })
+                ) =>
+            {
+                self.request_signature("eth_signTypedData", params, hash).await
+            }
+            result => result,
+        }
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> Option<ChainId> {
+        self.chain_id
+    }
+
+    fn set_chain_id(&mut self, chain_id: Option<ChainId>) {
+        self.chain_id = chain_id;
+    }
+}
+
+#[async_trait]
+impl TxSigner<PrimitiveSignature> for RemoteSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    async fn sign_transaction(
+        &self,
+        tx: &mut dyn SignableTransaction<PrimitiveSignature>,
+    ) -> alloy_signer::Result<PrimitiveSignature> {
+        if let Some(chain_id) = self.chain_id {
+            if !tx.set_chain_id_checked(chain_id) {
+                return Err(alloy_signer::Error::TransactionChainIdMismatch {
+                    signer: chain_id,
+                    tx: tx.chain_id().unwrap(),
+                })
+            }
+        }
+        self.sign_transaction_inner(tx).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::TxEip1559;
+    use alloy_eips::eip2718::Encodable2718;
+    use alloy_network::{Ethereum, Network, TxSignerSync};
+    use alloy_primitives::{TxKind, U256};
+    use alloy_signer::SignerSync;
+    use alloy_signer_local::PrivateKeySigner;
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+    };
+
+    alloy_sol_types::sol! {
+        #[derive(serde::Serialize)]
+        struct Mail {
+            address to;
+            uint256 amount;
+            bytes32 id;
+            string contents;
+        }
+    }
+
+    /// Serves the JSON-RPC signing methods with a local key, standing in for a remote signer.
+    fn serve(signer: PrivateKeySigner) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&mut stream);
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" {
+                        break
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+
+                let request: Value = serde_json::from_slice(&body).unwrap();
+                let params = &request["params"];
+                let result = match request["method"].as_str().unwrap() {
+                    "eth_accounts" => json!([signer.address()]),
+                    "eth_sign" => {
+                        let message: Bytes = serde_json::from_value(params[1].clone()).unwrap();
+                        let signature = signer.sign_message_sync(&message).unwrap();
+                        json!(Bytes::from(signature.as_bytes()))
+                    }
+                    "eth_signTypedData_v4" => {
+                        let typed_data: TypedData =
+                            serde_json::from_value(params[1].clone()).unwrap();
+                        let signature = signer
+                            .sign_hash_sync(&typed_data.eip712_signing_hash().unwrap())
+                            .unwrap();
+                        json!(Bytes::from(signature.as_bytes()))
+                    }
+                    "eth_signTransaction" => {
+                        let request: <Ethereum as Network>::TransactionRequest =
+                            serde_json::from_value(params[0].clone()).unwrap();
+                        let mut tx = request.build_typed_tx().unwrap().eip1559().unwrap().clone();
+                        let signature = signer.sign_transaction_sync(&mut tx).unwrap();
+                        let envelope = TxEnvelope::from(tx.into_signed(signature));
+                        json!({ "raw": Bytes::from(envelope.encoded_2718()) })
+                    }
+                    method => panic!("unexpected method {method}"),
+                };
+                let body = json!({ "jsonrpc": "2.0", "id": request["id"], "result": result });
+                let body = body.to_string();
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn can_sign_remotely() {
+        let local = PrivateKeySigner::random();
+        let url = serve(local.clone());
+
+        let remote = RemoteSigner::new(&url, None).await.unwrap();
+        assert_eq!(Signer::address(&remote), local.address());
+        assert!(RemoteSigner::new(&url, Some(Address::ZERO)).await.is_err());
+
+        let signature = remote.sign_message(b"hello").await.unwrap();
+        assert_eq!(signature, local.sign_message_sync(b"hello").unwrap());
+
+        let domain = alloy_sol_types::eip712_domain! { name: "Test", chain_id: 1, };
+        let payload = Mail {
+            to: Address::with_last_byte(1),
+            amount: U256::from(100),
+            id: B256::with_last_byte(2),
+            contents: "hello".to_string(),
+        };
+        let expected = local.sign_typed_data_sync(&payload, &domain).unwrap();
+        let typed_data = TypedData::from_struct(&payload, Some(domain.clone()));
+        let signature = remote.sign_dynamic_typed_data(&typed_data).await.unwrap();
+        assert_eq!(signature, expected);
+        let signature = remote.sign_typed_data(&payload, &domain).await.unwrap();
+        assert_eq!(signature, expected);
+
+        let mut tx = TxEip1559 {
+            chain_id: 1,
+            to: TxKind::Call(Address::with_last_byte(1)),
+            value: U256::from(1),
+            gas_limit: 21_000,
+            max_fee_per_gas: 2,
+            max_priority_fee_per_gas: 1,
+            ..Default::default()
+        };
+        let signature = remote.sign_transaction(&mut tx).await.unwrap();
+        assert_eq!(signature, local.sign_transaction_sync(&mut tx).unwrap());
+    }
+}
@@ -1,8 +1,14 @@
-use crate::{raw_wallet::RawWalletOpts, utils, wallet_signer::WalletSigner};
+use crate::{
+    keystore::{self, KeystoreMetadata},
+    raw_wallet::RawWalletOpts,
+    utils,
+    wallet_signer::WalletSigner,
+};
 use alloy_primitives::Address;
 use clap::Parser;
 use eyre::Result;
 use serde::Serialize;
+use std::path::PathBuf;
 
 /// The wallet options can either be:
 /// 1. Raw (via private key / mnemonic file, see `RawWallet`)
@@ -11,6 +17,8 @@
 /// 4. Keystore (via file path)
 /// 5. AWS KMS
 /// 6. Google Cloud KMS
+/// 7. Remote signer (via URL)
+/// 8. Signer plugin (via command)
 #[derive(Clone, Debug, Default, Serialize, Parser)]
 #[command(next_help_heading = "Wallet options", about = None, long_about = None)]
 pub struct WalletOpts {
@@ -84,6 +92,30 @@ pub struct WalletOpts {
     /// Use Google Cloud Key Management Service.
     #[arg(long, help_heading = "Wallet options - remote", hide = !cfg!(feature = "gcp-kms"))]
     pub gcp: bool,
+
+    /// Use a remote signer speaking the JSON-RPC signing methods, e.g. Web3Signer or Clef.
+    ///
+    /// Signs with the --from account, or the first account of the signer.
+    #[arg(
+        long,
+        help_heading = "Wallet options - remote",
+        value_name = "URL",
+        env = "ETH_REMOTE_SIGNER_URL"
+    )]
+    pub remote_signer_url: Option<String>,
+
+    /// Use an external program as signer, e.g. a bridge to HashiCorp Vault or an HSM.
+    ///
+    /// The command is run through the shell and signs requests received over stdio, see the
+    /// `foundry_wallets::plugin` documentation for the protocol.
+    #[arg(
+        long,
+        help_heading = "Wallet options - remote",
+        value_name = "COMMAND",
+        env = "ETH_SIGNER_CMD",
+        conflicts_with = "remote_signer_url"
+    )]
+    pub signer_cmd: Option<String>,
 }
 
 impl WalletOpts {
@@ -106,6 +138,17 @@ pub async fn signer(&self) -> Result<WalletSigner> {
             let key_name = std::env::var("GCP_KEY_NAME")?;
             let key_version = std::env::var("GCP_KEY_VERSION")?.parse()?;
             WalletSigner::from_gcp(project_id, location, keyring, key_name, key_version).await?
+        } else if let Some(url) = &self.remote_signer_url {
+            WalletSigner::from_remote(url, self.from).await?
+        } else if let Some(cmd) = &self.signer_cmd {
+            let signer = WalletSigner::from_plugin(cmd).await?;
+            if let Some(from) = self.from {
+                let address = alloy_signer::Signer::address(&signer);
+                if address != from {
+                    eyre::bail!("signer plugin signs for {address} instead of --from {from}")
+                }
+            }
+            signer
         } else if let Some(raw_wallet) = self.raw.signer()? {
             raw_wallet
         } else if let Some(path) = utils::maybe_get_keystore_path(
@@ -130,7 +173,8 @@ pub async fn signer(&self) -> Result<WalletSigner> {
 Error accessing local wallet. Did you set a private key, mnemonic or keystore?
 Run `cast send --help` or `forge create --help` and use the corresponding CLI
 flag to set your key via:
---private-key, --mnemonic-path, --aws, --gcp, --interactive, --trezor or --ledger.
+--private-key, --mnemonic-path, --aws, --gcp, --remote-signer-url, --signer-cmd, --interactive,
+--trezor or --ledger.
 Alternatively, if you're using a local node with unlocked accounts,
 use the --unlocked flag and either set the `ETH_FROM` environment variable to the address
 of the unlocked account you want to use, or provide the --from flag with the address directly."
@@ -139,6 +183,59 @@ pub async fn signer(&self) -> Result<WalletSigner> {
 
         Ok(signer)
     }
+
+    /// Returns an error if the keystore, if any, is restricted to other chains than `chain_id` by
+    /// its [`KeystoreMetadata`].
+    pub fn ensure_chain_allowed(&self, chain_id: u64) -> Result<()> {
+        if let Some(metadata) = self.keystore_metadata()? {
+            metadata.ensure_chain_allowed(chain_id)?;
+        }
+        Ok(())
+    }
+
+    /// Returns an error if the keystore, if any, is restricted to some chains by its
+    /// [`KeystoreMetadata`], for signatures which aren't bound to a chain, e.g. of raw hashes.
+    pub fn ensure_chain_unrestricted(&self) -> Result<()> {
+        if let Some(metadata) = self.keystore_metadata()? {
+            if !metadata.chains.is_empty() {
+                eyre::bail!(
+                    "Keystore account is restricted to chains {:?} and can't sign data which \
+                     isn't bound to a chain",
+                    metadata.chains
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the address of the keystore, if any, as recorded in the keystore file without
+    /// decrypting it.
+    pub fn keystore_address(&self) -> Result<Option<Address>> {
+        let Some(path) = self.keystore_file()? else { return Ok(None) };
+        match keystore::keystore_address(&path)? {
+            Some(address) => Ok(Some(address)),
+            None => eyre::bail!("The keystore {path:?} doesn't record its address"),
+        }
+    }
+
+    /// Returns the metadata of the keystore, if any.
+    fn keystore_metadata(&self) -> Result<Option<KeystoreMetadata>> {
+        match self.keystore_file()? {
+            Some(path) => KeystoreMetadata::load_for_keystore(&path),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the path of the keystore, if any.
+    fn keystore_file(&self) -> Result<Option<PathBuf>> {
+        if self.keystore_path.is_none() && self.keystore_account_name.is_none() {
+            return Ok(None);
+        }
+        utils::maybe_get_keystore_path(
+            self.keystore_path.as_deref(),
+            self.keystore_account_name.as_deref(),
+        )
+    }
 }
 
 impl From<RawWalletOpts> for WalletOpts {
@@ -196,6 +293,8 @@ async fn illformed_private_key_generates_user_friendly_error() {
             trezor: false,
             aws: false,
             gcp: false,
+            remote_signer_url: None,
+            signer_cmd: None,
         };
         match wallet.signer().await {
             Ok(_) => {
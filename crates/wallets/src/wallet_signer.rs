@@ -1,4 +1,4 @@
-use crate::error::WalletSignerError;
+use crate::{error::WalletSignerError, plugin::PluginSigner, remote::RemoteSigner};
 use alloy_consensus::SignableTransaction;
 use alloy_dyn_abi::TypedData;
 use alloy_network::TxSigner;
@@ -40,6 +40,10 @@ pub enum WalletSigner {
     /// Wrapper around Google Cloud KMS signer.
     #[cfg(feature = "gcp-kms")]
     Gcp(GcpSigner),
+    /// Wrapper around a remote signer speaking the JSON-RPC signing methods.
+    Remote(RemoteSigner),
+    /// Wrapper around an external signer program.
+    Plugin(PluginSigner),
 }
 
 impl WalletSigner {
@@ -106,6 +110,14 @@ pub async fn from_gcp(
         }
     }
 
+    pub async fn from_remote(url: &str, address: Option<Address>) -> Result<Self> {
+        Ok(Self::Remote(RemoteSigner::new(url, address).await?))
+    }
+
+    pub async fn from_plugin(cmd: &str) -> Result<Self> {
+        Ok(Self::Plugin(PluginSigner::spawn(cmd).await?))
+    }
+
     pub fn from_private_key(private_key: &B256) -> Result<Self> {
         Ok(Self::Local(PrivateKeySigner::from_bytes(private_key)?))
     }
@@ -115,7 +127,7 @@ pub fn from_private_key(private_key: &B256) -> Result<Self> {
     /// - for Ledger and Trezor signers the number of addresses to retrieve is specified as argument
     /// - the result for Ledger signers includes addresses available for both LedgerLive and Legacy
     ///   derivation paths
-    /// - for Local and AWS signers the result contains a single address
+    /// - for Local, AWS, remote and plugin signers the result contains a single address
     pub async fn available_senders(&self, max: usize) -> Result<Vec<Address>> {
         let mut senders = Vec::new();
         match self {
@@ -155,6 +167,12 @@ pub async fn available_senders(&self, max: usize) -> Result<Vec<Address>> {
             Self::Gcp(gcp) => {
                 senders.push(alloy_signer::Signer::address(gcp));
             }
+            Self::Remote(remote) => {
+                senders.push(alloy_signer::Signer::address(remote));
+            }
+            Self::Plugin(plugin) => {
+                senders.push(alloy_signer::Signer::address(plugin));
+            }
         }
         Ok(senders)
     }
@@ -179,6 +197,13 @@ pub fn from_mnemonic(
 
         Ok(Self::Local(builder.build()?))
     }
+
+    /// Returns whether the signer can sign raw hashes, e.g. EIP-7702 authorizations.
+    ///
+    /// Hardware wallets and remote signers only sign messages, transactions and typed data.
+    pub fn can_sign_hash(&self) -> bool {
+        !matches!(self, Self::Ledger(_) | Self::Trezor(_) | Self::Remote(_))
+    }
 }
 
 macro_rules! delegate {
@@ -191,6 +216,8 @@ macro_rules! delegate {
             Self::Aws($inner) => $e,
             #[cfg(feature = "gcp-kms")]
             Self::Gcp($inner) => $e,
+            Self::Remote($inner) => $e,
+            Self::Plugin($inner) => $e,
         }
     };
 }